@@ -90,7 +90,9 @@ fn session_keys(
     im_online: ImOnlineId,
     authority_discovery: AuthorityDiscoveryId,
 ) -> SessionKeys {
-    SessionKeys { grandpa, babe, im_online, authority_discovery }
+    // zk-compute verification reuses the im-online sr25519 key material.
+    let zk_compute = sr25519::Public::from(im_online.clone()).into();
+    SessionKeys { grandpa, babe, im_online, authority_discovery, zk_compute }
 }
 
 fn staging_testnet_config_genesis() -> GenesisConfig {
@@ -0,0 +1,52 @@
+"""Generate the fake NVIDIA CC certificate chain used by the `register_attested_node` benchmark.
+
+The chain has the most certificates a report may carry, all ECDSA P-384, valid from the Unix
+epoch so the benchmark passes whatever the chain time. The device key is the scalar `[7; 48]`.
+"""
+
+import datetime
+import pathlib
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.x509.oid import NameOID
+
+HERE = pathlib.Path(__file__).parent
+NOT_BEFORE = datetime.datetime(1970, 1, 1, tzinfo=datetime.timezone.utc)
+NOT_AFTER = datetime.datetime(9999, 12, 31, 23, 59, 59, tzinfo=datetime.timezone.utc)
+
+
+def key(byte):
+    return ec.derive_private_key(int.from_bytes(bytes([byte]) * 48, "big"), ec.SECP384R1())
+
+
+def name(common_name):
+    return x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, common_name)])
+
+
+def certificate(subject, subject_key, issuer, issuer_key, serial, ca):
+    return (
+        x509.CertificateBuilder()
+        .subject_name(name(subject))
+        .issuer_name(name(issuer))
+        .public_key(subject_key.public_key())
+        .serial_number(serial)
+        .not_valid_before(NOT_BEFORE)
+        .not_valid_after(NOT_AFTER)
+        .add_extension(x509.BasicConstraints(ca=ca, path_length=None), critical=True)
+        .sign(issuer_key, hashes.SHA384())
+    )
+
+
+chain = [
+    ("nvidia-root", "Fake NVIDIA Device Identity CA", key(1), True),
+    ("nvidia-ca-1", "Fake NVIDIA GH100 Provisioner CA", key(2), True),
+    ("nvidia-ca-2", "Fake NVIDIA GH100 Identity CA", key(3), True),
+    ("nvidia-device", "Fake NVIDIA GH100 Device", key(7), False),
+]
+issuer, issuer_key = chain[0][1], chain[0][2]
+for serial, (file, subject, subject_key, ca) in enumerate(chain, 1):
+    cert = certificate(subject, subject_key, issuer, issuer_key, serial, ca)
+    (HERE / f"{file}.der").write_bytes(cert.public_bytes(serialization.Encoding.DER))
+    issuer, issuer_key = subject, subject_key
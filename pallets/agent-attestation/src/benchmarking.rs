@@ -1,26 +1,94 @@
 #![cfg(feature = "runtime-benchmarks")]
 
 use super::*;
-use frame_benchmarking::v1::{account, benchmarks, whitelisted_caller};
-use frame_support::traits::Currency;
-use sp_std::vec;
+use frame_benchmarking::v1::{account, benchmarks, whitelisted_caller, BenchmarkError};
+use frame_support::traits::{Currency, EnsureOrigin, Hooks, ReservableCurrency};
+use sp_std::{vec, vec::Vec};
 use frame_system::RawOrigin;
 use sp_core::H256;
 
+/// Fake NVIDIA CC certificate chain generated by `fixtures/tee/generate.py`, as long as a chain
+/// may be. The device key is the scalar `[7; 48]`.
+const NVIDIA_CHAIN: [&[u8]; MAX_TEE_CHAIN_LEN as usize] = [
+    include_bytes!("../fixtures/tee/nvidia-device.der"),
+    include_bytes!("../fixtures/tee/nvidia-ca-2.der"),
+    include_bytes!("../fixtures/tee/nvidia-ca-1.der"),
+    include_bytes!("../fixtures/tee/nvidia-root.der"),
+];
+
+/// SPDM measurement report of the fixture device with `nonce`, as long as a quote may be, and
+/// the measurement it attests
+fn nvidia_report(nonce: H256) -> (Vec<u8>, H256) {
+    use p384::ecdsa::{signature::Signer, Signature, SigningKey};
+
+    let fixed_size = tee::SPDM_REQUEST_SIZE + 8 + 32 + 2 + tee::SPDM_SIGNATURE_SIZE;
+    let record = vec![0u8; MAX_TEE_QUOTE_LEN as usize - fixed_size];
+    let mut report = vec![tee::SPDM_VERSION_11, tee::SPDM_GET_MEASUREMENTS, 1, 0xff];
+    report.extend_from_slice(nonce.as_bytes());
+    report.push(0);
+    report.extend_from_slice(&[tee::SPDM_VERSION_11, tee::SPDM_MEASUREMENTS, 0, 0, 1]);
+    report.extend_from_slice(&(record.len() as u32).to_le_bytes()[..3]);
+    report.extend_from_slice(&record);
+    report.extend_from_slice(&[0u8; 32]);
+    report.extend_from_slice(&0u16.to_le_bytes());
+
+    let device = SigningKey::from_slice(&[7u8; 48]).expect("valid scalar");
+    let signature: Signature = device.sign(&report);
+    report.extend_from_slice(&signature.to_bytes());
+    (report, H256(sp_io::hashing::sha2_256(&record)))
+}
+
+fn nvidia_certificate(index: usize) -> TeeCertificate {
+    NVIDIA_CHAIN[index].to_vec().try_into().expect("fixture fits")
+}
+
+// Registers a node for `who`, funding its registration bond.
+fn register_bonded_node<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
+    let balance = T::RegistrationBond::get().saturating_mul(2u32.into()) + T::Currency::minimum_balance();
+    T::Currency::make_free_balance_be(who, balance);
+    Pallet::<T>::register_node(RawOrigin::Signed(who.clone()).into(), b"GPU-1234".to_vec(), 100u32)?;
+    Ok(())
+}
+
+// Submits attestation 0 and makes enough committee members available to judge it.
+fn setup_challengeable_attestation<T: Config>() -> Result<T::AccountId, &'static str> {
+    let attester: T::AccountId = whitelisted_caller();
+    let challenger: T::AccountId = account("challenger", 0, 0);
+
+    register_bonded_node::<T>(&attester)?;
+
+    let amount = T::AttestationDeposit::get().saturating_mul(10u32.into());
+    T::Currency::make_free_balance_be(&attester, amount);
+    T::Currency::make_free_balance_be(&challenger, T::ChallengeBond::get().saturating_mul(10u32.into()));
+
+    Pallet::<T>::submit_attestation(
+        RawOrigin::Signed(attester).into(),
+        1,
+        H256::repeat_byte(1),
+        b"llama-70b".to_vec(),
+        1000,
+        500,
+    )?;
+
+    for i in 0..T::JurySize::get() {
+        let member: T::AccountId = account("juror", i, 0);
+        T::BenchmarkHelper::add_committee_member(&member);
+    }
+    Ok(challenger)
+}
+
 benchmarks! {
     register_node {
         let caller: T::AccountId = whitelisted_caller();
+        let balance = T::RegistrationBond::get().saturating_mul(2u32.into()) + T::Currency::minimum_balance();
+        T::Currency::make_free_balance_be(&caller, balance);
         let gpu_uuid = b"GPU-1234".to_vec();
         let tflops = 100u32;
     }: _(RawOrigin::Signed(caller), gpu_uuid, tflops)
 
     heartbeat {
         let caller: T::AccountId = whitelisted_caller();
-        Pallet::<T>::register_node(
-            RawOrigin::Signed(caller.clone()).into(),
-            b"GPU-1234".to_vec(),
-            100u32,
-        )?;
+        register_bonded_node::<T>(&caller)?;
 
         let now = frame_system::Pallet::<T>::block_number();
         frame_system::Pallet::<T>::set_block_number(now + T::HeartbeatInterval::get());
@@ -28,11 +96,7 @@ benchmarks! {
 
     submit_attestation {
         let caller: T::AccountId = whitelisted_caller();
-        Pallet::<T>::register_node(
-            RawOrigin::Signed(caller.clone()).into(),
-            b"GPU-1234".to_vec(),
-            100u32,
-        )?;
+        register_bonded_node::<T>(&caller)?;
 
         let amount = T::AttestationDeposit::get().saturating_mul(10u32.into());
         T::Currency::make_free_balance_be(&caller, amount);
@@ -45,36 +109,16 @@ benchmarks! {
     }: _(RawOrigin::Signed(caller), task_id, result_hash, model_id, input_tokens, output_tokens)
 
     challenge_attestation {
-        let attester: T::AccountId = whitelisted_caller();
-        let challenger: T::AccountId = account("challenger", 0, 0);
-
-        Pallet::<T>::register_node(
-            RawOrigin::Signed(attester.clone()).into(),
-            b"GPU-1234".to_vec(),
-            100u32,
-        )?;
-
-        let amount = T::AttestationDeposit::get().saturating_mul(10u32.into());
-        T::Currency::make_free_balance_be(&attester, amount);
-
-        Pallet::<T>::submit_attestation(
-            RawOrigin::Signed(attester).into(),
-            1,
-            H256::repeat_byte(1),
-            b"llama-70b".to_vec(),
-            1000,
-            500,
-        )?;
+        let challenger = setup_challengeable_attestation::<T>()?;
     }: _(RawOrigin::Signed(challenger), 0u64)
+    verify {
+        assert!(Disputes::<T>::contains_key(0u64));
+    }
 
     confirm_attestation {
         let attester: T::AccountId = whitelisted_caller();
 
-        Pallet::<T>::register_node(
-            RawOrigin::Signed(attester.clone()).into(),
-            b"GPU-1234".to_vec(),
-            100u32,
-        )?;
+        register_bonded_node::<T>(&attester)?;
 
         let amount = T::AttestationDeposit::get().saturating_mul(10u32.into());
         T::Currency::make_free_balance_be(&attester, amount);
@@ -88,45 +132,125 @@ benchmarks! {
             500,
         )?;
 
+        // Worst case: the attestation is backed by an order and triggers settlement
+        let customer: T::AccountId = account("customer", 0, 0);
+        T::Currency::make_free_balance_be(&customer, amount);
+        T::Currency::reserve(&customer, T::AttestationDeposit::get())?;
+        AttestationOrders::<T>::insert(0u64, AttestationOrder {
+            order_id: 0,
+            customer,
+            agreed_price: T::AttestationDeposit::get(),
+        });
+
+        // A third party confirms it and is paid the tip
+        let caller: T::AccountId = account("caller", 0, 0);
+        T::Currency::make_free_balance_be(&caller, amount);
+
         let now = frame_system::Pallet::<T>::block_number();
         frame_system::Pallet::<T>::set_block_number(now + T::ChallengeWindow::get() + 1u32.into());
-    }: _(RawOrigin::Signed(attester), 0u64)
+    }: _(RawOrigin::Signed(caller), 0u64)
+    verify {
+        let att = Attestations::<T>::get(0u64).ok_or("attestation removed")?;
+        assert!(matches!(att.status, AttestationStatus::Confirmed));
+    }
+
+    // Worst case: every queued attestation is backed by an order and triggers settlement.
+    on_initialize {
+        let n in 0 .. T::MaxFinalizationsPerBlock::get();
 
-    resolve_challenge {
         let attester: T::AccountId = whitelisted_caller();
-        let challenger: T::AccountId = account("challenger", 0, 0);
+        register_bonded_node::<T>(&attester)?;
+        let amount = T::AttestationDeposit::get().saturating_mul((n + 1).into());
+        T::Currency::make_free_balance_be(&attester, amount);
 
-        Pallet::<T>::register_node(
-            RawOrigin::Signed(attester.clone()).into(),
-            b"GPU-1234".to_vec(),
-            100u32,
-        )?;
+        let customer: T::AccountId = account("customer", 0, 0);
+        T::Currency::make_free_balance_be(&customer, amount);
 
-        let amount = T::AttestationDeposit::get().saturating_mul(10u32.into());
-        T::Currency::make_free_balance_be(&attester, amount);
+        for id in 0..n as u64 {
+            Pallet::<T>::submit_attestation(
+                RawOrigin::Signed(attester.clone()).into(),
+                id,
+                H256::repeat_byte(1),
+                b"llama-70b".to_vec(),
+                1000,
+                500,
+            )?;
+            T::Currency::reserve(&customer, T::AttestationDeposit::get())?;
+            AttestationOrders::<T>::insert(id, AttestationOrder {
+                order_id: id,
+                customer: customer.clone(),
+                agreed_price: T::AttestationDeposit::get(),
+            });
+        }
 
-        Pallet::<T>::submit_attestation(
-            RawOrigin::Signed(attester).into(),
-            1,
-            H256::repeat_byte(1),
-            b"llama-70b".to_vec(),
-            1000,
-            500,
-        )?;
+        let challenge_end = frame_system::Pallet::<T>::block_number() + T::ChallengeWindow::get();
+        let now = challenge_end + 1u32.into();
+        frame_system::Pallet::<T>::set_block_number(now);
+    }: {
+        Pallet::<T>::on_initialize(now);
+    }
+    verify {
+        assert!(!FinalizationQueue::<T>::contains_key(challenge_end));
+        for id in 0..n as u64 {
+            let att = Attestations::<T>::get(id).ok_or("attestation removed")?;
+            assert!(matches!(att.status, AttestationStatus::Confirmed));
+        }
+    }
+
+    commit_vote {
+        let challenger = setup_challengeable_attestation::<T>()?;
+        Pallet::<T>::challenge_attestation(RawOrigin::Signed(challenger).into(), 0)?;
+        let juror = Disputes::<T>::get(0u64).ok_or("dispute not opened")?.jury[0].clone();
+        let hash = Pallet::<T>::vote_hash(0, b"salt".to_vec(), true);
+    }: _(RawOrigin::Signed(juror.clone()), 0u64, hash)
+    verify {
+        assert!(JurorVotes::<T>::contains_key(0u64, &juror));
+    }
 
-        Pallet::<T>::challenge_attestation(
-            RawOrigin::Signed(challenger).into(),
+    reveal_vote {
+        let challenger = setup_challengeable_attestation::<T>()?;
+        Pallet::<T>::challenge_attestation(RawOrigin::Signed(challenger).into(), 0)?;
+        let dispute = Disputes::<T>::get(0u64).ok_or("dispute not opened")?;
+        let juror = dispute.jury[0].clone();
+        Pallet::<T>::commit_vote(
+            RawOrigin::Signed(juror.clone()).into(),
             0,
+            Pallet::<T>::vote_hash(0, b"salt".to_vec(), true),
         )?;
-    }: _(RawOrigin::Root, 0u64, true)
+        frame_system::Pallet::<T>::set_block_number(dispute.commit_end + 1u32.into());
+    }: _(RawOrigin::Signed(juror), 0u64, b"salt".to_vec(), true)
+    verify {
+        assert_eq!(Disputes::<T>::get(0u64).ok_or("dispute removed")?.guilty_votes, 1);
+    }
+
+    // Worst case: `j` jurors found the attester guilty, so the deposit is slashed and split.
+    resolve_challenge {
+        let j in 1 .. T::JurySize::get();
+
+        let challenger = setup_challengeable_attestation::<T>()?;
+        Pallet::<T>::challenge_attestation(RawOrigin::Signed(challenger.clone()).into(), 0)?;
+        let dispute = Disputes::<T>::get(0u64).ok_or("dispute not opened")?;
+        for juror in dispute.jury.iter().take(j as usize) {
+            Pallet::<T>::commit_vote(
+                RawOrigin::Signed(juror.clone()).into(),
+                0,
+                Pallet::<T>::vote_hash(0, b"salt".to_vec(), true),
+            )?;
+        }
+        frame_system::Pallet::<T>::set_block_number(dispute.commit_end + 1u32.into());
+        for juror in dispute.jury.iter().take(j as usize) {
+            Pallet::<T>::reveal_vote(RawOrigin::Signed(juror.clone()).into(), 0, b"salt".to_vec(), true)?;
+        }
+        frame_system::Pallet::<T>::set_block_number(dispute.reveal_end + 1u32.into());
+    }: _(RawOrigin::Signed(challenger), 0u64)
+    verify {
+        let att = Attestations::<T>::get(0u64).ok_or("attestation removed")?;
+        assert!(matches!(att.status, AttestationStatus::Slashed));
+    }
 
     update_capability {
         let caller: T::AccountId = whitelisted_caller();
-        Pallet::<T>::register_node(
-            RawOrigin::Signed(caller.clone()).into(),
-            b"GPU-1234".to_vec(),
-            100u32,
-        )?;
+        register_bonded_node::<T>(&caller)?;
 
         let model_ids = vec![b"llama-70b".to_vec(), b"gpt-4".to_vec()];
         let max_concurrent = 4u32;
@@ -135,4 +259,132 @@ benchmarks! {
         let region = b"us-east".to_vec();
     }: _(RawOrigin::Signed(caller), model_ids, max_concurrent, price_per_token, region)
 
+    // Worst case: a P-384 chain and quote of the largest sizes are verified for real, and a new
+    // node reserves its bond
+    register_attested_node {
+        let caller: T::AccountId = whitelisted_caller();
+        let balance = T::RegistrationBond::get().saturating_mul(2u32.into()) + T::Currency::minimum_balance();
+        T::Currency::make_free_balance_be(&caller, balance);
+
+        let kind = TeeKind::NvidiaCc;
+        let gpu_uuid = b"GPU-1234".to_vec();
+        let (quote, measurement) = nvidia_report(Pallet::<T>::tee_report_data(&caller, &gpu_uuid, 100));
+        let root = tee::root_key(NVIDIA_CHAIN[MAX_TEE_CHAIN_LEN as usize - 1]).map_err(|_| "invalid root")?;
+        TeeRoots::<T>::insert(kind, Pallet::<T>::tee_root_id(&root), true);
+        TeeMeasurements::<T>::insert(kind, measurement, true);
+
+        let report = TeeReport {
+            kind,
+            quote: quote.try_into().map_err(|_| "quote too long")?,
+            certificates: (0..NVIDIA_CHAIN.len())
+                .map(nvidia_certificate)
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| "chain too long")?,
+        };
+    }: _(RawOrigin::Signed(caller.clone()), gpu_uuid, 100u32, report)
+    verify {
+        assert!(Nodes::<T>::get(&caller).ok_or("node removed")?.attested);
+    }
+
+    // Worst case: every checked node missed its heartbeats and has its bond slashed
+    sweep_liveness {
+        let n in 0 .. T::MaxLivenessChecksPerBlock::get();
+
+        for i in 0..n {
+            let owner: T::AccountId = account("node", i, 0);
+            register_bonded_node::<T>(&owner)?;
+        }
+        let deadline = frame_system::Pallet::<T>::block_number()
+            + T::HeartbeatInterval::get() * (T::MaxMissedHeartbeats::get() + 1).into();
+        frame_system::Pallet::<T>::set_block_number(deadline);
+    }: {
+        Pallet::<T>::sweep_liveness(deadline);
+    }
+    verify {
+        assert_eq!(Nodes::<T>::iter_values().filter(|node| !node.is_active).count(), n as usize);
+    }
+
+    // Worst case: the node serves as many models as it can
+    deregister_node {
+        let caller: T::AccountId = whitelisted_caller();
+        register_bonded_node::<T>(&caller)?;
+        Pallet::<T>::update_capability(
+            RawOrigin::Signed(caller.clone()).into(),
+            (0..T::MaxModelsPerAgent::get()).map(|i| vec![b'm', i as u8]).collect(),
+            10,
+            T::AttestationDeposit::get(),
+            b"us-east".to_vec(),
+        )?;
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert!(Unbonding::<T>::contains_key(&caller));
+        assert!(AgentCapabilities::<T>::get(&caller).is_none());
+    }
+
+    withdraw_node_bond {
+        let caller: T::AccountId = whitelisted_caller();
+        register_bonded_node::<T>(&caller)?;
+        Pallet::<T>::deregister_node(RawOrigin::Signed(caller.clone()).into())?;
+        let now = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(now + T::UnbondingPeriod::get());
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert!(Nodes::<T>::get(&caller).is_none());
+    }
+
+    retry_settlement {
+        let attester: T::AccountId = whitelisted_caller();
+        register_bonded_node::<T>(&attester)?;
+        let amount = T::AttestationDeposit::get().saturating_mul(10u32.into());
+        T::Currency::make_free_balance_be(&attester, amount);
+        Pallet::<T>::submit_attestation(
+            RawOrigin::Signed(attester.clone()).into(),
+            1,
+            H256::repeat_byte(1),
+            b"llama-70b".to_vec(),
+            1000,
+            500,
+        )?;
+
+        // An upheld attestation whose order settlement failed and is now payable
+        let customer: T::AccountId = account("customer", 0, 0);
+        T::Currency::make_free_balance_be(&customer, amount);
+        T::Currency::reserve(&customer, T::AttestationDeposit::get())?;
+        AttestationOrders::<T>::insert(0u64, AttestationOrder {
+            order_id: 0,
+            customer,
+            agreed_price: T::AttestationDeposit::get(),
+        });
+        Attestations::<T>::mutate(0u64, |maybe_att| {
+            if let Some(att) = maybe_att.as_mut() {
+                att.status = AttestationStatus::Confirmed;
+            }
+        });
+        UnsettledAttestations::<T>::insert(0u64, ());
+
+        let caller: T::AccountId = account("caller", 0, 0);
+    }: _(RawOrigin::Signed(caller), 0u64)
+    verify {
+        assert!(!UnsettledAttestations::<T>::contains_key(0u64));
+    }
+
+    // The self-signature of a P-384 root is checked
+    set_tee_root {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let root = nvidia_certificate(MAX_TEE_CHAIN_LEN as usize - 1);
+        let root_id = Pallet::<T>::tee_root_id(&tee::root_key(&root).map_err(|_| "invalid root")?);
+    }: _<T::RuntimeOrigin>(origin, TeeKind::NvidiaCc, root, true)
+    verify {
+        assert!(TeeRoots::<T>::get(TeeKind::NvidiaCc, root_id));
+    }
+
+    set_tee_measurement {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+    }: _<T::RuntimeOrigin>(origin, TeeKind::IntelTdx, H256::repeat_byte(7), true)
+    verify {
+        assert!(TeeMeasurements::<T>::get(TeeKind::IntelTdx, H256::repeat_byte(7)));
+    }
 }
@@ -1,9 +1,34 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub mod migrations;
+pub mod tee;
 pub mod weights;
 
-use sp_runtime::traits::Saturating;
+use sp_runtime::{traits::Saturating, Perbill};
+
+pub(crate) const LOG_TARGET: &str = "runtime::agent-attestation";
+
+/// Blocks after `challenge_end` an attestation can be carried over to when the finalization
+/// queues are full
+pub const MAX_FINALIZATION_CARRY_OVER: u32 = 8;
+
+/// Most providers returned by one page of `discover_providers`
+pub const MAX_PROVIDER_PAGE: u32 = 100;
+
+/// Share of a provider's discovery score given by its price
+pub const PRICE_SCORE_WEIGHT: Perbill = Perbill::from_parts(500_000_000);
+/// Share of a provider's discovery score given by its attested task history
+pub const HISTORY_SCORE_WEIGHT: Perbill = Perbill::from_parts(300_000_000);
+/// Share of a provider's discovery score given by the time left until its liveness deadline
+pub const LIVENESS_SCORE_WEIGHT: Perbill = Perbill::from_parts(200_000_000);
+
+/// Largest TEE quote a node can submit
+pub const MAX_TEE_QUOTE_LEN: u32 = 8 * 1024;
+/// Largest DER certificate of a TEE certificate chain
+pub const MAX_TEE_CERTIFICATE_LEN: u32 = 2 * 1024;
+/// Most certificates in a TEE certificate chain, root included
+pub const MAX_TEE_CHAIN_LEN: u32 = 4;
 
 #[cfg(test)]
 mod mock;
@@ -16,21 +41,37 @@ mod benchmarking;
 pub mod pallet {
     use frame_support::traits::StorageVersion;
     use frame_support::{
-        traits::EnsureOrigin,
         dispatch::DispatchResult,
         pallet_prelude::*,
-        traits::{Currency, ReservableCurrency},
+        traits::{
+            BalanceStatus, Currency, Imbalance, OnUnbalanced, Randomness, ReservableCurrency,
+            UnixTime,
+        },
         BoundedVec,
     };
     use frame_system::pallet_prelude::*;
-    use sp_std::vec::Vec;
+    use sp_std::{vec, vec::Vec};
     use sp_core::H256;
-    use sp_runtime::traits::Saturating;
-    use crate::weights::WeightInfo;
-    use dbc_support::traits::AttestationSettler;
+    use sp_io::hashing::blake2_256;
+    use sp_runtime::{
+        traits::{One, Saturating, Zero},
+        Perbill,
+    };
+    use crate::{
+        tee::{self, QuoteClaims, TeeError},
+        weights::WeightInfo,
+        MAX_TEE_CERTIFICATE_LEN, MAX_TEE_CHAIN_LEN, MAX_TEE_QUOTE_LEN,
+    };
+    use dbc_support::{
+        traits::{AttestationSettler, ManageCommittee, TaskOrderContext},
+        utils::get_hash,
+    };
 
     type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
 
     /// Status of an attestation
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -63,6 +104,32 @@ pub mod pallet {
         pub challenger: Option<T::AccountId>,
     }
 
+    /// Jury dispute opened by a challenge
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Dispute<T: Config> {
+        pub challenger: T::AccountId,
+        /// Bond reserved from the challenger, forfeited if the attester is not found guilty
+        pub bond: BalanceOf<T>,
+        /// Committee members drawn to judge the attestation
+        pub jury: BoundedVec<T::AccountId, T::JurySize>,
+        /// Last block jurors may commit a vote hash
+        pub commit_end: BlockNumberFor<T>,
+        /// Last block jurors may reveal their vote
+        pub reveal_end: BlockNumberFor<T>,
+        pub committed: u32,
+        pub guilty_votes: u32,
+        pub innocent_votes: u32,
+    }
+
+    /// A juror's sealed vote, revealed after the commit phase
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct JurorVote {
+        /// `get_hash([attestation_id.encode(), salt, "1" if guilty else "0"])`
+        pub hash: [u8; 16],
+        pub revealed: Option<bool>,
+    }
+
     /// Hardware info registered by a node
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
@@ -73,6 +140,42 @@ pub mod pallet {
         pub registered_at: BlockNumberFor<T>,
         pub last_heartbeat: BlockNumberFor<T>,
         pub is_active: bool,
+        /// Hardware proven by a TEE attestation report rather than self-reported
+        pub attested: bool,
+        /// Registration bond reserved from the owner
+        pub bond: BalanceOf<T>,
+    }
+
+    /// Trusted execution environment an attestation report comes from
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum TeeKind {
+        IntelSgx,
+        IntelTdx,
+        NvidiaCc,
+    }
+
+    /// DER encoded X.509 certificate
+    pub type TeeCertificate = BoundedVec<u8, ConstU32<MAX_TEE_CERTIFICATE_LEN>>;
+
+    /// Attestation report of a node's enclave or confidential-computing GPU, verified on-chain
+    /// as described in [`tee`](crate::tee)
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct TeeReport {
+        pub kind: TeeKind,
+        /// DCAP quote for SGX and TDX, SPDM measurement request and response for NVIDIA CC
+        pub quote: BoundedVec<u8, ConstU32<MAX_TEE_QUOTE_LEN>>,
+        /// Certificate chain from the key signing the quote up to its root, which comes last:
+        /// the PCK chain for SGX and TDX, the device chain for NVIDIA CC
+        pub certificates: BoundedVec<TeeCertificate, ConstU32<MAX_TEE_CHAIN_LEN>>,
+    }
+
+    /// Task order an attestation was produced for
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct AttestationOrder<T: Config> {
+        pub order_id: u64,
+        pub customer: T::AccountId,
+        pub agreed_price: BalanceOf<T>,
     }
 
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -86,6 +189,43 @@ pub mod pallet {
         pub updated_at: BlockNumberFor<T>,
     }
 
+    /// Conditions a provider has to meet to be returned by `discover_providers`
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
+    pub struct ProviderFilter<Balance> {
+        pub region: Option<Vec<u8>>,
+        /// Highest acceptable price per token
+        pub max_price: Option<Balance>,
+        pub min_free_concurrency: u32,
+    }
+
+    /// Provider of a model as ranked by `discover_providers`
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ProviderInfo<T: Config> {
+        pub account: T::AccountId,
+        pub price_per_token: BalanceOf<T>,
+        pub region: BoundedVec<u8, ConstU32<16>>,
+        pub max_concurrent: u32,
+        /// `max_concurrent` less the provider's pending attestations
+        pub free_concurrency: u32,
+        /// Attestations of the provider that were confirmed or defended
+        pub completed_tasks: u32,
+        pub last_heartbeat: BlockNumberFor<T>,
+        pub attested: bool,
+        /// Blend of price, history and liveness scores, higher ranks first
+        pub score: Perbill,
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    pub trait BenchmarkHelper<AccountId> {
+        /// Make `who` an available committee member that juries can be drawn from
+        fn add_committee_member(who: &AccountId);
+    }
+    #[cfg(feature = "runtime-benchmarks")]
+    impl<AccountId> BenchmarkHelper<AccountId> for () {
+        fn add_committee_member(_who: &AccountId) {}
+    }
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
@@ -108,6 +248,27 @@ pub mod pallet {
         #[pallet::constant]
         type HeartbeatInterval: Get<BlockNumberFor<Self>>;
 
+        /// Heartbeats a node may miss in a row before it is deactivated
+        #[pallet::constant]
+        type MaxMissedHeartbeats: Get<u32>;
+
+        /// Max nodes checked for missed heartbeats in a block. The sweep resumes where it
+        /// stopped in the next block.
+        #[pallet::constant]
+        type MaxLivenessChecksPerBlock: Get<u32>;
+
+        /// Bond reserved from the owner of a newly registered node
+        #[pallet::constant]
+        type RegistrationBond: Get<BalanceOf<Self>>;
+
+        /// Share of the registration bond slashed to `Slash` when a node is deactivated
+        #[pallet::constant]
+        type InactivitySlash: Get<Perbill>;
+
+        /// Blocks between deregistering a node and withdrawing its registration bond
+        #[pallet::constant]
+        type UnbondingPeriod: Get<BlockNumberFor<Self>>;
+
         /// Max length of model ID
         #[pallet::constant]
         type MaxModelIdLen: Get<u32>;
@@ -127,11 +288,55 @@ pub mod pallet {
             Balance = BalanceOf<Self>
         >;
 
-        /// Origin that can confirm attestations
+        /// Tip paid out of the attester's deposit to whoever confirms an attestation by signed call
+        #[pallet::constant]
+        type FinalizationTip: Get<BalanceOf<Self>>;
+
+        /// Max attestations finalized automatically when their challenge window ends in the same block
+        #[pallet::constant]
+        type MaxFinalizationsPerBlock: Get<u32>;
+
+        /// Committee that juries are drawn from
+        type ManageCommittee: ManageCommittee<AccountId = Self::AccountId, Balance = BalanceOf<Self>>;
+
+        /// Randomness used to draw juries
+        type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+
+        /// Receives the treasury share of slashed deposits and forfeited challenge bonds
+        type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// Bond a challenger reserves when opening a dispute
+        #[pallet::constant]
+        type ChallengeBond: Get<BalanceOf<Self>>;
+
+        /// Share of a slashed deposit paid to a successful challenger; the rest goes to `Slash`
+        #[pallet::constant]
+        type ChallengerSlashShare: Get<Perbill>;
+
+        /// Number of committee members drawn for each dispute
+        #[pallet::constant]
+        type JurySize: Get<u32>;
+
+        /// Blocks jurors have to commit their vote hash
+        #[pallet::constant]
+        type CommitDuration: Get<BlockNumberFor<Self>>;
+
+        /// Blocks jurors have to reveal their vote after the commit phase
+        #[pallet::constant]
+        type RevealDuration: Get<BlockNumberFor<Self>>;
+
+        /// Origin that manages the trusted TEE roots and measurements
         type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Time TEE certificates must be valid at
+        type UnixTime: UnixTime;
+
+        /// Helper trait for benchmarks.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: BenchmarkHelper<Self::AccountId>;
     }
 
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(7);
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
@@ -148,6 +353,17 @@ pub mod pallet {
     pub type Attestations<T: Config> =
         StorageMap<_, Blake2_128Concat, u64, Attestation<T>>;
 
+    /// Order provenance for attestations created from task-mode orders
+    #[pallet::storage]
+    #[pallet::getter(fn attestation_order)]
+    pub type AttestationOrders<T: Config> =
+        StorageMap<_, Blake2_128Concat, u64, AttestationOrder<T>>;
+
+    /// Upheld attestations whose order could not be settled, until `retry_settlement` succeeds
+    #[pallet::storage]
+    #[pallet::getter(fn unsettled_attestation)]
+    pub type UnsettledAttestations<T: Config> = StorageMap<_, Blake2_128Concat, u64, ()>;
+
     #[pallet::storage]
     #[pallet::getter(fn node_of)]
     pub type Nodes<T: Config> =
@@ -158,6 +374,23 @@ pub mod pallet {
     pub type AttesterTaskCount<T: Config> =
         StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u64, u32, ValueQuery>;
 
+    /// Attestations of an attester that are still pending, challenged or not
+    #[pallet::storage]
+    #[pallet::getter(fn pending_attestations)]
+    pub type PendingAttestations<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Attestations of an attester that were confirmed or defended against a challenge
+    #[pallet::storage]
+    #[pallet::getter(fn completed_attestations)]
+    pub type CompletedAttestations<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Block from which deregistered nodes can withdraw their registration bond
+    #[pallet::storage]
+    #[pallet::getter(fn unbonding_of)]
+    pub type Unbonding<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>>;
+
     #[pallet::storage]
     #[pallet::getter(fn agent_capability)]
     pub type AgentCapabilities<T: Config> =
@@ -168,6 +401,46 @@ pub mod pallet {
     pub type ModelProviders<T: Config> =
         StorageDoubleMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxModelIdLen>, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
 
+    /// Jury disputes of challenged attestations, by attestation id
+    #[pallet::storage]
+    #[pallet::getter(fn dispute_of)]
+    pub type Disputes<T: Config> = StorageMap<_, Blake2_128Concat, u64, Dispute<T>>;
+
+    /// Unchallenged attestations finalized in `on_initialize` once their challenge window is over,
+    /// keyed by `challenge_end`
+    #[pallet::storage]
+    #[pallet::getter(fn finalization_queue)]
+    pub type FinalizationQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<u64, T::MaxFinalizationsPerBlock>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn juror_vote)]
+    pub type JurorVotes<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, T::AccountId, JurorVote>;
+
+    /// Raw `Nodes` key of the last node checked by the liveness sweep, unless it went through
+    /// every node
+    #[pallet::storage]
+    pub type LivenessCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<256>>>;
+
+    /// Roots allowed to anchor the certificate chains of TEE reports, by `tee_root_id` of
+    /// their key
+    #[pallet::storage]
+    #[pallet::getter(fn tee_root_trusted)]
+    pub type TeeRoots<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, TeeKind, Blake2_128Concat, H256, bool, ValueQuery>;
+
+    /// Measurements of the code attested nodes may run
+    #[pallet::storage]
+    #[pallet::getter(fn tee_measurement_trusted)]
+    pub type TeeMeasurements<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, TeeKind, Blake2_128Concat, H256, bool, ValueQuery>;
+
     // ---- Events ----
 
     #[pallet::event]
@@ -181,6 +454,22 @@ pub mod pallet {
             who: T::AccountId,
             block: BlockNumberFor<T>,
         },
+        NodeDeactivated {
+            who: T::AccountId,
+            last_heartbeat: BlockNumberFor<T>,
+            slashed: BalanceOf<T>,
+        },
+        NodeReactivated {
+            who: T::AccountId,
+        },
+        NodeDeregistered {
+            who: T::AccountId,
+            unbonds_at: BlockNumberFor<T>,
+        },
+        NodeBondWithdrawn {
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
         AttestationSubmitted {
             id: u64,
             attester: T::AccountId,
@@ -191,9 +480,61 @@ pub mod pallet {
             id: u64,
             challenger: T::AccountId,
         },
+        JuryAssigned {
+            id: u64,
+            jury: Vec<T::AccountId>,
+            commit_end: BlockNumberFor<T>,
+            reveal_end: BlockNumberFor<T>,
+        },
+        VoteCommitted {
+            id: u64,
+            juror: T::AccountId,
+        },
+        VoteRevealed {
+            id: u64,
+            juror: T::AccountId,
+            attester_is_guilty: bool,
+        },
+        ChallengeResolved {
+            id: u64,
+            guilty_votes: u32,
+            innocent_votes: u32,
+        },
+        ChallengerRewarded {
+            id: u64,
+            challenger: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        ChallengeBondForfeited {
+            id: u64,
+            challenger: T::AccountId,
+            amount: BalanceOf<T>,
+        },
         AttestationConfirmed {
             id: u64,
         },
+        FinalizationTipPaid {
+            id: u64,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        AttestationSettled {
+            id: u64,
+            order_id: u64,
+            receipt_id: u64,
+        },
+        /// Paying the miner of an upheld attestation from its order failed; it can be retried
+        /// with `retry_settlement`
+        SettlementFailed {
+            id: u64,
+            order_id: u64,
+        },
+        /// The customer of a slashed attestation's order got its funds back
+        AttestationRefunded {
+            id: u64,
+            order_id: u64,
+            amount: BalanceOf<T>,
+        },
         AgentCapabilityUpdated {
             who: T::AccountId,
             model_count: u32,
@@ -206,6 +547,21 @@ pub mod pallet {
         AttestationDefended {
             id: u64,
         },
+        NodeAttested {
+            who: T::AccountId,
+            kind: TeeKind,
+            measurement: H256,
+        },
+        TeeRootSet {
+            kind: TeeKind,
+            root: H256,
+            trusted: bool,
+        },
+        TeeMeasurementSet {
+            kind: TeeKind,
+            measurement: H256,
+            trusted: bool,
+        },
     }
 
     // ---- Errors ----
@@ -227,6 +583,30 @@ pub mod pallet {
         TooManyModels,
         InvalidRegion,
         DuplicateAttestation,
+        InsufficientBond,
+        NotEnoughJurors,
+        DisputeNotFound,
+        NotJuror,
+        AlreadyCommitted,
+        NotCommitted,
+        AlreadyRevealed,
+        CommitPhaseOver,
+        RevealPhaseNotStarted,
+        RevealPhaseOver,
+        VoteNotMatchHash,
+        DisputeNotFinished,
+        UntrustedTeeRoot,
+        InvalidTeeCertificate,
+        TeeCertificateExpired,
+        InvalidTeeReport,
+        UntrustedMeasurement,
+        ReportDataMismatch,
+        AttestationsPending,
+        NodeUnbonding,
+        NotUnbonding,
+        UnbondingNotFinished,
+        SettlementNotPending,
+        SettlementRejected,
     }
 
     // ---- Hooks ----
@@ -249,7 +629,19 @@ pub mod pallet {
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            // Windows that ended in the previous block can no longer be challenged
+            let due = FinalizationQueue::<T>::take(now.saturating_sub(One::one()));
+            for attestation_id in due.iter() {
+                // Challenged or already confirmed attestations are skipped
+                let _ = Self::do_confirm(*attestation_id, None);
+            }
+            let checked = Self::sweep_liveness(now);
+            T::WeightInfo::on_initialize(due.len() as u32)
+                .saturating_add(T::WeightInfo::sweep_liveness(checked))
+        }
+    }
 
     // ---- Extrinsics ----
 
@@ -274,6 +666,8 @@ pub mod pallet {
                 .try_into()
                 .map_err(|_| Error::<T>::ArithmeticOverflow)?;
 
+            let bond = T::RegistrationBond::get();
+            T::Currency::reserve(&who, bond).map_err(|_| Error::<T>::InsufficientBond)?;
             let now = <frame_system::Pallet<T>>::block_number();
 
             Nodes::<T>::insert(
@@ -285,6 +679,8 @@ pub mod pallet {
                     registered_at: now,
                     last_heartbeat: now,
                     is_active: true,
+                    attested: false,
+                    bond,
                 },
             );
 
@@ -292,11 +688,13 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Send heartbeat to prove liveness (every HeartbeatInterval blocks)
+        /// Send heartbeat to prove liveness (every HeartbeatInterval blocks). Reactivates a node
+        /// deactivated for missing heartbeats.
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::heartbeat())]
         pub fn heartbeat(origin: OriginFor<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            ensure!(!Unbonding::<T>::contains_key(&who), Error::<T>::NodeUnbonding);
 
             Nodes::<T>::try_mutate(&who, |maybe_node| -> DispatchResult {
                 let node = maybe_node.as_mut().ok_or(Error::<T>::NodeNotRegistered)?;
@@ -308,7 +706,10 @@ pub mod pallet {
                 );
 
                 node.last_heartbeat = now;
-                node.is_active = true;
+                if !node.is_active {
+                    node.is_active = true;
+                    Self::deposit_event(Event::NodeReactivated { who: who.clone() });
+                }
 
                 Self::deposit_event(Event::HeartbeatReceived { who: who.clone(), block: now });
                 Ok(())
@@ -333,6 +734,7 @@ pub mod pallet {
                 Nodes::<T>::contains_key(&attester),
                 Error::<T>::NodeNotRegistered
             );
+            ensure!(!Unbonding::<T>::contains_key(&attester), Error::<T>::NodeUnbonding);
 
             // Prevent duplicate attestations for the same (attester, task_id)
             ensure!(
@@ -377,6 +779,8 @@ pub mod pallet {
             AttesterTaskCount::<T>::mutate(&attester, task_id, |count| {
                 *count = count.saturating_add(1);
             });
+            PendingAttestations::<T>::mutate(&attester, |count| *count = count.saturating_add(1));
+            Self::schedule_finalization(id, challenge_end);
 
             Self::deposit_event(Event::AttestationSubmitted {
                 id,
@@ -387,7 +791,8 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Challenge an attestation within the challenge window
+        /// Challenge an attestation within the challenge window. The challenger reserves
+        /// `ChallengeBond` and a jury is drawn from the committee to judge the attestation.
         #[pallet::call_index(3)]
         #[pallet::weight(T::WeightInfo::challenge_attestation())]
         pub fn challenge_attestation(
@@ -396,133 +801,161 @@ pub mod pallet {
         ) -> DispatchResult {
             let challenger = ensure_signed(origin)?;
 
-            Attestations::<T>::try_mutate(attestation_id, |maybe_att| -> DispatchResult {
-                let att = maybe_att.as_mut().ok_or(Error::<T>::AttestationNotFound)?;
-
-                ensure!(
-                    matches!(att.status, AttestationStatus::Pending),
-                    Error::<T>::InvalidStatus
-                );
-                ensure!(
-                    att.challenger.is_none(),
-                    Error::<T>::AlreadyChallenged
-                );
+            let mut att = Attestations::<T>::get(attestation_id).ok_or(Error::<T>::AttestationNotFound)?;
+            ensure!(
+                matches!(att.status, AttestationStatus::Pending),
+                Error::<T>::InvalidStatus
+            );
+            ensure!(
+                att.challenger.is_none(),
+                Error::<T>::AlreadyChallenged
+            );
 
-                let now = <frame_system::Pallet<T>>::block_number();
-                ensure!(
-                    now <= att.challenge_end,
-                    Error::<T>::ChallengeWindowExpired
-                );
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now <= att.challenge_end,
+                Error::<T>::ChallengeWindowExpired
+            );
 
-                att.challenger = Some(challenger.clone());
+            let jury = Self::draw_jury(attestation_id, &att.attester, &challenger)?;
+            let bond = T::ChallengeBond::get();
+            T::Currency::reserve(&challenger, bond)
+                .map_err(|_| Error::<T>::InsufficientBond)?;
+
+            let commit_end = now.saturating_add(T::CommitDuration::get());
+            let reveal_end = commit_end.saturating_add(T::RevealDuration::get());
+
+            att.challenger = Some(challenger.clone());
+            Attestations::<T>::insert(attestation_id, att);
+            Disputes::<T>::insert(
+                attestation_id,
+                Dispute {
+                    challenger: challenger.clone(),
+                    bond,
+                    jury: jury.clone(),
+                    commit_end,
+                    reveal_end,
+                    committed: 0,
+                    guilty_votes: 0,
+                    innocent_votes: 0,
+                },
+            );
 
-                Self::deposit_event(Event::AttestationChallenged {
-                    id: attestation_id,
-                    challenger,
-                });
-                Ok(())
-            })
+            Self::deposit_event(Event::AttestationChallenged {
+                id: attestation_id,
+                challenger,
+            });
+            Self::deposit_event(Event::JuryAssigned {
+                id: attestation_id,
+                jury: jury.into_inner(),
+                commit_end,
+                reveal_end,
+            });
+            Ok(())
         }
 
-        /// Confirm an attestation after challenge window expires with no challenge
+        /// Confirm an attestation after challenge window expires with no challenge.
+        /// Anyone may call it and receives `FinalizationTip` out of the attester's deposit.
         #[pallet::call_index(4)]
         #[pallet::weight(T::WeightInfo::confirm_attestation())]
         pub fn confirm_attestation(
             origin: OriginFor<T>,
             attestation_id: u64,
         ) -> DispatchResult {
-            T::AdminOrigin::ensure_origin(origin)?;
-
-            Attestations::<T>::try_mutate(attestation_id, |maybe_att| -> DispatchResult {
-                let att = maybe_att.as_mut().ok_or(Error::<T>::AttestationNotFound)?;
-
-                ensure!(
-                    matches!(att.status, AttestationStatus::Pending),
-                    Error::<T>::InvalidStatus
-                );
-                ensure!(
-                    att.challenger.is_none(),
-                    Error::<T>::AlreadyChallenged
-                );
-
-                let now = <frame_system::Pallet<T>>::block_number();
-                ensure!(
-                    now > att.challenge_end,
-                    Error::<T>::ChallengeWindowNotExpired
-                );
-
-                att.status = AttestationStatus::Confirmed;
-
-                // Unreserve deposit
-                T::Currency::unreserve(&att.attester, att.deposit);
-
-                Self::deposit_event(Event::AttestationConfirmed { id: attestation_id });
-
-                // Trigger settlement (ignore errors to not block confirmation)
-                // In a real implementation, merchant and amount would be tracked in the attestation
-                // For now, we use placeholder values
-                let _ = T::OnAttestationConfirmed::settle_for_attestation(
-                    &att.attester,  // Using attester as merchant placeholder
-                    &att.attester,  // miner
-                    att.deposit,    // amount placeholder
-                    attestation_id,
-                );
-
-                Ok(())
-            })
+            let who = ensure_signed(origin)?;
+            Self::do_confirm(attestation_id, Some(who))
         }
 
-        /// Resolve a challenged attestation (simplified: root/committee decides)
+        /// Settle a dispute by the jury's revealed votes, once every juror has revealed or
+        /// the reveal phase is over. Anyone may call it.
+        ///
+        /// A guilty verdict slashes `SlashPercent` of the attester's deposit, split between the
+        /// challenger and `Slash` by `ChallengerSlashShare`. Otherwise the challenger forfeits the
+        /// bond to `Slash`, unless no juror revealed, in which case the bond is returned.
         #[pallet::call_index(5)]
-        #[pallet::weight(T::WeightInfo::resolve_challenge())]
+        // An upheld attestation settles its order like `confirm_attestation` does
+        #[pallet::weight(
+            T::WeightInfo::resolve_challenge(T::JurySize::get())
+                .saturating_add(T::WeightInfo::confirm_attestation())
+        )]
         pub fn resolve_challenge(
             origin: OriginFor<T>,
             attestation_id: u64,
-            attester_is_guilty: bool,
         ) -> DispatchResult {
-            ensure_root(origin)?;
+            ensure_signed(origin)?;
 
-            Attestations::<T>::try_mutate(attestation_id, |maybe_att| -> DispatchResult {
-                let att = maybe_att.as_mut().ok_or(Error::<T>::AttestationNotFound)?;
-
-                ensure!(
-                    matches!(att.status, AttestationStatus::Pending),
-                    Error::<T>::InvalidStatus
-                );
-                ensure!(
-                    att.challenger.is_some(),
-                    Error::<T>::InvalidStatus
-                );
+            let mut att = Attestations::<T>::get(attestation_id).ok_or(Error::<T>::AttestationNotFound)?;
+            ensure!(
+                matches!(att.status, AttestationStatus::Pending),
+                Error::<T>::InvalidStatus
+            );
+            let dispute = Disputes::<T>::get(attestation_id).ok_or(Error::<T>::DisputeNotFound)?;
 
-                if attester_is_guilty {
-                    // Slash deposit
-                    let slash_percent = T::SlashPercent::get();
-                    let slash_amount = sp_runtime::Perbill::from_percent(slash_percent) * att.deposit;
+            let now = <frame_system::Pallet<T>>::block_number();
+            let revealed = dispute.guilty_votes.saturating_add(dispute.innocent_votes);
+            ensure!(
+                now > dispute.reveal_end || revealed as usize == dispute.jury.len(),
+                Error::<T>::DisputeNotFinished
+            );
 
-                    // Slash from reserved
-                    let _imbalance = T::Currency::slash_reserved(&att.attester, slash_amount);
+            if dispute.guilty_votes > dispute.innocent_votes {
+                let slash_amount = Perbill::from_percent(T::SlashPercent::get()) * att.deposit;
+                let (imbalance, _) = T::Currency::slash_reserved(&att.attester, slash_amount);
+                T::Currency::unreserve(&att.attester, att.deposit.saturating_sub(slash_amount));
 
-                    // Unreserve remainder
-                    let remainder = att.deposit.saturating_sub(slash_amount);
-                    T::Currency::unreserve(&att.attester, remainder);
+                let challenger_share = T::ChallengerSlashShare::get() * imbalance.peek();
+                let (to_challenger, to_treasury) = imbalance.split(challenger_share);
+                let reward = to_challenger.peek();
+                T::Currency::resolve_creating(&dispute.challenger, to_challenger);
+                T::Slash::on_unbalanced(to_treasury);
+                T::Currency::unreserve(&dispute.challenger, dispute.bond);
 
-                    att.status = AttestationStatus::Slashed;
+                att.status = AttestationStatus::Slashed;
+                Self::refund_attestation_order(attestation_id, &att);
 
-                    Self::deposit_event(Event::AttestationSlashed {
+                Self::deposit_event(Event::AttestationSlashed {
+                    id: attestation_id,
+                    attester: att.attester.clone(),
+                    slash_amount,
+                });
+                Self::deposit_event(Event::ChallengerRewarded {
+                    id: attestation_id,
+                    challenger: dispute.challenger.clone(),
+                    amount: reward,
+                });
+            } else {
+                T::Currency::unreserve(&att.attester, att.deposit);
+                if revealed == 0 {
+                    // No juror judged the case; the challenger is not to blame.
+                    T::Currency::unreserve(&dispute.challenger, dispute.bond);
+                } else {
+                    let (imbalance, _) = T::Currency::slash_reserved(&dispute.challenger, dispute.bond);
+                    Self::deposit_event(Event::ChallengeBondForfeited {
                         id: attestation_id,
-                        attester: att.attester.clone(),
-                        slash_amount,
+                        challenger: dispute.challenger.clone(),
+                        amount: imbalance.peek(),
                     });
-                } else {
-                    // Attester wins, unreserve deposit
-                    T::Currency::unreserve(&att.attester, att.deposit);
-                    att.status = AttestationStatus::Defended;
-
-                    Self::deposit_event(Event::AttestationDefended { id: attestation_id });
+                    T::Slash::on_unbalanced(imbalance);
                 }
 
-                Ok(())
-            })
+                att.status = AttestationStatus::Defended;
+                Self::record_completed_attestation(&att.attester);
+                Self::settle_attestation_order(attestation_id, &att);
+
+                Self::deposit_event(Event::AttestationDefended { id: attestation_id });
+            }
+
+            Self::release_pending_attestation(&att.attester);
+            Attestations::<T>::insert(attestation_id, att);
+            Disputes::<T>::remove(attestation_id);
+            let _ = JurorVotes::<T>::clear_prefix(attestation_id, T::JurySize::get(), None);
+
+            Self::deposit_event(Event::ChallengeResolved {
+                id: attestation_id,
+                guilty_votes: dispute.guilty_votes,
+                innocent_votes: dispute.innocent_votes,
+            });
+            Ok(())
         }
 
         #[pallet::call_index(6)]
@@ -536,6 +969,7 @@ pub mod pallet {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
             ensure!(Nodes::<T>::contains_key(&who), Error::<T>::NodeNotRegistered);
+            ensure!(!Unbonding::<T>::contains_key(&who), Error::<T>::NodeUnbonding);
 
             let bounded_models: BoundedVec<BoundedVec<u8, T::MaxModelIdLen>, T::MaxModelsPerAgent> =
                 model_ids.into_iter()
@@ -570,15 +1004,614 @@ pub mod pallet {
             Self::deposit_event(Event::AgentCapabilityUpdated { who, model_count });
             Ok(())
         }
+
+        /// Commit a juror's sealed vote on a dispute during its commit phase
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::commit_vote())]
+        pub fn commit_vote(
+            origin: OriginFor<T>,
+            attestation_id: u64,
+            hash: [u8; 16],
+        ) -> DispatchResult {
+            let juror = ensure_signed(origin)?;
+
+            let mut dispute = Disputes::<T>::get(attestation_id).ok_or(Error::<T>::DisputeNotFound)?;
+            ensure!(dispute.jury.contains(&juror), Error::<T>::NotJuror);
+            ensure!(
+                !JurorVotes::<T>::contains_key(attestation_id, &juror),
+                Error::<T>::AlreadyCommitted
+            );
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(now <= dispute.commit_end, Error::<T>::CommitPhaseOver);
+
+            JurorVotes::<T>::insert(attestation_id, &juror, JurorVote { hash, revealed: None });
+            dispute.committed = dispute.committed.saturating_add(1);
+            Disputes::<T>::insert(attestation_id, dispute);
+
+            Self::deposit_event(Event::VoteCommitted { id: attestation_id, juror });
+            Ok(())
+        }
+
+        /// Reveal a committed vote, once every juror has committed or the commit phase is over
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::reveal_vote())]
+        pub fn reveal_vote(
+            origin: OriginFor<T>,
+            attestation_id: u64,
+            salt: Vec<u8>,
+            attester_is_guilty: bool,
+        ) -> DispatchResult {
+            let juror = ensure_signed(origin)?;
+
+            let mut dispute = Disputes::<T>::get(attestation_id).ok_or(Error::<T>::DisputeNotFound)?;
+            let mut vote = JurorVotes::<T>::get(attestation_id, &juror).ok_or(Error::<T>::NotCommitted)?;
+            ensure!(vote.revealed.is_none(), Error::<T>::AlreadyRevealed);
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now > dispute.commit_end || dispute.committed as usize == dispute.jury.len(),
+                Error::<T>::RevealPhaseNotStarted
+            );
+            ensure!(now <= dispute.reveal_end, Error::<T>::RevealPhaseOver);
+            ensure!(
+                Self::vote_hash(attestation_id, salt, attester_is_guilty) == vote.hash,
+                Error::<T>::VoteNotMatchHash
+            );
+
+            vote.revealed = Some(attester_is_guilty);
+            JurorVotes::<T>::insert(attestation_id, &juror, vote);
+            if attester_is_guilty {
+                dispute.guilty_votes = dispute.guilty_votes.saturating_add(1);
+            } else {
+                dispute.innocent_votes = dispute.innocent_votes.saturating_add(1);
+            }
+            Disputes::<T>::insert(attestation_id, dispute);
+
+            Self::deposit_event(Event::VoteRevealed {
+                id: attestation_id,
+                juror,
+                attester_is_guilty,
+            });
+            Ok(())
+        }
+
+        /// Register a node with hardware info proven by a TEE attestation report, or attest the
+        /// hardware of an already registered node. The report's certificate chain must end at a
+        /// trusted root, and its quote must attest a trusted measurement and bind
+        /// `tee_report_data(who, gpu_uuid, tflops)`.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::register_attested_node())]
+        pub fn register_attested_node(
+            origin: OriginFor<T>,
+            gpu_uuid: Vec<u8>,
+            tflops: u32,
+            report: TeeReport,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!Unbonding::<T>::contains_key(&who), Error::<T>::NodeUnbonding);
+
+            let gpu_uuid_bounded: BoundedVec<u8, T::MaxGpuUuidLen> = gpu_uuid
+                .try_into()
+                .map_err(|_| Error::<T>::ArithmeticOverflow)?;
+            let claims = Self::verify_tee_report(&report)?;
+            ensure!(
+                H256(claims.report_data) == Self::tee_report_data(&who, &gpu_uuid_bounded, tflops),
+                Error::<T>::ReportDataMismatch
+            );
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let registered = Nodes::<T>::contains_key(&who);
+            let bond = if registered { Zero::zero() } else { T::RegistrationBond::get() };
+            T::Currency::reserve(&who, bond).map_err(|_| Error::<T>::InsufficientBond)?;
+            Nodes::<T>::mutate(&who, |maybe_node| {
+                let node = maybe_node.get_or_insert_with(|| NodeRegistration {
+                    owner: who.clone(),
+                    gpu_uuid: Default::default(),
+                    tflops: 0,
+                    registered_at: now,
+                    last_heartbeat: now,
+                    is_active: true,
+                    attested: false,
+                    bond,
+                });
+                node.gpu_uuid = gpu_uuid_bounded;
+                node.tflops = tflops;
+                node.attested = true;
+            });
+
+            if !registered {
+                Self::deposit_event(Event::NodeRegistered { who: who.clone(), tflops });
+            }
+            Self::deposit_event(Event::NodeAttested {
+                who,
+                kind: report.kind,
+                measurement: H256(claims.measurement),
+            });
+            Ok(())
+        }
+
+        /// Trust the self-signed root `certificate` to anchor the certificate chains of `kind`
+        /// reports, or stop trusting it. Nodes attested through the root keep their flag.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::set_tee_root())]
+        pub fn set_tee_root(
+            origin: OriginFor<T>,
+            kind: TeeKind,
+            certificate: TeeCertificate,
+            trusted: bool,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let key = tee::root_key(&certificate).map_err(Self::tee_error)?;
+            let root = Self::tee_root_id(&key);
+            if trusted {
+                TeeRoots::<T>::insert(kind, root, true);
+            } else {
+                TeeRoots::<T>::remove(kind, root);
+            }
+            Self::deposit_event(Event::TeeRootSet { kind, root, trusted });
+            Ok(())
+        }
+
+        /// Allow or disallow attested nodes of `kind` to run the code measured by `measurement`
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::set_tee_measurement())]
+        pub fn set_tee_measurement(
+            origin: OriginFor<T>,
+            kind: TeeKind,
+            measurement: H256,
+            trusted: bool,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            if trusted {
+                TeeMeasurements::<T>::insert(kind, measurement, true);
+            } else {
+                TeeMeasurements::<T>::remove(kind, measurement);
+            }
+            Self::deposit_event(Event::TeeMeasurementSet { kind, measurement, trusted });
+            Ok(())
+        }
+
+        /// Stop serving as a node. The node leaves the provider indexes right away and its
+        /// bond can be withdrawn after `UnbondingPeriod`. Not possible while any attestation of
+        /// the node is pending.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::deregister_node())]
+        pub fn deregister_node(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!Unbonding::<T>::contains_key(&who), Error::<T>::NodeUnbonding);
+            ensure!(PendingAttestations::<T>::get(&who) == 0, Error::<T>::AttestationsPending);
+
+            Nodes::<T>::try_mutate(&who, |maybe_node| -> DispatchResult {
+                let node = maybe_node.as_mut().ok_or(Error::<T>::NodeNotRegistered)?;
+                node.is_active = false;
+                Ok(())
+            })?;
+
+            if let Some(capability) = AgentCapabilities::<T>::take(&who) {
+                for model in capability.model_ids.iter() {
+                    ModelProviders::<T>::remove(model, &who);
+                }
+            }
+
+            let unbonds_at = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(T::UnbondingPeriod::get());
+            Unbonding::<T>::insert(&who, unbonds_at);
+
+            Self::deposit_event(Event::NodeDeregistered { who, unbonds_at });
+            Ok(())
+        }
+
+        /// Unreserve the bond of a deregistered node once its unbonding period is over and
+        /// remove the node
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::withdraw_node_bond())]
+        pub fn withdraw_node_bond(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let unbonds_at = Unbonding::<T>::get(&who).ok_or(Error::<T>::NotUnbonding)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= unbonds_at,
+                Error::<T>::UnbondingNotFinished
+            );
+            // Task completions may still attest for the node while it unbonds
+            ensure!(PendingAttestations::<T>::get(&who) == 0, Error::<T>::AttestationsPending);
+
+            let node = Nodes::<T>::take(&who).ok_or(Error::<T>::NodeNotRegistered)?;
+            Unbonding::<T>::remove(&who);
+            let unreserved = node.bond.saturating_sub(T::Currency::unreserve(&who, node.bond));
+
+            Self::deposit_event(Event::NodeBondWithdrawn { who, amount: unreserved });
+            Ok(())
+        }
+
+        /// Settle the order of an upheld attestation whose settlement failed. Anyone can call it.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::retry_settlement())]
+        pub fn retry_settlement(origin: OriginFor<T>, attestation_id: u64) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(
+                UnsettledAttestations::<T>::contains_key(attestation_id),
+                Error::<T>::SettlementNotPending
+            );
+
+            let att = Attestations::<T>::get(attestation_id).ok_or(Error::<T>::AttestationNotFound)?;
+            let context = Self::attestation_order_context(attestation_id, &att)
+                .ok_or(Error::<T>::SettlementNotPending)?;
+            let receipt_id =
+                T::OnAttestationConfirmed::settle_for_attestation(&context, &att.attester, attestation_id)
+                    .map_err(|_| Error::<T>::SettlementRejected)?;
+
+            UnsettledAttestations::<T>::remove(attestation_id);
+            Self::deposit_event(Event::AttestationSettled {
+                id: attestation_id,
+                order_id: context.order_id,
+                receipt_id,
+            });
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
+        /// Queue `attestation_id` to be finalized after the first block from `challenge_end`
+        /// whose queue has room. When none has, it is left to be confirmed by signed call.
+        pub(crate) fn schedule_finalization(attestation_id: u64, challenge_end: BlockNumberFor<T>) {
+            let mut at = challenge_end;
+            for _ in 0..=crate::MAX_FINALIZATION_CARRY_OVER {
+                if FinalizationQueue::<T>::mutate(at, |queue| queue.try_push(attestation_id)).is_ok() {
+                    return
+                }
+                at = at.saturating_add(One::one());
+            }
+            log::warn!(
+                target: crate::LOG_TARGET,
+                "finalization queues full, attestation {} must be confirmed by signed call",
+                attestation_id,
+            );
+        }
+
+        /// Confirm an unchallenged attestation whose challenge window is over, returning the
+        /// attester's deposit less the tip for `tipped`, and trigger settlement of its order
+        pub(crate) fn do_confirm(attestation_id: u64, tipped: Option<T::AccountId>) -> DispatchResult {
+            Attestations::<T>::try_mutate(attestation_id, |maybe_att| -> DispatchResult {
+                let att = maybe_att.as_mut().ok_or(Error::<T>::AttestationNotFound)?;
+
+                ensure!(
+                    matches!(att.status, AttestationStatus::Pending),
+                    Error::<T>::InvalidStatus
+                );
+                ensure!(
+                    att.challenger.is_none(),
+                    Error::<T>::AlreadyChallenged
+                );
+
+                let now = <frame_system::Pallet<T>>::block_number();
+                ensure!(
+                    now > att.challenge_end,
+                    Error::<T>::ChallengeWindowNotExpired
+                );
+
+                att.status = AttestationStatus::Confirmed;
+                Self::release_pending_attestation(&att.attester);
+                Self::record_completed_attestation(&att.attester);
+
+                // Pay the tip out of the deposit, then unreserve the rest
+                let mut tip = BalanceOf::<T>::zero();
+                if let Some(who) = tipped.filter(|who| *who != att.attester) {
+                    let amount = T::FinalizationTip::get().min(att.deposit);
+                    if let Ok(unpaid) = T::Currency::repatriate_reserved(
+                        &att.attester,
+                        &who,
+                        amount,
+                        BalanceStatus::Free,
+                    ) {
+                        tip = amount.saturating_sub(unpaid);
+                    }
+                    if !tip.is_zero() {
+                        Self::deposit_event(Event::FinalizationTipPaid {
+                            id: attestation_id,
+                            who,
+                            amount: tip,
+                        });
+                    }
+                }
+                T::Currency::unreserve(&att.attester, att.deposit.saturating_sub(tip));
+
+                Self::deposit_event(Event::AttestationConfirmed { id: attestation_id });
+                Self::settle_attestation_order(attestation_id, att);
+
+                Ok(())
+            })
+        }
+
+        /// Order context of an attestation backed by a task order
+        fn attestation_order_context(
+            attestation_id: u64,
+            att: &Attestation<T>,
+        ) -> Option<TaskOrderContext<T::AccountId, BalanceOf<T>>> {
+            AttestationOrders::<T>::get(attestation_id).map(|order| TaskOrderContext {
+                order_id: order.order_id,
+                customer: order.customer,
+                model_id: att.model_id.to_vec(),
+                input_tokens: att.input_tokens,
+                output_tokens: att.output_tokens,
+                agreed_price: order.agreed_price,
+            })
+        }
+
+        /// Pay the miner of an upheld attestation from its order, if it has one. A failed
+        /// settlement does not block confirmation; it is recorded for `retry_settlement`.
+        fn settle_attestation_order(attestation_id: u64, att: &Attestation<T>) {
+            if let Some(context) = Self::attestation_order_context(attestation_id, att) {
+                match T::OnAttestationConfirmed::settle_for_attestation(
+                    &context,
+                    &att.attester,
+                    attestation_id,
+                ) {
+                    Ok(receipt_id) => Self::deposit_event(Event::AttestationSettled {
+                        id: attestation_id,
+                        order_id: context.order_id,
+                        receipt_id,
+                    }),
+                    Err(e) => {
+                        log::warn!(
+                            target: crate::LOG_TARGET,
+                            "failed to settle order {} of attestation {}: {}",
+                            context.order_id,
+                            attestation_id,
+                            e,
+                        );
+                        UnsettledAttestations::<T>::insert(attestation_id, ());
+                        Self::deposit_event(Event::SettlementFailed {
+                            id: attestation_id,
+                            order_id: context.order_id,
+                        });
+                    },
+                }
+            }
+        }
+
+        /// Return its funds to the customer of a slashed attestation's order, if it has one
+        fn refund_attestation_order(attestation_id: u64, att: &Attestation<T>) {
+            if let Some(context) = Self::attestation_order_context(attestation_id, att) {
+                if let Ok(amount) =
+                    T::OnAttestationConfirmed::refund_for_attestation(&context, attestation_id)
+                {
+                    Self::deposit_event(Event::AttestationRefunded {
+                        id: attestation_id,
+                        order_id: context.order_id,
+                        amount,
+                    });
+                }
+            }
+        }
+
+        /// Count one attestation of `attester` as no longer pending
+        fn release_pending_attestation(attester: &T::AccountId) {
+            PendingAttestations::<T>::mutate_exists(attester, |count| {
+                *count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+            });
+        }
+
+        fn record_completed_attestation(attester: &T::AccountId) {
+            CompletedAttestations::<T>::mutate(attester, |count| *count = count.saturating_add(1));
+        }
+
+        /// Check up to `MaxLivenessChecksPerBlock` nodes, from where the previous block stopped,
+        /// and deactivate those that missed too many heartbeats. Returns how many were checked.
+        pub(crate) fn sweep_liveness(now: BlockNumberFor<T>) -> u32 {
+            let limit = T::MaxLivenessChecksPerBlock::get();
+            if limit == 0 {
+                return 0
+            }
+            let mut nodes = match LivenessCursor::<T>::take() {
+                Some(cursor) => Nodes::<T>::iter_from(cursor.into_inner()),
+                None => Nodes::<T>::iter(),
+            };
+
+            let mut checked = 0u32;
+            let mut missed = Vec::new();
+            while let Some((who, node)) = nodes.next() {
+                checked += 1;
+                if node.is_active && now >= Self::liveness_deadline(&node) {
+                    missed.push(who);
+                }
+                if checked == limit {
+                    LivenessCursor::<T>::set(nodes.last_raw_key().to_vec().try_into().ok());
+                    break
+                }
+            }
+
+            for who in missed {
+                Self::deactivate_node(&who);
+            }
+            checked
+        }
+
+        /// Block from which a node that sent no further heartbeat is deactivated
+        pub fn liveness_deadline(node: &NodeRegistration<T>) -> BlockNumberFor<T> {
+            let intervals = T::MaxMissedHeartbeats::get().saturating_add(1);
+            node.last_heartbeat
+                .saturating_add(T::HeartbeatInterval::get().saturating_mul(intervals.into()))
+        }
+
+        /// Mark `who` inactive until its next heartbeat and slash `InactivitySlash` of its bond
+        fn deactivate_node(who: &T::AccountId) {
+            Nodes::<T>::mutate(who, |maybe_node| {
+                if let Some(node) = maybe_node {
+                    let (imbalance, _) = T::Currency::slash_reserved(who, T::InactivitySlash::get() * node.bond);
+                    let slashed = imbalance.peek();
+                    T::Slash::on_unbalanced(imbalance);
+                    node.bond = node.bond.saturating_sub(slashed);
+                    node.is_active = false;
+
+                    Self::deposit_event(Event::NodeDeactivated {
+                        who: who.clone(),
+                        last_heartbeat: node.last_heartbeat,
+                        slashed,
+                    });
+                }
+            });
+        }
+
+        /// Hash a juror commits to before revealing `attester_is_guilty`
+        pub fn vote_hash(attestation_id: u64, salt: Vec<u8>, attester_is_guilty: bool) -> [u8; 16] {
+            let vote: Vec<u8> = if attester_is_guilty { "1".into() } else { "0".into() };
+            get_hash(vec![attestation_id.encode(), salt, vote])
+        }
+
+        /// Draw `JurySize` distinct committee members, excluding the parties to the dispute
+        fn draw_jury(
+            attestation_id: u64,
+            attester: &T::AccountId,
+            challenger: &T::AccountId,
+        ) -> Result<BoundedVec<T::AccountId, T::JurySize>, Error<T>> {
+            let mut candidates: Vec<T::AccountId> = T::ManageCommittee::available_committee()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|member| member != attester && member != challenger)
+                .collect();
+            let jury_size = T::JurySize::get() as usize;
+            ensure!(jury_size > 0 && candidates.len() >= jury_size, Error::<T>::NotEnoughJurors);
+
+            let (seed, _) = T::Randomness::random(&(b"attestation/jury", attestation_id).encode());
+            let mut jury = Vec::with_capacity(jury_size);
+            for round in 0..jury_size as u32 {
+                let random = blake2_256(&(seed, round).encode());
+                let index = u32::from_le_bytes([random[0], random[1], random[2], random[3]]) as usize %
+                    candidates.len();
+                jury.push(candidates.swap_remove(index));
+            }
+            jury.try_into().map_err(|_| Error::<T>::NotEnoughJurors)
+        }
+
+        /// Data a node's enclave binds into its attestation report to register `gpu_uuid` and
+        /// `tflops` for `who`
+        pub fn tee_report_data(who: &T::AccountId, gpu_uuid: &[u8], tflops: u32) -> H256 {
+            H256(blake2_256(&(b"attestation/tee-report-data", who, gpu_uuid, tflops).encode()))
+        }
+
+        /// Id of a TEE root in `TeeRoots`: the hash of its uncompressed SEC1 key
+        pub fn tee_root_id(key: &tee::TeeKey) -> H256 {
+            H256(blake2_256(&key.to_sec1()))
+        }
+
+        /// Check the certificate chain of `report` is valid now and ends at a root trusted for
+        /// its TEE, and that its quote is signed by the certified key and attests a trusted
+        /// measurement
+        fn verify_tee_report(report: &TeeReport) -> Result<QuoteClaims, DispatchError> {
+            let now = T::UnixTime::now().as_secs();
+            let (leaf, root) =
+                tee::verify_chain(&report.certificates, now).map_err(Self::tee_error)?;
+            ensure!(
+                TeeRoots::<T>::get(report.kind, Self::tee_root_id(&root)),
+                Error::<T>::UntrustedTeeRoot
+            );
+
+            let claims = match report.kind {
+                TeeKind::IntelSgx => tee::verify_sgx_quote(&report.quote, &leaf),
+                TeeKind::IntelTdx => tee::verify_tdx_quote(&report.quote, &leaf),
+                TeeKind::NvidiaCc => tee::verify_spdm_measurements(&report.quote, &leaf),
+            }
+            .map_err(Self::tee_error)?;
+            ensure!(
+                TeeMeasurements::<T>::get(report.kind, H256(claims.measurement)),
+                Error::<T>::UntrustedMeasurement
+            );
+            Ok(claims)
+        }
+
+        fn tee_error(error: TeeError) -> DispatchError {
+            match error {
+                TeeError::InvalidCertificate => Error::<T>::InvalidTeeCertificate,
+                TeeError::CertificateExpired => Error::<T>::TeeCertificateExpired,
+                TeeError::InvalidQuote => Error::<T>::InvalidTeeReport,
+            }
+            .into()
+        }
+
+        /// Active providers of `model_id`, nodes with attested hardware first
         pub fn get_providers_for_model(model_id: &BoundedVec<u8, T::MaxModelIdLen>) -> Vec<T::AccountId> {
-            ModelProviders::<T>::iter_prefix(model_id)
-                .filter(|(account, _)| {
-                    Nodes::<T>::get(account).map_or(false, |n| n.is_active)
+            let mut providers: Vec<(T::AccountId, bool)> = ModelProviders::<T>::iter_prefix(model_id)
+                .filter_map(|(account, _)| {
+                    Nodes::<T>::get(&account)
+                        .filter(|n| n.is_active)
+                        .map(|n| (account, n.attested))
+                })
+                .collect();
+            providers.sort_by_key(|(_, attested)| !attested);
+            providers.into_iter().map(|(account, _)| account).collect()
+        }
+
+        /// Active providers of `model_id` that pass `filter`, best score first. Skips `offset`
+        /// providers and returns at most `limit`, capped at `MAX_PROVIDER_PAGE`.
+        ///
+        /// The price score is the cheapest price among the matches over the provider's price, the
+        /// history score its completed tasks over the most completed by any match, and the
+        /// liveness score the share of its liveness window left. Ties go to attested hardware.
+        pub fn discover_providers(
+            model_id: &[u8],
+            filter: &ProviderFilter<BalanceOf<T>>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<ProviderInfo<T>> {
+            let model: BoundedVec<u8, T::MaxModelIdLen> = match model_id.to_vec().try_into() {
+                Ok(model) => model,
+                Err(_) => return Vec::new(),
+            };
+
+            let mut providers: Vec<ProviderInfo<T>> = ModelProviders::<T>::iter_key_prefix(&model)
+                .filter_map(|account| {
+                    let node = Nodes::<T>::get(&account).filter(|n| n.is_active)?;
+                    let capability = AgentCapabilities::<T>::get(&account)?;
+                    let pending = PendingAttestations::<T>::get(&account);
+                    let completed_tasks = CompletedAttestations::<T>::get(&account);
+                    Some(ProviderInfo {
+                        account,
+                        price_per_token: capability.price_per_token,
+                        region: capability.region,
+                        max_concurrent: capability.max_concurrent,
+                        free_concurrency: capability.max_concurrent.saturating_sub(pending),
+                        completed_tasks,
+                        last_heartbeat: node.last_heartbeat,
+                        attested: node.attested,
+                        score: Perbill::zero(),
+                    })
                 })
-                .map(|(account, _)| account)
+                .filter(|p| filter.region.as_ref().map_or(true, |region| p.region[..] == region[..]))
+                .filter(|p| filter.max_price.map_or(true, |max| p.price_per_token <= max))
+                .filter(|p| p.free_concurrency >= filter.min_free_concurrency)
+                .collect();
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let window = T::HeartbeatInterval::get()
+                .saturating_mul(T::MaxMissedHeartbeats::get().saturating_add(1).into());
+            let min_price = providers.iter().map(|p| p.price_per_token).min().unwrap_or_else(Zero::zero);
+            let max_completed = providers.iter().map(|p| p.completed_tasks).max().unwrap_or(0);
+            for p in providers.iter_mut() {
+                let price = if p.price_per_token.is_zero() {
+                    Perbill::one()
+                } else {
+                    Perbill::from_rational(min_price, p.price_per_token)
+                };
+                let history = if max_completed == 0 {
+                    Perbill::zero()
+                } else {
+                    Perbill::from_rational(p.completed_tasks, max_completed)
+                };
+                let left = p.last_heartbeat.saturating_add(window).saturating_sub(now);
+                let liveness = if window.is_zero() { Perbill::one() } else { Perbill::from_rational(left, window) };
+
+                p.score = (crate::PRICE_SCORE_WEIGHT * price)
+                    .saturating_add(crate::HISTORY_SCORE_WEIGHT * history)
+                    .saturating_add(crate::LIVENESS_SCORE_WEIGHT * liveness);
+            }
+            providers.sort_by(|a, b| b.score.cmp(&a.score).then(b.attested.cmp(&a.attested)));
+
+            providers
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit.min(crate::MAX_PROVIDER_PAGE) as usize)
                 .collect()
         }
     }
@@ -588,29 +1621,32 @@ pub mod pallet {
 // Cross-Pallet Integration: TaskCompletionHandler Implementation
 // ============================================================
 
-use frame_support::traits::Get;
+use frame_support::traits::{Currency, Get};
 
+type BalanceOf<T> = <<T as pallet::Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 impl<T: Config> dbc_support::traits::TaskCompletionHandler for Pallet<T> {
     type AccountId = T::AccountId;
+    type Balance = BalanceOf<T>;
 
     fn on_task_completed(
         attester: &Self::AccountId,
         task_id: u64,
         result_hash: sp_core::H256,
-        model_id: &[u8],
-        input_tokens: u64,
-        output_tokens: u64,
+        order: Option<&dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>>,
     ) -> Result<u64, &'static str> {
         use frame_support::traits::ReservableCurrency;
-        
+
         // Get the next attestation ID
         let attestation_id = pallet::NextAttestationId::<T>::get();
         let next_id = attestation_id.checked_add(1).ok_or("Attestation ID overflow")?;
         pallet::NextAttestationId::<T>::put(next_id);
 
-        // Convert model_id to BoundedVec
-        let model_id_bounded = model_id.to_vec().try_into()
+        // Tasks scheduled without an order carry no model or token usage
+        let (model_id, input_tokens, output_tokens) = order
+            .map(|o| (o.model_id.clone(), o.input_tokens, o.output_tokens))
+            .unwrap_or_default();
+        let model_id_bounded = model_id.try_into()
             .map_err(|_| "Model ID too long")?;
 
         let now = frame_system::Pallet::<T>::block_number();
@@ -639,11 +1675,24 @@ impl<T: Config> dbc_support::traits::TaskCompletionHandler for Pallet<T> {
         };
 
         pallet::Attestations::<T>::insert(attestation_id, attestation);
+        Pallet::<T>::schedule_finalization(attestation_id, challenge_end);
+
+        if let Some(order) = order {
+            pallet::AttestationOrders::<T>::insert(
+                attestation_id,
+                pallet::AttestationOrder {
+                    order_id: order.order_id,
+                    customer: order.customer.clone(),
+                    agreed_price: order.agreed_price,
+                },
+            );
+        }
 
         // Increment task count for attester
         pallet::AttesterTaskCount::<T>::mutate(attester, task_id, |count| {
             *count = count.saturating_add(1);
         });
+        pallet::PendingAttestations::<T>::mutate(attester, |count| *count = count.saturating_add(1));
 
         // Emit event
         Pallet::<T>::deposit_event(pallet::Event::AttestationSubmitted {
@@ -0,0 +1,373 @@
+use crate::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use sp_runtime::traits::Saturating;
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+const TARGET: &str = "runtime::agent-attestation";
+
+pub mod v2 {
+    use super::*;
+
+    /// Reopens challenges made before jury disputes existed. Those challengers posted no bond
+    /// and no jury was drawn, so the attestation gets a fresh challenge window instead.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 1 {
+                log::info!(target: TARGET, "v2 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 1, "this migration can be deleted");
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration needs to be removed");
+            ensure!(
+                Attestations::<T>::iter().all(|(id, att)| {
+                    att.challenger.is_none() ||
+                        !matches!(att.status, AttestationStatus::Pending) ||
+                        Disputes::<T>::contains_key(id)
+                }),
+                "a pending challenge has no dispute"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let now = <frame_system::Pallet<T>>::block_number();
+        let challenge_end = now.saturating_add(T::ChallengeWindow::get());
+
+        let mut read = 0u64;
+        let mut reopened = 0u64;
+        Attestations::<T>::translate(|_id, mut att: Attestation<T>| {
+            read += 1;
+            if att.challenger.is_some() && matches!(att.status, AttestationStatus::Pending) {
+                att.challenger = None;
+                att.challenge_end = att.challenge_end.max(challenge_end);
+                reopened += 1;
+            }
+            Some(att)
+        });
+
+        StorageVersion::new(2).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v2 migration reopened {} challenged attestations", reopened);
+        T::DbWeight::get().reads_writes(read + 1, read + 1)
+    }
+}
+
+pub mod v3 {
+    use super::*;
+    use frame_system::pallet_prelude::BlockNumberFor;
+
+    #[derive(Decode)]
+    struct OldNodeRegistration<T: Config> {
+        owner: T::AccountId,
+        gpu_uuid: BoundedVec<u8, T::MaxGpuUuidLen>,
+        tflops: u32,
+        registered_at: BlockNumberFor<T>,
+        last_heartbeat: BlockNumberFor<T>,
+        is_active: bool,
+    }
+
+    #[derive(Encode, Decode)]
+    pub(crate) struct NodeRegistration<T: Config> {
+        pub owner: T::AccountId,
+        pub gpu_uuid: BoundedVec<u8, T::MaxGpuUuidLen>,
+        pub tflops: u32,
+        pub registered_at: BlockNumberFor<T>,
+        pub last_heartbeat: BlockNumberFor<T>,
+        pub is_active: bool,
+        pub attested: bool,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type Nodes<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, <T as frame_system::Config>::AccountId, NodeRegistration<T>>;
+
+    /// Node registrations record whether their hardware is TEE attested. Every node
+    /// registered before reported its own hardware.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 2 {
+                log::info!(target: TARGET, "v3 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration can be deleted");
+            Ok((Nodes::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let nodes: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration needs to be removed");
+            ensure!(Nodes::<T>::iter().count() as u64 == nodes, "node registrations were lost");
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut translated = 0u64;
+        Nodes::<T>::translate(|_who, old: OldNodeRegistration<T>| {
+            translated += 1;
+            Some(NodeRegistration {
+                owner: old.owner,
+                gpu_uuid: old.gpu_uuid,
+                tflops: old.tflops,
+                registered_at: old.registered_at,
+                last_heartbeat: old.last_heartbeat,
+                is_active: old.is_active,
+                attested: false,
+            })
+        });
+
+        StorageVersion::new(3).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v3 migration moved {} node registrations", translated);
+        T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+    }
+}
+
+pub mod v4 {
+    use super::*;
+    use sp_runtime::traits::Zero;
+
+    /// Node registrations record their registration bond. Nodes registered before were not
+    /// bonded, so they have nothing to be slashed for missing heartbeats.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 3 {
+                log::info!(target: TARGET, "v4 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration can be deleted");
+            Ok((Nodes::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let nodes: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 4, "this migration needs to be removed");
+            ensure!(Nodes::<T>::iter().count() as u64 == nodes, "node registrations were lost");
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut translated = 0u64;
+        Nodes::<T>::translate(|_who, old: v3::NodeRegistration<T>| {
+            translated += 1;
+            Some(NodeRegistration {
+                owner: old.owner,
+                gpu_uuid: old.gpu_uuid,
+                tflops: old.tflops,
+                registered_at: old.registered_at,
+                last_heartbeat: old.last_heartbeat,
+                is_active: old.is_active,
+                attested: old.attested,
+                bond: Zero::zero(),
+            })
+        });
+
+        StorageVersion::new(4).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v4 migration moved {} node registrations", translated);
+        T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+    }
+}
+
+pub mod v5 {
+    use super::*;
+
+    /// Counts the pending attestations of every attester, which nodes need to be free of
+    /// before they can deregister.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 4 {
+                log::info!(target: TARGET, "v5 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 4, "this migration can be deleted");
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 5, "this migration needs to be removed");
+            let pending = Attestations::<T>::iter_values()
+                .filter(|att| matches!(att.status, AttestationStatus::Pending))
+                .count() as u64;
+            let counted: u64 = PendingAttestations::<T>::iter_values().map(u64::from).sum();
+            ensure!(pending == counted, "pending attestations were miscounted");
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut read = 0u64;
+        let mut counted = 0u64;
+        for att in Attestations::<T>::iter_values() {
+            read += 1;
+            if matches!(att.status, AttestationStatus::Pending) {
+                PendingAttestations::<T>::mutate(&att.attester, |count| *count = count.saturating_add(1));
+                counted += 1;
+            }
+        }
+
+        StorageVersion::new(5).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v5 migration counted {} pending attestations", counted);
+        T::DbWeight::get().reads_writes(read + counted + 1, counted + 1)
+    }
+}
+
+pub mod v6 {
+    use super::*;
+
+    /// Counts the confirmed and defended attestations of every attester, which rank providers
+    /// in `discover_providers`.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 5 {
+                log::info!(target: TARGET, "v6 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 5, "this migration can be deleted");
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 6, "this migration needs to be removed");
+            let completed = Attestations::<T>::iter_values()
+                .filter(|att| {
+                    matches!(att.status, AttestationStatus::Confirmed | AttestationStatus::Defended)
+                })
+                .count() as u64;
+            let counted: u64 = CompletedAttestations::<T>::iter_values().map(u64::from).sum();
+            ensure!(completed == counted, "completed attestations were miscounted");
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut read = 0u64;
+        let mut counted = 0u64;
+        for att in Attestations::<T>::iter_values() {
+            read += 1;
+            if matches!(att.status, AttestationStatus::Confirmed | AttestationStatus::Defended) {
+                CompletedAttestations::<T>::mutate(&att.attester, |count| *count = count.saturating_add(1));
+                counted += 1;
+            }
+        }
+
+        StorageVersion::new(6).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v6 migration counted {} completed attestations", counted);
+        T::DbWeight::get().reads_writes(read + counted + 1, counted + 1)
+    }
+}
+
+pub mod v7 {
+    use super::*;
+    use sp_runtime::traits::One;
+
+    /// Queues the unchallenged pending attestations made before `FinalizationQueue` existed, the
+    /// challenges `v2` reopens included, for finalization at their `challenge_end`, or at the
+    /// upgrade block when it is already past. Full queues carry them over to later blocks.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 6 {
+                log::info!(target: TARGET, "v7 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 6, "this migration can be deleted");
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 7, "this migration needs to be removed");
+            let queued: Vec<u64> = FinalizationQueue::<T>::iter_values().flatten().collect();
+            ensure!(
+                Attestations::<T>::iter().all(|(id, att)| {
+                    att.challenger.is_some() ||
+                        !matches!(att.status, AttestationStatus::Pending) ||
+                        queued.contains(&id)
+                }),
+                "an unchallenged pending attestation was not queued"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let now = <frame_system::Pallet<T>>::block_number();
+        let mut reads = 1u64;
+        let mut queued = 0u64;
+        for (id, att) in Attestations::<T>::iter() {
+            reads += 1;
+            if att.challenger.is_some() || !matches!(att.status, AttestationStatus::Pending) {
+                continue
+            }
+            // Unlike new attestations, existing ones are carried over as far as it takes to
+            // queue them
+            let mut at = att.challenge_end.max(now);
+            loop {
+                reads += 1;
+                if FinalizationQueue::<T>::mutate(at, |queue| queue.try_push(id)).is_ok() {
+                    break
+                }
+                at = at.saturating_add(One::one());
+            }
+            queued += 1;
+        }
+
+        StorageVersion::new(7).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v7 migration queued {} pending attestations", queued);
+        T::DbWeight::get().reads_writes(reads, queued + 1)
+    }
+}
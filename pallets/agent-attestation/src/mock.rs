@@ -7,6 +7,7 @@ use sp_core::H256;
 use sp_runtime::{
     generic::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
 };
 
 pub type AccountId = u64;
@@ -38,6 +39,19 @@ parameter_types! {
     pub const MaxModelIdLen: u32 = 256;
     pub const MaxGpuUuidLen: u32 = 128;
     pub const MaxModelsPerAgent: u32 = 10;
+    pub const ChallengeBond: u128 = 400;
+    pub const ChallengerSlashShare: Perbill = Perbill::from_percent(60);
+    pub const JurySize: u32 = 3;
+    pub const CommitDuration: BlockNumber = 10;
+    pub const RevealDuration: BlockNumber = 10;
+    pub const FinalizationTip: u128 = 10;
+    pub const MaxFinalizationsPerBlock: u32 = 2;
+    pub const MaxMissedHeartbeats: u32 = 2;
+    pub const MaxLivenessChecksPerBlock: u32 = 2;
+    pub const InactivitySlash: Perbill = Perbill::from_percent(10);
+    pub static RegistrationBond: u128 = 0;
+    pub const UnbondingPeriod: BlockNumber = 20;
+    pub static UnixNow: u64 = 1_700_000_000;
 }
 
 impl frame_system::Config for Test {
@@ -83,6 +97,72 @@ impl pallet_balances::Config for Test {
     type HoldIdentifier = ();
 }
 
+thread_local! {
+    /// (order_id, customer, miner, agreed_price, attestation_id) for every settlement request
+    pub static SETTLED: std::cell::RefCell<Vec<(u64, u64, u64, u128, u64)>> =
+        std::cell::RefCell::new(Vec::new());
+    /// (order_id, customer, attestation_id) for every refund request
+    pub static REFUNDED: std::cell::RefCell<Vec<(u64, u64, u64)>> =
+        std::cell::RefCell::new(Vec::new());
+    /// Makes every settlement request fail
+    pub static REJECT_SETTLEMENT: std::cell::RefCell<bool> = std::cell::RefCell::new(false);
+}
+
+thread_local! {
+    pub static COMMITTEE: std::cell::RefCell<Vec<u64>> = std::cell::RefCell::new(vec![10, 11, 12, 13, 14]);
+}
+
+pub struct MockCommittee;
+
+impl dbc_support::traits::ManageCommittee for MockCommittee {
+    type AccountId = u64;
+    type Balance = u128;
+    type ReportId = u64;
+
+    fn is_valid_committee(who: &u64) -> bool {
+        COMMITTEE.with(|c| c.borrow().contains(who))
+    }
+    fn available_committee() -> Option<Vec<u64>> {
+        let committee = COMMITTEE.with(|c| c.borrow().clone());
+        (!committee.is_empty()).then_some(committee)
+    }
+    fn change_used_stake(_: u64, _: u128, _: bool) -> Result<(), ()> {
+        Ok(())
+    }
+    fn change_total_stake(_: u64, _: u128, _: bool, _: bool) -> Result<(), ()> {
+        Ok(())
+    }
+    fn stake_per_order() -> Option<u128> {
+        None
+    }
+    fn add_reward(_: u64, _: u128) {}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<u64> for MockCommittee {
+    fn add_committee_member(who: &u64) {
+        COMMITTEE.with(|c| c.borrow_mut().push(*who));
+    }
+}
+
+/// Derives the "random" seed from the subject, so juries are deterministic in tests
+pub struct TestRandomness;
+
+impl frame_support::traits::Randomness<H256, BlockNumber> for TestRandomness {
+    fn random(subject: &[u8]) -> (H256, BlockNumber) {
+        (H256(sp_io::hashing::blake2_256(subject)), 0)
+    }
+}
+
+/// Unix time of `UnixNow`, in seconds
+pub struct MockUnixTime;
+
+impl frame_support::traits::UnixTime for MockUnixTime {
+    fn now() -> core::time::Duration {
+        core::time::Duration::from_secs(UnixNow::get())
+    }
+}
+
 // Mock implementation for AttestationSettler
 pub struct MockAttestationSettler;
 
@@ -91,13 +171,26 @@ impl dbc_support::traits::AttestationSettler for MockAttestationSettler {
     type Balance = u128;
 
     fn settle_for_attestation(
-        _merchant: &Self::AccountId,
-        _miner: &Self::AccountId,
-        _amount: Self::Balance,
-        _attestation_id: u64,
+        order: &dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>,
+        miner: &Self::AccountId,
+        attestation_id: u64,
     ) -> Result<u64, &'static str> {
+        if REJECT_SETTLEMENT.with(|r| *r.borrow()) {
+            return Err("Settlement rejected")
+        }
+        SETTLED.with(|s| {
+            s.borrow_mut().push((order.order_id, order.customer, *miner, order.agreed_price, attestation_id))
+        });
         Ok(0)
     }
+
+    fn refund_for_attestation(
+        order: &dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>,
+        attestation_id: u64,
+    ) -> Result<Self::Balance, &'static str> {
+        REFUNDED.with(|r| r.borrow_mut().push((order.order_id, order.customer, attestation_id)));
+        Ok(order.agreed_price)
+    }
 }
 
 impl crate::Config for Test {
@@ -107,12 +200,30 @@ impl crate::Config for Test {
     type ChallengeWindow = ChallengeWindow;
     type SlashPercent = SlashPercent;
     type HeartbeatInterval = HeartbeatInterval;
+    type MaxMissedHeartbeats = MaxMissedHeartbeats;
+    type MaxLivenessChecksPerBlock = MaxLivenessChecksPerBlock;
+    type RegistrationBond = RegistrationBond;
+    type InactivitySlash = InactivitySlash;
+    type UnbondingPeriod = UnbondingPeriod;
     type MaxModelIdLen = MaxModelIdLen;
     type MaxGpuUuidLen = MaxGpuUuidLen;
     type MaxModelsPerAgent = MaxModelsPerAgent;
     type WeightInfo = ();
-    type AdminOrigin = frame_system::EnsureSigned<AccountId>;
+    type FinalizationTip = FinalizationTip;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
     type OnAttestationConfirmed = MockAttestationSettler;
+    type ManageCommittee = MockCommittee;
+    type Randomness = TestRandomness;
+    type Slash = ();
+    type ChallengeBond = ChallengeBond;
+    type ChallengerSlashShare = ChallengerSlashShare;
+    type JurySize = JurySize;
+    type CommitDuration = CommitDuration;
+    type RevealDuration = RevealDuration;
+    type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+    type UnixTime = MockUnixTime;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = MockCommittee;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -0,0 +1,302 @@
+//! On-chain verification of TEE attestation reports.
+//!
+//! A report is a quote signed by a device key, with the X.509 chain certifying that key up to
+//! a vendor root. Certificates are DER encoded, leaf first and root last, and signed with
+//! ECDSA P-256/SHA-256 or P-384/SHA-384. Roots are trusted by their SEC1 public key.
+//!
+//! Quotes:
+//!
+//! - Intel SGX: DCAP quote v3. The quoting enclave report is signed by the PCK leaf and binds
+//!   the attestation key, which signs the quote header and enclave report. The measurement is
+//!   MRENCLAVE.
+//! - Intel TDX: DCAP quote v4, with the quoting enclave report in certification data of type 6.
+//!   The measurement is the SHA-256 of MRTD.
+//! - NVIDIA CC: SPDM 1.1 `GET_MEASUREMENTS` request followed by its signed `MEASUREMENTS`
+//!   response, signed by the device key over both messages. The measurement is the SHA-256 of
+//!   the measurement record.
+//!
+//! The report data is the first 32 bytes of REPORTDATA for SGX and TDX, and the requester
+//! nonce for NVIDIA CC.
+
+use der::{Decode, Encode};
+use p256::ecdsa::signature::Verifier;
+use sp_io::hashing::sha2_256;
+use sp_std::vec::Vec;
+use x509_cert::{ext::pkix::BasicConstraints, spki::DecodePublicKey, Certificate};
+
+/// ecdsa-with-SHA256
+const ECDSA_WITH_SHA256: der::oid::ObjectIdentifier =
+    der::oid::ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+/// ecdsa-with-SHA384
+const ECDSA_WITH_SHA384: der::oid::ObjectIdentifier =
+    der::oid::ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+
+pub const DCAP_HEADER_SIZE: usize = 48;
+pub const SGX_REPORT_BODY_SIZE: usize = 384;
+pub const TDX_REPORT_BODY_SIZE: usize = 584;
+pub const DCAP_ATTESTATION_KEY_TYPE_P256: u16 = 2;
+pub const SGX_TEE_TYPE: u32 = 0;
+pub const TDX_TEE_TYPE: u32 = 0x81;
+pub const QE_REPORT_SIZE: usize = 384;
+/// Certification data type holding the quoting enclave report in v4 quotes
+pub const QE_REPORT_CERTIFICATION_DATA: u16 = 6;
+pub const SPDM_VERSION_11: u8 = 0x11;
+pub const SPDM_GET_MEASUREMENTS: u8 = 0xe0;
+pub const SPDM_MEASUREMENTS: u8 = 0x60;
+pub const SPDM_REQUEST_SIZE: usize = 37;
+pub const SPDM_SIGNATURE_SIZE: usize = 96;
+
+/// Offset of REPORTDATA in an SGX enclave or quoting enclave report
+const SGX_REPORT_DATA_OFFSET: usize = 320;
+const SGX_MRENCLAVE_OFFSET: usize = 64;
+const TDX_MRTD_OFFSET: usize = 136;
+const TDX_REPORT_DATA_OFFSET: usize = 520;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TeeError {
+    /// A certificate is malformed, uses an unsupported key or algorithm, or is not issued by
+    /// the next one of the chain
+    InvalidCertificate,
+    /// A certificate is not valid at the current time
+    CertificateExpired,
+    /// The quote is malformed or not signed by the certified key
+    InvalidQuote,
+}
+
+/// Measurement and report data attested by a verified quote
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct QuoteClaims {
+    pub measurement: [u8; 32],
+    pub report_data: [u8; 32],
+}
+
+/// Public key of a certificate
+#[derive(Clone, Debug)]
+pub enum TeeKey {
+    P256(p256::ecdsa::VerifyingKey),
+    P384(p384::ecdsa::VerifyingKey),
+}
+
+impl TeeKey {
+    fn of(certificate: &Certificate) -> Result<Self, TeeError> {
+        let spki = certificate
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .map_err(|_| TeeError::InvalidCertificate)?;
+        p256::ecdsa::VerifyingKey::from_public_key_der(&spki)
+            .map(TeeKey::P256)
+            .or_else(|_| p384::ecdsa::VerifyingKey::from_public_key_der(&spki).map(TeeKey::P384))
+            .map_err(|_| TeeError::InvalidCertificate)
+    }
+
+    /// Uncompressed SEC1 encoding of the key
+    pub fn to_sec1(&self) -> Vec<u8> {
+        match self {
+            TeeKey::P256(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+            TeeKey::P384(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+        }
+    }
+
+    /// Check the DER `signature` of `certificate` by this key
+    fn verifies_certificate(&self, certificate: &Certificate) -> bool {
+        let (tbs, signature) =
+            match (certificate.tbs_certificate.to_der(), certificate.signature.as_bytes()) {
+                (Ok(tbs), Some(signature)) => (tbs, signature),
+                _ => return false,
+            };
+        let algorithm = certificate.signature_algorithm.oid;
+        match self {
+            TeeKey::P256(key) if algorithm == ECDSA_WITH_SHA256 => {
+                p256::ecdsa::Signature::from_der(signature)
+                    .is_ok_and(|signature| key.verify(&tbs, &signature).is_ok())
+            },
+            TeeKey::P384(key) if algorithm == ECDSA_WITH_SHA384 => {
+                p384::ecdsa::Signature::from_der(signature)
+                    .is_ok_and(|signature| key.verify(&tbs, &signature).is_ok())
+            },
+            _ => false,
+        }
+    }
+
+    /// Check the `r || s` `signature` of `message` by this key
+    fn verifies(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            TeeKey::P256(key) => p256::ecdsa::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok()),
+            TeeKey::P384(key) => p384::ecdsa::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok()),
+        }
+    }
+}
+
+/// Key of the self-signed root certificate `root`
+pub fn root_key(root: &[u8]) -> Result<TeeKey, TeeError> {
+    let root = Certificate::from_der(root).map_err(|_| TeeError::InvalidCertificate)?;
+    let key = TeeKey::of(&root)?;
+    if !is_issuer(&root, &root) || !key.verifies_certificate(&root) {
+        return Err(TeeError::InvalidCertificate)
+    }
+    Ok(key)
+}
+
+/// Check each certificate of `chain` is valid at `now`, in seconds since the Unix epoch, and
+/// issued by the next one. Returns the keys of the leaf and of the root.
+pub fn verify_chain<C: AsRef<[u8]>>(chain: &[C], now: u64) -> Result<(TeeKey, TeeKey), TeeError> {
+    let certificates = chain
+        .iter()
+        .map(|certificate| Certificate::from_der(certificate.as_ref()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| TeeError::InvalidCertificate)?;
+    let (leaf, root) = match (certificates.first(), certificates.last()) {
+        (Some(leaf), Some(root)) => (leaf, root),
+        _ => return Err(TeeError::InvalidCertificate),
+    };
+
+    for certificate in &certificates {
+        let validity = &certificate.tbs_certificate.validity;
+        if now < validity.not_before.to_unix_duration().as_secs() ||
+            now > validity.not_after.to_unix_duration().as_secs()
+        {
+            return Err(TeeError::CertificateExpired)
+        }
+    }
+    for pair in certificates.windows(2) {
+        let (certificate, issuer) = (&pair[0], &pair[1]);
+        if !is_issuer(certificate, issuer) || !TeeKey::of(issuer)?.verifies_certificate(certificate)
+        {
+            return Err(TeeError::InvalidCertificate)
+        }
+    }
+    Ok((TeeKey::of(leaf)?, TeeKey::of(root)?))
+}
+
+/// Whether `issuer` is a certificate authority named as the issuer of `certificate`
+fn is_issuer(certificate: &Certificate, issuer: &Certificate) -> bool {
+    let is_ca = matches!(
+        issuer.tbs_certificate.get::<BasicConstraints>(),
+        Ok(Some((_, BasicConstraints { ca: true, .. })))
+    );
+    is_ca && certificate.tbs_certificate.issuer == issuer.tbs_certificate.subject
+}
+
+/// Verify an SGX DCAP quote v3 whose quoting enclave report is signed by `pck`
+pub fn verify_sgx_quote(quote: &[u8], pck: &TeeKey) -> Result<QuoteClaims, TeeError> {
+    let body = verify_dcap_quote(quote, pck, 3, SGX_TEE_TYPE, SGX_REPORT_BODY_SIZE)?;
+    Ok(QuoteClaims {
+        measurement: array(&body[SGX_MRENCLAVE_OFFSET..])?,
+        report_data: array(&body[SGX_REPORT_DATA_OFFSET..])?,
+    })
+}
+
+/// Verify a TDX DCAP quote v4 whose quoting enclave report is signed by `pck`
+pub fn verify_tdx_quote(quote: &[u8], pck: &TeeKey) -> Result<QuoteClaims, TeeError> {
+    let body = verify_dcap_quote(quote, pck, 4, TDX_TEE_TYPE, TDX_REPORT_BODY_SIZE)?;
+    let mr_td = body.get(TDX_MRTD_OFFSET..TDX_MRTD_OFFSET + 48).ok_or(TeeError::InvalidQuote)?;
+    Ok(QuoteClaims {
+        measurement: sha2_256(mr_td),
+        report_data: array(&body[TDX_REPORT_DATA_OFFSET..])?,
+    })
+}
+
+/// Check the signatures of a DCAP quote and return its report body
+fn verify_dcap_quote<'a>(
+    quote: &'a [u8],
+    pck: &TeeKey,
+    version: u16,
+    tee_type: u32,
+    body_size: usize,
+) -> Result<&'a [u8], TeeError> {
+    let mut reader = Reader(quote);
+    let header = reader.take(DCAP_HEADER_SIZE)?;
+    if u16::from_le_bytes(array(header)?) != version ||
+        u16::from_le_bytes(array(&header[2..])?) != DCAP_ATTESTATION_KEY_TYPE_P256 ||
+        u32::from_le_bytes(array(&header[4..])?) != tee_type
+    {
+        return Err(TeeError::InvalidQuote)
+    }
+    let body = reader.take(body_size)?;
+    let signed = &quote[..DCAP_HEADER_SIZE + body_size];
+
+    let signature_data_size = u32::from_le_bytes(array(reader.take(4)?)?) as usize;
+    let mut reader = Reader(reader.take(signature_data_size)?);
+    let signature = reader.take(64)?;
+    let attestation_key = reader.take(64)?;
+    if version == 4 {
+        if u16::from_le_bytes(array(reader.take(2)?)?) != QE_REPORT_CERTIFICATION_DATA {
+            return Err(TeeError::InvalidQuote)
+        }
+        let size = u32::from_le_bytes(array(reader.take(4)?)?) as usize;
+        reader = Reader(reader.take(size)?);
+    }
+    let qe_report = reader.take(QE_REPORT_SIZE)?;
+    let qe_report_signature = reader.take(64)?;
+    let qe_auth_data_size = u16::from_le_bytes(array(reader.take(2)?)?) as usize;
+    let qe_auth_data = reader.take(qe_auth_data_size)?;
+
+    // The quoting enclave vouches for the attestation key in its report data
+    if !matches!(pck, TeeKey::P256(_)) || !pck.verifies(qe_report, qe_report_signature) {
+        return Err(TeeError::InvalidQuote)
+    }
+    let bound_key = sha2_256(&[attestation_key, qe_auth_data].concat());
+    let qe_report_data = &qe_report[SGX_REPORT_DATA_OFFSET..];
+    if qe_report_data[..32] != bound_key || qe_report_data[32..].iter().any(|byte| *byte != 0) {
+        return Err(TeeError::InvalidQuote)
+    }
+
+    let attestation_key =
+        p256::ecdsa::VerifyingKey::from_sec1_bytes(&[&[0x04][..], attestation_key].concat())
+            .map_err(|_| TeeError::InvalidQuote)?;
+    if !TeeKey::P256(attestation_key).verifies(signed, signature) {
+        return Err(TeeError::InvalidQuote)
+    }
+    Ok(body)
+}
+
+/// Verify an NVIDIA CC SPDM 1.1 measurement report signed by `device`
+pub fn verify_spdm_measurements(report: &[u8], device: &TeeKey) -> Result<QuoteClaims, TeeError> {
+    if !matches!(device, TeeKey::P384(_)) || report.len() < SPDM_SIGNATURE_SIZE {
+        return Err(TeeError::InvalidQuote)
+    }
+    let (signed, signature) = report.split_at(report.len() - SPDM_SIGNATURE_SIZE);
+
+    let mut reader = Reader(signed);
+    let request = reader.take(SPDM_REQUEST_SIZE)?;
+    if request[0] != SPDM_VERSION_11 || request[1] != SPDM_GET_MEASUREMENTS {
+        return Err(TeeError::InvalidQuote)
+    }
+    let response = reader.take(8)?;
+    if response[0] != SPDM_VERSION_11 || response[1] != SPDM_MEASUREMENTS {
+        return Err(TeeError::InvalidQuote)
+    }
+    let record_size = u32::from_le_bytes([response[5], response[6], response[7], 0]) as usize;
+    let record = reader.take(record_size)?;
+    let _responder_nonce = reader.take(32)?;
+    let opaque_size = u16::from_le_bytes(array(reader.take(2)?)?) as usize;
+    reader.take(opaque_size)?;
+    if !reader.0.is_empty() || !device.verifies(signed, signature) {
+        return Err(TeeError::InvalidQuote)
+    }
+    Ok(QuoteClaims { measurement: sha2_256(record), report_data: array(&request[4..])? })
+}
+
+/// Cursor over the fields of a quote
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, size: usize) -> Result<&'a [u8], TeeError> {
+        if self.0.len() < size {
+            return Err(TeeError::InvalidQuote)
+        }
+        let (field, rest) = self.0.split_at(size);
+        self.0 = rest;
+        Ok(field)
+    }
+}
+
+fn array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], TeeError> {
+    bytes
+        .get(..N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(TeeError::InvalidQuote)
+}
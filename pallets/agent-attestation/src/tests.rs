@@ -1,6 +1,11 @@
 use crate::mock::*;
-use crate::pallet::{AttestationStatus, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::pallet::{
+    AttestationOrder, AttestationOrders, AttestationStatus, Error, Event, FinalizationQueue, ProviderFilter,
+    TeeCertificate, TeeKind, TeeReport,
+};
+use crate::tee;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use codec::Encode;
 use sp_core::H256;
 
 fn gpu_uuid() -> Vec<u8> {
@@ -75,6 +80,171 @@ fn heartbeat_too_early_fails() {
     });
 }
 
+#[test]
+fn nodes_missing_heartbeats_are_deactivated_and_slashed() {
+    new_test_ext().execute_with(|| {
+        RegistrationBond::set(1_000);
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        assert_eq!(Balances::reserved_balance(1), 1_000);
+
+        // Two missed heartbeats are tolerated
+        AgentAttestation::on_initialize(300);
+        assert!(AgentAttestation::node_of(1).unwrap().is_active);
+
+        AgentAttestation::on_initialize(301);
+        let node = AgentAttestation::node_of(1).unwrap();
+        assert!(!node.is_active);
+        assert_eq!(node.bond, 900);
+        assert_eq!(Balances::reserved_balance(1), 900);
+        System::assert_has_event(
+            Event::NodeDeactivated { who: 1, last_heartbeat: 1, slashed: 100 }.into(),
+        );
+
+        // Inactive nodes are not slashed again
+        AgentAttestation::on_initialize(302);
+        assert_eq!(Balances::reserved_balance(1), 900);
+    });
+}
+
+#[test]
+fn heartbeat_reactivates_a_deactivated_node() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        AgentAttestation::on_initialize(301);
+        assert!(!AgentAttestation::node_of(1).unwrap().is_active);
+
+        System::set_block_number(305);
+        assert_ok!(AgentAttestation::heartbeat(RuntimeOrigin::signed(1)));
+        let node = AgentAttestation::node_of(1).unwrap();
+        assert!(node.is_active);
+        assert_eq!(node.last_heartbeat, 305);
+        System::assert_has_event(Event::NodeReactivated { who: 1 }.into());
+
+        AgentAttestation::on_initialize(604);
+        assert!(AgentAttestation::node_of(1).unwrap().is_active);
+    });
+}
+
+#[test]
+fn liveness_sweep_resumes_where_it_stopped() {
+    new_test_ext().execute_with(|| {
+        for who in 1..=3 {
+            assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(who), gpu_uuid(), 120));
+        }
+        let inactive = || crate::Nodes::<Test>::iter_values().filter(|node| !node.is_active).count();
+
+        // Only two nodes are checked per block
+        AgentAttestation::on_initialize(301);
+        assert_eq!(inactive(), 2);
+        assert!(crate::LivenessCursor::<Test>::get().is_some());
+
+        AgentAttestation::on_initialize(302);
+        assert_eq!(inactive(), 3);
+        assert!(crate::LivenessCursor::<Test>::get().is_none());
+    });
+}
+
+#[test]
+fn deregister_node_unbonds_and_clears_provider_indexes() {
+    new_test_ext().execute_with(|| {
+        RegistrationBond::set(1_000);
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        assert_ok!(AgentAttestation::update_capability(
+            RuntimeOrigin::signed(1),
+            vec![model_id(), model_id_2()],
+            8,
+            10,
+            b"us-west".to_vec(),
+        ));
+
+        assert_ok!(AgentAttestation::deregister_node(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::NodeDeregistered { who: 1, unbonds_at: 21 }.into());
+        assert!(!AgentAttestation::node_of(1).unwrap().is_active);
+        assert!(AgentAttestation::agent_capability(1).is_none());
+        let model_bounded: frame_support::BoundedVec<u8, MaxModelIdLen> = model_id().try_into().unwrap();
+        assert!(!AgentAttestation::model_providers(&model_bounded, 1));
+        assert!(AgentAttestation::get_providers_for_model(&model_bounded).is_empty());
+
+        System::set_block_number(20);
+        assert_noop!(
+            AgentAttestation::withdraw_node_bond(RuntimeOrigin::signed(1)),
+            Error::<Test>::UnbondingNotFinished
+        );
+
+        System::set_block_number(21);
+        assert_ok!(AgentAttestation::withdraw_node_bond(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::NodeBondWithdrawn { who: 1, amount: 1_000 }.into());
+        assert!(AgentAttestation::node_of(1).is_none());
+        assert!(AgentAttestation::unbonding_of(1).is_none());
+        assert_eq!(Balances::reserved_balance(1), 0);
+
+        // The account may register again
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+    });
+}
+
+#[test]
+fn deregister_node_waits_for_pending_attestations() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        assert_ok!(AgentAttestation::submit_attestation(
+            RuntimeOrigin::signed(1),
+            1,
+            H256::from_low_u64_be(42),
+            model_id(),
+            1000,
+            500,
+        ));
+        assert_eq!(AgentAttestation::pending_attestations(1), 1);
+        assert_noop!(
+            AgentAttestation::deregister_node(RuntimeOrigin::signed(1)),
+            Error::<Test>::AttestationsPending
+        );
+
+        System::set_block_number(52);
+        AgentAttestation::on_initialize(52);
+        assert_eq!(AgentAttestation::pending_attestations(1), 0);
+        assert_ok!(AgentAttestation::deregister_node(RuntimeOrigin::signed(1)));
+    });
+}
+
+#[test]
+fn unbonding_nodes_cannot_serve() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AgentAttestation::withdraw_node_bond(RuntimeOrigin::signed(1)),
+            Error::<Test>::NotUnbonding
+        );
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        assert_ok!(AgentAttestation::deregister_node(RuntimeOrigin::signed(1)));
+
+        System::set_block_number(102);
+        assert_noop!(
+            AgentAttestation::heartbeat(RuntimeOrigin::signed(1)),
+            Error::<Test>::NodeUnbonding
+        );
+        assert_noop!(
+            AgentAttestation::submit_attestation(
+                RuntimeOrigin::signed(1),
+                1,
+                H256::from_low_u64_be(42),
+                model_id(),
+                1000,
+                500,
+            ),
+            Error::<Test>::NodeUnbonding
+        );
+        assert_noop!(
+            AgentAttestation::update_capability(RuntimeOrigin::signed(1), vec![model_id()], 8, 10, b"us-west".to_vec()),
+            Error::<Test>::NodeUnbonding
+        );
+        assert_noop!(
+            AgentAttestation::deregister_node(RuntimeOrigin::signed(1)),
+            Error::<Test>::NodeUnbonding
+        );
+    });
+}
+
 #[test]
 fn submit_attestation_works() {
     new_test_ext().execute_with(|| {
@@ -187,6 +357,7 @@ fn confirm_attestation_works() {
         ));
         let reserved = Balances::reserved_balance(1);
         assert_eq!(reserved, 1_000);
+        let free = Balances::free_balance(1);
         System::set_block_number(52);
         assert_ok!(AgentAttestation::confirm_attestation(
             RuntimeOrigin::signed(3),
@@ -196,12 +367,145 @@ fn confirm_attestation_works() {
         assert!(matches!(att.status, AttestationStatus::Confirmed));
         let reserved_after = Balances::reserved_balance(1);
         assert_eq!(reserved_after, 0);
+        // The caller is tipped out of the deposit
+        assert_eq!(Balances::free_balance(1), free + 1_000 - FinalizationTip::get());
+        assert_eq!(Balances::free_balance(3), 1_000_000_000_000 + FinalizationTip::get());
+        // Not backed by a task order, so nothing to settle
+        SETTLED.with(|s| assert!(s.borrow().is_empty()));
     });
 }
 
+fn submit(attester: u64, task_id: u64) {
+    assert_ok!(AgentAttestation::submit_attestation(
+        RuntimeOrigin::signed(attester),
+        task_id,
+        H256::from_low_u64_be(task_id),
+        model_id(),
+        1000,
+        500,
+    ));
+}
+
 #[test]
-fn resolve_challenge_slash_works() {
+fn on_initialize_finalizes_unchallenged_attestations() {
     new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        submit(1, 1);
+        submit(1, 2);
+        assert_eq!(AgentAttestation::finalization_queue(51).to_vec(), vec![0, 1]);
+        assert_ok!(AgentAttestation::challenge_attestation(RuntimeOrigin::signed(2), 1));
+
+        // The window is still open during block 51
+        System::set_block_number(51);
+        AgentAttestation::on_initialize(51);
+        assert!(matches!(AgentAttestation::attestation_of(0).unwrap().status, AttestationStatus::Pending));
+
+        System::set_block_number(52);
+        AgentAttestation::on_initialize(52);
+        assert!(matches!(AgentAttestation::attestation_of(0).unwrap().status, AttestationStatus::Confirmed));
+        // The challenged attestation waits for its jury
+        assert!(matches!(AgentAttestation::attestation_of(1).unwrap().status, AttestationStatus::Pending));
+        assert!(!FinalizationQueue::<Test>::contains_key(51));
+        // Nobody is tipped for automatic finalization
+        assert_eq!(Balances::reserved_balance(1), 1_000);
+        System::assert_has_event(Event::AttestationConfirmed { id: 0 }.into());
+    });
+}
+
+#[test]
+fn full_finalization_queues_carry_over_to_later_blocks() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        submit(1, 1);
+        submit(1, 2);
+        submit(1, 3);
+        submit(1, 4);
+        assert_eq!(AgentAttestation::finalization_queue(51).to_vec(), vec![0, 1]);
+        assert_eq!(AgentAttestation::finalization_queue(52).to_vec(), vec![2, 3]);
+
+        System::set_block_number(52);
+        AgentAttestation::on_initialize(52);
+        assert!(matches!(AgentAttestation::attestation_of(2).unwrap().status, AttestationStatus::Pending));
+        assert_noop!(
+            AgentAttestation::confirm_attestation(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::InvalidStatus
+        );
+
+        // Carried over attestations can still be confirmed by signed call in the meantime.
+        // The attester confirming its own attestation is not tipped.
+        assert_ok!(AgentAttestation::confirm_attestation(RuntimeOrigin::signed(1), 2));
+        assert!(matches!(AgentAttestation::attestation_of(2).unwrap().status, AttestationStatus::Confirmed));
+
+        System::set_block_number(53);
+        AgentAttestation::on_initialize(53);
+        assert!(matches!(AgentAttestation::attestation_of(3).unwrap().status, AttestationStatus::Confirmed));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000_000_000_000);
+    });
+}
+
+/// Submits attestation 0 from account 1, challenges it from account 2 and returns the jury
+fn open_dispute() -> Vec<u64> {
+    assert_ok!(AgentAttestation::register_node(
+        RuntimeOrigin::signed(1),
+        gpu_uuid(),
+        120,
+    ));
+    assert_ok!(AgentAttestation::submit_attestation(
+        RuntimeOrigin::signed(1),
+        1,
+        H256::from_low_u64_be(42),
+        model_id(),
+        1000,
+        500,
+    ));
+    assert_ok!(AgentAttestation::challenge_attestation(
+        RuntimeOrigin::signed(2),
+        0,
+    ));
+    AgentAttestation::dispute_of(0).unwrap().jury.into_inner()
+}
+
+fn commit(juror: u64, guilty: bool) {
+    let hash = AgentAttestation::vote_hash(0, juror.to_le_bytes().to_vec(), guilty);
+    assert_ok!(AgentAttestation::commit_vote(RuntimeOrigin::signed(juror), 0, hash));
+}
+
+fn reveal(juror: u64, guilty: bool) {
+    assert_ok!(AgentAttestation::reveal_vote(
+        RuntimeOrigin::signed(juror),
+        0,
+        juror.to_le_bytes().to_vec(),
+        guilty,
+    ));
+}
+
+#[test]
+fn challenge_reserves_bond_and_draws_jury() {
+    new_test_ext().execute_with(|| {
+        // A party to the dispute is never drawn as a juror
+        COMMITTEE.with(|c| c.borrow_mut().push(2));
+        let jury = open_dispute();
+
+        assert_eq!(jury.len(), 3);
+        assert!(jury.iter().all(|juror| (10..=14).contains(juror)));
+        let mut unique = jury.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 3);
+
+        assert_eq!(Balances::reserved_balance(2), ChallengeBond::get());
+        let dispute = AgentAttestation::dispute_of(0).unwrap();
+        assert_eq!(dispute.challenger, 2);
+        assert_eq!(dispute.commit_end, 11);
+        assert_eq!(dispute.reveal_end, 21);
+    });
+}
+
+#[test]
+fn challenge_without_enough_jurors_fails() {
+    new_test_ext().execute_with(|| {
+        COMMITTEE.with(|c| *c.borrow_mut() = vec![10, 11]);
         assert_ok!(AgentAttestation::register_node(
             RuntimeOrigin::signed(1),
             gpu_uuid(),
@@ -215,26 +519,176 @@ fn resolve_challenge_slash_works() {
             1000,
             500,
         ));
-        assert_ok!(AgentAttestation::challenge_attestation(
-            RuntimeOrigin::signed(2),
-            0,
-        ));
+        assert_noop!(
+            AgentAttestation::challenge_attestation(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::NotEnoughJurors
+        );
+    });
+}
+
+#[test]
+fn jury_votes_follow_commit_reveal() {
+    new_test_ext().execute_with(|| {
+        let jury = open_dispute();
+
+        let hash = AgentAttestation::vote_hash(0, b"salt".to_vec(), true);
+        assert_noop!(
+            AgentAttestation::commit_vote(RuntimeOrigin::signed(3), 0, hash),
+            Error::<Test>::NotJuror
+        );
+
+        commit(jury[0], true);
+        assert_noop!(
+            AgentAttestation::commit_vote(RuntimeOrigin::signed(jury[0]), 0, hash),
+            Error::<Test>::AlreadyCommitted
+        );
+        // Reveals wait for the other jurors or the end of the commit phase
+        assert_noop!(
+            AgentAttestation::reveal_vote(RuntimeOrigin::signed(jury[0]), 0, jury[0].to_le_bytes().to_vec(), true),
+            Error::<Test>::RevealPhaseNotStarted
+        );
+
+        System::set_block_number(12);
+        assert_noop!(
+            AgentAttestation::commit_vote(RuntimeOrigin::signed(jury[1]), 0, hash),
+            Error::<Test>::CommitPhaseOver
+        );
+        assert_noop!(
+            AgentAttestation::reveal_vote(RuntimeOrigin::signed(jury[0]), 0, jury[0].to_le_bytes().to_vec(), false),
+            Error::<Test>::VoteNotMatchHash
+        );
+        assert_noop!(
+            AgentAttestation::resolve_challenge(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::DisputeNotFinished
+        );
+
+        reveal(jury[0], true);
+        assert_eq!(AgentAttestation::juror_vote(0, jury[0]).unwrap().revealed, Some(true));
+        // The other jurors never voted, so the dispute ends with the reveal phase
+        System::set_block_number(22);
+        assert_ok!(AgentAttestation::resolve_challenge(RuntimeOrigin::signed(3), 0));
+        assert!(AgentAttestation::dispute_of(0).is_none());
+        assert!(AgentAttestation::juror_vote(0, jury[0]).is_none());
+    });
+}
+
+#[test]
+fn resolve_challenge_slash_works() {
+    new_test_ext().execute_with(|| {
+        let jury = open_dispute();
+        for juror in &jury {
+            commit(*juror, *juror != jury[2]);
+        }
+        for juror in &jury {
+            reveal(*juror, *juror != jury[2]);
+        }
+
         let balance_before = Balances::free_balance(1);
+        let challenger_before = Balances::free_balance(2);
+        let issuance_before = Balances::total_issuance();
         assert_ok!(AgentAttestation::resolve_challenge(
-            RuntimeOrigin::root(),
+            RuntimeOrigin::signed(3),
             0,
-            true,
         ));
         let att = AgentAttestation::attestation_of(0).unwrap();
         assert!(matches!(att.status, AttestationStatus::Slashed));
         let balance_after = Balances::free_balance(1);
         // 500 of 1000 deposit slashed, remainder 500 unreserved back
         assert_eq!(balance_after, balance_before + 500);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        // The challenger gets the bond back plus 60% of the slash; the treasury takes the rest
+        assert_eq!(Balances::free_balance(2), challenger_before + ChallengeBond::get() + 300);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::total_issuance(), issuance_before - 200);
     });
 }
 
 #[test]
 fn resolve_challenge_defend_works() {
+    new_test_ext().execute_with(|| {
+        let jury = open_dispute();
+        for juror in &jury {
+            commit(*juror, false);
+        }
+        for juror in &jury {
+            reveal(*juror, false);
+        }
+
+        let balance_before = Balances::free_balance(1);
+        let challenger_before = Balances::free_balance(2);
+        let issuance_before = Balances::total_issuance();
+        assert_ok!(AgentAttestation::resolve_challenge(
+            RuntimeOrigin::signed(3),
+            0,
+        ));
+        let att = AgentAttestation::attestation_of(0).unwrap();
+        assert!(matches!(att.status, AttestationStatus::Defended));
+        let balance_after = Balances::free_balance(1);
+        assert_eq!(balance_after - balance_before, 1_000);
+        // The losing challenger forfeits the bond to the treasury
+        assert_eq!(Balances::free_balance(2), challenger_before);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::total_issuance(), issuance_before - ChallengeBond::get());
+    });
+}
+
+/// Opens a dispute over attestation 0 backed by order 9 and resolves it with a unanimous jury
+fn resolve_order_dispute(guilty: bool) {
+    let jury = open_dispute();
+    AttestationOrders::<Test>::insert(
+        0,
+        AttestationOrder::<Test> { order_id: 9, customer: 2, agreed_price: 34_000 },
+    );
+    for juror in &jury {
+        commit(*juror, guilty);
+    }
+    for juror in &jury {
+        reveal(*juror, guilty);
+    }
+    assert_ok!(AgentAttestation::resolve_challenge(RuntimeOrigin::signed(3), 0));
+}
+
+#[test]
+fn resolve_challenge_defended_settles_the_order() {
+    new_test_ext().execute_with(|| {
+        resolve_order_dispute(false);
+        SETTLED.with(|s| assert_eq!(*s.borrow(), vec![(9, 2, 1, 34_000, 0)]));
+        REFUNDED.with(|r| assert!(r.borrow().is_empty()));
+        assert_eq!(AgentAttestation::completed_attestations(1), 1);
+    });
+}
+
+#[test]
+fn resolve_challenge_slashed_refunds_the_order() {
+    new_test_ext().execute_with(|| {
+        resolve_order_dispute(true);
+        // The customer gets the reserve back and the attester is never paid
+        SETTLED.with(|s| assert!(s.borrow().is_empty()));
+        REFUNDED.with(|r| assert_eq!(*r.borrow(), vec![(9, 2, 0)]));
+        System::assert_has_event(Event::AttestationRefunded { id: 0, order_id: 9, amount: 34_000 }.into());
+        // Slashed attestations do not count toward the attester's history
+        assert_eq!(AgentAttestation::completed_attestations(1), 0);
+    });
+}
+
+#[test]
+fn resolve_challenge_without_votes_returns_bond() {
+    new_test_ext().execute_with(|| {
+        open_dispute();
+        System::set_block_number(22);
+
+        let challenger_before = Balances::free_balance(2);
+        assert_ok!(AgentAttestation::resolve_challenge(RuntimeOrigin::signed(3), 0));
+        let att = AgentAttestation::attestation_of(0).unwrap();
+        assert!(matches!(att.status, AttestationStatus::Defended));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(2), challenger_before + ChallengeBond::get());
+    });
+}
+
+#[test]
+fn migration_v2_reopens_unbonded_challenges() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
     new_test_ext().execute_with(|| {
         assert_ok!(AgentAttestation::register_node(
             RuntimeOrigin::signed(1),
@@ -249,20 +703,17 @@ fn resolve_challenge_defend_works() {
             1000,
             500,
         ));
-        assert_ok!(AgentAttestation::challenge_attestation(
-            RuntimeOrigin::signed(2),
-            0,
-        ));
-        let balance_before = Balances::free_balance(1);
-        assert_ok!(AgentAttestation::resolve_challenge(
-            RuntimeOrigin::root(),
-            0,
-            false,
-        ));
+        crate::Attestations::<Test>::mutate(0, |att| att.as_mut().unwrap().challenger = Some(2));
+        StorageVersion::new(1).put::<AgentAttestation>();
+        System::set_block_number(40);
+
+        crate::migrations::v2::migrate::<Test>();
+
         let att = AgentAttestation::attestation_of(0).unwrap();
-        assert!(matches!(att.status, AttestationStatus::Defended));
-        let balance_after = Balances::free_balance(1);
-        assert_eq!(balance_after - balance_before, 1_000);
+        assert_eq!(att.challenger, None);
+        assert_eq!(att.challenge_end, 40 + ChallengeWindow::get());
+        assert_eq!(AgentAttestation::on_chain_storage_version(), 2);
+        assert_ok!(AgentAttestation::challenge_attestation(RuntimeOrigin::signed(2), 0));
     });
 }
 
@@ -306,3 +757,626 @@ fn update_capability_works() {
         assert!(old_providers.is_empty());
     });
 }
+
+fn serve_model(who: u64, price_per_token: u128, region: &[u8], max_concurrent: u32) {
+    assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(who), gpu_uuid(), 120));
+    assert_ok!(AgentAttestation::update_capability(
+        RuntimeOrigin::signed(who),
+        vec![model_id()],
+        max_concurrent,
+        price_per_token,
+        region.to_vec(),
+    ));
+}
+
+fn providers(filter: ProviderFilter<u128>, offset: u32, limit: u32) -> Vec<u64> {
+    AgentAttestation::discover_providers(&model_id(), &filter, offset, limit)
+        .into_iter()
+        .map(|p| p.account)
+        .collect()
+}
+
+#[test]
+fn discover_providers_filters_and_paginates() {
+    new_test_ext().execute_with(|| {
+        serve_model(1, 10, b"us-west", 8);
+        serve_model(2, 20, b"eu", 4);
+        serve_model(3, 5, b"us-west", 1);
+        // Provider 3 has its only slot taken
+        assert_ok!(AgentAttestation::submit_attestation(
+            RuntimeOrigin::signed(3),
+            1,
+            H256::from_low_u64_be(42),
+            model_id(),
+            1000,
+            500,
+        ));
+
+        // Cheapest first when history and liveness are equal
+        assert_eq!(providers(ProviderFilter::default(), 0, 10), vec![3, 1, 2]);
+        let found = AgentAttestation::discover_providers(&model_id(), &ProviderFilter::default(), 0, 10);
+        assert_eq!(found[0].free_concurrency, 0);
+        assert_eq!(found[1].free_concurrency, 8);
+
+        let in_region = ProviderFilter { region: Some(b"us-west".to_vec()), ..Default::default() };
+        assert_eq!(providers(in_region, 0, 10), vec![3, 1]);
+        let affordable = ProviderFilter { max_price: Some(10), ..Default::default() };
+        assert_eq!(providers(affordable, 0, 10), vec![3, 1]);
+        let with_capacity = ProviderFilter { min_free_concurrency: 1, ..Default::default() };
+        assert_eq!(providers(with_capacity, 0, 10), vec![1, 2]);
+
+        assert_eq!(providers(ProviderFilter::default(), 1, 1), vec![1]);
+        assert!(providers(ProviderFilter::default(), 3, 10).is_empty());
+        assert!(AgentAttestation::discover_providers(b"unknown", &ProviderFilter::default(), 0, 10).is_empty());
+    });
+}
+
+#[test]
+fn discover_providers_ranks_history_and_liveness() {
+    new_test_ext().execute_with(|| {
+        serve_model(1, 10, b"us-west", 8);
+        serve_model(2, 5, b"us-west", 8);
+        assert_eq!(providers(ProviderFilter::default(), 0, 10), vec![2, 1]);
+
+        // A completed task outweighs twice the price
+        assert_ok!(AgentAttestation::submit_attestation(
+            RuntimeOrigin::signed(1),
+            1,
+            H256::from_low_u64_be(42),
+            model_id(),
+            1000,
+            500,
+        ));
+        System::set_block_number(52);
+        AgentAttestation::on_initialize(52);
+        assert_eq!(providers(ProviderFilter::default(), 0, 10), vec![1, 2]);
+
+        // Provider 1 nears its liveness deadline while provider 2 just sent a heartbeat
+        System::set_block_number(290);
+        assert_ok!(AgentAttestation::heartbeat(RuntimeOrigin::signed(2)));
+        assert_eq!(providers(ProviderFilter::default(), 0, 10), vec![2, 1]);
+
+        // Deactivated providers are not returned
+        AgentAttestation::on_initialize(301);
+        assert_eq!(providers(ProviderFilter::default(), 0, 10), vec![2]);
+    });
+}
+
+#[test]
+fn order_backed_attestation_settles_with_order_context() {
+    use dbc_support::traits::{TaskCompletionHandler, TaskOrderContext};
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(
+            RuntimeOrigin::signed(1),
+            gpu_uuid(),
+            120,
+        ));
+        let order = TaskOrderContext {
+            order_id: 9,
+            customer: 2,
+            model_id: model_id(),
+            input_tokens: 1000,
+            output_tokens: 500,
+            agreed_price: 34_000,
+        };
+        let id = <AgentAttestation as TaskCompletionHandler>::on_task_completed(
+            &1,
+            5,
+            H256::from_low_u64_be(42),
+            Some(&order),
+        )
+        .unwrap();
+
+        let att = AgentAttestation::attestation_of(id).unwrap();
+        assert_eq!(att.model_id.to_vec(), model_id());
+        assert_eq!(att.input_tokens, 1000);
+        assert_eq!(att.output_tokens, 500);
+        let provenance = AgentAttestation::attestation_order(id).unwrap();
+        assert_eq!(provenance.order_id, 9);
+        assert_eq!(provenance.customer, 2);
+
+        System::set_block_number(52);
+        assert_ok!(AgentAttestation::confirm_attestation(RuntimeOrigin::signed(3), id));
+        SETTLED.with(|s| assert_eq!(*s.borrow(), vec![(9, 2, 1, 34_000, id)]));
+    });
+}
+
+#[test]
+fn failed_settlement_can_be_retried() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        submit(1, 1);
+        AttestationOrders::<Test>::insert(
+            0,
+            AttestationOrder::<Test> { order_id: 9, customer: 2, agreed_price: 34_000 },
+        );
+        assert_noop!(
+            AgentAttestation::retry_settlement(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::SettlementNotPending
+        );
+
+        REJECT_SETTLEMENT.with(|r| *r.borrow_mut() = true);
+        System::set_block_number(52);
+        assert_ok!(AgentAttestation::confirm_attestation(RuntimeOrigin::signed(3), 0));
+        assert!(matches!(AgentAttestation::attestation_of(0).unwrap().status, AttestationStatus::Confirmed));
+        System::assert_has_event(Event::SettlementFailed { id: 0, order_id: 9 }.into());
+        assert!(AgentAttestation::unsettled_attestation(0).is_some());
+        assert_noop!(
+            AgentAttestation::retry_settlement(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::SettlementRejected
+        );
+
+        REJECT_SETTLEMENT.with(|r| *r.borrow_mut() = false);
+        assert_ok!(AgentAttestation::retry_settlement(RuntimeOrigin::signed(3), 0));
+        SETTLED.with(|s| assert_eq!(*s.borrow(), vec![(9, 2, 1, 34_000, 0)]));
+        System::assert_has_event(Event::AttestationSettled { id: 0, order_id: 9, receipt_id: 0 }.into());
+        assert!(AgentAttestation::unsettled_attestation(0).is_none());
+        assert_noop!(
+            AgentAttestation::retry_settlement(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::SettlementNotPending
+        );
+    });
+}
+
+/// Certificate of `name` for a fresh key, a certificate authority when `ca`
+fn fake_certificate(name: &str, alg: &'static rcgen::SignatureAlgorithm, ca: bool) -> rcgen::Certificate {
+    let mut params = rcgen::CertificateParams::new(vec![]);
+    params.alg = alg;
+    params.distinguished_name.push(rcgen::DnType::CommonName, name);
+    params.not_before = rcgen::date_time_ymd(2020, 1, 1);
+    params.not_after = rcgen::date_time_ymd(2040, 1, 1);
+    if ca {
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    }
+    params.key_pair = Some(rcgen::KeyPair::generate(alg).unwrap());
+    rcgen::Certificate::from_params(params).unwrap()
+}
+
+/// Locally generated fake vendor CA, P-384 for NVIDIA CC and P-256 for Intel
+struct FakeTeeCa {
+    /// DER chain of a device leaf, an intermediate and the self-signed root
+    chain: Vec<Vec<u8>>,
+    /// PKCS#8 key of the device leaf
+    device_key: Vec<u8>,
+}
+
+impl FakeTeeCa {
+    fn new(kind: TeeKind) -> Self {
+        let alg = match kind {
+            TeeKind::NvidiaCc => &rcgen::PKCS_ECDSA_P384_SHA384,
+            TeeKind::IntelSgx | TeeKind::IntelTdx => &rcgen::PKCS_ECDSA_P256_SHA256,
+        };
+        let root = fake_certificate("Fake TEE Root CA", alg, true);
+        let intermediate = fake_certificate("Fake TEE Platform CA", alg, true);
+        let device = fake_certificate("Fake TEE Device", alg, false);
+        FakeTeeCa {
+            chain: vec![
+                device.serialize_der_with_signer(&intermediate).unwrap(),
+                intermediate.serialize_der_with_signer(&root).unwrap(),
+                root.serialize_der().unwrap(),
+            ],
+            device_key: device.serialize_private_key_der(),
+        }
+    }
+
+    fn root(&self) -> TeeCertificate {
+        self.chain.last().unwrap().clone().try_into().unwrap()
+    }
+
+    /// Quote of `kind` with `report_data`, signed by the device key
+    fn quote(&self, kind: TeeKind, report_data: H256) -> Vec<u8> {
+        match kind {
+            TeeKind::IntelSgx => {
+                let mut body = vec![0u8; tee::SGX_REPORT_BODY_SIZE];
+                body[64..96].copy_from_slice(&measured_code()[..32]);
+                body[320..352].copy_from_slice(report_data.as_bytes());
+                self.dcap_quote(3, tee::SGX_TEE_TYPE, body)
+            },
+            TeeKind::IntelTdx => {
+                let mut body = vec![0u8; tee::TDX_REPORT_BODY_SIZE];
+                body[136..184].copy_from_slice(&measured_code());
+                body[520..552].copy_from_slice(report_data.as_bytes());
+                self.dcap_quote(4, tee::TDX_TEE_TYPE, body)
+            },
+            TeeKind::NvidiaCc => self.spdm_report(report_data),
+        }
+    }
+
+    /// DCAP quote whose quoting enclave report, signed by the device key as PCK, binds a fixed
+    /// attestation key
+    fn dcap_quote(&self, version: u16, tee_type: u32, body: Vec<u8>) -> Vec<u8> {
+        use p256::{
+            ecdsa::{signature::Signer, Signature, SigningKey},
+            pkcs8::DecodePrivateKey,
+        };
+
+        let attestation_key = SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let attestation_public = attestation_key.verifying_key().to_encoded_point(false);
+        let attestation_public = &attestation_public.as_bytes()[1..];
+        let auth_data = [5u8; 32];
+        let mut qe_report = vec![0u8; tee::QE_REPORT_SIZE];
+        qe_report[320..352]
+            .copy_from_slice(&sp_io::hashing::sha2_256(&[attestation_public, &auth_data].concat()));
+
+        let mut quote = version.to_le_bytes().to_vec();
+        quote.extend(tee::DCAP_ATTESTATION_KEY_TYPE_P256.to_le_bytes());
+        quote.extend(tee_type.to_le_bytes());
+        quote.resize(tee::DCAP_HEADER_SIZE, 0);
+        quote.extend(body);
+        let signature: Signature = attestation_key.sign(&quote);
+
+        let pck = SigningKey::from_pkcs8_der(&self.device_key).unwrap();
+        let qe_report_signature: Signature = pck.sign(&qe_report);
+        let mut qe_data = qe_report;
+        qe_data.extend(qe_report_signature.to_bytes());
+        qe_data.extend((auth_data.len() as u16).to_le_bytes());
+        qe_data.extend(auth_data);
+        // PCK chain certification data, passed in the report instead
+        qe_data.extend(5u16.to_le_bytes());
+        qe_data.extend(0u32.to_le_bytes());
+
+        let mut signature_data = signature.to_bytes().to_vec();
+        signature_data.extend(attestation_public);
+        if version == 4 {
+            signature_data.extend(tee::QE_REPORT_CERTIFICATION_DATA.to_le_bytes());
+            signature_data.extend((qe_data.len() as u32).to_le_bytes());
+        }
+        signature_data.extend(qe_data);
+        quote.extend((signature_data.len() as u32).to_le_bytes());
+        quote.extend(signature_data);
+        quote
+    }
+
+    /// SPDM `GET_MEASUREMENTS` request with `nonce` and its response, signed by the device key
+    fn spdm_report(&self, nonce: H256) -> Vec<u8> {
+        use p384::{
+            ecdsa::{signature::Signer, Signature, SigningKey},
+            pkcs8::DecodePrivateKey,
+        };
+
+        let record = measured_code();
+        let mut report = vec![tee::SPDM_VERSION_11, tee::SPDM_GET_MEASUREMENTS, 1, 0xff];
+        report.extend(nonce.as_bytes());
+        report.push(0);
+        report.extend([tee::SPDM_VERSION_11, tee::SPDM_MEASUREMENTS, 0, 0, 1]);
+        report.extend(&(record.len() as u32).to_le_bytes()[..3]);
+        report.extend(record);
+        report.extend([3u8; 32]);
+        report.extend(0u16.to_le_bytes());
+        let signature: Signature = SigningKey::from_pkcs8_der(&self.device_key).unwrap().sign(&report);
+        report.extend(signature.to_bytes());
+        report
+    }
+
+    /// Report of `kind`, registering `tflops` of `gpu_uuid()` for `who`
+    fn report(&self, who: u64, tflops: u32, kind: TeeKind) -> TeeReport {
+        let report_data = AgentAttestation::tee_report_data(&who, &gpu_uuid(), tflops);
+        tee_report(kind, self.quote(kind, report_data), self.chain.clone())
+    }
+}
+
+fn tee_report(kind: TeeKind, quote: Vec<u8>, chain: Vec<Vec<u8>>) -> TeeReport {
+    let certificates: Vec<TeeCertificate> =
+        chain.into_iter().map(|certificate| certificate.try_into().unwrap()).collect();
+    TeeReport { kind, quote: quote.try_into().unwrap(), certificates: certificates.try_into().unwrap() }
+}
+
+/// MRENCLAVE (its first 32 bytes), MRTD or GPU measurement record of the attested code
+fn measured_code() -> [u8; 48] {
+    [7u8; 48]
+}
+
+fn measurement(kind: TeeKind) -> H256 {
+    match kind {
+        TeeKind::IntelSgx => H256::from_slice(&measured_code()[..32]),
+        TeeKind::IntelTdx | TeeKind::NvidiaCc => H256(sp_io::hashing::sha2_256(&measured_code())),
+    }
+}
+
+fn trust_tee(kind: TeeKind, ca: &FakeTeeCa) {
+    assert_ok!(AgentAttestation::set_tee_root(RuntimeOrigin::root(), kind, ca.root(), true));
+    assert_ok!(AgentAttestation::set_tee_measurement(RuntimeOrigin::root(), kind, measurement(kind), true));
+}
+
+#[test]
+fn attested_registration_works() {
+    new_test_ext().execute_with(|| {
+        let ca = FakeTeeCa::new(TeeKind::NvidiaCc);
+        trust_tee(TeeKind::NvidiaCc, &ca);
+
+        let report = ca.report(1, 120, TeeKind::NvidiaCc);
+        assert_ok!(AgentAttestation::register_attested_node(RuntimeOrigin::signed(1), gpu_uuid(), 120, report));
+
+        let node = AgentAttestation::node_of(1).unwrap();
+        assert!(node.attested);
+        assert_eq!(node.tflops, 120);
+        System::assert_has_event(Event::NodeRegistered { who: 1, tflops: 120 }.into());
+        System::assert_last_event(
+            Event::NodeAttested { who: 1, kind: TeeKind::NvidiaCc, measurement: measurement(TeeKind::NvidiaCc) }
+                .into(),
+        );
+
+        // Attested providers come before self-reported ones
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(2), gpu_uuid(), 200));
+        for who in [2, 1] {
+            assert_ok!(AgentAttestation::update_capability(
+                RuntimeOrigin::signed(who),
+                vec![model_id()],
+                8,
+                10,
+                b"us-west".to_vec(),
+            ));
+        }
+        let model_bounded: frame_support::BoundedVec<u8, MaxModelIdLen> = model_id().try_into().unwrap();
+        assert_eq!(AgentAttestation::get_providers_for_model(&model_bounded), vec![1, 2]);
+    });
+}
+
+#[test]
+fn registered_nodes_can_attest_their_hardware() {
+    new_test_ext().execute_with(|| {
+        let ca = FakeTeeCa::new(TeeKind::IntelTdx);
+        trust_tee(TeeKind::IntelTdx, &ca);
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 500));
+        System::set_block_number(10);
+
+        let report = ca.report(1, 120, TeeKind::IntelTdx);
+        assert_ok!(AgentAttestation::register_attested_node(RuntimeOrigin::signed(1), gpu_uuid(), 120, report));
+
+        let node = AgentAttestation::node_of(1).unwrap();
+        assert!(node.attested);
+        assert_eq!(node.tflops, 120);
+        assert_eq!(node.registered_at, 1);
+    });
+}
+
+#[test]
+fn attested_registration_rejects_untrusted_reports() {
+    new_test_ext().execute_with(|| {
+        let ca = FakeTeeCa::new(TeeKind::IntelSgx);
+        let other = FakeTeeCa::new(TeeKind::IntelSgx);
+        trust_tee(TeeKind::IntelSgx, &ca);
+        let register = |report: TeeReport| {
+            AgentAttestation::register_attested_node(RuntimeOrigin::signed(1), gpu_uuid(), 120, report)
+        };
+
+        // The quote must bind the registered hardware
+        assert_noop!(register(ca.report(1, 240, TeeKind::IntelSgx)), Error::<Test>::ReportDataMismatch);
+        assert_noop!(register(ca.report(2, 120, TeeKind::IntelSgx)), Error::<Test>::ReportDataMismatch);
+
+        // Roots are trusted for one kind of TEE only
+        assert_noop!(register(other.report(1, 120, TeeKind::IntelSgx)), Error::<Test>::UntrustedTeeRoot);
+        assert_noop!(register(ca.report(1, 120, TeeKind::IntelTdx)), Error::<Test>::UntrustedTeeRoot);
+
+        // Every certificate must be issued by the next one
+        let report = ca.report(1, 120, TeeKind::IntelSgx);
+        let chain = vec![ca.chain[0].clone(), ca.chain[1].clone(), other.chain[2].clone()];
+        assert_noop!(
+            register(tee_report(TeeKind::IntelSgx, report.quote.to_vec(), chain)),
+            Error::<Test>::InvalidTeeCertificate
+        );
+        let chain = vec![ca.chain[0].clone(), ca.chain[2].clone()];
+        assert_noop!(
+            register(tee_report(TeeKind::IntelSgx, report.quote.to_vec(), chain)),
+            Error::<Test>::InvalidTeeCertificate
+        );
+        UnixNow::set(2_250_000_000);
+        assert_noop!(register(ca.report(1, 120, TeeKind::IntelSgx)), Error::<Test>::TeeCertificateExpired);
+        UnixNow::set(1_700_000_000);
+
+        // The quote must be signed through the certified key and attest a trusted measurement
+        let report_data = AgentAttestation::tee_report_data(&1, &gpu_uuid(), 120);
+        let quote = other.quote(TeeKind::IntelSgx, report_data);
+        assert_noop!(
+            register(tee_report(TeeKind::IntelSgx, quote, ca.chain.clone())),
+            Error::<Test>::InvalidTeeReport
+        );
+        let mut quote = ca.quote(TeeKind::IntelSgx, report_data);
+        quote[100] ^= 1;
+        assert_noop!(
+            register(tee_report(TeeKind::IntelSgx, quote, ca.chain.clone())),
+            Error::<Test>::InvalidTeeReport
+        );
+        let quote = ca.quote(TeeKind::IntelTdx, report_data);
+        assert_noop!(
+            register(tee_report(TeeKind::IntelSgx, quote, ca.chain.clone())),
+            Error::<Test>::InvalidTeeReport
+        );
+        assert_ok!(AgentAttestation::set_tee_measurement(
+            RuntimeOrigin::root(),
+            TeeKind::IntelSgx,
+            measurement(TeeKind::IntelSgx),
+            false
+        ));
+        assert_noop!(register(ca.report(1, 120, TeeKind::IntelSgx)), Error::<Test>::UntrustedMeasurement);
+
+        trust_tee(TeeKind::IntelSgx, &ca);
+        assert_ok!(register(ca.report(1, 120, TeeKind::IntelSgx)));
+
+        // Revoked roots no longer anchor new attestations
+        assert_ok!(AgentAttestation::set_tee_root(RuntimeOrigin::root(), TeeKind::IntelSgx, ca.root(), false));
+        assert_noop!(register(ca.report(1, 120, TeeKind::IntelSgx)), Error::<Test>::UntrustedTeeRoot);
+    });
+}
+
+#[test]
+fn tee_registry_is_managed_by_admin() {
+    new_test_ext().execute_with(|| {
+        let ca = FakeTeeCa::new(TeeKind::IntelSgx);
+        assert_noop!(
+            AgentAttestation::set_tee_root(RuntimeOrigin::signed(1), TeeKind::IntelSgx, ca.root(), true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            AgentAttestation::set_tee_measurement(
+                RuntimeOrigin::signed(1),
+                TeeKind::IntelSgx,
+                measurement(TeeKind::IntelSgx),
+                true
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // Roots are self-signed certificate authorities
+        let intermediate: TeeCertificate = ca.chain[1].clone().try_into().unwrap();
+        assert_noop!(
+            AgentAttestation::set_tee_root(RuntimeOrigin::root(), TeeKind::IntelSgx, intermediate, true),
+            Error::<Test>::InvalidTeeCertificate
+        );
+
+        let root_id = AgentAttestation::tee_root_id(&tee::root_key(&ca.root()).unwrap());
+        assert_ok!(AgentAttestation::set_tee_root(RuntimeOrigin::root(), TeeKind::IntelSgx, ca.root(), true));
+        assert!(AgentAttestation::tee_root_trusted(TeeKind::IntelSgx, root_id));
+        assert!(!AgentAttestation::tee_root_trusted(TeeKind::IntelTdx, root_id));
+        System::assert_last_event(Event::TeeRootSet { kind: TeeKind::IntelSgx, root: root_id, trusted: true }.into());
+        assert_ok!(AgentAttestation::set_tee_root(RuntimeOrigin::root(), TeeKind::IntelSgx, ca.root(), false));
+        assert!(!AgentAttestation::tee_root_trusted(TeeKind::IntelSgx, root_id));
+
+        let sgx_measurement = measurement(TeeKind::IntelSgx);
+        assert_ok!(AgentAttestation::set_tee_measurement(RuntimeOrigin::root(), TeeKind::IntelSgx, sgx_measurement, true));
+        assert!(AgentAttestation::tee_measurement_trusted(TeeKind::IntelSgx, sgx_measurement));
+        assert!(!AgentAttestation::tee_measurement_trusted(TeeKind::IntelTdx, sgx_measurement));
+        assert_ok!(AgentAttestation::set_tee_measurement(RuntimeOrigin::root(), TeeKind::IntelSgx, sgx_measurement, false));
+        assert!(!AgentAttestation::tee_measurement_trusted(TeeKind::IntelSgx, sgx_measurement));
+    });
+}
+
+#[test]
+fn migration_v3_marks_existing_nodes_unattested() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        let old_node = (1u64, gpu_uuid(), 120u32, 1u64, 5u64, true);
+        frame_support::storage::unhashed::put_raw(&crate::Nodes::<Test>::hashed_key_for(1), &old_node.encode());
+        StorageVersion::new(2).put::<AgentAttestation>();
+
+        crate::migrations::v3::migrate::<Test>();
+
+        let node = crate::migrations::v3::Nodes::<Test>::get(1).unwrap();
+        assert_eq!(node.gpu_uuid.to_vec(), gpu_uuid());
+        assert_eq!(node.last_heartbeat, 5);
+        assert!(!node.attested);
+        assert_eq!(AgentAttestation::on_chain_storage_version(), 3);
+    });
+}
+
+#[test]
+fn migration_v4_adds_an_empty_bond_to_existing_nodes() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        let old_node = (1u64, gpu_uuid(), 120u32, 1u64, 5u64, true, true);
+        frame_support::storage::unhashed::put_raw(&crate::Nodes::<Test>::hashed_key_for(1), &old_node.encode());
+        StorageVersion::new(3).put::<AgentAttestation>();
+
+        crate::migrations::v4::migrate::<Test>();
+
+        let node = AgentAttestation::node_of(1).unwrap();
+        assert_eq!(node.last_heartbeat, 5);
+        assert!(node.attested);
+        assert_eq!(node.bond, 0);
+        assert_eq!(AgentAttestation::on_chain_storage_version(), 4);
+    });
+}
+
+#[test]
+fn migration_v5_counts_pending_attestations() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        for task_id in 1..=3 {
+            assert_ok!(AgentAttestation::submit_attestation(
+                RuntimeOrigin::signed(1),
+                task_id,
+                H256::from_low_u64_be(task_id),
+                model_id(),
+                1000,
+                500,
+            ));
+        }
+        crate::Attestations::<Test>::mutate(0, |att| {
+            att.as_mut().unwrap().status = AttestationStatus::Confirmed;
+        });
+        crate::PendingAttestations::<Test>::remove(1);
+        StorageVersion::new(4).put::<AgentAttestation>();
+
+        crate::migrations::v5::migrate::<Test>();
+
+        assert_eq!(AgentAttestation::pending_attestations(1), 2);
+        assert_eq!(AgentAttestation::on_chain_storage_version(), 5);
+    });
+}
+
+#[test]
+fn migration_v6_counts_completed_attestations() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        for task_id in 1..=4 {
+            assert_ok!(AgentAttestation::submit_attestation(
+                RuntimeOrigin::signed(1),
+                task_id,
+                H256::from_low_u64_be(task_id),
+                model_id(),
+                1000,
+                500,
+            ));
+        }
+        let statuses = [AttestationStatus::Confirmed, AttestationStatus::Defended, AttestationStatus::Slashed];
+        for (id, status) in statuses.into_iter().enumerate() {
+            crate::Attestations::<Test>::mutate(id as u64, |att| att.as_mut().unwrap().status = status);
+        }
+        StorageVersion::new(5).put::<AgentAttestation>();
+
+        crate::migrations::v6::migrate::<Test>();
+
+        assert_eq!(AgentAttestation::completed_attestations(1), 2);
+        assert_eq!(AgentAttestation::on_chain_storage_version(), 6);
+    });
+}
+
+#[test]
+fn migration_v7_queues_pending_attestations_for_finalization() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        assert_ok!(AgentAttestation::register_node(RuntimeOrigin::signed(1), gpu_uuid(), 120));
+        for task_id in 1..=4 {
+            assert_ok!(AgentAttestation::submit_attestation(
+                RuntimeOrigin::signed(1),
+                task_id,
+                H256::from_low_u64_be(task_id),
+                model_id(),
+                1000,
+                500,
+            ));
+        }
+        // Before the upgrade nothing was queued
+        let _ = FinalizationQueue::<Test>::clear(u32::MAX, None);
+        crate::Attestations::<Test>::mutate(1, |att| att.as_mut().unwrap().challenge_end = 10);
+        crate::Attestations::<Test>::mutate(2, |att| att.as_mut().unwrap().challenger = Some(2));
+        crate::Attestations::<Test>::mutate(3, |att| {
+            att.as_mut().unwrap().status = AttestationStatus::Confirmed;
+        });
+        // The queue of the upgrade block is already full
+        FinalizationQueue::<Test>::insert(40, frame_support::BoundedVec::truncate_from(vec![90, 91]));
+        StorageVersion::new(6).put::<AgentAttestation>();
+        System::set_block_number(40);
+
+        crate::migrations::v7::migrate::<Test>();
+
+        let challenge_end = 1 + ChallengeWindow::get();
+        assert_eq!(AgentAttestation::finalization_queue(challenge_end).to_vec(), vec![0]);
+        // A window that is already over is finalized from the upgrade block
+        assert_eq!(AgentAttestation::finalization_queue(41).to_vec(), vec![1]);
+        assert_eq!(AgentAttestation::on_chain_storage_version(), 7);
+
+        System::set_block_number(42);
+        AgentAttestation::on_initialize(42);
+        assert!(matches!(
+            AgentAttestation::attestation_of(1).unwrap().status,
+            AttestationStatus::Confirmed
+        ));
+        assert!(matches!(
+            AgentAttestation::attestation_of(0).unwrap().status,
+            AttestationStatus::Pending
+        ));
+    });
+}
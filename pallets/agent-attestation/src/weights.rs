@@ -41,197 +41,663 @@ pub trait WeightInfo {
 	fn submit_attestation() -> Weight;
 	fn challenge_attestation() -> Weight;
 	fn confirm_attestation() -> Weight;
-	fn resolve_challenge() -> Weight;
+	fn resolve_challenge(j: u32, ) -> Weight;
 	fn update_capability() -> Weight;
+	fn commit_vote() -> Weight;
+	fn reveal_vote() -> Weight;
+	fn on_initialize(n: u32, ) -> Weight;
+	fn register_attested_node() -> Weight;
+	fn set_tee_root() -> Weight;
+	fn set_tee_measurement() -> Weight;
+	fn sweep_liveness(n: u32, ) -> Weight;
+	fn deregister_node() -> Weight;
+	fn withdraw_node_bond() -> Weight;
+	fn retry_settlement() -> Weight;
 }
 
 /// Weights for `pallet_agent_attestation` using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Storage: AgentAttestation Nodes (r:1 w:1)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	fn register_node() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `42`
-		//  Estimated: `3688`
-		// Minimum execution time: 15_904_000 picoseconds.
-		Weight::from_parts(16_670_000, 3688)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `145`
+		//  Estimated: `3705`
+		// Minimum execution time: 40_062_000 picoseconds.
+		Weight::from_parts(41_302_000, 3705)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
 	/// Storage: AgentAttestation Nodes (r:1 w:1)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
 	fn heartbeat() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `172`
-		//  Estimated: `3688`
+		//  Estimated: `3705`
 		// Minimum execution time: 19_077_000 picoseconds.
-		Weight::from_parts(19_360_000, 3688)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
+		Weight::from_parts(19_360_000, 3705)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
 	/// Storage: AgentAttestation Nodes (r:1 w:0)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation AttesterTaskCount (r:1 w:1)
 	/// Proof: AgentAttestation AttesterTaskCount (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation NextAttestationId (r:1 w:1)
 	/// Proof: AgentAttestation NextAttestationId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation FinalizationQueue (r:1 w:1)
+	/// Proof: AgentAttestation FinalizationQueue (max_values: None, max_size: Some(819), added: 3294, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation Attestations (r:0 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:1)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn submit_attestation() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `147`
-		//  Estimated: `3688`
-		// Minimum execution time: 43_240_000 picoseconds.
-		Weight::from_parts(44_226_000, 3688)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+		//  Measured:  `1428`
+		//  Estimated: `4284`
+		// Minimum execution time: 46_477_000 picoseconds.
+		Weight::from_parts(47_915_000, 4284)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
 	}
 	/// Storage: AgentAttestation Attestations (r:1 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: Committee Committee (r:1 w:0)
+	/// Proof Skipped: Committee Committee (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	/// Proof: RandomnessCollectiveFlip RandomMaterial (max_values: Some(1), max_size: Some(2594), added: 3089, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Disputes (r:0 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
 	fn challenge_attestation() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `320`
-		//  Estimated: `3901`
-		// Minimum execution time: 20_976_000 picoseconds.
-		Weight::from_parts(21_594_000, 3901)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `1359`
+		//  Estimated: `4079`
+		// Minimum execution time: 57_144_000 picoseconds.
+		Weight::from_parts(58_912_000, 4079)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
 	/// Storage: AgentAttestation Attestations (r:1 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AttestationOrders (r:1 w:0)
+	/// Proof: AgentAttestation AttestationOrders (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OrderReceipts (r:1 w:1)
+	/// Proof: X402Settlement OrderReceipts (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:1 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: System Account (r:4 w:4)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: X402Settlement NextIntentId (r:1 w:1)
 	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
 	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:1)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation CompletedAttestations (r:1 w:1)
+	/// Proof: AgentAttestation CompletedAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn confirm_attestation() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `434`
-		//  Estimated: `3901`
-		// Minimum execution time: 52_570_000 picoseconds.
-		Weight::from_parts(53_324_000, 3901)
-			.saturating_add(T::DbWeight::get().reads(2_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+		//  Measured:  `3800`
+		//  Estimated: `11402`
+		// Minimum execution time: 127_925_000 picoseconds.
+		Weight::from_parts(131_882_000, 11402)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
 	}
 	/// Storage: AgentAttestation Attestations (r:1 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
-	fn resolve_challenge() -> Weight {
+	/// Storage: AgentAttestation Disputes (r:1 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation JurorVotes (r:0 w:5)
+	/// Proof: AgentAttestation JurorVotes (max_values: None, max_size: Some(90), added: 2565, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:1)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation CompletedAttestations (r:1 w:1)
+	/// Proof: AgentAttestation CompletedAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `j` is `[1, 5]`.
+	fn resolve_challenge(j: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `352`
-		//  Estimated: `3901`
-		// Minimum execution time: 53_067_000 picoseconds.
-		Weight::from_parts(53_528_000, 3901)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 69_341_000 picoseconds.
+		Weight::from_parts(71_486_000, 6196)
+			// Standard Error: 1_036
+			.saturating_add(Weight::from_parts(2_318_000, 0).saturating_mul(j.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(j.into())))
 	}
 	/// Storage: AgentAttestation Nodes (r:1 w:0)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation AgentCapabilities (r:1 w:1)
 	/// Proof: AgentAttestation AgentCapabilities (max_values: None, max_size: Some(2702), added: 5177, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation ModelProviders (r:0 w:2)
 	/// Proof: AgentAttestation ModelProviders (max_values: None, max_size: Some(323), added: 2798, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
 	fn update_capability() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `147`
 		//  Estimated: `6167`
 		// Minimum execution time: 25_246_000 picoseconds.
 		Weight::from_parts(25_451_000, 6167)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: AgentAttestation Disputes (r:1 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation JurorVotes (r:1 w:1)
+	/// Proof: AgentAttestation JurorVotes (max_values: None, max_size: Some(90), added: 2565, mode: MaxEncodedLen)
+	fn commit_vote() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1230`
+		//  Estimated: `3690`
+		// Minimum execution time: 26_484_000 picoseconds.
+		Weight::from_parts(27_304_000, 3690)
 			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AgentAttestation Disputes (r:1 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation JurorVotes (r:1 w:1)
+	/// Proof: AgentAttestation JurorVotes (max_values: None, max_size: Some(90), added: 2565, mode: MaxEncodedLen)
+	fn reveal_vote() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1230`
+		//  Estimated: `3690`
+		// Minimum execution time: 29_601_000 picoseconds.
+		Weight::from_parts(30_517_000, 3690)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AgentAttestation FinalizationQueue (r:1 w:1)
+	/// Proof: AgentAttestation FinalizationQueue (max_values: None, max_size: Some(819), added: 3294, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Attestations (r:100 w:100)
+	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AttestationOrders (r:100 w:0)
+	/// Proof: AgentAttestation AttestationOrders (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OrderReceipts (r:100 w:100)
+	/// Proof: X402Settlement OrderReceipts (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:100 w:100)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: System Account (r:300 w:300)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement SettlementReceipts (r:0 w:100)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:100 w:100)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation CompletedAttestations (r:100 w:100)
+	/// Proof: AgentAttestation CompletedAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 100]`.
+	fn on_initialize(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1168 + n * (4399 ±0)`
+		//  Estimated: `3506 + n * (8799 ±0)`
+		// Minimum execution time: 4_038_000 picoseconds.
+		Weight::from_parts(4_163_000, 3506)
+			// Standard Error: 52_706
+			.saturating_add(Weight::from_parts(117_904_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((10_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((10_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 8799).saturating_mul(n.into()))
+	}
+	/// Storage: AgentAttestation TeeRoots (r:1 w:0)
+	/// Proof: AgentAttestation TeeRoots (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation TeeMeasurements (r:1 w:0)
+	/// Proof: AgentAttestation TeeMeasurements (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:1 w:1)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	fn register_attested_node() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1235`
+		//  Estimated: `3705`
+		// Minimum execution time: 18_409_352_000 picoseconds.
+		Weight::from_parts(18_612_447_000, 3705)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AgentAttestation TeeRoots (r:0 w:1)
+	/// Proof: AgentAttestation TeeRoots (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	fn set_tee_root() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 4_471_208_000 picoseconds.
+		Weight::from_parts(4_516_093_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AgentAttestation TeeMeasurements (r:0 w:1)
+	/// Proof: AgentAttestation TeeMeasurements (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	fn set_tee_measurement() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 12_484_000 picoseconds.
+		Weight::from_parts(12_871_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AgentAttestation LivenessCursor (r:1 w:1)
+	/// Proof: AgentAttestation LivenessCursor (max_values: Some(1), max_size: Some(258), added: 753, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:51 w:50)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:50 w:50)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 50]`.
+	fn sweep_liveness(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `505 + n * (1357 ±0)`
+		//  Estimated: `1517 + n * (2715 ±0)`
+		// Minimum execution time: 6_031_000 picoseconds.
+		Weight::from_parts(6_218_000, 1517)
+			// Standard Error: 13_354
+			.saturating_add(Weight::from_parts(29_874_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2715).saturating_mul(n.into()))
+	}
+	/// Storage: AgentAttestation Unbonding (r:1 w:1)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:0)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:1 w:1)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AgentCapabilities (r:1 w:1)
+	/// Proof: AgentAttestation AgentCapabilities (max_values: None, max_size: Some(2702), added: 5177, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation ModelProviders (r:0 w:10)
+	/// Proof: AgentAttestation ModelProviders (max_values: None, max_size: Some(323), added: 2798, mode: MaxEncodedLen)
+	fn deregister_node() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2055`
+		//  Estimated: `6167`
+		// Minimum execution time: 56_968_000 picoseconds.
+		Weight::from_parts(58_730_000, 6167)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(13_u64))
+	}
+	/// Storage: AgentAttestation Unbonding (r:1 w:1)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:0)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:1 w:1)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_node_bond() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1235`
+		//  Estimated: `3705`
+		// Minimum execution time: 38_008_000 picoseconds.
+		Weight::from_parts(39_184_000, 3705)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
+	/// Storage: AgentAttestation UnsettledAttestations (r:1 w:1)
+	/// Proof: AgentAttestation UnsettledAttestations (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Attestations (r:1 w:0)
+	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AttestationOrders (r:1 w:0)
+	/// Proof: AgentAttestation AttestationOrders (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OrderReceipts (r:1 w:1)
+	/// Proof: X402Settlement OrderReceipts (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:1 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	fn retry_settlement() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `3410`
+		//  Estimated: `8796`
+		// Minimum execution time: 92_317_000 picoseconds.
+		Weight::from_parts(95_048_000, 8796)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
 }
 
 // For backwards compatibility and tests.
 impl WeightInfo for () {
 	/// Storage: AgentAttestation Nodes (r:1 w:1)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	fn register_node() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `42`
-		//  Estimated: `3688`
-		// Minimum execution time: 15_904_000 picoseconds.
-		Weight::from_parts(16_670_000, 3688)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `145`
+		//  Estimated: `3705`
+		// Minimum execution time: 40_062_000 picoseconds.
+		Weight::from_parts(41_302_000, 3705)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
 	/// Storage: AgentAttestation Nodes (r:1 w:1)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
 	fn heartbeat() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `172`
-		//  Estimated: `3688`
+		//  Estimated: `3705`
 		// Minimum execution time: 19_077_000 picoseconds.
-		Weight::from_parts(19_360_000, 3688)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
+		Weight::from_parts(19_360_000, 3705)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
 	/// Storage: AgentAttestation Nodes (r:1 w:0)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation AttesterTaskCount (r:1 w:1)
 	/// Proof: AgentAttestation AttesterTaskCount (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation NextAttestationId (r:1 w:1)
 	/// Proof: AgentAttestation NextAttestationId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation FinalizationQueue (r:1 w:1)
+	/// Proof: AgentAttestation FinalizationQueue (max_values: None, max_size: Some(819), added: 3294, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation Attestations (r:0 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:1)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn submit_attestation() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `147`
-		//  Estimated: `3688`
-		// Minimum execution time: 43_240_000 picoseconds.
-		Weight::from_parts(44_226_000, 3688)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+		//  Measured:  `1428`
+		//  Estimated: `4284`
+		// Minimum execution time: 46_477_000 picoseconds.
+		Weight::from_parts(47_915_000, 4284)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
 	}
 	/// Storage: AgentAttestation Attestations (r:1 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: Committee Committee (r:1 w:0)
+	/// Proof Skipped: Committee Committee (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	/// Proof: RandomnessCollectiveFlip RandomMaterial (max_values: Some(1), max_size: Some(2594), added: 3089, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Disputes (r:0 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
 	fn challenge_attestation() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `320`
-		//  Estimated: `3901`
-		// Minimum execution time: 20_976_000 picoseconds.
-		Weight::from_parts(21_594_000, 3901)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `1359`
+		//  Estimated: `4079`
+		// Minimum execution time: 57_144_000 picoseconds.
+		Weight::from_parts(58_912_000, 4079)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
 	/// Storage: AgentAttestation Attestations (r:1 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AttestationOrders (r:1 w:0)
+	/// Proof: AgentAttestation AttestationOrders (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OrderReceipts (r:1 w:1)
+	/// Proof: X402Settlement OrderReceipts (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:1 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: System Account (r:4 w:4)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: X402Settlement NextIntentId (r:1 w:1)
 	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
 	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:1)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation CompletedAttestations (r:1 w:1)
+	/// Proof: AgentAttestation CompletedAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn confirm_attestation() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `434`
-		//  Estimated: `3901`
-		// Minimum execution time: 52_570_000 picoseconds.
-		Weight::from_parts(53_324_000, 3901)
-			.saturating_add(RocksDbWeight::get().reads(2_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+		//  Measured:  `3800`
+		//  Estimated: `11402`
+		// Minimum execution time: 127_925_000 picoseconds.
+		Weight::from_parts(131_882_000, 11402)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
 	}
 	/// Storage: AgentAttestation Attestations (r:1 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
-	fn resolve_challenge() -> Weight {
+	/// Storage: AgentAttestation Disputes (r:1 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation JurorVotes (r:0 w:5)
+	/// Proof: AgentAttestation JurorVotes (max_values: None, max_size: Some(90), added: 2565, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:1)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation CompletedAttestations (r:1 w:1)
+	/// Proof: AgentAttestation CompletedAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `j` is `[1, 5]`.
+	fn resolve_challenge(j: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `352`
-		//  Estimated: `3901`
-		// Minimum execution time: 53_067_000 picoseconds.
-		Weight::from_parts(53_528_000, 3901)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 69_341_000 picoseconds.
+		Weight::from_parts(71_486_000, 6196)
+			// Standard Error: 1_036
+			.saturating_add(Weight::from_parts(2_318_000, 0).saturating_mul(j.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(j.into())))
 	}
 	/// Storage: AgentAttestation Nodes (r:1 w:0)
-	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(223), added: 2698, mode: MaxEncodedLen)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation AgentCapabilities (r:1 w:1)
 	/// Proof: AgentAttestation AgentCapabilities (max_values: None, max_size: Some(2702), added: 5177, mode: MaxEncodedLen)
 	/// Storage: AgentAttestation ModelProviders (r:0 w:2)
 	/// Proof: AgentAttestation ModelProviders (max_values: None, max_size: Some(323), added: 2798, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
 	fn update_capability() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `147`
 		//  Estimated: `6167`
 		// Minimum execution time: 25_246_000 picoseconds.
 		Weight::from_parts(25_451_000, 6167)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: AgentAttestation Disputes (r:1 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation JurorVotes (r:1 w:1)
+	/// Proof: AgentAttestation JurorVotes (max_values: None, max_size: Some(90), added: 2565, mode: MaxEncodedLen)
+	fn commit_vote() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1230`
+		//  Estimated: `3690`
+		// Minimum execution time: 26_484_000 picoseconds.
+		Weight::from_parts(27_304_000, 3690)
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AgentAttestation Disputes (r:1 w:1)
+	/// Proof: AgentAttestation Disputes (max_values: None, max_size: Some(225), added: 2700, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation JurorVotes (r:1 w:1)
+	/// Proof: AgentAttestation JurorVotes (max_values: None, max_size: Some(90), added: 2565, mode: MaxEncodedLen)
+	fn reveal_vote() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1230`
+		//  Estimated: `3690`
+		// Minimum execution time: 29_601_000 picoseconds.
+		Weight::from_parts(30_517_000, 3690)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AgentAttestation FinalizationQueue (r:1 w:1)
+	/// Proof: AgentAttestation FinalizationQueue (max_values: None, max_size: Some(819), added: 3294, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Attestations (r:100 w:100)
+	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AttestationOrders (r:100 w:0)
+	/// Proof: AgentAttestation AttestationOrders (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OrderReceipts (r:100 w:100)
+	/// Proof: X402Settlement OrderReceipts (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:100 w:100)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: System Account (r:300 w:300)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement SettlementReceipts (r:0 w:100)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:100 w:100)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation CompletedAttestations (r:100 w:100)
+	/// Proof: AgentAttestation CompletedAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 100]`.
+	fn on_initialize(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1168 + n * (4399 ±0)`
+		//  Estimated: `3506 + n * (8799 ±0)`
+		// Minimum execution time: 4_038_000 picoseconds.
+		Weight::from_parts(4_163_000, 3506)
+			// Standard Error: 52_706
+			.saturating_add(Weight::from_parts(117_904_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((10_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((10_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 8799).saturating_mul(n.into()))
+	}
+	/// Storage: AgentAttestation TeeRoots (r:1 w:0)
+	/// Proof: AgentAttestation TeeRoots (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation TeeMeasurements (r:1 w:0)
+	/// Proof: AgentAttestation TeeMeasurements (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:1 w:1)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Unbonding (r:1 w:0)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	fn register_attested_node() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1235`
+		//  Estimated: `3705`
+		// Minimum execution time: 18_409_352_000 picoseconds.
+		Weight::from_parts(18_612_447_000, 3705)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AgentAttestation TeeRoots (r:0 w:1)
+	/// Proof: AgentAttestation TeeRoots (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	fn set_tee_root() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 4_471_208_000 picoseconds.
+		Weight::from_parts(4_516_093_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AgentAttestation TeeMeasurements (r:0 w:1)
+	/// Proof: AgentAttestation TeeMeasurements (max_values: None, max_size: Some(58), added: 2533, mode: MaxEncodedLen)
+	fn set_tee_measurement() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 12_484_000 picoseconds.
+		Weight::from_parts(12_871_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AgentAttestation LivenessCursor (r:1 w:1)
+	/// Proof: AgentAttestation LivenessCursor (max_values: Some(1), max_size: Some(258), added: 753, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:51 w:50)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:50 w:50)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 50]`.
+	fn sweep_liveness(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `505 + n * (1357 ±0)`
+		//  Estimated: `1517 + n * (2715 ±0)`
+		// Minimum execution time: 6_031_000 picoseconds.
+		Weight::from_parts(6_218_000, 1517)
+			// Standard Error: 13_354
+			.saturating_add(Weight::from_parts(29_874_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2715).saturating_mul(n.into()))
+	}
+	/// Storage: AgentAttestation Unbonding (r:1 w:1)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:0)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:1 w:1)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AgentCapabilities (r:1 w:1)
+	/// Proof: AgentAttestation AgentCapabilities (max_values: None, max_size: Some(2702), added: 5177, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation ModelProviders (r:0 w:10)
+	/// Proof: AgentAttestation ModelProviders (max_values: None, max_size: Some(323), added: 2798, mode: MaxEncodedLen)
+	fn deregister_node() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2055`
+		//  Estimated: `6167`
+		// Minimum execution time: 56_968_000 picoseconds.
+		Weight::from_parts(58_730_000, 6167)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(13_u64))
+	}
+	/// Storage: AgentAttestation Unbonding (r:1 w:1)
+	/// Proof: AgentAttestation Unbonding (max_values: None, max_size: Some(56), added: 2531, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation PendingAttestations (r:1 w:0)
+	/// Proof: AgentAttestation PendingAttestations (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Nodes (r:1 w:1)
+	/// Proof: AgentAttestation Nodes (max_values: None, max_size: Some(240), added: 2715, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_node_bond() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1235`
+		//  Estimated: `3705`
+		// Minimum execution time: 38_008_000 picoseconds.
+		Weight::from_parts(39_184_000, 3705)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
+	/// Storage: AgentAttestation UnsettledAttestations (r:1 w:1)
+	/// Proof: AgentAttestation UnsettledAttestations (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation Attestations (r:1 w:0)
+	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: AgentAttestation AttestationOrders (r:1 w:0)
+	/// Proof: AgentAttestation AttestationOrders (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OrderReceipts (r:1 w:1)
+	/// Proof: X402Settlement OrderReceipts (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:1 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	fn retry_settlement() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `3410`
+		//  Estimated: `8796`
+		// Minimum execution time: 92_317_000 picoseconds.
+		Weight::from_parts(95_048_000, 8796)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
 }
@@ -4,8 +4,11 @@ use super::*;
 use frame_benchmarking::v1::{account, benchmarks, whitelisted_caller};
 use frame_support::traits::{Currency, Get, ReservableCurrency};
 use frame_support::BoundedVec;
+use dbc_support::traits::GpuModelName;
 use frame_system::RawOrigin;
 use sp_runtime::traits::Saturating;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::{vec, vec::Vec};
 
 fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
     let caller: T::AccountId = account(name, index, 0);
@@ -15,18 +18,140 @@ fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
 }
 
 fn create_pool<T: Config>(owner: &T::AccountId) -> PoolId {
+    create_sized_pool::<T>(owner, 16_384u32, true, *b"US")
+}
+
+fn create_sized_pool<T: Config>(
+    owner: &T::AccountId,
+    gpu_memory: u32,
+    has_nvlink: bool,
+    region: RegionCode,
+) -> PoolId {
     let gpu_model: BoundedVec<u8, T::MaxGpuModelLen> = b"RTX4090".to_vec().try_into().unwrap();
     Pallet::<T>::register_pool(
         RawOrigin::Signed(owner.clone()).into(),
         gpu_model,
-        16_384u32,
-        true,
+        gpu_memory,
+        8u32,
+        has_nvlink,
         130u32,
+        region,
         1000u32.into(),
     ).expect("register_pool failed");
     NextPoolId::<T>::get().saturating_sub(1)
 }
 
+fn create_priced_pool<T: Config>(
+    owner: &T::AccountId,
+    gpu_memory: u32,
+    nvlink_efficiency: u32,
+    price: u32,
+) -> PoolId {
+    let gpu_model: BoundedVec<u8, T::MaxGpuModelLen> = b"RTX4090".to_vec().try_into().unwrap();
+    Pallet::<T>::register_pool(
+        RawOrigin::Signed(owner.clone()).into(),
+        gpu_model,
+        gpu_memory,
+        8u32,
+        true,
+        nvlink_efficiency,
+        *b"US",
+        price.into(),
+    ).expect("register_pool failed");
+    NextPoolId::<T>::get().saturating_sub(1)
+}
+
+/// Pools indexed for the selection benchmarks
+const INDEXED_POOLS: u32 = 10_000;
+
+/// `profiles` pool profiles below 16 GiB with `pools` pools each. In each profile a pool
+/// within the price limit of `profile_requirements` ranks behind the others, which are over
+/// the limit in the price tier the limit falls in.
+fn create_profiled_pools<T: Config>(profiles: u32, pools: u32) {
+    for i in 0 .. profiles {
+        for j in 1 .. pools {
+            let expensive: T::AccountId = funded_account::<T>("profiled", i * pools + j);
+            create_priced_pool::<T>(&expensive, 128 + i, 150, 2000);
+        }
+        let cheap: T::AccountId = funded_account::<T>("profiled", i * pools);
+        create_priced_pool::<T>(&cheap, 128 + i, 100, 1000);
+    }
+}
+
+/// Requirements every profile of `create_profiled_pools` meets, but for the price of its best
+/// ranked pool, with the longest list of GPU models
+fn profile_requirements<T: Config>(
+) -> TaskRequirements<BalanceOf<T>, frame_system::pallet_prelude::BlockNumberFor<T>> {
+    let model_len = (T::MaxGpuModelLen::get() as usize).min(GpuModelName::bound());
+    let mut gpu_models: Vec<GpuModelName> = (1 .. T::MaxRequiredGpuModels::get())
+        .map(|_| vec![b'X'; model_len].try_into().unwrap())
+        .collect();
+    gpu_models.push(b"RTX4090".to_vec().try_into().unwrap());
+    TaskRequirements {
+        min_vram: 128,
+        gpu_models: gpu_models.try_into().unwrap(),
+        gpu_count: 1,
+        nvlink_required: true,
+        region: Some(*b"US"),
+        max_price: Some(1500u32.into()),
+        deadline: None,
+    }
+}
+
+/// Delegate `MinPoolStake` to a pool and credit the pool with delegator rewards to pay out
+fn delegate_with_rewards<T: Config>(staker: &T::AccountId, pool_id: PoolId) -> BalanceOf<T> {
+    let amount = T::MinPoolStake::get();
+    Pallet::<T>::stake_to_pool(RawOrigin::Signed(staker.clone()).into(), pool_id, amount)
+        .expect("stake failed");
+    let _ = T::Currency::deposit_creating(&Pallet::<T>::pool_account(pool_id), amount);
+    StakePools::<T>::mutate(pool_id, |stake_pool| {
+        stake_pool.reward_per_point =
+            FixedU128::saturating_from_rational(amount, stake_pool.total_points);
+    });
+    amount
+}
+
+/// Queue a pending task on `pool_id` as if the pool had no free slot
+fn queue_task<T: Config>(user: &T::AccountId, pool_id: PoolId) -> TaskId {
+    let requirements = TaskRequirements { min_vram: 128, ..Default::default() };
+    queue_task_with::<T>(user, Some(pool_id), requirements)
+}
+
+/// Queue a pending task with `requirements` on `pool_id`, or globally
+fn queue_task_with<T: Config>(
+    user: &T::AccountId,
+    pool_id: Option<PoolId>,
+    requirements: TaskRequirements<BalanceOf<T>, frame_system::pallet_prelude::BlockNumberFor<T>>,
+) -> TaskId {
+    let task_id = NextTaskId::<T>::get();
+    T::Currency::reserve(user, T::TaskDeposit::get()).expect("reserve failed");
+    Tasks::<T>::insert(task_id, ComputeTask {
+        task_id,
+        user: user.clone(),
+        pool_id: pool_id.unwrap_or_default(),
+        dimensions: TaskDimensions { m: 128, n: 128, k: 128 },
+        requirements,
+        priority: TaskPriority::Normal,
+        status: TaskStatus::Pending,
+        submitted_at: frame_system::Pallet::<T>::block_number(),
+        proof_hash: None,
+        verification_result: None,
+        reward_amount: None,
+        disputed: false,
+    });
+    let entry = (TaskPriority::Normal, task_id);
+    match pool_id {
+        Some(pool_id) => PoolQueue::<T>::mutate(pool_id, |queue| {
+            queue.try_push(entry).expect("queue full");
+        }),
+        None => GlobalQueue::<T>::mutate(|queue| {
+            queue.try_push(entry).expect("queue full");
+        }),
+    }
+    NextTaskId::<T>::put(task_id.saturating_add(1));
+    task_id
+}
+
 /// Create a task and force it into Computing status with escrow set up.
 fn create_computing_task<T: Config>(owner: &T::AccountId, user: &T::AccountId, pool_id: PoolId) -> TaskId {
     let task_id = NextTaskId::<T>::get();
@@ -43,6 +168,7 @@ fn create_computing_task<T: Config>(owner: &T::AccountId, user: &T::AccountId, p
         user: user.clone(),
         pool_id,
         dimensions: TaskDimensions { m: 128, n: 128, k: 128 },
+        requirements: TaskRequirements { min_vram: 128, ..Default::default() },
         priority: TaskPriority::Normal,
         status: TaskStatus::Computing,
         submitted_at: now,
@@ -66,39 +192,190 @@ fn create_computing_task<T: Config>(owner: &T::AccountId, user: &T::AccountId, p
     task_id
 }
 
+/// Fill the global queue with tasks the pools of `create_pool` do not meet, but for the last
+/// `MAX_QUEUED_STARTS` ones: a pool taking them scans the whole queue for each
+fn queue_global_tasks<T: Config>() {
+    let user: T::AccountId = funded_account::<T>("queued", 3);
+    let queued = T::MaxQueuedTasks::get();
+    let starts = MAX_QUEUED_STARTS.min(queued);
+    for i in 0 .. queued {
+        let min_vram = if i < queued - starts { u32::MAX } else { 128 };
+        let requirements = TaskRequirements { min_vram, ..Default::default() };
+        queue_task_with::<T>(&user, None, requirements);
+    }
+}
+
+/// Queue the worst case for filling a free slot of `pool_id`: it takes `MAX_QUEUED_STARTS`
+/// tasks off the pool queue, each after scanning a full global queue of tasks the pool does
+/// not meet. All but the last are past their deadline and dropped, the last one starts.
+fn queue_slot_fill<T: Config>(pool_id: PoolId) -> TaskId {
+    let queued_user: T::AccountId = funded_account::<T>("queued", 3);
+    for _ in 0 .. T::MaxQueuedTasks::get() {
+        let requirements = TaskRequirements { min_vram: u32::MAX, ..Default::default() };
+        queue_task_with::<T>(&queued_user, None, requirements);
+    }
+    let now = frame_system::Pallet::<T>::block_number().saturating_add(2u32.into());
+    frame_system::Pallet::<T>::set_block_number(now);
+    for _ in 1 .. MAX_QUEUED_STARTS.min(T::MaxQueuedTasks::get()) {
+        let requirements = TaskRequirements {
+            min_vram: 128,
+            deadline: Some(now.saturating_sub(1u32.into())),
+            ..Default::default()
+        };
+        queue_task_with::<T>(&queued_user, Some(pool_id), requirements);
+    }
+    queue_task::<T>(&queued_user, pool_id)
+}
+
 benchmarks! {
     register_pool {
+        queue_global_tasks::<T>();
         let caller: T::AccountId = funded_account::<T>("caller", 0);
         let gpu_model: BoundedVec<u8, T::MaxGpuModelLen> = b"RTX4090".to_vec().try_into().unwrap();
         let gpu_memory = 16_384u32;
+        let gpu_count = 8u32;
         let has_nvlink = true;
         let nvlink_efficiency = 130u32;
+        let region = *b"US";
         let price: BalanceOf<T> = 1000u32.into();
-    }: _(RawOrigin::Signed(caller), gpu_model, gpu_memory, has_nvlink, nvlink_efficiency, price)
+    }: _(
+        RawOrigin::Signed(caller),
+        gpu_model,
+        gpu_memory,
+        gpu_count,
+        has_nvlink,
+        nvlink_efficiency,
+        region,
+        price
+    )
+    verify {
+        let pool_id = NextPoolId::<T>::get().saturating_sub(1);
+        let starts = MAX_QUEUED_STARTS.min(T::MaxQueuedTasks::get());
+        assert_eq!(ActiveTaskCount::<T>::get(pool_id), starts);
+    }
 
     update_pool_config {
         let caller: T::AccountId = funded_account::<T>("caller", 0);
         let pool_id = create_pool::<T>(&caller);
+        queue_global_tasks::<T>();
 
         let gpu_model: BoundedVec<u8, T::MaxGpuModelLen> = b"A100".to_vec().try_into().unwrap();
         let gpu_memory = 32_768u32;
+        let gpu_count = 4u32;
         let has_nvlink = true;
         let nvlink_efficiency = 135u32;
+        let region = *b"EU";
         let price: BalanceOf<T> = 1200u32.into();
-    }: _(RawOrigin::Signed(caller), pool_id, gpu_model, gpu_memory, has_nvlink, nvlink_efficiency, price)
+    }: _(
+        RawOrigin::Signed(caller),
+        pool_id,
+        gpu_model,
+        gpu_memory,
+        gpu_count,
+        has_nvlink,
+        nvlink_efficiency,
+        region,
+        price
+    )
+    verify {
+        let starts = MAX_QUEUED_STARTS.min(T::MaxQueuedTasks::get());
+        assert_eq!(ActiveTaskCount::<T>::get(pool_id), starts);
+    }
 
     deregister_pool {
+        let n in 0 .. T::MaxQueuedTasks::get();
         let caller: T::AccountId = funded_account::<T>("caller", 0);
         let pool_id = create_pool::<T>(&caller);
+        for i in 0 .. n {
+            let user: T::AccountId = funded_account::<T>("user", i);
+            queue_task::<T>(&user, pool_id);
+        }
     }: _(RawOrigin::Signed(caller), pool_id)
 
     submit_task {
-        let _owner: T::AccountId = funded_account::<T>("owner", 0);
-        let _pool_id = create_pool::<T>(&_owner);
+        // `INDEXED_POOLS` pools over every profile. Every profile meets the task and the pools
+        // ranked ahead in its limit tier are over the price limit: selection reads all the
+        // profiles, walks `MAX_TIER_SCAN` pools of each limit tier and takes a cheaper tier's head
+        let profiles = T::MaxPoolProfiles::get();
+        create_profiled_pools::<T>(profiles, (INDEXED_POOLS / profiles).max(MAX_TIER_SCAN + 2));
         let user: T::AccountId = funded_account::<T>("user", 1);
+        let task_id = NextTaskId::<T>::get();
         let dimensions = TaskDimensions { m: 128, n: 128, k: 128 };
+        let requirements = profile_requirements::<T>();
         let priority = TaskPriority::Normal;
-    }: _(RawOrigin::Signed(user), dimensions, priority, None)
+    }: _(RawOrigin::Signed(user), dimensions, requirements, priority, None)
+    verify {
+        let task = Tasks::<T>::get(task_id).expect("task exists");
+        assert!(matches!(task.status, TaskStatus::Assigned));
+        let pool = Pools::<T>::get(task.pool_id).expect("pool exists");
+        assert_eq!(pool.price_per_task, 1000u32.into());
+    }
+
+    submit_sharded_task {
+        let s in 2 .. T::MaxShardsPerTask::get();
+        // The other profiles fill the index without meeting the shards' memory requirement.
+        // In the shards' profile the best ranked pool is over the price limit, so each shard
+        // walks the price tier below past the pools earlier shards took.
+        create_profiled_pools::<T>(T::MaxPoolProfiles::get().saturating_sub(1), 2);
+        let expensive: T::AccountId = funded_account::<T>("owner", 0);
+        create_priced_pool::<T>(&expensive, 16_384, 150, 2000);
+        for i in 1 ..= s {
+            let owner: T::AccountId = funded_account::<T>("owner", i);
+            create_priced_pool::<T>(&owner, 16_384, 100, 1000);
+        }
+        let user: T::AccountId = funded_account::<T>("user", 1);
+        let task_id = NextTaskId::<T>::get();
+        let dimensions = TaskDimensions { m: 128, n: 128, k: 128 };
+        let requirements = TaskRequirements { min_vram: 16_384, ..profile_requirements::<T>() };
+    }: _(RawOrigin::Signed(user), dimensions, requirements, TaskPriority::Normal, s, 1)
+    verify {
+        let shards = TaskShards::<T>::get(task_id);
+        assert_eq!(shards.len() as u32, s);
+        let mut pools: Vec<PoolId> = shards
+            .iter()
+            .map(|shard_id| Tasks::<T>::get(shard_id).expect("shard exists").pool_id)
+            .collect();
+        pools.sort();
+        pools.dedup();
+        assert_eq!(pools.len() as u32, s);
+    }
+
+    reassign_shard {
+        let shards = T::MaxShardsPerTask::get();
+        // The other profiles fill the index without meeting the shards' memory requirement
+        create_profiled_pools::<T>(T::MaxPoolProfiles::get().saturating_sub(1), 2);
+        // One pool per shard and a spare one for the failed shard, all with the same profile:
+        // selection skips every pool the shards took before reaching the spare one
+        for i in 0 ..= shards {
+            let owner: T::AccountId = funded_account::<T>("owner", i);
+            create_pool::<T>(&owner);
+        }
+        let user: T::AccountId = funded_account::<T>("user", 1);
+        let task_id = NextTaskId::<T>::get();
+        Pallet::<T>::submit_sharded_task(
+            RawOrigin::Signed(user.clone()).into(),
+            TaskDimensions { m: 128, n: 128, k: 128 },
+            TaskRequirements { min_vram: 16_384, ..Default::default() },
+            TaskPriority::Normal,
+            shards,
+            1,
+        ).expect("submit_sharded_task failed");
+
+        // The first shard's proof is rejected
+        let shard_id = task_id.saturating_add(1);
+        let pool_id = Tasks::<T>::get(shard_id).expect("shard exists").pool_id;
+        let pool_owner = Pools::<T>::get(pool_id).expect("pool exists").owner;
+        Pallet::<T>::submit_proof(RawOrigin::Signed(pool_owner).into(), shard_id, [1u8; 32])
+            .expect("submit_proof failed");
+        let verifier: T::AccountId = funded_account::<T>("verifier", 2);
+        Pallet::<T>::verify_proof(RawOrigin::Signed(verifier).into(), shard_id, false)
+            .expect("verify_proof failed");
+    }: _(RawOrigin::Signed(user), shard_id)
+    verify {
+        let shard = Tasks::<T>::get(shard_id).expect("shard exists");
+        assert!(matches!(shard.status, TaskStatus::Computing));
+        assert!(shard.pool_id != pool_id);
+    }
 
     submit_proof {
         let owner: T::AccountId = funded_account::<T>("owner", 0);
@@ -123,10 +400,15 @@ benchmarks! {
             }
         });
         ProofSubmittedAt::<T>::insert(task_id, frame_system::Pallet::<T>::block_number());
+        let started = queue_slot_fill::<T>(pool_id);
 
         // Independent verifier (not the pool owner)
         let verifier: T::AccountId = funded_account::<T>("verifier", 2);
     }: _(RawOrigin::Signed(verifier), task_id, true)
+    verify {
+        let started = Tasks::<T>::get(started).expect("task exists");
+        assert!(matches!(started.status, TaskStatus::Computing));
+    }
 
     claim_reward {
         let owner: T::AccountId = funded_account::<T>("owner", 0);
@@ -143,6 +425,10 @@ benchmarks! {
             }
         });
         Rewards::<T>::insert(task_id, BalanceOf::<T>::from(500u32));
+
+        // Delegators share the reward
+        let staker: T::AccountId = funded_account::<T>("staker", 3);
+        delegate_with_rewards::<T>(&staker, pool_id);
     }: _(RawOrigin::Signed(owner), task_id)
 
     dispute_verification {
@@ -161,32 +447,129 @@ benchmarks! {
         });
     }: _(RawOrigin::Signed(user), task_id)
 
+    // Scan of `n` active tasks, each in its own pool and waiting for verification, none of
+    // them due: every task reads its pool's task list, the task and its proof submission
     on_initialize {
+        let n in 0 .. 1_000;
         use frame_support::traits::Hooks;
         let owner: T::AccountId = funded_account::<T>("owner", 0);
-        let _pool_id = create_pool::<T>(&owner);
-        frame_system::Pallet::<T>::set_block_number(100u32.into());
+        let user: T::AccountId = funded_account::<T>("user", 1);
+        let now: frame_system::pallet_prelude::BlockNumberFor<T> = 100u32.into();
+        frame_system::Pallet::<T>::set_block_number(now);
+        for pool_id in 0 .. n as PoolId {
+            let task_id = create_computing_task::<T>(&owner, &user, pool_id);
+            Tasks::<T>::mutate(task_id, |maybe_task| {
+                if let Some(task) = maybe_task.as_mut() {
+                    task.proof_hash = Some([1u8; 32]);
+                    task.status = TaskStatus::ProofSubmitted;
+                }
+            });
+            ProofSubmittedAt::<T>::insert(task_id, now);
+            PoolTasks::<T>::insert(pool_id, BoundedVec::truncate_from(vec![task_id]));
+        }
+    }: {
+        <Pallet::<T> as Hooks<frame_system::pallet_prelude::BlockNumberFor<T>>>::on_initialize(now);
+    }
+    verify {
+        for task_id in 0 .. n as TaskId {
+            let task = Tasks::<T>::get(task_id).expect("task exists");
+            assert!(matches!(task.status, TaskStatus::ProofSubmitted));
+        }
+    }
+
+    // A timed out shard of a sharded task with `MaxShardsPerTask` shards, from a pool with
+    // delegated stake to slash. The freed slot has no queued task to take, see
+    // `fill_pool_slots`.
+    time_out_task {
+        let shards = T::MaxShardsPerTask::get();
+        for i in 0 ..= shards {
+            let owner: T::AccountId = funded_account::<T>("owner", i);
+            create_pool::<T>(&owner);
+        }
+        let user: T::AccountId = funded_account::<T>("user", 1);
+        let task_id = NextTaskId::<T>::get();
+        Pallet::<T>::submit_sharded_task(
+            RawOrigin::Signed(user).into(),
+            TaskDimensions { m: 128, n: 128, k: 128 },
+            TaskRequirements { min_vram: 16_384, ..Default::default() },
+            TaskPriority::Normal,
+            shards,
+            1,
+        ).expect("submit_sharded_task failed");
+
+        let shard_id = task_id.saturating_add(1);
+        let shard = Tasks::<T>::get(shard_id).expect("shard exists");
+        let staker: T::AccountId = funded_account::<T>("staker", 3);
+        delegate_with_rewards::<T>(&staker, shard.pool_id);
+        let now = Pallet::<T>::expires_at(&shard).saturating_add(1u32.into());
+        frame_system::Pallet::<T>::set_block_number(now);
+    }: {
+        Pallet::<T>::mark_task_failed(shard_id, true).expect("mark_task_failed failed");
+    }
+    verify {
+        let shard = Tasks::<T>::get(shard_id).expect("shard exists");
+        assert!(matches!(shard.status, TaskStatus::Failed));
+    }
+
+    fill_pool_slots {
+        let owner: T::AccountId = funded_account::<T>("owner", 0);
+        let pool_id = create_pool::<T>(&owner);
+        let started = queue_slot_fill::<T>(pool_id);
     }: {
-        <Pallet::<T> as Hooks<frame_system::pallet_prelude::BlockNumberFor<T>>>::on_initialize(100u32.into());
+        Pallet::<T>::fill_pool_slots(pool_id);
+    }
+    verify {
+        let started = Tasks::<T>::get(started).expect("task exists");
+        assert!(matches!(started.status, TaskStatus::Computing));
     }
 
     stake_to_pool {
         let owner: T::AccountId = funded_account::<T>("owner", 0);
         let pool_id = create_pool::<T>(&owner);
         let staker: T::AccountId = funded_account::<T>("staker", 1);
-        let amount: BalanceOf<T> = 100u32.into();
+        let amount = delegate_with_rewards::<T>(&staker, pool_id);
     }: _(RawOrigin::Signed(staker), pool_id, amount)
 
     unstake_from_pool {
         let owner: T::AccountId = funded_account::<T>("owner", 0);
         let pool_id = create_pool::<T>(&owner);
         let staker: T::AccountId = funded_account::<T>("staker", 1);
-        let amount: BalanceOf<T> = 100u32.into();
-        Pallet::<T>::stake_to_pool(
+        let amount = delegate_with_rewards::<T>(&staker, pool_id);
+    }: _(RawOrigin::Signed(staker), pool_id, amount)
+
+    withdraw_unbonded {
+        let owner: T::AccountId = funded_account::<T>("owner", 0);
+        let pool_id = create_pool::<T>(&owner);
+        let staker: T::AccountId = funded_account::<T>("staker", 1);
+        let amount = delegate_with_rewards::<T>(&staker, pool_id);
+        Pallet::<T>::unstake_from_pool(
             RawOrigin::Signed(staker.clone()).into(),
             pool_id,
             amount,
-        ).expect("stake failed");
-    }: _(RawOrigin::Signed(staker), pool_id, amount)
+        ).expect("unstake failed");
+        let unlock_at = frame_system::Pallet::<T>::block_number()
+            .saturating_add(T::UnbondingPeriod::get());
+        frame_system::Pallet::<T>::set_block_number(unlock_at);
+    }: _(RawOrigin::Signed(staker), pool_id)
+
+    claim_delegator_rewards {
+        let owner: T::AccountId = funded_account::<T>("owner", 0);
+        let pool_id = create_pool::<T>(&owner);
+        let staker: T::AccountId = funded_account::<T>("staker", 1);
+        delegate_with_rewards::<T>(&staker, pool_id);
+    }: _(RawOrigin::Signed(staker), pool_id)
+
+    cancel_queued_task {
+        let owner: T::AccountId = funded_account::<T>("owner", 0);
+        let pool_id = create_pool::<T>(&owner);
+        let user: T::AccountId = funded_account::<T>("user", 1);
+        let task_id = queue_task::<T>(&user, pool_id);
+    }: _(RawOrigin::Signed(user), task_id)
+
+    set_pool_commission {
+        let owner: T::AccountId = funded_account::<T>("owner", 0);
+        let pool_id = create_pool::<T>(&owner);
+        let commission = T::MaxCommission::get();
+    }: _(RawOrigin::Signed(owner), pool_id, commission)
 
-}
\ No newline at end of file
+}
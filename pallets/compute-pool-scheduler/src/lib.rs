@@ -1,13 +1,16 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub mod migrations;
 pub mod weights;
 
 use frame_support::{
     traits::Currency,
     weights::Weight,
 };
-use dbc_support::traits::TaskCompletionHandler;
+use dbc_support::traits::{
+    MaxTaskGpuModels, RegionCode, TaskCompletionHandler, TaskOrderContext, TaskRequirements,
+};
 
 // Re-export BalanceOf for use in trait implementations
 type BalanceOf<T> = <<T as pallet::Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -33,16 +36,23 @@ pub mod pallet {
     use frame_support::{
         dispatch::DispatchResult,
         pallet_prelude::*,
-        traits::{tokens::BalanceStatus, Currency, ReservableCurrency},
+        traits::{tokens::BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency},
+        PalletId,
     };
     use frame_system::pallet_prelude::*;
-    use sp_runtime::traits::{Saturating, UniqueSaturatedInto, Zero};
-    use sp_runtime::{ArithmeticError, RuntimeDebug};
+    use sp_runtime::traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero};
+    use sp_runtime::{
+        helpers_128bit::multiply_by_rational_with_rounding, ArithmeticError, FixedPointNumber,
+        FixedU128, Perbill, Rounding, RuntimeDebug,
+    };
     use sp_std::vec::Vec;
 
     type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+    /// Queued tasks with their priority, highest priority and then oldest first
+    pub type TaskQueue<T> = BoundedVec<(TaskPriority, TaskId), <T as Config>::MaxQueuedTasks>;
+
     #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     pub enum PoolStatus {
         Active,
@@ -50,7 +60,10 @@ pub mod pallet {
         Deregistered,
     }
 
-    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    /// Variants are ordered from lowest to highest priority
+    #[derive(
+        Encode, Decode, Clone, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    )]
     pub enum TaskPriority {
         Low,
         Normal,
@@ -87,6 +100,112 @@ pub mod pallet {
         pub final_score: u32,
     }
 
+    /// Hardware a pool offers, what task requirements other than the price are matched
+    /// against. Pools with the same profile share the buckets of the selection index.
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct PoolProfile {
+        pub gpu_memory: u32,
+        pub gpu_count: u32,
+        pub has_nvlink: bool,
+        pub region: RegionCode,
+        /// `blake2_256` of the GPU model
+        pub gpu_model_hash: [u8; 32],
+    }
+
+    /// Bucket of the pool selection index
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct PoolBucket {
+        pub profile: PoolProfile,
+        /// Whether the pool has a free task slot
+        pub available: bool,
+    }
+
+    /// Position of a pool inside its bucket: the inverted cached score, the price and the pool
+    /// id, all big-endian so that a bucket iterates best score first, then cheapest.
+    pub type PoolRank = [u8; 28];
+
+    /// Price tier of a pool: the bit length of its price, so that each tier spans a doubling
+    pub type PriceTier = u8;
+
+    /// Price tiers a bucket can span, one per bit length of a `u128` price
+    pub const PRICE_TIERS: u32 = 129;
+
+    /// Pools of the price tier a task's price limit falls in that selection looks through for
+    /// one within the limit, so that the cost of a selection does not grow with the tier
+    pub const MAX_TIER_SCAN: u32 = 8;
+
+    /// A profile of the selection index
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct IndexedProfile {
+        pub profile: PoolProfile,
+        /// Indexed pools with this profile
+        pub pools: u32,
+    }
+
+    /// Where a pool currently sits in the selection index
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct IndexedPool {
+        pub bucket: PoolBucket,
+        pub rank: PoolRank,
+    }
+
+    /// Queued tasks a pool takes in one go, whether they start or are dropped for no longer
+    /// being able to, so that freeing or adding slots has a bounded cost
+    pub const MAX_QUEUED_STARTS: u32 = 4;
+
+    /// `TaskRequirements` prepared for matching against pool profiles
+    pub(crate) struct RequirementFilter {
+        min_vram: u32,
+        gpu_count: u32,
+        nvlink_required: bool,
+        region: Option<RegionCode>,
+        max_price: Option<u128>,
+        gpu_model_hashes: Vec<[u8; 32]>,
+    }
+
+    impl RequirementFilter {
+        pub(crate) fn new<Balance, BlockNumber>(
+            requirements: &TaskRequirements<Balance, BlockNumber>,
+        ) -> Self
+        where
+            Balance: Clone + UniqueSaturatedInto<u128>,
+        {
+            Self {
+                min_vram: requirements.min_vram,
+                gpu_count: requirements.gpu_count,
+                nvlink_required: requirements.nvlink_required,
+                region: requirements.region,
+                max_price: requirements
+                    .max_price
+                    .clone()
+                    .map(|price| price.unique_saturated_into()),
+                gpu_model_hashes: requirements
+                    .gpu_models
+                    .iter()
+                    .map(|model| sp_io::hashing::blake2_256(model))
+                    .collect(),
+            }
+        }
+
+        /// Whether a pool with `profile` charging `price` meets the requirements
+        pub(crate) fn matches(&self, profile: &PoolProfile, price: u128) -> bool {
+            self.matches_profile(profile) && self.accepts_price(price)
+        }
+
+        pub(crate) fn matches_profile(&self, profile: &PoolProfile) -> bool {
+            profile.gpu_memory >= self.min_vram &&
+                profile.gpu_count >= self.gpu_count &&
+                (profile.has_nvlink || !self.nvlink_required) &&
+                self.region.map_or(true, |region| profile.region == region) &&
+                (self.gpu_model_hashes.is_empty() ||
+                    self.gpu_model_hashes.contains(&profile.gpu_model_hash))
+        }
+
+        pub(crate) fn accepts_price(&self, price: u128) -> bool {
+            self.max_price.map_or(true, |max_price| price <= max_price)
+        }
+    }
+
     #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(MaxGpuModelLen))]
     pub struct ComputePool<AccountId, Balance, MaxGpuModelLen: Get<u32>> {
@@ -94,8 +213,10 @@ pub mod pallet {
         pub owner: AccountId,
         pub gpu_model: BoundedVec<u8, MaxGpuModelLen>,
         pub gpu_memory: u32,
+        pub gpu_count: u32,
         pub has_nvlink: bool,
         pub nvlink_efficiency: u32,
+        pub region: RegionCode,
         pub price_per_task: Balance,
         pub reputation: u32,
         pub success_rate: u32,
@@ -107,12 +228,14 @@ pub mod pallet {
         pub score: PoolScore,
     }
 
-    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
     pub struct ComputeTask<AccountId, BlockNumber, Balance> {
         pub task_id: TaskId,
         pub user: AccountId,
         pub pool_id: PoolId,
         pub dimensions: TaskDimensions,
+        /// Pools the task may run on
+        pub requirements: TaskRequirements<Balance, BlockNumber>,
         pub priority: TaskPriority,
         pub status: TaskStatus,
         pub submitted_at: BlockNumber,
@@ -146,6 +269,38 @@ pub mod pallet {
         pub claimed: bool,
     }
 
+    /// Delegated stake of a pool. Delegators hold points rather than balances so that rewards
+    /// and slashes apply to everyone at once without iterating them.
+    #[derive(
+        Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default,
+    )]
+    pub struct StakePool<Balance> {
+        /// Share of each task reward the pool owner keeps before splitting the rest
+        pub commission: Perbill,
+        /// Points issued against the active delegated stake (`TotalPoolStake`)
+        pub total_points: Balance,
+        /// Delegator rewards accumulated per active point
+        pub reward_per_point: FixedU128,
+        /// Points issued against the unbonding stake
+        pub unbonding_points: Balance,
+        /// Stake that is unbonding and still exposed to slashes
+        pub unbonding_stake: Balance,
+    }
+
+    #[derive(
+        Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default,
+    )]
+    pub struct Delegation<Balance, BlockNumber> {
+        /// Share of the pool's active delegated stake
+        pub points: Balance,
+        /// Rewards per point already paid out for `points`
+        pub reward_debt: Balance,
+        /// Share of the pool's unbonding stake
+        pub unbonding_points: Balance,
+        /// Block from which the unbonding stake can be withdrawn
+        pub unlock_at: BlockNumber,
+    }
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
@@ -160,10 +315,22 @@ pub mod pallet {
         type TaskTimeout: Get<BlockNumberFor<Self>>;
         #[pallet::constant]
         type MaxGpuModelLen: Get<u32>;
+        /// Minimum stake a delegator keeps in a pool
         #[pallet::constant]
         type MinPoolStake: Get<BalanceOf<Self>>;
+        /// Percent of the delegated stake added to `FailureSlash` on each pool slash. The total
+        /// is then split pro rata between the owner deposit and the delegators.
         #[pallet::constant]
         type StakeSlashPercent: Get<u32>;
+        /// Blocks delegated stake stays slashable after unstaking
+        #[pallet::constant]
+        type UnbondingPeriod: Get<BlockNumberFor<Self>>;
+        /// Highest commission a pool owner can set
+        #[pallet::constant]
+        type MaxCommission: Get<Perbill>;
+        /// Derives the accounts holding each pool's delegated stake and rewards
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
         #[pallet::constant]
         type MaxTasksPerPool: Get<u32>;
         #[pallet::constant]
@@ -171,12 +338,31 @@ pub mod pallet {
         /// Blocks to wait for independent verification before auto-approving a submitted proof
         #[pallet::constant]
         type VerificationTimeout: Get<BlockNumberFor<Self>>;
+        /// Tasks each pool queue and the global queue can hold
+        #[pallet::constant]
+        type MaxQueuedTasks: Get<u32>;
+        /// Extra reward charged per priority level above `Normal`, as a share of the base reward
+        #[pallet::constant]
+        type PriorityFee: Get<Perbill>;
+        /// GPU models a task can list in its requirements, at most `MaxTaskGpuModels`
+        #[pallet::constant]
+        type MaxRequiredGpuModels: Get<u32>;
+        /// Distinct pool profiles (GPU model, memory, count, NVLink and region) the selection
+        /// index can hold
+        #[pallet::constant]
+        type MaxPoolProfiles: Get<u32>;
+        /// Shards a task can be split into
+        #[pallet::constant]
+        type MaxShardsPerTask: Get<u32>;
         type WeightInfo: WeightInfo;
         /// Handler to notify when a task is completed
-        type OnTaskCompleted: dbc_support::traits::TaskCompletionHandler<AccountId = Self::AccountId>;
+        type OnTaskCompleted: dbc_support::traits::TaskCompletionHandler<
+            AccountId = Self::AccountId,
+            Balance = BalanceOf<Self>,
+        >;
     }
 
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
@@ -247,20 +433,106 @@ pub mod pallet {
 
     #[pallet::storage]
     #[pallet::getter(fn pool_stakes)]
-    pub type PoolStakes<T: Config> =
-        StorageDoubleMap<_, Blake2_128Concat, PoolId, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+    pub type PoolStakes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        PoolId,
+        Blake2_128Concat,
+        T::AccountId,
+        Delegation<BalanceOf<T>, BlockNumberFor<T>>,
+        OptionQuery,
+    >;
 
+    /// Active delegated stake of each pool
     #[pallet::storage]
     #[pallet::getter(fn total_pool_stake)]
     pub type TotalPoolStake<T: Config> =
         StorageMap<_, Blake2_128Concat, PoolId, BalanceOf<T>, ValueQuery>;
 
+    #[pallet::storage]
+    #[pallet::getter(fn stake_pools)]
+    pub type StakePools<T: Config> =
+        StorageMap<_, Blake2_128Concat, PoolId, StakePool<BalanceOf<T>>, ValueQuery>;
+
+    /// Tasks waiting for a slot on a specific pool, by priority and then age
+    #[pallet::storage]
+    #[pallet::getter(fn pool_queue)]
+    pub type PoolQueue<T: Config> =
+        StorageMap<_, Blake2_128Concat, PoolId, TaskQueue<T>, ValueQuery>;
+
+    /// Tasks waiting for a slot on any pool that fits them, by priority and then age
+    #[pallet::storage]
+    #[pallet::getter(fn global_queue)]
+    pub type GlobalQueue<T: Config> = StorageValue<_, TaskQueue<T>, ValueQuery>;
+
+    /// Active pools bucketed by profile and free slots, then split by price tier. Ranks are
+    /// stored unhashed so each tier iterates in rank order.
+    #[pallet::storage]
+    pub type PoolIndex<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        (PoolBucket, PriceTier),
+        Identity,
+        PoolRank,
+        PoolId,
+        OptionQuery,
+    >;
+
+    /// Best ranked pool of each price tier of a bucket, cheapest tier first
+    #[pallet::storage]
+    pub type PoolTierHeads<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        PoolBucket,
+        BoundedVec<PoolRank, ConstU32<PRICE_TIERS>>,
+        OptionQuery,
+    >;
+
+    /// Profiles of the indexed pools
+    #[pallet::storage]
+    #[pallet::getter(fn indexed_profiles)]
+    pub type IndexedProfiles<T: Config> =
+        StorageValue<_, BoundedVec<IndexedProfile, T::MaxPoolProfiles>, ValueQuery>;
+
+    /// Current entry of each pool in `PoolIndex`
+    #[pallet::storage]
+    #[pallet::getter(fn indexed_pool)]
+    pub type IndexedPools<T: Config> =
+        StorageMap<_, Blake2_128Concat, PoolId, IndexedPool, OptionQuery>;
+
+    /// Shards of a sharded task, row by row over the shard grid
+    #[pallet::storage]
+    #[pallet::getter(fn task_shards)]
+    pub type TaskShards<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        TaskId,
+        BoundedVec<TaskId, T::MaxShardsPerTask>,
+        ValueQuery,
+    >;
+
+    /// Sharded task each shard belongs to
+    #[pallet::storage]
+    #[pallet::getter(fn shard_parent)]
+    pub type ShardParent<T: Config> = StorageMap<_, Blake2_128Concat, TaskId, TaskId, OptionQuery>;
+
     /// Block number when proof was submitted, used for verification timeout
     #[pallet::storage]
     #[pallet::getter(fn proof_submitted_at)]
     pub type ProofSubmittedAt<T: Config> =
         StorageMap<_, Blake2_128Concat, TaskId, BlockNumberFor<T>, OptionQuery>;
 
+    /// Order context for tasks created on behalf of a task-mode order
+    #[pallet::storage]
+    #[pallet::getter(fn task_order_context)]
+    pub type TaskOrderContexts<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        TaskId,
+        TaskOrderContext<T::AccountId, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -268,6 +540,11 @@ pub mod pallet {
         PoolConfigUpdated { pool_id: PoolId },
         PoolDeregistered { pool_id: PoolId, owner: T::AccountId },
         TaskSubmitted { task_id: TaskId, user: T::AccountId },
+        /// No pool had a free slot; `pool_id` is the preferred pool, if any
+        TaskQueued { task_id: TaskId, pool_id: Option<PoolId>, priority: TaskPriority },
+        /// A queued low priority task made room for a critical one
+        TaskPreempted { task_id: TaskId, by: TaskId },
+        QueuedTaskCancelled { task_id: TaskId },
         TaskAssigned { task_id: TaskId, pool_id: PoolId, final_score: u32 },
         TaskStatusChanged { task_id: TaskId, status: TaskStatus },
         ProofSubmitted { task_id: TaskId, pool_id: PoolId },
@@ -278,8 +555,26 @@ pub mod pallet {
         TaskTimedOut { task_id: TaskId },
         PoolSlashed { pool_id: PoolId, amount: BalanceOf<T> },
         Staked { who: T::AccountId, pool_id: PoolId, amount: BalanceOf<T> },
+        /// Delegated stake started unbonding
+        Unbonding {
+            who: T::AccountId,
+            pool_id: PoolId,
+            amount: BalanceOf<T>,
+            unlock_at: BlockNumberFor<T>,
+        },
+        /// Unbonded stake was withdrawn
         Unstaked { who: T::AccountId, pool_id: PoolId, amount: BalanceOf<T> },
         StakeSlashed { pool_id: PoolId, amount: BalanceOf<T> },
+        PoolCommissionSet { pool_id: PoolId, commission: Perbill },
+        /// Part of a task reward went to the pool's delegators
+        DelegatorRewardsDistributed { pool_id: PoolId, amount: BalanceOf<T> },
+        DelegatorRewardPaid { who: T::AccountId, pool_id: PoolId, amount: BalanceOf<T> },
+        /// A task was split into `shards` shard tasks
+        TaskSharded { task_id: TaskId, shards: u32 },
+        /// A failed shard was submitted again
+        ShardReassigned { shard_id: TaskId, task_id: TaskId },
+        /// Every shard of a task verified; `result_hash` aggregates the shard proofs
+        ShardedTaskCompleted { task_id: TaskId, result_hash: [u8; 32] },
     }
 
     #[pallet::error]
@@ -311,6 +606,35 @@ pub mod pallet {
         SelfVerificationNotAllowed,
         /// Task proof has not been submitted yet
         ProofNotSubmitted,
+        /// Commission is above `MaxCommission`
+        CommissionTooHigh,
+        /// Unbonding stake cannot be withdrawn yet
+        StakeStillUnbonding,
+        /// The pool's stake was slashed to zero while points remain
+        PoolStakeDepleted,
+        /// The delegator has no rewards to claim
+        NoRewards,
+        /// The task queue is full
+        QueueFull,
+        /// Region is not an upper case ISO 3166-1 alpha-2 code
+        InvalidRegion,
+        /// Too many GPU models, or a model longer than `MaxGpuModelLen`
+        InvalidRequirements,
+        /// The preferred pool does not meet the task requirements
+        RequirementsNotMet,
+        /// The task deadline has passed
+        DeadlinePassed,
+        /// The selection index already holds `MaxPoolProfiles` other pool profiles
+        TooManyPoolProfiles,
+        /// The shard grid is empty, a single shard, larger than the task or has more than
+        /// `MaxShardsPerTask` shards
+        InvalidShardGrid,
+        /// Proofs and disputes go to the shards of a sharded task
+        ShardedTask,
+        /// The task is not a shard of a sharded task
+        NotAShard,
+        /// The sharded task the shard belongs to has already completed or failed
+        ShardedTaskFinished,
     }
 
     #[pallet::genesis_config]
@@ -335,13 +659,14 @@ pub mod pallet {
         fn on_initialize(now: BlockNumberFor<T>) -> Weight {
             let mut expired: Vec<TaskId> = Vec::new();
             let mut auto_verify: Vec<TaskId> = Vec::new();
-            let mut reads: u64 = 0;
+            let mut scanned: u32 = 0;
 
             // Only iterate active tasks via PoolTasks (bounded per pool by MaxTasksPerPool)
             // instead of Tasks::iter() which grows unbounded
             for (_pool_id, task_ids) in PoolTasks::<T>::iter() {
+                // A pool without tasks costs as much as a scanned task
+                scanned = scanned.saturating_add((task_ids.len() as u32).max(1));
                 for task_id in task_ids.iter() {
-                    reads = reads.saturating_add(1);
                     if let Some(task) = Tasks::<T>::get(task_id) {
                         if Self::is_terminal(&task.status) {
                             continue;
@@ -349,7 +674,6 @@ pub mod pallet {
                         // Auto-verify: ProofSubmitted tasks past VerificationTimeout
                         if matches!(task.status, TaskStatus::ProofSubmitted) {
                             if let Some(proof_at) = ProofSubmittedAt::<T>::get(task_id) {
-                                reads = reads.saturating_add(1);
                                 if now > proof_at.saturating_add(T::VerificationTimeout::get()) {
                                     auto_verify.push(*task_id);
                                     continue;
@@ -357,7 +681,7 @@ pub mod pallet {
                             }
                         }
                         // Total task timeout — fail tasks that exceeded TaskTimeout
-                        if now > task.submitted_at.saturating_add(T::TaskTimeout::get()) {
+                        if now > Self::expires_at(&task) {
                             expired.push(*task_id);
                         }
                     }
@@ -378,13 +702,19 @@ pub mod pallet {
                 let _ = Self::mark_task_failed(task_id, true);
             }
 
-            // Return accurate weight based on actual work done
-            let total_processed = expired_count.saturating_add(auto_verify_count);
-            T::DbWeight::get().reads(reads.saturating_add(1))
-                .saturating_add(T::DbWeight::get().reads_writes(
-                    total_processed.saturating_mul(5),
-                    total_processed.saturating_mul(5),
-                ))
+            // Auto-verification runs the `verify_proof` path, which includes filling the freed
+            // slot. A timed out task frees a slot the queued tasks are taken into.
+            T::WeightInfo::on_initialize(scanned)
+                .saturating_add(T::WeightInfo::verify_proof().saturating_mul(auto_verify_count))
+                .saturating_add(
+                    T::WeightInfo::time_out_task()
+                        .saturating_add(T::WeightInfo::fill_pool_slots())
+                        .saturating_mul(expired_count),
+                )
+        }
+
+        fn integrity_test() {
+            assert!(T::MaxRequiredGpuModels::get() <= MaxTaskGpuModels::get());
         }
     }
 
@@ -396,15 +726,18 @@ pub mod pallet {
             origin: OriginFor<T>,
             gpu_model: BoundedVec<u8, T::MaxGpuModelLen>,
             gpu_memory: u32,
+            gpu_count: u32,
             has_nvlink: bool,
             nvlink_efficiency: u32,
+            region: RegionCode,
             price_per_task: BalanceOf<T>,
         ) -> DispatchResult {
             let owner = ensure_signed(origin)?;
             ensure!(!PoolByOwner::<T>::contains_key(&owner), Error::<T>::PoolAlreadyExists);
-            ensure!(gpu_memory > 0, Error::<T>::InvalidDimensions);
+            ensure!(gpu_memory > 0 && gpu_count > 0, Error::<T>::InvalidDimensions);
             ensure!(!price_per_task.is_zero(), Error::<T>::InsufficientBalance);
             Self::ensure_nvlink(has_nvlink, nvlink_efficiency)?;
+            Self::ensure_region(&region)?;
 
             T::Currency::reserve(&owner, T::PoolDeposit::get())
                 .map_err(|_| Error::<T>::InsufficientBalance)?;
@@ -414,8 +747,10 @@ pub mod pallet {
                 owner: owner.clone(),
                 gpu_model,
                 gpu_memory,
+                gpu_count,
                 has_nvlink,
                 nvlink_efficiency,
+                region,
                 price_per_task,
                 reputation: T::InitialReputation::get().min(100),
                 success_rate: 100,
@@ -426,6 +761,7 @@ pub mod pallet {
                 deposit_held: T::PoolDeposit::get(),
                 score: PoolScore::default(),
             };
+            Self::ensure_profile_room(pool_id, &Self::pool_profile(&pool))?;
 
             Pools::<T>::insert(pool_id, pool);
             PoolByOwner::<T>::insert(&owner, pool_id);
@@ -439,10 +775,12 @@ pub mod pallet {
                 },
             );
             ActiveTaskCount::<T>::insert(pool_id, 0);
+            Self::reindex_pool(pool_id);
             let next_pool_id = pool_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
             NextPoolId::<T>::put(next_pool_id);
 
             Self::deposit_event(Event::PoolRegistered { pool_id, owner });
+            Self::fill_pool_slots(pool_id);
             Ok(())
         }
 
@@ -453,14 +791,17 @@ pub mod pallet {
             pool_id: PoolId,
             gpu_model: BoundedVec<u8, T::MaxGpuModelLen>,
             gpu_memory: u32,
+            gpu_count: u32,
             has_nvlink: bool,
             nvlink_efficiency: u32,
+            region: RegionCode,
             price_per_task: BalanceOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
-            ensure!(gpu_memory > 0, Error::<T>::InvalidDimensions);
+            ensure!(gpu_memory > 0 && gpu_count > 0, Error::<T>::InvalidDimensions);
             ensure!(!price_per_task.is_zero(), Error::<T>::InsufficientBalance);
             Self::ensure_nvlink(has_nvlink, nvlink_efficiency)?;
+            Self::ensure_region(&region)?;
 
             Pools::<T>::try_mutate(pool_id, |maybe_pool| -> DispatchResult {
                 let pool = maybe_pool.as_mut().ok_or(Error::<T>::PoolNotFound)?;
@@ -472,19 +813,23 @@ pub mod pallet {
 
                 pool.gpu_model = gpu_model;
                 pool.gpu_memory = gpu_memory;
+                pool.gpu_count = gpu_count;
                 pool.has_nvlink = has_nvlink;
                 pool.nvlink_efficiency = nvlink_efficiency;
+                pool.region = region;
                 pool.price_per_task = price_per_task;
-
-                Ok(())
+                Self::ensure_profile_room(pool_id, &Self::pool_profile(pool))
             })?;
+            Self::reindex_pool(pool_id);
 
             Self::deposit_event(Event::PoolConfigUpdated { pool_id });
+            // The pool may now meet queued tasks it did not before
+            Self::fill_pool_slots(pool_id);
             Ok(())
         }
 
         #[pallet::call_index(2)]
-        #[pallet::weight(T::WeightInfo::deregister_pool())]
+        #[pallet::weight(T::WeightInfo::deregister_pool(T::MaxQueuedTasks::get()))]
         pub fn deregister_pool(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
             let sender = ensure_signed(origin)?;
             let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
@@ -496,18 +841,25 @@ pub mod pallet {
             Pools::<T>::remove(pool_id);
             PoolTasks::<T>::remove(pool_id);
             ActiveTaskCount::<T>::remove(pool_id);
+            Self::reindex_pool(pool_id);
             PoolByOwner::<T>::remove(&owner);
+            for (_, task_id) in PoolQueue::<T>::take(pool_id) {
+                Self::drop_queued_task(task_id);
+            }
             let _ = T::Currency::unreserve(&owner, held);
 
             Self::deposit_event(Event::PoolDeregistered { pool_id, owner });
             Ok(())
         }
 
+        /// Submit a task. It runs on a pool with a free slot right away, otherwise it waits in
+        /// the preferred pool's queue or the global queue, ordered by priority and then age.
         #[pallet::call_index(3)]
         #[pallet::weight(T::WeightInfo::submit_task())]
         pub fn submit_task(
             origin: OriginFor<T>,
             dimensions: TaskDimensions,
+            requirements: TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
             priority: TaskPriority,
             preferred_pool_id: Option<PoolId>,
         ) -> DispatchResult {
@@ -516,15 +868,21 @@ pub mod pallet {
                 dimensions.m > 0 && dimensions.n > 0 && dimensions.k > 0,
                 Error::<T>::InvalidDimensions
             );
+            Self::ensure_requirements(&requirements)?;
 
             let task_id = NextTaskId::<T>::get();
             let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                requirements.deadline.map_or(true, |deadline| now <= deadline),
+                Error::<T>::DeadlinePassed
+            );
             let task = ComputeTask::<T::AccountId, BlockNumberFor<T>, BalanceOf<T>> {
                 task_id,
                 user: user.clone(),
-                pool_id: 0,
+                pool_id: preferred_pool_id.unwrap_or_default(),
                 dimensions,
-                priority,
+                requirements,
+                priority: priority.clone(),
                 status: TaskStatus::Pending,
                 submitted_at: now,
                 proof_hash: None,
@@ -533,90 +891,220 @@ pub mod pallet {
                 disputed: false,
             };
             Tasks::<T>::insert(task_id, task.clone());
+            let next_task_id = task_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
+            NextTaskId::<T>::put(next_task_id);
             Self::deposit_event(Event::TaskSubmitted { task_id, user: user.clone() });
             Self::deposit_event(Event::TaskStatusChanged { task_id, status: TaskStatus::Pending });
 
-            let (pool_id, score) = if let Some(pool_id) = preferred_pool_id {
-                let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
-                ensure!(matches!(pool.status, PoolStatus::Active), Error::<T>::PoolInactive);
-                ensure!(
-                    ActiveTaskCount::<T>::get(pool_id) < T::MaxTasksPerPool::get(),
-                    Error::<T>::TooManyActiveTasks
-                );
-                ensure!(pool.gpu_memory >= task.dimensions.k, Error::<T>::NoAvailablePool);
+            T::Currency::reserve(&user, T::TaskDeposit::get())
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
 
-                let score = Self::calculate_pool_score(
-                    &pool,
-                    pool.price_per_task.unique_saturated_into(),
-                    pool.price_per_task.unique_saturated_into(),
-                );
-                Pools::<T>::mutate(pool_id, |maybe_pool| {
-                    if let Some(pool) = maybe_pool {
-                        pool.score = score.clone();
-                    }
-                });
-                (pool_id, score)
+            let placement = if let Some(pool_id) = preferred_pool_id {
+                Self::preferred_placement(pool_id, &task.requirements)?
             } else {
-                Self::select_best_pool_for_task(&task)?
+                match Self::select_best_pool_for_task(&task, &[]) {
+                    Ok(selected) => Some(selected),
+                    // Every pool that fits the task is busy
+                    Err(_) if Self::best_indexed_pool(&task.requirements, false).is_some() => None,
+                    Err(e) => return Err(e),
+                }
             };
-            let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+            match placement {
+                Some((pool_id, score)) => Self::assign_task(task_id, pool_id, score),
+                None => Self::enqueue_task(task_id, priority, preferred_pool_id),
+            }
+        }
+
+        /// Withdraw a task that is still waiting in a queue
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::cancel_queued_task())]
+        pub fn cancel_queued_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+            ensure!(task.user == sender, Error::<T>::NotTaskUser);
+            ensure!(matches!(task.status, TaskStatus::Pending), Error::<T>::InvalidTaskState);
+
+            PoolQueue::<T>::mutate(task.pool_id, |queue| queue.retain(|(_, id)| *id != task_id));
+            GlobalQueue::<T>::mutate(|queue| queue.retain(|(_, id)| *id != task_id));
+            Self::drop_queued_task(task_id);
+            Self::deposit_event(Event::QueuedTaskCancelled { task_id });
+            Ok(())
+        }
+
+        /// Split a task into a `shards_m` by `shards_n` grid of shards along `m` and `n` and
+        /// spread them over pools meeting `requirements`. The task is priced as a whole on the
+        /// best such pool and each shard pays its share of that reward by volume. Shards are
+        /// proved, verified and paid like any task, and the task completes once all of them
+        /// verify.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::submit_sharded_task(shards_m.saturating_mul(*shards_n)))]
+        pub fn submit_sharded_task(
+            origin: OriginFor<T>,
+            dimensions: TaskDimensions,
+            requirements: TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+            priority: TaskPriority,
+            shards_m: u32,
+            shards_n: u32,
+        ) -> DispatchResult {
+            let user = ensure_signed(origin)?;
             ensure!(
-                ActiveTaskCount::<T>::get(pool_id) < T::MaxTasksPerPool::get(),
-                Error::<T>::TooManyActiveTasks
+                dimensions.m > 0 && dimensions.n > 0 && dimensions.k > 0,
+                Error::<T>::InvalidDimensions
+            );
+            let shard_count = shards_m.saturating_mul(shards_n);
+            ensure!(
+                (1..=dimensions.m).contains(&shards_m) &&
+                    (1..=dimensions.n).contains(&shards_n) &&
+                    (2..=T::MaxShardsPerTask::get()).contains(&shard_count),
+                Error::<T>::InvalidShardGrid
             );
+            Self::ensure_requirements(&requirements)?;
 
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                requirements.deadline.map_or(true, |deadline| now <= deadline),
+                Error::<T>::DeadlinePassed
+            );
+            let pricing_pool = Self::best_indexed_pool(&requirements, true)
+                .or_else(|| Self::best_indexed_pool(&requirements, false))
+                .and_then(Pools::<T>::get)
+                .ok_or(Error::<T>::NoAvailablePool)?;
             let reward = Self::calculate_reward(
-                &task.dimensions,
-                &pool.price_per_task,
-                pool.has_nvlink,
-                pool.nvlink_efficiency,
+                &dimensions,
+                &pricing_pool.price_per_task,
+                pricing_pool.has_nvlink,
+                pricing_pool.nvlink_efficiency,
             )?;
-            let task_deposit = T::TaskDeposit::get();
-            let total_reserved = reward.saturating_add(task_deposit);
-            T::Currency::reserve(&user, total_reserved)
-                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            let reward = reward.saturating_add(Self::priority_fee(&priority, reward));
+            let reward: u128 = reward.unique_saturated_into();
 
-            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
-                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
-                t.pool_id = pool_id;
-                t.status = TaskStatus::Assigned;
-                t.reward_amount = Some(reward);
-                Ok(())
-            })?;
-            Self::deposit_event(Event::TaskAssigned {
+            let task_id = NextTaskId::<T>::get();
+            let next_task_id = task_id
+                .checked_add(shard_count as u64 + 1)
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+            NextTaskId::<T>::put(next_task_id);
+            // The task itself runs nowhere; `pool_id` stays unused
+            let task = ComputeTask::<T::AccountId, BlockNumberFor<T>, BalanceOf<T>> {
                 task_id,
-                pool_id,
-                final_score: score.final_score,
-            });
-            Self::deposit_event(Event::TaskStatusChanged { task_id, status: TaskStatus::Assigned });
-
-            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
-                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
-                t.status = TaskStatus::Computing;
-                Ok(())
-            })?;
+                user: user.clone(),
+                pool_id: Default::default(),
+                dimensions: dimensions.clone(),
+                requirements: requirements.clone(),
+                priority: priority.clone(),
+                status: TaskStatus::Computing,
+                submitted_at: now,
+                proof_hash: None,
+                verification_result: None,
+                reward_amount: Some(reward.unique_saturated_into()),
+                disputed: false,
+            };
+            Tasks::<T>::insert(task_id, task);
+            Self::deposit_event(Event::TaskSubmitted { task_id, user: user.clone() });
+            Self::deposit_event(Event::TaskSharded { task_id, shards: shard_count });
             Self::deposit_event(Event::TaskStatusChanged {
                 task_id,
                 status: TaskStatus::Computing,
             });
 
-            PoolTasks::<T>::try_mutate(pool_id, |task_ids| {
-                task_ids.try_push(task_id).map_err(|_| Error::<T>::TooManyActiveTasks)
-            })?;
-            ActiveTaskCount::<T>::mutate(pool_id, |v| *v = v.saturating_add(1));
+            let total_volume = dimensions.m as u128 * dimensions.n as u128;
+            let rows = Self::split_dimension(dimensions.m, shards_m);
+            let columns = Self::split_dimension(dimensions.n, shards_n);
+            let mut shard_ids: BoundedVec<TaskId, T::MaxShardsPerTask> = BoundedVec::new();
+            let mut used_pools: Vec<PoolId> = Vec::new();
+            let mut paid = 0u128;
+            for (m, n) in rows.iter().flat_map(|m| columns.iter().map(move |n| (*m, *n))) {
+                let shard_id = task_id.saturating_add(shard_ids.len() as u64 + 1);
+                // The last shard takes the rounding remainder
+                let share = if shard_ids.len() as u32 + 1 == shard_count {
+                    reward.saturating_sub(paid)
+                } else {
+                    multiply_by_rational_with_rounding(
+                        reward,
+                        m as u128 * n as u128,
+                        total_volume,
+                        Rounding::Down,
+                    )
+                    .ok_or(ArithmeticError::Overflow)?
+                };
+                paid = paid.saturating_add(share);
+
+                let shard = ComputeTask::<T::AccountId, BlockNumberFor<T>, BalanceOf<T>> {
+                    task_id: shard_id,
+                    user: user.clone(),
+                    pool_id: Default::default(),
+                    dimensions: TaskDimensions { m, n, k: dimensions.k },
+                    requirements: requirements.clone(),
+                    priority: priority.clone(),
+                    status: TaskStatus::Pending,
+                    submitted_at: now,
+                    proof_hash: None,
+                    verification_result: None,
+                    reward_amount: Some(share.unique_saturated_into()),
+                    disputed: false,
+                };
+                Tasks::<T>::insert(shard_id, shard.clone());
+                ShardParent::<T>::insert(shard_id, task_id);
+                shard_ids.try_push(shard_id).map_err(|_| Error::<T>::InvalidShardGrid)?;
+                Self::deposit_event(Event::TaskSubmitted { task_id: shard_id, user: user.clone() });
+                Self::deposit_event(Event::TaskStatusChanged {
+                    task_id: shard_id,
+                    status: TaskStatus::Pending,
+                });
 
-            TaskEscrowStore::<T>::insert(
-                task_id,
-                TaskEscrow {
-                    user,
-                    pool_owner: pool.owner,
-                    reward_amount: reward,
-                    task_deposit,
-                    claimed: false,
-                },
+                T::Currency::reserve(&user, T::TaskDeposit::get())
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                if let Some(pool_id) = Self::place_task(&shard, &used_pools)? {
+                    used_pools.push(pool_id);
+                }
+            }
+            TaskShards::<T>::insert(task_id, shard_ids);
+            Ok(())
+        }
+
+        /// Submit a failed shard again, preferably on a pool other than the ones it failed on
+        /// or its siblings run on. Shards that already verified are kept.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::reassign_shard())]
+        pub fn reassign_shard(origin: OriginFor<T>, shard_id: TaskId) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let task_id = ShardParent::<T>::get(shard_id).ok_or(Error::<T>::NotAShard)?;
+            let shard = Tasks::<T>::get(shard_id).ok_or(Error::<T>::TaskNotFound)?;
+            ensure!(shard.user == sender, Error::<T>::NotTaskUser);
+            ensure!(matches!(shard.status, TaskStatus::Failed), Error::<T>::InvalidTaskState);
+            let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+            ensure!(!Self::is_terminal(&task.status), Error::<T>::InvalidTaskState);
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                shard.requirements.deadline.map_or(true, |deadline| now <= deadline),
+                Error::<T>::DeadlinePassed
             );
-            let next_task_id = task_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
-            NextTaskId::<T>::put(next_task_id);
+
+            T::Currency::reserve(&sender, T::TaskDeposit::get())
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            // The shard's own pool and those its siblings were assigned to
+            let avoid: Vec<PoolId> = TaskShards::<T>::get(task_id)
+                .iter()
+                .filter_map(|id| Tasks::<T>::get(id))
+                .filter(|sibling| !matches!(sibling.status, TaskStatus::Pending))
+                .map(|sibling| sibling.pool_id)
+                .collect();
+
+            let shard = Tasks::<T>::try_mutate(shard_id, |maybe_shard| {
+                let shard = maybe_shard.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                shard.status = TaskStatus::Pending;
+                shard.submitted_at = now;
+                shard.proof_hash = None;
+                shard.verification_result = None;
+                shard.disputed = false;
+                Ok::<_, DispatchError>(shard.clone())
+            })?;
+            Self::deposit_event(Event::ShardReassigned { shard_id, task_id });
+            Self::deposit_event(Event::TaskStatusChanged {
+                task_id: shard_id,
+                status: TaskStatus::Pending,
+            });
+            Self::place_task(&shard, &avoid)?;
             Ok(())
         }
 
@@ -633,6 +1121,7 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
             ensure!(proof_hash != [0u8; 32], Error::<T>::InvalidProof);
 
+            ensure!(!TaskShards::<T>::contains_key(task_id), Error::<T>::ShardedTask);
             let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
             ensure!(matches!(task.status, TaskStatus::Computing), Error::<T>::InvalidTaskState);
             let pool = Pools::<T>::get(task.pool_id).ok_or(Error::<T>::PoolNotFound)?;
@@ -640,7 +1129,7 @@ pub mod pallet {
 
             let now = frame_system::Pallet::<T>::block_number();
             ensure!(
-                now <= task.submitted_at.saturating_add(T::TaskTimeout::get()),
+                now <= Self::expires_at(&task),
                 Error::<T>::TaskExpired
             );
 
@@ -696,6 +1185,8 @@ pub mod pallet {
             ensure!(pool.owner == sender, Error::<T>::NotAssignedPoolOwner);
 
             let reward = Rewards::<T>::get(task_id).ok_or(Error::<T>::RewardNotAvailable)?;
+            let delegator_share = Self::delegator_share(task.pool_id, pool.deposit_held, reward);
+            let owner_share = reward.saturating_sub(delegator_share);
             TaskEscrowStore::<T>::try_mutate_exists(task_id, |maybe_escrow| -> DispatchResult {
                 let escrow = maybe_escrow.as_mut().ok_or(Error::<T>::RewardNotAvailable)?;
                 ensure!(!escrow.claimed, Error::<T>::RewardAlreadyClaimed);
@@ -703,21 +1194,45 @@ pub mod pallet {
                 let remainder = T::Currency::repatriate_reserved(
                     &escrow.user,
                     &sender,
-                    reward,
+                    owner_share,
                     BalanceStatus::Free,
                 )
                 .map_err(|_| Error::<T>::InsufficientBalance)?;
                 ensure!(remainder.is_zero(), Error::<T>::InsufficientBalance);
+                if !delegator_share.is_zero() {
+                    let remainder = T::Currency::repatriate_reserved(
+                        &escrow.user,
+                        &Self::pool_account(task.pool_id),
+                        delegator_share,
+                        BalanceStatus::Free,
+                    )
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                    ensure!(remainder.is_zero(), Error::<T>::InsufficientBalance);
+                }
                 let _ = T::Currency::unreserve(&escrow.user, escrow.task_deposit);
                 escrow.claimed = true;
                 Ok(())
             })?;
 
             Rewards::<T>::remove(task_id);
+            if !delegator_share.is_zero() {
+                StakePools::<T>::mutate(task.pool_id, |stake_pool| {
+                    if let Some(per_point) =
+                        FixedU128::checked_from_rational(delegator_share, stake_pool.total_points)
+                    {
+                        stake_pool.reward_per_point =
+                            stake_pool.reward_per_point.saturating_add(per_point);
+                    }
+                });
+                Self::deposit_event(Event::DelegatorRewardsDistributed {
+                    pool_id: task.pool_id,
+                    amount: delegator_share,
+                });
+            }
             Self::deposit_event(Event::RewardClaimed {
                 task_id,
                 pool_owner: sender,
-                amount: reward,
+                amount: owner_share,
             });
             Ok(())
         }
@@ -726,8 +1241,15 @@ pub mod pallet {
         #[pallet::weight(T::WeightInfo::dispute_verification())]
         pub fn dispute_verification(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+            ensure!(!TaskShards::<T>::contains_key(task_id), Error::<T>::ShardedTask);
             let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
             ensure!(task.user == sender, Error::<T>::NotTaskUser);
+            // The outcome of a finished sharded task no longer follows its shards
+            if let Some(parent) = ShardParent::<T>::get(task_id) {
+                let finished = Tasks::<T>::get(parent)
+                    .map_or(true, |parent| Self::is_terminal(&parent.status));
+                ensure!(!finished, Error::<T>::ShardedTaskFinished);
+            }
             ensure!(
                 matches!(task.status, TaskStatus::Completed | TaskStatus::Failed),
                 Error::<T>::DisputeNotAllowed
@@ -771,10 +1293,12 @@ pub mod pallet {
                 return Err(Error::<T>::DisputeNotAllowed.into());
             }
 
+            Self::update_sharded_task(task_id);
             Self::deposit_event(Event::VerificationDisputed { task_id, user: sender });
             Ok(())
         }
 
+        /// Delegate `amount` to a pool. Pending delegator rewards are paid out first.
         #[pallet::call_index(7)]
         #[pallet::weight(T::WeightInfo::stake_to_pool())]
         pub fn stake_to_pool(
@@ -783,14 +1307,51 @@ pub mod pallet {
             amount: BalanceOf<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            ensure!(Pools::<T>::contains_key(pool_id), Error::<T>::PoolNotFound);
-            T::Currency::reserve(&who, amount).map_err(|_| Error::<T>::InsufficientBalance)?;
-            PoolStakes::<T>::mutate(pool_id, &who, |stake| *stake = stake.saturating_add(amount));
-            TotalPoolStake::<T>::mutate(pool_id, |total| *total = total.saturating_add(amount));
+            ensure!(!amount.is_zero(), Error::<T>::InsufficientStake);
+            let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(matches!(pool.status, PoolStatus::Active), Error::<T>::PoolInactive);
+
+            let mut stake_pool = StakePools::<T>::get(pool_id);
+            let active = TotalPoolStake::<T>::get(pool_id);
+            ensure!(
+                stake_pool.total_points.is_zero() || !active.is_zero(),
+                Error::<T>::PoolStakeDepleted
+            );
+            let mut delegation = PoolStakes::<T>::get(pool_id, &who).unwrap_or_default();
+            let staked = Self::points_to_stake(delegation.points, active, stake_pool.total_points);
+            ensure!(
+                staked.saturating_add(amount) >= T::MinPoolStake::get(),
+                Error::<T>::InsufficientStake
+            );
+
+            Self::pay_delegator_rewards(pool_id, &who, &stake_pool, &delegation)?;
+            T::Currency::transfer(
+                &who,
+                &Self::pool_account(pool_id),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+            let points = if stake_pool.total_points.is_zero() {
+                amount
+            } else {
+                Self::proportion(amount, stake_pool.total_points, active, Rounding::Down)
+            };
+            stake_pool.total_points = stake_pool.total_points.saturating_add(points);
+            delegation.points = delegation.points.saturating_add(points);
+            delegation.reward_debt =
+                stake_pool.reward_per_point.saturating_mul_int(delegation.points);
+            PoolStakes::<T>::insert(pool_id, &who, delegation);
+            StakePools::<T>::insert(pool_id, stake_pool);
+            TotalPoolStake::<T>::insert(pool_id, active.saturating_add(amount));
             Self::deposit_event(Event::Staked { who, pool_id, amount });
             Ok(())
         }
 
+        /// Start unbonding `amount` of delegated stake. It stops earning rewards at once but
+        /// stays slashable until `UnbondingPeriod` has passed. Unstaking again restarts the
+        /// period for everything that is unbonding.
         #[pallet::call_index(8)]
         #[pallet::weight(T::WeightInfo::unstake_from_pool())]
         pub fn unstake_from_pool(
@@ -799,36 +1360,158 @@ pub mod pallet {
             amount: BalanceOf<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let current_stake = PoolStakes::<T>::get(pool_id, &who);
-            ensure!(current_stake >= amount, Error::<T>::StakeNotFound);
-            T::Currency::unreserve(&who, amount);
-            PoolStakes::<T>::mutate(pool_id, &who, |stake| *stake = stake.saturating_sub(amount));
-            TotalPoolStake::<T>::mutate(pool_id, |total| *total = total.saturating_sub(amount));
-            Self::deposit_event(Event::Unstaked { who, pool_id, amount });
+            ensure!(!amount.is_zero(), Error::<T>::InsufficientStake);
+            let mut delegation =
+                PoolStakes::<T>::get(pool_id, &who).ok_or(Error::<T>::StakeNotFound)?;
+            let mut stake_pool = StakePools::<T>::get(pool_id);
+            let active = TotalPoolStake::<T>::get(pool_id);
+            let staked = Self::points_to_stake(delegation.points, active, stake_pool.total_points);
+            ensure!(staked >= amount, Error::<T>::StakeNotFound);
+            let remaining = staked.saturating_sub(amount);
+            ensure!(
+                remaining.is_zero() || remaining >= T::MinPoolStake::get(),
+                Error::<T>::InsufficientStake
+            );
+            ensure!(
+                stake_pool.unbonding_points.is_zero() || !stake_pool.unbonding_stake.is_zero(),
+                Error::<T>::PoolStakeDepleted
+            );
+
+            Self::pay_delegator_rewards(pool_id, &who, &stake_pool, &delegation)?;
+
+            let points = if remaining.is_zero() {
+                delegation.points
+            } else {
+                Self::proportion(amount, stake_pool.total_points, active, Rounding::Up)
+                    .min(delegation.points)
+            };
+            let unbonding_points = if stake_pool.unbonding_points.is_zero() {
+                amount
+            } else {
+                Self::proportion(
+                    amount,
+                    stake_pool.unbonding_points,
+                    stake_pool.unbonding_stake,
+                    Rounding::Down,
+                )
+            };
+            stake_pool.total_points = stake_pool.total_points.saturating_sub(points);
+            stake_pool.unbonding_points =
+                stake_pool.unbonding_points.saturating_add(unbonding_points);
+            stake_pool.unbonding_stake = stake_pool.unbonding_stake.saturating_add(amount);
+
+            let unlock_at =
+                frame_system::Pallet::<T>::block_number().saturating_add(T::UnbondingPeriod::get());
+            delegation.points = delegation.points.saturating_sub(points);
+            delegation.reward_debt =
+                stake_pool.reward_per_point.saturating_mul_int(delegation.points);
+            delegation.unbonding_points =
+                delegation.unbonding_points.saturating_add(unbonding_points);
+            delegation.unlock_at = unlock_at;
+
+            PoolStakes::<T>::insert(pool_id, &who, delegation);
+            StakePools::<T>::insert(pool_id, stake_pool);
+            TotalPoolStake::<T>::insert(pool_id, active.saturating_sub(amount));
+            Self::deposit_event(Event::Unbonding { who, pool_id, amount, unlock_at });
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Shared logic for finalizing verification (called by verify_proof and auto-verify)
-        fn finalize_verification(task_id: TaskId, pool_id: PoolId, result: bool) -> DispatchResult {
-            let proof_hash = Tasks::<T>::get(task_id)
-                .and_then(|t| t.proof_hash)
-                .ok_or(Error::<T>::ProofNotSubmitted)?;
+        /// Withdraw stake whose unbonding period has passed
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+        pub fn withdraw_unbonded(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut delegation =
+                PoolStakes::<T>::get(pool_id, &who).ok_or(Error::<T>::StakeNotFound)?;
+            ensure!(!delegation.unbonding_points.is_zero(), Error::<T>::StakeNotFound);
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= delegation.unlock_at,
+                Error::<T>::StakeStillUnbonding
+            );
 
-            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
-                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
-                t.status = TaskStatus::Verifying;
-                Ok(())
-            })?;
-            Self::deposit_event(Event::TaskStatusChanged {
-                task_id,
-                status: TaskStatus::Verifying,
-            });
+            let mut stake_pool = StakePools::<T>::get(pool_id);
+            let amount = Self::points_to_stake(
+                delegation.unbonding_points,
+                stake_pool.unbonding_stake,
+                stake_pool.unbonding_points,
+            );
+            T::Currency::transfer(
+                &Self::pool_account(pool_id),
+                &who,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            )?;
+            stake_pool.unbonding_points =
+                stake_pool.unbonding_points.saturating_sub(delegation.unbonding_points);
+            stake_pool.unbonding_stake = stake_pool.unbonding_stake.saturating_sub(amount);
+            delegation.unbonding_points = Zero::zero();
 
-            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
-                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
-                t.verification_result = Some(result);
+            if delegation.points.is_zero() {
+                PoolStakes::<T>::remove(pool_id, &who);
+            } else {
+                PoolStakes::<T>::insert(pool_id, &who, delegation);
+            }
+            StakePools::<T>::insert(pool_id, stake_pool);
+            Self::deposit_event(Event::Unstaked { who, pool_id, amount });
+            Ok(())
+        }
+
+        /// Pay out the caller's share of the delegator rewards
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::claim_delegator_rewards())]
+        pub fn claim_delegator_rewards(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut delegation =
+                PoolStakes::<T>::get(pool_id, &who).ok_or(Error::<T>::StakeNotFound)?;
+            let stake_pool = StakePools::<T>::get(pool_id);
+            let paid = Self::pay_delegator_rewards(pool_id, &who, &stake_pool, &delegation)?;
+            ensure!(!paid.is_zero(), Error::<T>::NoRewards);
+
+            delegation.reward_debt =
+                stake_pool.reward_per_point.saturating_mul_int(delegation.points);
+            PoolStakes::<T>::insert(pool_id, &who, delegation);
+            Ok(())
+        }
+
+        /// Set the share of each task reward the pool owner keeps before delegators are paid
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::set_pool_commission())]
+        pub fn set_pool_commission(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            commission: Perbill,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(pool.owner == sender, Error::<T>::NotPoolOwner);
+            ensure!(commission <= T::MaxCommission::get(), Error::<T>::CommissionTooHigh);
+
+            StakePools::<T>::mutate(pool_id, |stake_pool| stake_pool.commission = commission);
+            Self::deposit_event(Event::PoolCommissionSet { pool_id, commission });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Shared logic for finalizing verification (called by verify_proof and auto-verify)
+        fn finalize_verification(task_id: TaskId, pool_id: PoolId, result: bool) -> DispatchResult {
+            let proof_hash = Tasks::<T>::get(task_id)
+                .and_then(|t| t.proof_hash)
+                .ok_or(Error::<T>::ProofNotSubmitted)?;
+
+            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
+                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                t.status = TaskStatus::Verifying;
+                Ok(())
+            })?;
+            Self::deposit_event(Event::TaskStatusChanged {
+                task_id,
+                status: TaskStatus::Verifying,
+            });
+
+            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
+                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                t.verification_result = Some(result);
                 t.status = if result { TaskStatus::Completed } else { TaskStatus::Failed };
                 Ok(())
             })?;
@@ -842,6 +1525,7 @@ pub mod pallet {
             Self::decrement_pool_activity(pool_id, task_id);
             Self::update_reputation(pool_id, result);
             ProofSubmittedAt::<T>::remove(task_id);
+            let order = TaskOrderContexts::<T>::take(task_id);
 
             if result {
                 if let Some(amount) = Tasks::<T>::get(task_id).and_then(|t| t.reward_amount) {
@@ -859,14 +1543,13 @@ pub mod pallet {
                     &pool.owner,
                     task_id,
                     result_hash,
-                    &[], // model_id placeholder
-                    0,   // input_tokens placeholder
-                    0,   // output_tokens placeholder
+                    order.as_ref(),
                 );
             } else {
                 Self::release_escrow(task_id)?;
                 Self::slash_pool(pool_id)?;
             }
+            Self::update_sharded_task(task_id);
 
             Ok(())
         }
@@ -883,11 +1566,16 @@ pub mod pallet {
             Ok(())
         }
 
+        fn ensure_region(region: &RegionCode) -> DispatchResult {
+            ensure!(region.iter().all(u8::is_ascii_uppercase), Error::<T>::InvalidRegion);
+            Ok(())
+        }
+
         fn is_terminal(status: &TaskStatus) -> bool {
             matches!(status, TaskStatus::Completed | TaskStatus::Failed)
         }
 
-        fn mark_task_failed(task_id: TaskId, timed_out: bool) -> DispatchResult {
+        pub(crate) fn mark_task_failed(task_id: TaskId, timed_out: bool) -> DispatchResult {
             let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
             if Self::is_terminal(&task.status) {
                 return Ok(());
@@ -903,12 +1591,14 @@ pub mod pallet {
             Self::decrement_pool_activity(task.pool_id, task_id);
             Self::update_reputation(task.pool_id, false);
             ProofSubmittedAt::<T>::remove(task_id);
+            TaskOrderContexts::<T>::remove(task_id);
             Self::release_escrow(task_id)?;
             Self::slash_pool(task.pool_id)?;
             if timed_out {
                 Self::deposit_event(Event::TaskTimedOut { task_id });
             }
             Self::deposit_event(Event::TaskStatusChanged { task_id, status: TaskStatus::Failed });
+            Self::update_sharded_task(task_id);
             Ok(())
         }
 
@@ -921,28 +1611,169 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Slash `FailureSlash` plus `StakeSlashPercent` of the delegated stake, split pro rata
+        /// between the owner deposit and the active and unbonding delegated stake
         fn slash_pool(pool_id: PoolId) -> DispatchResult {
             Pools::<T>::try_mutate(pool_id, |maybe_pool| -> DispatchResult {
                 let pool = maybe_pool.as_mut().ok_or(Error::<T>::PoolNotFound)?;
-                if pool.deposit_held.is_zero() {
+                let mut stake_pool = StakePools::<T>::get(pool_id);
+                let active = TotalPoolStake::<T>::get(pool_id);
+                let delegated = active.saturating_add(stake_pool.unbonding_stake);
+                let bonded = pool.deposit_held.saturating_add(delegated);
+                if bonded.is_zero() {
                     return Ok(());
                 }
-                let slash_amount = if pool.deposit_held > T::FailureSlash::get() {
-                    T::FailureSlash::get()
-                } else {
-                    pool.deposit_held
-                };
-                let (_imbalance, unslashed) =
-                    T::Currency::slash_reserved(&pool.owner, slash_amount);
-                let actual_slashed = slash_amount.saturating_sub(unslashed);
-                pool.deposit_held = pool.deposit_held.saturating_sub(actual_slashed);
-                if !actual_slashed.is_zero() {
-                    Self::deposit_event(Event::PoolSlashed { pool_id, amount: actual_slashed });
+                let slash_amount = T::FailureSlash::get()
+                    .saturating_add(Perbill::from_percent(T::StakeSlashPercent::get()) * delegated)
+                    .min(bonded);
+                let delegator_slash =
+                    Self::proportion(slash_amount, delegated, bonded, Rounding::Down);
+                let owner_slash =
+                    slash_amount.saturating_sub(delegator_slash).min(pool.deposit_held);
+
+                if !owner_slash.is_zero() {
+                    let (_imbalance, unslashed) =
+                        T::Currency::slash_reserved(&pool.owner, owner_slash);
+                    let actual_slashed = owner_slash.saturating_sub(unslashed);
+                    pool.deposit_held = pool.deposit_held.saturating_sub(actual_slashed);
+                    if !actual_slashed.is_zero() {
+                        Self::deposit_event(Event::PoolSlashed { pool_id, amount: actual_slashed });
+                    }
+                }
+                if !delegator_slash.is_zero() {
+                    let (_imbalance, unslashed) =
+                        T::Currency::slash(&Self::pool_account(pool_id), delegator_slash);
+                    let actual_slashed = delegator_slash.saturating_sub(unslashed);
+                    let from_active =
+                        Self::proportion(actual_slashed, active, delegated, Rounding::Down);
+                    stake_pool.unbonding_stake = stake_pool
+                        .unbonding_stake
+                        .saturating_sub(actual_slashed.saturating_sub(from_active));
+                    TotalPoolStake::<T>::insert(pool_id, active.saturating_sub(from_active));
+                    StakePools::<T>::insert(pool_id, stake_pool);
+                    if !actual_slashed.is_zero() {
+                        Self::deposit_event(Event::StakeSlashed {
+                            pool_id,
+                            amount: actual_slashed,
+                        });
+                    }
                 }
                 Ok(())
             })
         }
 
+        /// Account holding a pool's delegated stake and undistributed delegator rewards
+        pub fn pool_account(pool_id: PoolId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating(pool_id)
+        }
+
+        /// Active delegated stake of `who` in a pool
+        pub fn delegated_stake(pool_id: PoolId, who: &T::AccountId) -> BalanceOf<T> {
+            PoolStakes::<T>::get(pool_id, who).map_or_else(Zero::zero, |delegation| {
+                Self::points_to_stake(
+                    delegation.points,
+                    TotalPoolStake::<T>::get(pool_id),
+                    StakePools::<T>::get(pool_id).total_points,
+                )
+            })
+        }
+
+        /// Unbonding stake of `who` in a pool
+        pub fn unbonding_stake(pool_id: PoolId, who: &T::AccountId) -> BalanceOf<T> {
+            PoolStakes::<T>::get(pool_id, who).map_or_else(Zero::zero, |delegation| {
+                let stake_pool = StakePools::<T>::get(pool_id);
+                Self::points_to_stake(
+                    delegation.unbonding_points,
+                    stake_pool.unbonding_stake,
+                    stake_pool.unbonding_points,
+                )
+            })
+        }
+
+        /// Delegator rewards `who` can claim from a pool
+        pub fn pending_delegator_rewards(pool_id: PoolId, who: &T::AccountId) -> BalanceOf<T> {
+            PoolStakes::<T>::get(pool_id, who).map_or_else(Zero::zero, |delegation| {
+                StakePools::<T>::get(pool_id)
+                    .reward_per_point
+                    .saturating_mul_int(delegation.points)
+                    .saturating_sub(delegation.reward_debt)
+            })
+        }
+
+        /// Part of a task reward owed to delegators: what is left after commission, split
+        /// between the owner deposit and the active delegated stake
+        fn delegator_share(
+            pool_id: PoolId,
+            deposit: BalanceOf<T>,
+            reward: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            let active = TotalPoolStake::<T>::get(pool_id);
+            if active.is_zero() {
+                return Zero::zero();
+            }
+            let commission = StakePools::<T>::get(pool_id).commission * reward;
+            Self::proportion(
+                reward.saturating_sub(commission),
+                active,
+                deposit.saturating_add(active),
+                Rounding::Down,
+            )
+        }
+
+        /// Pay out the rewards `delegation` earned since its debt was last set. Callers reset
+        /// the debt afterwards.
+        fn pay_delegator_rewards(
+            pool_id: PoolId,
+            who: &T::AccountId,
+            stake_pool: &StakePool<BalanceOf<T>>,
+            delegation: &Delegation<BalanceOf<T>, BlockNumberFor<T>>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let pending = stake_pool
+                .reward_per_point
+                .saturating_mul_int(delegation.points)
+                .saturating_sub(delegation.reward_debt);
+            if !pending.is_zero() {
+                T::Currency::transfer(
+                    &Self::pool_account(pool_id),
+                    who,
+                    pending,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+                Self::deposit_event(Event::DelegatorRewardPaid {
+                    who: who.clone(),
+                    pool_id,
+                    amount: pending,
+                });
+            }
+            Ok(pending)
+        }
+
+        /// Stake backing `points` out of `total_points`
+        fn points_to_stake(
+            points: BalanceOf<T>,
+            stake: BalanceOf<T>,
+            total_points: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            Self::proportion(points, stake, total_points, Rounding::Down)
+        }
+
+        /// `amount * numerator / denominator`, or zero if the denominator is zero
+        fn proportion(
+            amount: BalanceOf<T>,
+            numerator: BalanceOf<T>,
+            denominator: BalanceOf<T>,
+            rounding: Rounding,
+        ) -> BalanceOf<T> {
+            let amount: u128 = amount.unique_saturated_into();
+            let numerator: u128 = numerator.unique_saturated_into();
+            let denominator: u128 = denominator.unique_saturated_into();
+            if denominator.is_zero() {
+                return Zero::zero();
+            }
+            multiply_by_rational_with_rounding(amount, numerator, denominator, rounding)
+                .map_or_else(Zero::zero, |value| value.unique_saturated_into())
+        }
+
         fn calculate_reward(
             dimensions: &TaskDimensions,
             price_per_task: &BalanceOf<T>,
@@ -966,55 +1797,266 @@ pub mod pallet {
             Ok(reward_u128.unique_saturated_into())
         }
 
-        fn select_best_pool_for_task(
+        /// Best pool with a free slot for a task, outside `avoid` unless no other pool fits
+        pub(crate) fn select_best_pool_for_task(
             task: &ComputeTask<T::AccountId, BlockNumberFor<T>, BalanceOf<T>>,
+            avoid: &[PoolId],
         ) -> Result<(PoolId, PoolScore), DispatchError> {
-            let mut candidates: Vec<(
-                PoolId,
-                ComputePool<T::AccountId, BalanceOf<T>, T::MaxGpuModelLen>,
-            )> = Vec::new();
-            for (pool_id, pool) in Pools::<T>::iter().take(50) {
-                if !matches!(pool.status, PoolStatus::Active) {
-                    continue;
+            let mut pool_id = Self::best_indexed_pool_avoiding(&task.requirements, true, avoid);
+            if pool_id.is_none() && !avoid.is_empty() {
+                pool_id = Self::best_indexed_pool(&task.requirements, true);
+            }
+            let pool_id = pool_id.ok_or(Error::<T>::NoAvailablePool)?;
+            let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let price: u128 = pool.price_per_task.unique_saturated_into();
+            let score = Self::calculate_pool_score(&pool, price, price);
+            Pools::<T>::mutate(pool_id, |maybe_pool| {
+                if let Some(pool) = maybe_pool {
+                    pool.score = score.clone();
                 }
-                if ActiveTaskCount::<T>::get(pool_id) >= T::MaxTasksPerPool::get() {
+            });
+            Ok((pool_id, score))
+        }
+
+        /// Best indexed pool meeting `requirements`, among the pools with a free slot or among
+        /// the busy ones. Selection reads the indexed profiles and, for each matching one, the
+        /// price tier heads of its bucket, so the cost does not grow with the number of pools.
+        pub fn best_indexed_pool(
+            requirements: &TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+            available: bool,
+        ) -> Option<PoolId> {
+            Self::best_indexed_pool_avoiding(requirements, available, &[])
+        }
+
+        fn best_indexed_pool_avoiding(
+            requirements: &TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+            available: bool,
+            avoid: &[PoolId],
+        ) -> Option<PoolId> {
+            let filter = RequirementFilter::new(requirements);
+            let mut best: Option<PoolRank> = None;
+            for indexed in IndexedProfiles::<T>::get() {
+                if !filter.matches_profile(&indexed.profile) {
                     continue;
                 }
-                if pool.gpu_memory < task.dimensions.k {
-                    continue;
+                let bucket = PoolBucket { profile: indexed.profile, available };
+                if let Some(rank) = Self::bucket_candidate(&bucket, &filter, avoid) {
+                    if best.map_or(true, |best_rank| rank < best_rank) {
+                        best = Some(rank);
+                    }
                 }
-                candidates.push((pool_id, pool));
             }
-            ensure!(!candidates.is_empty(), Error::<T>::NoAvailablePool);
-
-            let mut min_price = u128::MAX;
-            let mut max_price = 0u128;
-            for (_, pool) in &candidates {
-                let p: u128 = pool.price_per_task.unique_saturated_into();
-                if p < min_price {
-                    min_price = p;
-                }
-                if p > max_price {
-                    max_price = p;
-                }
+            best.map(|rank| Self::rank_pool(&rank))
+        }
+
+        /// Best ranked pool of a bucket outside `avoid` within the price limit: the best of the
+        /// price tier heads within the limit. Where a head is over the limit or in `avoid`, its
+        /// tier is walked in rank order for the next pool that is not, through at most
+        /// `MAX_TIER_SCAN` pools besides those in `avoid`.
+        fn bucket_candidate(
+            bucket: &PoolBucket,
+            filter: &RequirementFilter,
+            avoid: &[PoolId],
+        ) -> Option<PoolRank> {
+            let usable = |rank: &PoolRank| {
+                filter.accepts_price(Self::rank_price(rank)) &&
+                    !avoid.contains(&Self::rank_pool(rank))
+            };
+            PoolTierHeads::<T>::get(bucket)?
+                .into_iter()
+                .take_while(|head| filter.accepts_price(Self::tier_floor(Self::rank_tier(head))))
+                .filter_map(|head| {
+                    if usable(&head) {
+                        return Some(head);
+                    }
+                    let tier = Self::rank_tier(&head);
+                    PoolIndex::<T>::iter_key_prefix((bucket.clone(), tier))
+                        .take(MAX_TIER_SCAN as usize + avoid.len())
+                        .find(|rank| usable(rank))
+                })
+                .min()
+        }
+
+        /// Whether some indexed pool, free or busy, meets `requirements`
+        fn has_indexed_pool(
+            requirements: &TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+        ) -> bool {
+            let filter = RequirementFilter::new(requirements);
+            IndexedProfiles::<T>::get()
+                .into_iter()
+                .filter(|indexed| filter.matches_profile(&indexed.profile))
+                .any(|indexed| {
+                    [true, false].into_iter().any(|available| {
+                        let bucket = PoolBucket { profile: indexed.profile.clone(), available };
+                        Self::bucket_candidate(&bucket, &filter, &[]).is_some()
+                    })
+                })
+        }
+
+        fn ensure_requirements(
+            requirements: &TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            ensure!(
+                requirements.gpu_models.len() as u32 <= T::MaxRequiredGpuModels::get() &&
+                    requirements
+                        .gpu_models
+                        .iter()
+                        .all(|model| model.len() as u32 <= T::MaxGpuModelLen::get()),
+                Error::<T>::InvalidRequirements
+            );
+            if let Some(region) = &requirements.region {
+                Self::ensure_region(region)?;
             }
+            Ok(())
+        }
 
-            let mut best: Option<(PoolId, PoolScore)> = None;
-            for (pool_id, pool) in &candidates {
-                let score = Self::calculate_pool_score(pool, min_price, max_price);
-                match best {
-                    Some((_, ref current)) if current.final_score >= score.final_score => {},
-                    _ => best = Some((*pool_id, score)),
-                }
+        /// Block after which a computing task fails: its timeout, or its deadline if sooner
+        pub(crate) fn expires_at(
+            task: &ComputeTask<T::AccountId, BlockNumberFor<T>, BalanceOf<T>>,
+        ) -> BlockNumberFor<T> {
+            let timeout = task.submitted_at.saturating_add(T::TaskTimeout::get());
+            task.requirements.deadline.map_or(timeout, |deadline| deadline.min(timeout))
+        }
+
+        pub(crate) fn pool_profile(
+            pool: &ComputePool<T::AccountId, BalanceOf<T>, T::MaxGpuModelLen>,
+        ) -> PoolProfile {
+            PoolProfile {
+                gpu_memory: pool.gpu_memory,
+                gpu_count: pool.gpu_count,
+                has_nvlink: pool.has_nvlink,
+                region: pool.region,
+                gpu_model_hash: sp_io::hashing::blake2_256(&pool.gpu_model),
             }
+        }
 
-            let (selected_pool, selected_score) = best.ok_or(Error::<T>::NoAvailablePool)?;
-            Pools::<T>::mutate(selected_pool, |maybe_pool| {
-                if let Some(pool) = maybe_pool {
-                    pool.score = selected_score.clone();
+        /// Rank of a pool by its score with a neutral price component, then by its price
+        fn pool_rank(
+            pool: &ComputePool<T::AccountId, BalanceOf<T>, T::MaxGpuModelLen>,
+        ) -> PoolRank {
+            let price: u128 = pool.price_per_task.unique_saturated_into();
+            let score = Self::calculate_pool_score(pool, price, price).final_score;
+            let mut rank = [0u8; 28];
+            rank[..4].copy_from_slice(&u32::MAX.saturating_sub(score).to_be_bytes());
+            rank[4..20].copy_from_slice(&price.to_be_bytes());
+            rank[20..].copy_from_slice(&pool.pool_id.to_be_bytes());
+            rank
+        }
+
+        fn rank_price(rank: &PoolRank) -> u128 {
+            let mut price = [0u8; 16];
+            price.copy_from_slice(&rank[4..20]);
+            u128::from_be_bytes(price)
+        }
+
+        fn rank_tier(rank: &PoolRank) -> PriceTier {
+            (u128::BITS - Self::rank_price(rank).leading_zeros()) as PriceTier
+        }
+
+        /// Lowest price of a price tier
+        fn tier_floor(tier: PriceTier) -> u128 {
+            tier.checked_sub(1).map_or(0, |bits| 1u128 << bits)
+        }
+
+        fn rank_pool(rank: &PoolRank) -> PoolId {
+            let mut pool_id = [0u8; 8];
+            pool_id.copy_from_slice(&rank[20..]);
+            PoolId::from_be_bytes(pool_id)
+        }
+
+        /// Price tier of the bucket an index entry sits in
+        fn tier_key(indexed: &IndexedPool) -> (PoolBucket, PriceTier) {
+            (indexed.bucket.clone(), Self::rank_tier(&indexed.rank))
+        }
+
+        /// Move a pool to the bucket and rank matching its current state, or drop it from the
+        /// index once it is no longer active, then refresh the heads of the tiers it left and
+        /// joined and the pool counts of the profiles
+        pub(crate) fn reindex_pool(pool_id: PoolId) {
+            let entry = Pools::<T>::get(pool_id)
+                .filter(|pool| matches!(pool.status, PoolStatus::Active))
+                .map(|pool| IndexedPool {
+                    bucket: PoolBucket {
+                        profile: Self::pool_profile(&pool),
+                        available: ActiveTaskCount::<T>::get(pool_id) < T::MaxTasksPerPool::get(),
+                    },
+                    rank: Self::pool_rank(&pool),
+                });
+            let current = IndexedPools::<T>::get(pool_id);
+            if current == entry {
+                return;
+            }
+
+            if let Some(current) = &current {
+                PoolIndex::<T>::remove(Self::tier_key(current), current.rank);
+            }
+            if let Some(entry) = &entry {
+                PoolIndex::<T>::insert(Self::tier_key(entry), entry.rank, pool_id);
+            }
+            IndexedPools::<T>::set(pool_id, entry.clone());
+
+            let mut tiers: Vec<(PoolBucket, PriceTier)> =
+                current.iter().chain(entry.iter()).map(Self::tier_key).collect();
+            tiers.dedup();
+            for (bucket, tier) in tiers {
+                Self::refresh_tier_head(&bucket, tier);
+            }
+
+            let old_profile = current.map(|current| current.bucket.profile);
+            let new_profile = entry.map(|entry| entry.bucket.profile);
+            if old_profile == new_profile {
+                return;
+            }
+            IndexedProfiles::<T>::mutate(|profiles| {
+                if let Some(profile) = old_profile {
+                    if let Some(indexed) = profiles.iter_mut().find(|i| i.profile == profile) {
+                        indexed.pools = indexed.pools.saturating_sub(1);
+                    }
+                    profiles.retain(|indexed| indexed.pools > 0);
+                }
+                if let Some(profile) = new_profile {
+                    match profiles.iter_mut().find(|indexed| indexed.profile == profile) {
+                        Some(indexed) => indexed.pools = indexed.pools.saturating_add(1),
+                        None => {
+                            let added = profiles.try_push(IndexedProfile { profile, pools: 1 });
+                            if added.is_err() {
+                                log::warn!("pool {} left out of a full profile index", pool_id);
+                            }
+                        },
+                    }
+                }
+            });
+        }
+
+        /// Put the best ranked pool of a price tier of `bucket` in the bucket's tier heads
+        fn refresh_tier_head(bucket: &PoolBucket, tier: PriceTier) {
+            let head = PoolIndex::<T>::iter_key_prefix((bucket.clone(), tier)).next();
+            PoolTierHeads::<T>::mutate_exists(bucket, |maybe_heads| {
+                let mut heads = maybe_heads.take().unwrap_or_default();
+                heads.retain(|rank| Self::rank_tier(rank) != tier);
+                if let Some(head) = head {
+                    let at = heads.partition_point(|rank| Self::rank_tier(rank) < tier);
+                    // One head per tier always fits
+                    let _ = heads.try_insert(at, head);
                 }
+                *maybe_heads = (!heads.is_empty()).then_some(heads);
             });
-            Ok((selected_pool, selected_score))
+        }
+
+        /// Whether the index can take `pool_id` with `profile`: the profile is indexed, there
+        /// is room for another one, or the pool is the last one of the profile it leaves
+        fn ensure_profile_room(pool_id: PoolId, profile: &PoolProfile) -> DispatchResult {
+            let profiles = IndexedProfiles::<T>::get();
+            let current = IndexedPools::<T>::get(pool_id).map(|indexed| indexed.bucket.profile);
+            ensure!(
+                (profiles.len() as u32) < T::MaxPoolProfiles::get() ||
+                    profiles.iter().any(|indexed| {
+                        &indexed.profile == profile ||
+                            (Some(&indexed.profile) == current.as_ref() && indexed.pools == 1)
+                    }),
+                Error::<T>::TooManyPoolProfiles
+            );
+            Ok(())
         }
 
         fn calculate_pool_score(
@@ -1061,6 +2103,331 @@ pub mod pallet {
         fn decrement_pool_activity(pool_id: PoolId, task_id: TaskId) {
             ActiveTaskCount::<T>::mutate(pool_id, |count| *count = count.saturating_sub(1));
             PoolTasks::<T>::mutate(pool_id, |ids| ids.retain(|id| *id != task_id));
+            Self::reindex_pool(pool_id);
+            Self::fill_pool_slots(pool_id);
+        }
+
+        /// Score of `pool_id` if it has a free slot for a task with `requirements`, `None` if it
+        /// is busy
+        pub(crate) fn preferred_placement(
+            pool_id: PoolId,
+            requirements: &TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+        ) -> Result<Option<(PoolId, PoolScore)>, DispatchError> {
+            let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(matches!(pool.status, PoolStatus::Active), Error::<T>::PoolInactive);
+            let profile = Self::pool_profile(&pool);
+            let price = pool.price_per_task.unique_saturated_into();
+            ensure!(
+                RequirementFilter::new(requirements).matches(&profile, price),
+                Error::<T>::RequirementsNotMet
+            );
+
+            if ActiveTaskCount::<T>::get(pool_id) >= T::MaxTasksPerPool::get() {
+                return Ok(None);
+            }
+            let score = Self::calculate_pool_score(&pool, price, price);
+            Pools::<T>::mutate(pool_id, |maybe_pool| {
+                if let Some(pool) = maybe_pool {
+                    pool.score = score.clone();
+                }
+            });
+            Ok(Some((pool_id, score)))
+        }
+
+        /// Deposit reserved from the user of a task. Tasks of task-mode orders are paid through
+        /// attestation settlement and reserve none.
+        fn task_deposit(task_id: TaskId) -> BalanceOf<T> {
+            if TaskOrderContexts::<T>::contains_key(task_id) {
+                Zero::zero()
+            } else {
+                T::TaskDeposit::get()
+            }
+        }
+
+        /// Put a task on a pool, escrow its reward and start computing
+        pub(crate) fn assign_task(
+            task_id: TaskId,
+            pool_id: PoolId,
+            score: PoolScore,
+        ) -> DispatchResult {
+            let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+            let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(
+                ActiveTaskCount::<T>::get(pool_id) < T::MaxTasksPerPool::get(),
+                Error::<T>::TooManyActiveTasks
+            );
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                task.requirements.deadline.map_or(true, |deadline| now <= deadline),
+                Error::<T>::DeadlinePassed
+            );
+
+            // Shards carry their share of the task reward from submission, and tasks of
+            // task-mode orders escrow no reward
+            let order_task = TaskOrderContexts::<T>::contains_key(task_id);
+            let reward = match task.reward_amount {
+                Some(reward) => reward,
+                None if order_task => Zero::zero(),
+                None => {
+                    let reward = Self::calculate_reward(
+                        &task.dimensions,
+                        &pool.price_per_task,
+                        pool.has_nvlink,
+                        pool.nvlink_efficiency,
+                    )?;
+                    reward.saturating_add(Self::priority_fee(&task.priority, reward))
+                },
+            };
+            T::Currency::reserve(&task.user, reward)
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
+            PoolTasks::<T>::try_mutate(pool_id, |task_ids| {
+                task_ids.try_push(task_id).map_err(|_| Error::<T>::TooManyActiveTasks)
+            })?;
+            ActiveTaskCount::<T>::mutate(pool_id, |v| *v = v.saturating_add(1));
+            Self::reindex_pool(pool_id);
+
+            // The task timeout runs from assignment, not from the time spent queued
+            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
+                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                t.pool_id = pool_id;
+                t.status = TaskStatus::Assigned;
+                t.reward_amount = if order_task { None } else { Some(reward) };
+                t.submitted_at = now;
+                Ok(())
+            })?;
+            Self::deposit_event(Event::TaskAssigned {
+                task_id,
+                pool_id,
+                final_score: score.final_score,
+            });
+            Self::deposit_event(Event::TaskStatusChanged { task_id, status: TaskStatus::Assigned });
+
+            Tasks::<T>::try_mutate(task_id, |maybe_task| -> DispatchResult {
+                let t = maybe_task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+                t.status = TaskStatus::Computing;
+                Ok(())
+            })?;
+            Self::deposit_event(Event::TaskStatusChanged {
+                task_id,
+                status: TaskStatus::Computing,
+            });
+
+            TaskEscrowStore::<T>::insert(
+                task_id,
+                TaskEscrow {
+                    user: task.user,
+                    pool_owner: pool.owner,
+                    reward_amount: reward,
+                    task_deposit: Self::task_deposit(task_id),
+                    claimed: false,
+                },
+            );
+            Ok(())
+        }
+
+        /// Extra reward for tasks above normal priority
+        fn priority_fee(priority: &TaskPriority, reward: BalanceOf<T>) -> BalanceOf<T> {
+            let levels: u32 = match priority {
+                TaskPriority::High => 1,
+                TaskPriority::Critical => 2,
+                TaskPriority::Low | TaskPriority::Normal => 0,
+            };
+            (T::PriorityFee::get() * reward).saturating_mul(levels.into())
+        }
+
+        /// Queue a task behind every task of equal or higher priority. A full queue makes room
+        /// for a critical task by preempting its newest low priority task.
+        pub(crate) fn enqueue_task(
+            task_id: TaskId,
+            priority: TaskPriority,
+            pool_id: Option<PoolId>,
+        ) -> DispatchResult {
+            let insert = |queue: &mut TaskQueue<T>| -> DispatchResult {
+                if queue.len() as u32 >= T::MaxQueuedTasks::get() {
+                    let last = queue.len().saturating_sub(1);
+                    ensure!(
+                        priority == TaskPriority::Critical &&
+                            matches!(queue.get(last), Some((TaskPriority::Low, _))),
+                        Error::<T>::QueueFull
+                    );
+                    let (_, preempted) = queue.remove(last);
+                    Self::drop_queued_task(preempted);
+                    Self::deposit_event(Event::TaskPreempted { task_id: preempted, by: task_id });
+                }
+                let index = queue
+                    .iter()
+                    .position(|(queued, _)| *queued < priority)
+                    .unwrap_or(queue.len());
+                queue
+                    .try_insert(index, (priority.clone(), task_id))
+                    .map_err(|_| Error::<T>::QueueFull.into())
+            };
+            match pool_id {
+                Some(pool_id) => PoolQueue::<T>::try_mutate(pool_id, insert),
+                None => GlobalQueue::<T>::try_mutate(insert),
+            }?;
+
+            Self::deposit_event(Event::TaskQueued { task_id, pool_id, priority });
+            Ok(())
+        }
+
+        /// Fail a task that never left its queue and return the deposit
+        fn drop_queued_task(task_id: TaskId) {
+            let deposit = Self::task_deposit(task_id);
+            Tasks::<T>::mutate(task_id, |maybe_task| {
+                if let Some(task) = maybe_task {
+                    task.status = TaskStatus::Failed;
+                    let _ = T::Currency::unreserve(&task.user, deposit);
+                }
+            });
+            TaskOrderContexts::<T>::remove(task_id);
+            Self::deposit_event(Event::TaskStatusChanged { task_id, status: TaskStatus::Failed });
+            Self::update_sharded_task(task_id);
+        }
+
+        /// Start a task on the best pool with a free slot, preferably outside `avoid`, or queue
+        /// it when every pool meeting its requirements is busy. Returns the pool it started on.
+        fn place_task(
+            task: &ComputeTask<T::AccountId, BlockNumberFor<T>, BalanceOf<T>>,
+            avoid: &[PoolId],
+        ) -> Result<Option<PoolId>, DispatchError> {
+            match Self::select_best_pool_for_task(task, avoid) {
+                Ok((pool_id, score)) => {
+                    Self::assign_task(task.task_id, pool_id, score)?;
+                    Ok(Some(pool_id))
+                },
+                Err(_) if Self::best_indexed_pool(&task.requirements, false).is_some() => {
+                    Self::enqueue_task(task.task_id, task.priority.clone(), None)?;
+                    Ok(None)
+                },
+                Err(e) => Err(e),
+            }
+        }
+
+        /// `len` split into `parts` near equal lengths, the longer ones first
+        fn split_dimension(len: u32, parts: u32) -> Vec<u32> {
+            (0..parts).map(|part| len / parts + u32::from(part < len % parts)).collect()
+        }
+
+        /// Bring a sharded task in line with its shards once `shard_id` changes: completed with
+        /// the hash of all shard proofs once every shard verified, failed once a shard failed
+        /// that can no longer be reassigned, computing otherwise. A completed or failed task
+        /// stays that way.
+        fn update_sharded_task(shard_id: TaskId) {
+            let task_id = match ShardParent::<T>::get(shard_id) {
+                Some(task_id) => task_id,
+                None => return,
+            };
+            let shards: Vec<_> =
+                TaskShards::<T>::get(task_id).iter().filter_map(|id| Tasks::<T>::get(id)).collect();
+            let now = frame_system::Pallet::<T>::block_number();
+
+            Tasks::<T>::mutate(task_id, |maybe_task| {
+                let task = match maybe_task {
+                    Some(task) if !Self::is_terminal(&task.status) => task,
+                    _ => return,
+                };
+                let status = if shards.iter().all(|shard| shard.status == TaskStatus::Completed) {
+                    TaskStatus::Completed
+                } else if shards.iter().any(|shard| shard.status == TaskStatus::Failed) &&
+                    !Self::shards_reassignable(&task.requirements, now)
+                {
+                    TaskStatus::Failed
+                } else {
+                    TaskStatus::Computing
+                };
+                if task.status == status {
+                    return;
+                }
+
+                task.proof_hash = None;
+                task.verification_result = None;
+                match status {
+                    TaskStatus::Completed => {
+                        let proofs: Vec<[u8; 32]> = shards
+                            .iter()
+                            .map(|shard| shard.proof_hash.unwrap_or_default())
+                            .collect();
+                        let result_hash = proofs.using_encoded(sp_io::hashing::blake2_256);
+                        task.proof_hash = Some(result_hash);
+                        task.verification_result = Some(true);
+                        Self::deposit_event(Event::ShardedTaskCompleted { task_id, result_hash });
+                    },
+                    TaskStatus::Failed => task.verification_result = Some(false),
+                    _ => {},
+                }
+                task.status = status.clone();
+                Self::deposit_event(Event::TaskStatusChanged { task_id, status });
+            });
+        }
+
+        /// Whether `reassign_shard` can still place failed shards with `requirements`: the
+        /// deadline has not passed and some pool, free or busy, meets them
+        fn shards_reassignable(
+            requirements: &TaskRequirements<BalanceOf<T>, BlockNumberFor<T>>,
+            now: BlockNumberFor<T>,
+        ) -> bool {
+            requirements.deadline.map_or(true, |deadline| now <= deadline) &&
+                Self::has_indexed_pool(requirements)
+        }
+
+        /// Start queued tasks on a pool while it has free slots, taking at most
+        /// `MAX_QUEUED_STARTS` tasks off the queues
+        pub(crate) fn fill_pool_slots(pool_id: PoolId) {
+            let pool = match Pools::<T>::get(pool_id) {
+                Some(pool) if matches!(pool.status, PoolStatus::Active) => pool,
+                _ => return,
+            };
+            let price: u128 = pool.price_per_task.unique_saturated_into();
+            let profile = Self::pool_profile(&pool);
+            for _ in 0..MAX_QUEUED_STARTS {
+                if ActiveTaskCount::<T>::get(pool_id) >= T::MaxTasksPerPool::get() {
+                    break;
+                }
+                let task_id = match Self::dequeue_task(pool_id, &profile, price) {
+                    Some(task_id) => task_id,
+                    None => break,
+                };
+                let score = Self::calculate_pool_score(&pool, price, price);
+                let assigned = frame_support::storage::with_storage_layer(|| {
+                    Self::assign_task(task_id, pool_id, score)
+                });
+                // The user can no longer pay for the task, or its deadline passed
+                if assigned.is_err() {
+                    Self::drop_queued_task(task_id);
+                }
+            }
+        }
+
+        /// Take the next task for a pool: the better of its own queue's head and the first
+        /// task in the global queue whose requirements the pool meets
+        fn dequeue_task(pool_id: PoolId, profile: &PoolProfile, price: u128) -> Option<TaskId> {
+            let own = PoolQueue::<T>::get(pool_id).first().cloned();
+            let global = GlobalQueue::<T>::get().into_iter().enumerate().find(|(_, (_, id))| {
+                Tasks::<T>::get(id).map_or(false, |task| {
+                    RequirementFilter::new(&task.requirements).matches(profile, price)
+                })
+            });
+
+            let take_global = match (&own, &global) {
+                (Some((own_priority, own_id)), Some((_, (priority, id)))) =>
+                    priority > own_priority || (priority == own_priority && id < own_id),
+                (None, Some(_)) => true,
+                _ => false,
+            };
+            if take_global {
+                let (index, (_, task_id)) = global?;
+                GlobalQueue::<T>::mutate(|queue| {
+                    queue.remove(index);
+                });
+                Some(task_id)
+            } else {
+                let (_, task_id) = own?;
+                PoolQueue::<T>::mutate(pool_id, |queue| {
+                    queue.remove(0);
+                });
+                Some(task_id)
+            }
         }
 
         fn update_reputation(pool_id: PoolId, success: bool) {
@@ -1094,6 +2461,7 @@ pub mod pallet {
                     });
                 }
             });
+            Self::reindex_pool(pool_id);
         }
     }
 }
@@ -1105,76 +2473,72 @@ pub mod pallet {
 impl<T: Config> dbc_support::traits::TaskComputeScheduler for Pallet<T> {
     type AccountId = T::AccountId;
     type Balance = BalanceOf<T>;
+    type BlockNumber = frame_system::pallet_prelude::BlockNumberFor<T>;
 
     fn schedule_compute(
-        user: &Self::AccountId,
-        _model_id: &[u8],
+        order: &TaskOrderContext<Self::AccountId, Self::Balance>,
+        preferred_miner: Option<&Self::AccountId>,
         dimensions: (u32, u32, u32),
+        requirements: &TaskRequirements<Self::Balance, Self::BlockNumber>,
     ) -> Result<(u64, Self::AccountId, Self::Balance), &'static str> {
-
-        // Find the best available pool based on reputation and status
-        let mut best_pool: Option<(PoolId, ComputePool<T::AccountId, BalanceOf<T>, T::MaxGpuModelLen>)> = None;
-        let mut best_score = 0u32;
-
-        for (pool_id, pool) in Pools::<T>::iter().take(50) {
-            if pool.status != PoolStatus::Active {
-                continue;
-            }
-            
-            // Calculate a simple score based on reputation and success rate
-            let score = pool.reputation.saturating_add(pool.success_rate) / 2;
-            if score > best_score {
-                best_score = score;
-                best_pool = Some((pool_id, pool));
-            }
+        let now = frame_system::Pallet::<T>::block_number();
+        if requirements.deadline.map_or(false, |deadline| deadline < now) {
+            return Err("Task deadline has passed");
         }
 
-        let (pool_id, pool) = best_pool.ok_or("No active pool available")?;
-
-        // Create the task
         let task_id = NextTaskId::<T>::get();
         let next_task_id = task_id.checked_add(1).ok_or("Task ID overflow")?;
-        NextTaskId::<T>::put(next_task_id);
-
-        let task_dimensions = TaskDimensions {
-            m: dimensions.0,
-            n: dimensions.1,
-            k: dimensions.2,
-        };
-
-        let now = frame_system::Pallet::<T>::block_number();
-        let estimated_cost = pool.price_per_task;
-
         let task = ComputeTask {
             task_id,
-            user: user.clone(),
-            pool_id,
-            dimensions: task_dimensions,
+            user: order.customer.clone(),
+            pool_id: 0,
+            dimensions: TaskDimensions { m: dimensions.0, n: dimensions.1, k: dimensions.2 },
+            requirements: requirements.clone(),
             priority: TaskPriority::Normal,
             status: TaskStatus::Pending,
             submitted_at: now,
             proof_hash: None,
             verification_result: None,
-            reward_amount: Some(estimated_cost),
+            reward_amount: None,
             disputed: false,
         };
 
-        Tasks::<T>::insert(task_id, task);
+        // A busy pool queues the task, so the order always knows the miner that runs it
+        let (pool_id, placement) = match preferred_miner {
+            // The order already names a miner: run it on that miner's pool
+            Some(miner) => {
+                let pool_id = PoolByOwner::<T>::get(miner).ok_or("Miner has no compute pool")?;
+                (pool_id, Pallet::<T>::preferred_placement(pool_id, requirements)?)
+            },
+            None => match Pallet::<T>::select_best_pool_for_task(&task, &[]) {
+                Ok((pool_id, score)) => (pool_id, Some((pool_id, score))),
+                // Every pool meeting the requirements is busy: wait for the best of them
+                Err(_) => (
+                    Pallet::<T>::best_indexed_pool(requirements, false)
+                        .ok_or("No active pool available")?,
+                    None,
+                ),
+            },
+        };
+        let owner = Pools::<T>::get(pool_id).ok_or("Pool not found")?.owner;
 
-        // Update pool activity
-        ActiveTaskCount::<T>::mutate(pool_id, |count| *count = count.saturating_add(1));
-        PoolTasks::<T>::try_mutate(pool_id, |tasks| {
-            tasks.try_push(task_id).map_err(|_| "Pool task limit reached")
-        })?;
+        // The customer pays the miner through attestation settlement, so the
+        // task itself carries no escrowed reward.
+        Tasks::<T>::insert(task_id, task);
+        NextTaskId::<T>::put(next_task_id);
+        TaskOrderContexts::<T>::insert(task_id, order.clone());
+        Pallet::<T>::deposit_event(Event::TaskSubmitted { task_id, user: order.customer.clone() });
+        Pallet::<T>::deposit_event(Event::TaskStatusChanged {
+            task_id,
+            status: TaskStatus::Pending,
+        });
 
-        // Update task status to Computing
-        Tasks::<T>::try_mutate(task_id, |maybe_task| -> Result<(), &'static str> {
-            let t = maybe_task.as_mut().ok_or("Task not found")?;
-            t.status = TaskStatus::Computing;
-            Ok(())
-        })?;
+        match placement {
+            Some((pool_id, score)) => Pallet::<T>::assign_task(task_id, pool_id, score)?,
+            None => Pallet::<T>::enqueue_task(task_id, TaskPriority::Normal, Some(pool_id))?,
+        }
 
-        Ok((task_id, pool.owner, estimated_cost))
+        Ok((task_id, owner, order.agreed_price))
     }
 
     fn is_task_completed(scheduler_task_id: u64) -> bool {
@@ -1182,4 +2546,38 @@ impl<T: Config> dbc_support::traits::TaskComputeScheduler for Pallet<T> {
             .map(|task| matches!(task.status, TaskStatus::Completed))
             .unwrap_or(false)
     }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn prepare_benchmark_miner(miner: &Self::AccountId) {
+        use sp_runtime::traits::Saturating;
+        if PoolByOwner::<T>::contains_key(miner) {
+            return;
+        }
+        let _ = T::Currency::make_free_balance_be(
+            miner,
+            T::PoolDeposit::get().saturating_mul(1_000u32.into()),
+        );
+        let gpu_model: frame_support::BoundedVec<u8, T::MaxGpuModelLen> =
+            b"RTX4090".to_vec().try_into().unwrap_or_default();
+        let _ = Pallet::<T>::register_pool(
+            frame_system::RawOrigin::Signed(miner.clone()).into(),
+            gpu_model,
+            16_384u32,
+            8u32,
+            true,
+            130u32,
+            *b"US",
+            1000u32.into(),
+        );
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn complete_benchmark_task(scheduler_task_id: u64) {
+        Tasks::<T>::mutate(scheduler_task_id, |maybe_task| {
+            if let Some(task) = maybe_task {
+                task.status = TaskStatus::Completed;
+                task.verification_result = Some(true);
+            }
+        });
+    }
 }
@@ -0,0 +1,305 @@
+use crate::*;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement, OnRuntimeUpgrade, ReservableCurrency},
+};
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+const TARGET: &str = "runtime::compute-pool-scheduler";
+
+pub mod v1 {
+    use super::*;
+
+    /// Stakes reserved on the delegator before delegated staking
+    #[frame_support::storage_alias]
+    pub type PoolStakes<T: Config> = StorageDoubleMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        PoolId,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+}
+
+pub mod v2 {
+    use super::*;
+
+    /// Moves reserved pool stakes into the pool accounts and issues one point per unit
+    /// staked. Stakes that can no longer be moved are dropped and stay with the delegator.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 1 {
+                log::info!(target: TARGET, "v2 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 1, "this migration can be deleted");
+            Ok((v1::PoolStakes::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let stakes: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration needs to be removed");
+            ensure!(PoolStakes::<T>::iter_keys().count() as u64 <= stakes, "pool stakes were added");
+            ensure!(
+                TotalPoolStake::<T>::iter()
+                    .all(|(pool_id, total)| StakePools::<T>::get(pool_id).total_points == total),
+                "pool stake does not match its points"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let _ = TotalPoolStake::<T>::clear(u32::MAX, None);
+
+        let mut read = 0u64;
+        let mut moved = 0u64;
+        PoolStakes::<T>::translate::<BalanceOf<T>, _>(|pool_id, who, amount| {
+            read += 1;
+            let unreserved = amount.saturating_sub(T::Currency::unreserve(&who, amount));
+            let transferred = T::Currency::transfer(
+                &who,
+                &Pallet::<T>::pool_account(pool_id),
+                unreserved,
+                ExistenceRequirement::AllowDeath,
+            );
+            if unreserved.is_zero() || transferred.is_err() {
+                return None
+            }
+
+            moved += 1;
+            TotalPoolStake::<T>::mutate(pool_id, |total| *total = total.saturating_add(unreserved));
+            StakePools::<T>::mutate(pool_id, |stake_pool| {
+                stake_pool.total_points = stake_pool.total_points.saturating_add(unreserved)
+            });
+            Some(Delegation {
+                points: unreserved,
+                reward_debt: Zero::zero(),
+                unbonding_points: Zero::zero(),
+                unlock_at: Zero::zero(),
+            })
+        });
+
+        StorageVersion::new(2).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v2 migration moved {} of {} pool stakes", moved, read);
+        T::DbWeight::get().reads_writes(read.saturating_mul(4) + 1, read.saturating_mul(6) + 1)
+    }
+}
+
+pub mod v3 {
+    use super::*;
+
+    /// Pool layout before GPU counts and regions
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    #[scale_info(skip_type_params(MaxGpuModelLen))]
+    pub struct OldComputePool<AccountId, Balance, MaxGpuModelLen: Get<u32>> {
+        pub pool_id: PoolId,
+        pub owner: AccountId,
+        pub gpu_model: BoundedVec<u8, MaxGpuModelLen>,
+        pub gpu_memory: u32,
+        pub has_nvlink: bool,
+        pub nvlink_efficiency: u32,
+        pub price_per_task: Balance,
+        pub reputation: u32,
+        pub success_rate: u32,
+        pub total_tasks: u32,
+        pub completed_tasks: u32,
+        pub failed_tasks: u32,
+        pub status: PoolStatus,
+        pub deposit_held: Balance,
+        pub score: PoolScore,
+    }
+
+    /// Task layout before resource requirements
+    #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct OldComputeTask<AccountId, BlockNumber, Balance> {
+        pub task_id: TaskId,
+        pub user: AccountId,
+        pub pool_id: PoolId,
+        pub dimensions: TaskDimensions,
+        pub priority: TaskPriority,
+        pub status: TaskStatus,
+        pub submitted_at: BlockNumber,
+        pub proof_hash: Option<[u8; 32]>,
+        pub verification_result: Option<bool>,
+        pub reward_amount: Option<Balance>,
+        pub disputed: bool,
+    }
+
+    /// Introduces the pool selection index. Pools are indexed by the v4 migration, once they
+    /// carry the GPU counts and regions their profiles are made of.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 2 {
+                log::info!(target: TARGET, "v3 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration can be deleted");
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration needs to be removed");
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        StorageVersion::new(3).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v3 migration introduced the pool index");
+        T::DbWeight::get().writes(1)
+    }
+}
+
+pub mod v4 {
+    use super::*;
+    use frame_system::pallet_prelude::BlockNumberFor;
+
+    /// Region given to pools registered before regions existed (ISO 3166 user-assigned "unknown")
+    pub const UNKNOWN_REGION: RegionCode = *b"ZZ";
+
+    /// Adds GPU counts and regions to pools and resource requirements to tasks, then rebuilds
+    /// the pool index with the new profiles. Existing pools count as a single GPU in an unknown
+    /// region, and existing tasks only require the memory they were scheduled for.
+    ///
+    /// All pools and tasks are translated in the upgrade block. `pre_upgrade` checks that this
+    /// fits in a block.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 3 {
+                log::info!(target: TARGET, "v4 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration can be deleted");
+            let pools = Pools::<T>::iter_keys().count() as u64;
+            let tasks = Tasks::<T>::iter_keys().count() as u64;
+            ensure!(
+                weight::<T>(pools, tasks)
+                    .all_lte(<T as frame_system::Config>::BlockWeights::get().max_block),
+                "too many pools and tasks to migrate in one block"
+            );
+            Ok((pools, tasks).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (pools, tasks): (u64, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 4, "this migration needs to be removed");
+            ensure!(Pools::<T>::iter_values().count() as u64 == pools, "pool failed to decode");
+            ensure!(Tasks::<T>::iter_values().count() as u64 == tasks, "task failed to decode");
+            ensure!(
+                IndexedPools::<T>::iter_keys().count() == PoolIndex::<T>::iter_keys().count(),
+                "stale pool index entry"
+            );
+            let profiled: u32 =
+                IndexedProfiles::<T>::get().iter().map(|indexed| indexed.pools).sum();
+            ensure!(
+                profiled as usize == IndexedPools::<T>::iter_keys().count(),
+                "pool missing from the indexed profiles"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut pools = 0u64;
+        Pools::<T>::translate::<
+            v3::OldComputePool<T::AccountId, BalanceOf<T>, T::MaxGpuModelLen>,
+            _,
+        >(|_, old| {
+            pools += 1;
+            Some(ComputePool {
+                pool_id: old.pool_id,
+                owner: old.owner,
+                gpu_model: old.gpu_model,
+                gpu_memory: old.gpu_memory,
+                gpu_count: 1,
+                has_nvlink: old.has_nvlink,
+                nvlink_efficiency: old.nvlink_efficiency,
+                region: UNKNOWN_REGION,
+                price_per_task: old.price_per_task,
+                reputation: old.reputation,
+                success_rate: old.success_rate,
+                total_tasks: old.total_tasks,
+                completed_tasks: old.completed_tasks,
+                failed_tasks: old.failed_tasks,
+                status: old.status,
+                deposit_held: old.deposit_held,
+                score: old.score,
+            })
+        });
+
+        let mut tasks = 0u64;
+        Tasks::<T>::translate::<
+            v3::OldComputeTask<T::AccountId, BlockNumberFor<T>, BalanceOf<T>>,
+            _,
+        >(|_, old| {
+            tasks += 1;
+            Some(ComputeTask {
+                task_id: old.task_id,
+                user: old.user,
+                pool_id: old.pool_id,
+                requirements: TaskRequirements { min_vram: old.dimensions.k, ..Default::default() },
+                dimensions: old.dimensions,
+                priority: old.priority,
+                status: old.status,
+                submitted_at: old.submitted_at,
+                proof_hash: old.proof_hash,
+                verification_result: old.verification_result,
+                reward_amount: old.reward_amount,
+                disputed: old.disputed,
+            })
+        });
+
+        let _ = PoolIndex::<T>::clear(u32::MAX, None);
+        let _ = PoolTierHeads::<T>::clear(u32::MAX, None);
+        let _ = IndexedPools::<T>::clear(u32::MAX, None);
+        IndexedProfiles::<T>::kill();
+        for pool_id in Pools::<T>::iter_keys() {
+            Pallet::<T>::reindex_pool(pool_id);
+        }
+
+        StorageVersion::new(4).put::<Pallet<T>>();
+
+        log::info!(target: TARGET, "v4 migration updated {} pools and {} tasks", pools, tasks);
+        weight::<T>(pools, tasks)
+    }
+
+    /// Weight of migrating `pools` pools and `tasks` tasks
+    fn weight<T: Config>(pools: u64, tasks: u64) -> Weight {
+        T::DbWeight::get().reads_writes(
+            pools.saturating_mul(8).saturating_add(tasks) + 1,
+            pools.saturating_mul(6).saturating_add(tasks) + 1,
+        )
+    }
+}
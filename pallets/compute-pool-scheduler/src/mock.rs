@@ -2,11 +2,13 @@ use crate as pallet_compute_pool_scheduler;
 use frame_support::{
     construct_runtime, parameter_types,
     traits::{ConstU16, ConstU32, Everything},
+    PalletId,
 };
 use sp_core::H256;
 use sp_runtime::{
     generic::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
 };
 
 pub type AccountId = u64;
@@ -42,6 +44,14 @@ parameter_types! {
     pub const MaxTasksPerPool: u32 = 16;
     pub const InitialReputation: u32 = 80;
     pub const VerificationTimeout: BlockNumber = 3;
+    pub const UnbondingPeriod: BlockNumber = 10;
+    pub const MaxQueuedTasks: u32 = 2;
+    pub const PriorityFee: Perbill = Perbill::from_percent(10);
+    pub const MaxRequiredGpuModels: u32 = 4;
+    pub const MaxPoolProfiles: u32 = 8;
+    pub const MaxShardsPerTask: u32 = 4;
+    pub const MaxCommission: Perbill = Perbill::from_percent(50);
+    pub const SchedulerPalletId: PalletId = PalletId(*b"dbc/pool");
 }
 
 impl frame_system::Config for Test {
@@ -90,17 +100,21 @@ impl pallet_balances::Config for Test {
 // Mock implementation for TaskCompletionHandler
 pub struct MockTaskCompletionHandler;
 
+thread_local! {
+    pub static COMPLETED_ORDERS: std::cell::RefCell<Vec<(u64, Option<u64>)>> = std::cell::RefCell::new(Vec::new());
+}
+
 impl dbc_support::traits::TaskCompletionHandler for MockTaskCompletionHandler {
     type AccountId = u64;
+    type Balance = Balance;
 
     fn on_task_completed(
         _attester: &Self::AccountId,
-        _task_id: u64,
+        task_id: u64,
         _result_hash: sp_core::H256,
-        _model_id: &[u8],
-        _input_tokens: u64,
-        _output_tokens: u64,
+        order: Option<&dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>>,
     ) -> Result<u64, &'static str> {
+        COMPLETED_ORDERS.with(|c| c.borrow_mut().push((task_id, order.map(|o| o.order_id))));
         Ok(0)
     }
 }
@@ -118,6 +132,14 @@ impl crate::Config for Test {
     type MaxTasksPerPool = MaxTasksPerPool;
     type InitialReputation = InitialReputation;
     type VerificationTimeout = VerificationTimeout;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxQueuedTasks = MaxQueuedTasks;
+    type PriorityFee = PriorityFee;
+    type MaxRequiredGpuModels = MaxRequiredGpuModels;
+    type MaxPoolProfiles = MaxPoolProfiles;
+    type MaxShardsPerTask = MaxShardsPerTask;
+    type MaxCommission = MaxCommission;
+    type PalletId = SchedulerPalletId;
     type WeightInfo = ();
     type OnTaskCompleted = MockTaskCompletionHandler;
 }
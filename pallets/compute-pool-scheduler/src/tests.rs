@@ -2,7 +2,14 @@ use crate::{
     mock::*,
     pallet::{Error, PoolStatus, TaskDimensions, TaskPriority, TaskStatus},
 };
-use frame_support::{assert_noop, assert_ok, traits::Hooks, BoundedVec};
+use codec::Encode;
+use dbc_support::traits::{GpuModelName, MaxTaskGpuModels, TaskRequirements};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Get, Hooks},
+    BoundedVec,
+};
+use sp_runtime::Perbill;
 
 fn gpu_model() -> BoundedVec<u8, <Test as crate::Config>::MaxGpuModelLen> {
     b"RTX-4090".to_vec().try_into().unwrap()
@@ -12,6 +19,10 @@ fn dims() -> TaskDimensions {
     TaskDimensions { m: 8, n: 8, k: 8 }
 }
 
+fn reqs() -> TaskRequirements<Balance, u64> {
+    TaskRequirements { min_vram: 8, ..Default::default() }
+}
+
 #[test]
 fn mock_runtime_should_bootstrap() {
     new_test_ext().execute_with(|| {
@@ -26,8 +37,10 @@ fn register_pool_works() {
             RuntimeOrigin::signed(1),
             gpu_model(),
             24,     // 24GB GPU memory
+            8,      // GPUs in the pool
             true,   // has NVLink
             130,    // NVLink efficiency (must be 120-150)
+            *b"US", // region
             100,    // price per task
         ));
         let pool = ComputePoolScheduler::pools(0).unwrap();
@@ -43,11 +56,11 @@ fn register_pool_works() {
 fn register_pool_duplicate_fails() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_noop!(
             ComputePoolScheduler::register_pool(
-                RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+                RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
             ),
             Error::<Test>::PoolAlreadyExists
         );
@@ -59,7 +72,7 @@ fn register_pool_zero_memory_fails() {
     new_test_ext().execute_with(|| {
         assert_noop!(
             ComputePoolScheduler::register_pool(
-                RuntimeOrigin::signed(1), gpu_model(), 0, false, 100, 100,
+                RuntimeOrigin::signed(1), gpu_model(), 0, 8, false, 100, *b"US", 100,
             ),
             Error::<Test>::InvalidDimensions
         );
@@ -71,7 +84,7 @@ fn register_pool_zero_price_fails() {
     new_test_ext().execute_with(|| {
         assert_noop!(
             ComputePoolScheduler::register_pool(
-                RuntimeOrigin::signed(1), gpu_model(), 24, false, 100, 0,
+                RuntimeOrigin::signed(1), gpu_model(), 24, 8, false, 100, *b"US", 0,
             ),
             Error::<Test>::InsufficientBalance
         );
@@ -83,13 +96,14 @@ fn submit_task_works() {
     new_test_ext().execute_with(|| {
         // Register a pool first
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
 
         // Submit a task (from different account)
         assert_ok!(ComputePoolScheduler::submit_task(
             RuntimeOrigin::signed(2),
             dims(),
+            reqs(),
             TaskPriority::Normal,
             None,
         ));
@@ -109,6 +123,7 @@ fn submit_task_no_pool_fails() {
             ComputePoolScheduler::submit_task(
                 RuntimeOrigin::signed(2),
                 dims(),
+                reqs(),
                 TaskPriority::Normal,
                 None,
             ),
@@ -121,12 +136,13 @@ fn submit_task_no_pool_fails() {
 fn submit_task_invalid_dimensions_fails() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_noop!(
             ComputePoolScheduler::submit_task(
                 RuntimeOrigin::signed(2),
                 TaskDimensions { m: 0, n: 64, k: 64 },
+                reqs(),
                 TaskPriority::Normal,
                 None,
             ),
@@ -139,7 +155,7 @@ fn submit_task_invalid_dimensions_fails() {
 fn deregister_pool_works() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, false, 100, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, false, 100, *b"US", 100,
         ));
         assert_ok!(ComputePoolScheduler::deregister_pool(
             RuntimeOrigin::signed(1), 0,
@@ -156,7 +172,7 @@ fn deregister_pool_works() {
 fn deregister_pool_not_owner_fails() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, false, 100, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, false, 100, *b"US", 100,
         ));
         assert_noop!(
             ComputePoolScheduler::deregister_pool(RuntimeOrigin::signed(2), 0),
@@ -169,10 +185,10 @@ fn deregister_pool_not_owner_fails() {
 fn submit_proof_works() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_ok!(ComputePoolScheduler::submit_task(
-            RuntimeOrigin::signed(2), dims(), TaskPriority::Normal,
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal,
             None,
         ));
 
@@ -196,10 +212,10 @@ fn verify_proof_works() {
     new_test_ext().execute_with(|| {
         // Account 1 = pool owner, Account 2 = task user, Account 3 = independent verifier
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_ok!(ComputePoolScheduler::submit_task(
-            RuntimeOrigin::signed(2), dims(), TaskPriority::Normal, None,
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
         ));
         assert_ok!(ComputePoolScheduler::submit_proof(
             RuntimeOrigin::signed(1), 0, [42u8; 32],
@@ -220,10 +236,10 @@ fn verify_proof_works() {
 fn verify_proof_reject_works() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_ok!(ComputePoolScheduler::submit_task(
-            RuntimeOrigin::signed(2), dims(), TaskPriority::Normal, None,
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
         ));
         assert_ok!(ComputePoolScheduler::submit_proof(
             RuntimeOrigin::signed(1), 0, [42u8; 32],
@@ -244,10 +260,10 @@ fn verify_proof_reject_works() {
 fn self_verification_fails() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_ok!(ComputePoolScheduler::submit_task(
-            RuntimeOrigin::signed(2), dims(), TaskPriority::Normal, None,
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
         ));
         assert_ok!(ComputePoolScheduler::submit_proof(
             RuntimeOrigin::signed(1), 0, [42u8; 32],
@@ -269,10 +285,10 @@ fn self_verification_fails() {
 fn auto_verify_on_timeout() {
     new_test_ext().execute_with(|| {
         assert_ok!(ComputePoolScheduler::register_pool(
-            RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
         ));
         assert_ok!(ComputePoolScheduler::submit_task(
-            RuntimeOrigin::signed(2), dims(), TaskPriority::Normal, None,
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
         ));
         assert_ok!(ComputePoolScheduler::submit_proof(
             RuntimeOrigin::signed(1), 0, [42u8; 32],
@@ -295,7 +311,7 @@ fn auto_verify_on_timeout() {
 
 fn setup_default_pool() {
     assert_ok!(ComputePoolScheduler::register_pool(
-        RuntimeOrigin::signed(1), gpu_model(), 24, true, 130, 100,
+        RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 100,
     ));
 }
 
@@ -304,10 +320,1080 @@ fn staking_works() {
     new_test_ext().execute_with(|| {
         setup_default_pool();
         assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(2), 0, 5_000));
-        assert_eq!(ComputePoolScheduler::pool_stakes(0, 2), 5_000);
+        assert_eq!(ComputePoolScheduler::delegated_stake(0, &2), 5_000);
         assert_eq!(ComputePoolScheduler::total_pool_stake(0), 5_000);
+        assert_eq!(Balances::free_balance(ComputePoolScheduler::pool_account(0)), 5_000);
+
         assert_ok!(ComputePoolScheduler::unstake_from_pool(RuntimeOrigin::signed(2), 0, 3_000));
-        assert_eq!(ComputePoolScheduler::pool_stakes(0, 2), 2_000);
+        assert_eq!(ComputePoolScheduler::delegated_stake(0, &2), 2_000);
+        assert_eq!(ComputePoolScheduler::unbonding_stake(0, &2), 3_000);
         assert_eq!(ComputePoolScheduler::total_pool_stake(0), 2_000);
+        System::assert_last_event(
+            crate::Event::Unbonding { who: 2, pool_id: 0, amount: 3_000, unlock_at: 11 }.into(),
+        );
+    });
+}
+
+#[test]
+fn unbonded_stake_is_withdrawn_after_the_period() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(2), 0, 5_000));
+        assert_noop!(
+            ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(3), 0, 999),
+            Error::<Test>::InsufficientStake
+        );
+        // What is left staked must stay above MinPoolStake
+        assert_noop!(
+            ComputePoolScheduler::unstake_from_pool(RuntimeOrigin::signed(2), 0, 4_500),
+            Error::<Test>::InsufficientStake
+        );
+        assert_noop!(
+            ComputePoolScheduler::unstake_from_pool(RuntimeOrigin::signed(2), 0, 6_000),
+            Error::<Test>::StakeNotFound
+        );
+
+        assert_ok!(ComputePoolScheduler::unstake_from_pool(RuntimeOrigin::signed(2), 0, 5_000));
+        assert_noop!(
+            ComputePoolScheduler::withdraw_unbonded(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::StakeStillUnbonding
+        );
+
+        run_to_block(11);
+        assert_ok!(ComputePoolScheduler::withdraw_unbonded(RuntimeOrigin::signed(2), 0));
+        assert_eq!(Balances::free_balance(2), 1_000_000);
+        assert_eq!(ComputePoolScheduler::pool_stakes(0, 2), None);
+        assert_eq!(ComputePoolScheduler::stake_pools(0).unbonding_stake, 0);
+        System::assert_last_event(
+            crate::Event::Unstaked { who: 2, pool_id: 0, amount: 5_000 }.into(),
+        );
+    });
+}
+
+/// Run task 0 of pool 0 to a verified result
+fn complete_task(result: bool) {
+    assert_ok!(ComputePoolScheduler::submit_task(
+        RuntimeOrigin::signed(4), dims(), reqs(), TaskPriority::Normal, Some(0),
+    ));
+    assert_ok!(ComputePoolScheduler::submit_proof(RuntimeOrigin::signed(1), 0, [42u8; 32]));
+    assert_ok!(ComputePoolScheduler::verify_proof(RuntimeOrigin::signed(5), 0, result));
+}
+
+#[test]
+fn delegators_share_task_rewards_after_commission() {
+    new_test_ext().execute_with(|| {
+        // Reward is 1_000 * 130% = 1_300
+        assert_ok!(ComputePoolScheduler::register_pool(
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 130, *b"US", 1_000,
+        ));
+        assert_ok!(ComputePoolScheduler::set_pool_commission(
+            RuntimeOrigin::signed(1), 0, Perbill::from_percent(20),
+        ));
+        assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(2), 0, 1_000));
+        assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(3), 0, 2_000));
+
+        complete_task(true);
+        assert_ok!(ComputePoolScheduler::claim_reward(RuntimeOrigin::signed(1), 0));
+
+        // 260 commission, then 1_040 split 1_000 : 3_000 between deposit and delegators
+        assert_eq!(Balances::free_balance(1), 1_000_000 - 1_000 + 520);
+        System::assert_has_event(
+            crate::Event::DelegatorRewardsDistributed { pool_id: 0, amount: 780 }.into(),
+        );
+        assert_eq!(ComputePoolScheduler::pending_delegator_rewards(0, &2), 260);
+        assert_eq!(ComputePoolScheduler::pending_delegator_rewards(0, &3), 520);
+
+        // Later delegators do not share earlier rewards
+        assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(5), 0, 1_000));
+        assert_eq!(ComputePoolScheduler::pending_delegator_rewards(0, &5), 0);
+
+        assert_ok!(ComputePoolScheduler::claim_delegator_rewards(RuntimeOrigin::signed(2), 0));
+        assert_eq!(Balances::free_balance(2), 1_000_000 - 1_000 + 260);
+        assert_noop!(
+            ComputePoolScheduler::claim_delegator_rewards(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::NoRewards
+        );
+
+        // Unbonding pays out what was earned so far
+        assert_ok!(ComputePoolScheduler::unstake_from_pool(RuntimeOrigin::signed(3), 0, 2_000));
+        assert_eq!(Balances::free_balance(3), 1_000_000 - 2_000 + 520);
+        assert_eq!(ComputePoolScheduler::pending_delegator_rewards(0, &3), 0);
+    });
+}
+
+#[test]
+fn set_pool_commission_is_bounded() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        assert_noop!(
+            ComputePoolScheduler::set_pool_commission(
+                RuntimeOrigin::signed(2), 0, Perbill::from_percent(10),
+            ),
+            Error::<Test>::NotPoolOwner
+        );
+        assert_noop!(
+            ComputePoolScheduler::set_pool_commission(
+                RuntimeOrigin::signed(1), 0, Perbill::from_percent(60),
+            ),
+            Error::<Test>::CommissionTooHigh
+        );
+        assert_ok!(ComputePoolScheduler::set_pool_commission(
+            RuntimeOrigin::signed(1), 0, Perbill::from_percent(50),
+        ));
+        assert_eq!(ComputePoolScheduler::stake_pools(0).commission, Perbill::from_percent(50));
+    });
+}
+
+#[test]
+fn slashes_are_split_between_deposit_and_delegators() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(2), 0, 2_000));
+        assert_ok!(ComputePoolScheduler::stake_to_pool(RuntimeOrigin::signed(3), 0, 2_000));
+        assert_ok!(ComputePoolScheduler::unstake_from_pool(RuntimeOrigin::signed(3), 0, 1_000));
+
+        complete_task(false);
+
+        // 50 + 10% of 4_000 delegated, split 1_000 : 4_000
+        System::assert_has_event(crate::Event::PoolSlashed { pool_id: 0, amount: 90 }.into());
+        System::assert_has_event(crate::Event::StakeSlashed { pool_id: 0, amount: 360 }.into());
+        assert_eq!(ComputePoolScheduler::pools(0).unwrap().deposit_held, 910);
+        assert_eq!(Balances::reserved_balance(1), 910);
+        assert_eq!(Balances::free_balance(ComputePoolScheduler::pool_account(0)), 3_640);
+
+        // Unbonding stake is slashed alongside the active stake
+        assert_eq!(ComputePoolScheduler::total_pool_stake(0), 2_730);
+        assert_eq!(ComputePoolScheduler::delegated_stake(0, &2), 1_820);
+        assert_eq!(ComputePoolScheduler::delegated_stake(0, &3), 910);
+        assert_eq!(ComputePoolScheduler::unbonding_stake(0, &3), 910);
+    });
+}
+
+#[test]
+fn slash_without_delegators_only_takes_failure_slash() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        complete_task(false);
+        assert_eq!(ComputePoolScheduler::pools(0).unwrap().deposit_held, 950);
+        assert_eq!(Balances::reserved_balance(1), 950);
+    });
+}
+
+#[test]
+fn migration_v2_moves_reserved_stakes_into_the_pool() {
+    use frame_support::traits::{GetStorageVersion, ReservableCurrency, StorageVersion};
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        StorageVersion::new(1).put::<ComputePoolScheduler>();
+        assert_ok!(Balances::reserve(&2, 5_000));
+        crate::migrations::v1::PoolStakes::<Test>::insert(0, 2, 5_000);
+        crate::migrations::v1::PoolStakes::<Test>::insert(0, 3, 1_000);
+        crate::TotalPoolStake::<Test>::insert(0, 6_000);
+
+        crate::migrations::v2::migrate::<Test>();
+
+        assert_eq!(ComputePoolScheduler::on_chain_storage_version(), 2);
+        assert_eq!(ComputePoolScheduler::delegated_stake(0, &2), 5_000);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(ComputePoolScheduler::pool_account(0)), 5_000);
+        // Account 3 had nothing reserved to back its stake
+        assert_eq!(ComputePoolScheduler::pool_stakes(0, 3), None);
+        assert_eq!(ComputePoolScheduler::total_pool_stake(0), 5_000);
+        assert_eq!(ComputePoolScheduler::stake_pools(0).total_points, 5_000);
+    });
+}
+
+/// Fill every slot of pool 0 with normal priority tasks of account 2
+fn fill_pool() {
+    for _ in 0..MaxTasksPerPool::get() {
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
+        ));
+    }
+}
+
+#[test]
+fn full_pools_queue_tasks_by_priority_and_age() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        fill_pool();
+
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(3), dims(), reqs(), TaskPriority::Low, None,
+        ));
+        System::assert_last_event(
+            crate::Event::TaskQueued { task_id: 16, pool_id: None, priority: TaskPriority::Low }
+                .into(),
+        );
+        assert_eq!(ComputePoolScheduler::tasks(16).unwrap().status, TaskStatus::Pending);
+        assert_eq!(Balances::reserved_balance(3), 100);
+
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(4), dims(), reqs(), TaskPriority::High, None,
+        ));
+        assert_eq!(
+            ComputePoolScheduler::global_queue().into_inner(),
+            vec![(TaskPriority::High, 17), (TaskPriority::Low, 16)]
+        );
+
+        // Finishing a task pulls the highest priority task into the freed slot
+        run_to_block(3);
+        assert_ok!(ComputePoolScheduler::submit_proof(RuntimeOrigin::signed(1), 0, [42u8; 32]));
+        assert_ok!(ComputePoolScheduler::verify_proof(RuntimeOrigin::signed(5), 0, true));
+
+        let task = ComputePoolScheduler::tasks(17).unwrap();
+        assert_eq!(task.status, TaskStatus::Computing);
+        assert_eq!(task.pool_id, 0);
+        assert_eq!(task.submitted_at, 3);
+        // 130 reward plus one priority level of fees
+        assert_eq!(task.reward_amount, Some(143));
+        assert_eq!(Balances::reserved_balance(4), 143 + 100);
+        assert_eq!(ComputePoolScheduler::active_task_count(0), 16);
+        assert_eq!(
+            ComputePoolScheduler::global_queue().into_inner(),
+            vec![(TaskPriority::Low, 16)]
+        );
+    });
+}
+
+#[test]
+fn critical_tasks_preempt_queued_low_tasks() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        fill_pool();
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(3), dims(), reqs(), TaskPriority::Low, None,
+        ));
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(4), dims(), reqs(), TaskPriority::Low, None,
+        ));
+
+        assert_noop!(
+            ComputePoolScheduler::submit_task(
+                RuntimeOrigin::signed(5), dims(), reqs(), TaskPriority::High, None,
+            ),
+            Error::<Test>::QueueFull
+        );
+
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(5), dims(), reqs(), TaskPriority::Critical, None,
+        ));
+        System::assert_has_event(crate::Event::TaskPreempted { task_id: 17, by: 18 }.into());
+        assert_eq!(ComputePoolScheduler::tasks(17).unwrap().status, TaskStatus::Failed);
+        assert_eq!(Balances::reserved_balance(4), 0);
+        assert_eq!(
+            ComputePoolScheduler::global_queue().into_inner(),
+            vec![(TaskPriority::Critical, 18), (TaskPriority::Low, 16)]
+        );
+    });
+}
+
+#[test]
+fn queued_task_for_preferred_pool_can_be_cancelled() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        fill_pool();
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(3), dims(), reqs(), TaskPriority::Normal, Some(0),
+        ));
+        System::assert_last_event(
+            crate::Event::TaskQueued {
+                task_id: 16,
+                pool_id: Some(0),
+                priority: TaskPriority::Normal,
+            }
+            .into(),
+        );
+        assert_eq!(
+            ComputePoolScheduler::pool_queue(0).into_inner(),
+            vec![(TaskPriority::Normal, 16)]
+        );
+
+        assert_noop!(
+            ComputePoolScheduler::cancel_queued_task(RuntimeOrigin::signed(4), 16),
+            Error::<Test>::NotTaskUser
+        );
+        assert_ok!(ComputePoolScheduler::cancel_queued_task(RuntimeOrigin::signed(3), 16));
+        assert_eq!(ComputePoolScheduler::tasks(16).unwrap().status, TaskStatus::Failed);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert!(ComputePoolScheduler::pool_queue(0).is_empty());
+        assert_noop!(
+            ComputePoolScheduler::cancel_queued_task(RuntimeOrigin::signed(3), 16),
+            Error::<Test>::InvalidTaskState
+        );
+    });
+}
+
+#[test]
+fn new_pools_take_queued_tasks() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        fill_pool();
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(4), dims(), reqs(), TaskPriority::Normal, None,
+        ));
+        assert_eq!(ComputePoolScheduler::tasks(16).unwrap().status, TaskStatus::Pending);
+
+        assert_ok!(ComputePoolScheduler::register_pool(
+            RuntimeOrigin::signed(3), gpu_model(), 24, 8, true, 130, *b"US", 100,
+        ));
+        let task = ComputePoolScheduler::tasks(16).unwrap();
+        assert_eq!(task.status, TaskStatus::Computing);
+        assert_eq!(task.pool_id, 1);
+        assert_eq!(ComputePoolScheduler::active_task_count(1), 1);
+        assert!(ComputePoolScheduler::global_queue().is_empty());
+    });
+}
+
+#[test]
+fn critical_tasks_pay_two_priority_levels() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Critical, None,
+        ));
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().reward_amount, Some(156));
+        assert_eq!(Balances::reserved_balance(2), 156 + 100);
+    });
+}
+
+fn register_sized_pool(owner: AccountId, gpu_memory: u32, price: Balance) {
+    assert_ok!(ComputePoolScheduler::register_pool(
+        RuntimeOrigin::signed(owner), gpu_model(), gpu_memory, 8, true, 130, *b"US", price,
+    ));
+}
+
+#[test]
+fn selection_reaches_pools_beyond_the_first_fifty() {
+    use frame_support::traits::Currency;
+    new_test_ext().execute_with(|| {
+        for owner in 100..160 {
+            Balances::make_free_balance_be(&owner, 1_000_000);
+            register_sized_pool(owner, 24, 200);
+        }
+        // Same score as the others but cheaper
+        register_sized_pool(1, 24, 100);
+
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
+        ));
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().pool_id, 60);
+    });
+}
+
+#[test]
+fn selection_skips_pools_without_enough_memory() {
+    new_test_ext().execute_with(|| {
+        register_sized_pool(1, 24, 100);
+        register_sized_pool(2, 40, 100);
+        // Ranked last on price but the only pool large enough for the task
+        register_sized_pool(3, 60, 200);
+
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(4),
+            dims(),
+            TaskRequirements { min_vram: 48, ..Default::default() },
+            TaskPriority::Normal,
+            None,
+        ));
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().pool_id, 2);
+    });
+}
+
+#[test]
+fn pool_index_follows_pool_changes() {
+    use crate::pallet::PoolBucket;
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        let bucket = |available| PoolBucket {
+            profile: ComputePoolScheduler::pool_profile(&ComputePoolScheduler::pools(0).unwrap()),
+            available,
+        };
+        let first = bucket(true);
+        assert_eq!(ComputePoolScheduler::indexed_pool(0).unwrap().bucket, first);
+
+        assert_ok!(ComputePoolScheduler::update_pool_config(
+            RuntimeOrigin::signed(1), 0, gpu_model(), 80, 8, false, 100, *b"US", 100,
+        ));
+        let moved = bucket(true);
+        assert_eq!(moved.profile.gpu_memory, 80);
+        assert_eq!(ComputePoolScheduler::indexed_pool(0).unwrap().bucket, moved);
+        assert_eq!(crate::PoolTierHeads::<Test>::get(&first), None);
+        assert_eq!(crate::PoolTierHeads::<Test>::get(&moved).map(|heads| heads.len()), Some(1));
+        let profiles = ComputePoolScheduler::indexed_profiles();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].profile, moved.profile);
+        assert_eq!(profiles[0].pools, 1);
+
+        fill_pool();
+        let busy = bucket(false);
+        assert_eq!(ComputePoolScheduler::indexed_pool(0).unwrap().bucket, busy);
+        assert_eq!(crate::PoolTierHeads::<Test>::get(&moved), None);
+        assert_eq!(crate::PoolTierHeads::<Test>::get(&busy).map(|heads| heads.len()), Some(1));
+        assert_eq!(crate::PoolIndex::<Test>::iter().count(), 1);
+
+        register_sized_pool(2, 24, 100);
+        assert_eq!(ComputePoolScheduler::indexed_profiles().len(), 2);
+        assert_ok!(ComputePoolScheduler::deregister_pool(RuntimeOrigin::signed(2), 1));
+        assert_eq!(ComputePoolScheduler::indexed_pool(1), None);
+        assert_eq!(crate::PoolIndex::<Test>::iter().count(), 1);
+        assert_eq!(crate::PoolTierHeads::<Test>::iter().count(), 1);
+        assert_eq!(ComputePoolScheduler::indexed_profiles().len(), 1);
+    });
+}
+
+#[test]
+fn failed_tasks_lower_a_pool_in_the_index() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        register_sized_pool(2, 24, 100);
+
+        complete_task(false);
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(3), dims(), reqs(), TaskPriority::Normal, None,
+        ));
+        assert_eq!(ComputePoolScheduler::tasks(1).unwrap().pool_id, 1);
+    });
+}
+
+fn register_profiled_pool(
+    owner: AccountId,
+    model: &[u8],
+    gpu_memory: u32,
+    gpu_count: u32,
+    has_nvlink: bool,
+    region: [u8; 2],
+    price: Balance,
+) {
+    assert_ok!(ComputePoolScheduler::register_pool(
+        RuntimeOrigin::signed(owner),
+        model.to_vec().try_into().unwrap(),
+        gpu_memory,
+        gpu_count,
+        has_nvlink,
+        if has_nvlink { 130 } else { 100 },
+        region,
+        price,
+    ));
+}
+
+fn gpu_models(names: &[&[u8]]) -> BoundedVec<GpuModelName, MaxTaskGpuModels> {
+    let names: Vec<GpuModelName> =
+        names.iter().map(|name| name.to_vec().try_into().unwrap()).collect();
+    names.try_into().unwrap()
+}
+
+fn submit_with(
+    requirements: TaskRequirements<Balance, u64>,
+) -> Result<u64, sp_runtime::DispatchError> {
+    let task_id = ComputePoolScheduler::next_task_id();
+    ComputePoolScheduler::submit_task(
+        RuntimeOrigin::signed(4), dims(), requirements, TaskPriority::Normal, None,
+    )?;
+    Ok(ComputePoolScheduler::tasks(task_id).unwrap().pool_id)
+}
+
+#[test]
+fn requirements_select_matching_pools() {
+    new_test_ext().execute_with(|| {
+        register_profiled_pool(1, b"RTX-4090", 24, 8, true, *b"US", 100);
+        register_profiled_pool(2, b"A100", 80, 4, true, *b"EU", 300);
+        register_profiled_pool(3, b"RTX-4090", 24, 1, false, *b"EU", 50);
+
+        let models = gpu_models(&[b"H100", b"A100"]);
+        assert_eq!(submit_with(TaskRequirements { gpu_models: models, ..reqs() }), Ok(1));
+        assert_eq!(submit_with(TaskRequirements { gpu_count: 8, ..reqs() }), Ok(0));
+        assert_eq!(
+            submit_with(TaskRequirements { region: Some(*b"EU"), max_price: Some(100), ..reqs() }),
+            Ok(2)
+        );
+        assert_eq!(
+            submit_with(TaskRequirements {
+                region: Some(*b"EU"),
+                nvlink_required: true,
+                ..reqs()
+            }),
+            Ok(1)
+        );
+        assert_noop!(
+            submit_with(TaskRequirements { gpu_models: gpu_models(&[b"H100"]), ..reqs() }),
+            Error::<Test>::NoAvailablePool
+        );
+        assert_noop!(
+            submit_with(TaskRequirements { max_price: Some(10), ..reqs() }),
+            Error::<Test>::NoAvailablePool
+        );
+    });
+}
+
+#[test]
+fn selection_finds_a_matching_pool_behind_better_ranked_ones() {
+    use frame_support::traits::Currency;
+    new_test_ext().execute_with(|| {
+        for owner in 100..112 {
+            Balances::make_free_balance_be(&owner, 1_000_000);
+            register_profiled_pool(owner, b"A100", 80, 8, true, *b"US", 50);
+        }
+        register_profiled_pool(1, b"H100", 80, 8, true, *b"US", 300);
+
+        let models = gpu_models(&[b"H100"]);
+        assert_eq!(submit_with(TaskRequirements { gpu_models: models, ..reqs() }), Ok(12));
+    });
+}
+
+#[test]
+fn price_limit_passes_over_better_ranked_pools_above_it() {
+    new_test_ext().execute_with(|| {
+        // Same profile, the expensive pool ranks first on its NVLink efficiency
+        assert_ok!(ComputePoolScheduler::register_pool(
+            RuntimeOrigin::signed(1), gpu_model(), 24, 8, true, 150, *b"US", 300,
+        ));
+        assert_ok!(ComputePoolScheduler::register_pool(
+            RuntimeOrigin::signed(2), gpu_model(), 24, 8, true, 120, *b"US", 100,
+        ));
+        assert_eq!(ComputePoolScheduler::indexed_profiles().len(), 1);
+
+        assert_eq!(submit_with(reqs()), Ok(0));
+        assert_eq!(submit_with(TaskRequirements { max_price: Some(200), ..reqs() }), Ok(1));
+        assert_noop!(
+            submit_with(TaskRequirements { max_price: Some(50), ..reqs() }),
+            Error::<Test>::NoAvailablePool
+        );
+    });
+}
+
+#[test]
+fn price_limit_selects_the_best_ranked_pool_within_it() {
+    use frame_support::traits::Currency;
+    new_test_ext().execute_with(|| {
+        // Same profile, ranked by NVLink efficiency against their price
+        for (owner, efficiency, price) in [(1, 150, 300), (2, 135, 150), (3, 120, 50)] {
+            assert_ok!(ComputePoolScheduler::register_pool(
+                RuntimeOrigin::signed(owner), gpu_model(), 24, 8, true, efficiency, *b"US", price,
+            ));
+        }
+        assert_eq!(ComputePoolScheduler::indexed_profiles().len(), 1);
+
+        assert_eq!(submit_with(reqs()), Ok(0));
+        assert_eq!(submit_with(TaskRequirements { max_price: Some(200), ..reqs() }), Ok(1));
+        assert_eq!(submit_with(TaskRequirements { max_price: Some(150), ..reqs() }), Ok(1));
+        assert_eq!(submit_with(TaskRequirements { max_price: Some(100), ..reqs() }), Ok(2));
+
+        // The limit falls in the tier of the best ranked pool, whose second pool is within it
+        for (owner, efficiency, price) in [(10, 150, 150), (11, 135, 140), (12, 120, 50)] {
+            Balances::make_free_balance_be(&owner, 1_000_000);
+            assert_ok!(ComputePoolScheduler::register_pool(
+                RuntimeOrigin::signed(owner), gpu_model(), 48, 8, true, efficiency, *b"US", price,
+            ));
+        }
+        let large = TaskRequirements { min_vram: 48, max_price: Some(145), ..reqs() };
+        assert_eq!(submit_with(large), Ok(4));
+    });
+}
+
+#[test]
+fn pool_profiles_are_bounded() {
+    use frame_support::traits::Currency;
+    new_test_ext().execute_with(|| {
+        for (owner, gpu_memory) in (100..).zip(1..=MaxPoolProfiles::get()) {
+            Balances::make_free_balance_be(&owner, 1_000_000);
+            register_sized_pool(owner, gpu_memory, 100);
+        }
+        assert_noop!(
+            ComputePoolScheduler::register_pool(
+                RuntimeOrigin::signed(1), gpu_model(), 64, 8, true, 130, *b"US", 100,
+            ),
+            Error::<Test>::TooManyPoolProfiles
+        );
+
+        // An indexed profile still takes new pools
+        register_sized_pool(1, 1, 100);
+        // The last pool of a profile can move to a new one
+        assert_ok!(ComputePoolScheduler::update_pool_config(
+            RuntimeOrigin::signed(101), 1, gpu_model(), 64, 8, true, 130, *b"US", 100,
+        ));
+        assert_noop!(
+            ComputePoolScheduler::update_pool_config(
+                RuntimeOrigin::signed(100), 0, gpu_model(), 96, 8, true, 130, *b"US", 100,
+            ),
+            Error::<Test>::TooManyPoolProfiles
+        );
+        assert_eq!(
+            ComputePoolScheduler::indexed_profiles().len() as u32,
+            MaxPoolProfiles::get()
+        );
+    });
+}
+
+#[test]
+fn preferred_pool_must_meet_requirements() {
+    new_test_ext().execute_with(|| {
+        register_profiled_pool(1, b"RTX-4090", 24, 1, false, *b"EU", 50);
+        assert_noop!(
+            ComputePoolScheduler::submit_task(
+                RuntimeOrigin::signed(4),
+                dims(),
+                TaskRequirements { nvlink_required: true, ..reqs() },
+                TaskPriority::Normal,
+                Some(0),
+            ),
+            Error::<Test>::RequirementsNotMet
+        );
+        assert_noop!(
+            ComputePoolScheduler::submit_task(
+                RuntimeOrigin::signed(4),
+                dims(),
+                TaskRequirements { region: Some(*b"US"), ..reqs() },
+                TaskPriority::Normal,
+                Some(0),
+            ),
+            Error::<Test>::RequirementsNotMet
+        );
+    });
+}
+
+#[test]
+fn invalid_requirements_are_rejected() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        let models = gpu_models(&[b"RTX-4090"; 5]);
+        assert_noop!(
+            submit_with(TaskRequirements { gpu_models: models, ..reqs() }),
+            Error::<Test>::InvalidRequirements
+        );
+        assert_noop!(
+            submit_with(TaskRequirements { region: Some(*b"us"), ..reqs() }),
+            Error::<Test>::InvalidRegion
+        );
+        assert_noop!(
+            ComputePoolScheduler::register_pool(
+                RuntimeOrigin::signed(2), gpu_model(), 24, 0, true, 130, *b"US", 100,
+            ),
+            Error::<Test>::InvalidDimensions
+        );
+        assert_noop!(
+            ComputePoolScheduler::register_pool(
+                RuntimeOrigin::signed(2), gpu_model(), 24, 8, true, 130, *b"U1", 100,
+            ),
+            Error::<Test>::InvalidRegion
+        );
+    });
+}
+
+#[test]
+fn tasks_fail_at_their_deadline() {
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        System::set_block_number(2);
+        assert_noop!(
+            submit_with(TaskRequirements { deadline: Some(1), ..reqs() }),
+            Error::<Test>::DeadlinePassed
+        );
+
+        assert_eq!(submit_with(TaskRequirements { deadline: Some(4), ..reqs() }), Ok(0));
+        assert_eq!(submit_with(reqs()), Ok(0));
+
+        // The deadline comes before the task timeout
+        ComputePoolScheduler::on_initialize(4);
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Computing);
+        ComputePoolScheduler::on_initialize(5);
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Failed);
+        assert_eq!(ComputePoolScheduler::tasks(1).unwrap().status, TaskStatus::Computing);
+    });
+}
+
+#[test]
+fn migration_v4_adds_pool_profiles_and_task_requirements() {
+    use crate::migrations::v3::{OldComputePool, OldComputeTask};
+    use frame_support::{
+        storage::unhashed,
+        traits::{GetStorageVersion, StorageVersion},
+    };
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        assert_ok!(ComputePoolScheduler::submit_task(
+            RuntimeOrigin::signed(2), dims(), reqs(), TaskPriority::Normal, None,
+        ));
+        let pool = ComputePoolScheduler::pools(0).unwrap();
+        let task = ComputePoolScheduler::tasks(0).unwrap();
+        unhashed::put(
+            &crate::Pools::<Test>::hashed_key_for(0),
+            &OldComputePool::<AccountId, Balance, <Test as crate::Config>::MaxGpuModelLen> {
+                pool_id: 0,
+                owner: pool.owner,
+                gpu_model: pool.gpu_model.clone(),
+                gpu_memory: pool.gpu_memory,
+                has_nvlink: pool.has_nvlink,
+                nvlink_efficiency: pool.nvlink_efficiency,
+                price_per_task: pool.price_per_task,
+                reputation: pool.reputation,
+                success_rate: pool.success_rate,
+                total_tasks: pool.total_tasks,
+                completed_tasks: pool.completed_tasks,
+                failed_tasks: pool.failed_tasks,
+                status: pool.status.clone(),
+                deposit_held: pool.deposit_held,
+                score: pool.score.clone(),
+            },
+        );
+        unhashed::put(
+            &crate::Tasks::<Test>::hashed_key_for(0),
+            &OldComputeTask::<AccountId, u64, Balance> {
+                task_id: 0,
+                user: task.user,
+                pool_id: task.pool_id,
+                dimensions: TaskDimensions { m: 8, n: 8, k: 16 },
+                priority: task.priority.clone(),
+                status: task.status.clone(),
+                submitted_at: task.submitted_at,
+                proof_hash: None,
+                verification_result: None,
+                reward_amount: task.reward_amount,
+                disputed: false,
+            },
+        );
+        let _ = crate::PoolIndex::<Test>::clear(u32::MAX, None);
+        let _ = crate::PoolTierHeads::<Test>::clear(u32::MAX, None);
+        let _ = crate::IndexedPools::<Test>::clear(u32::MAX, None);
+        crate::IndexedProfiles::<Test>::kill();
+        StorageVersion::new(3).put::<ComputePoolScheduler>();
+
+        crate::migrations::v4::migrate::<Test>();
+
+        assert_eq!(ComputePoolScheduler::on_chain_storage_version(), 4);
+        let migrated = ComputePoolScheduler::pools(0).unwrap();
+        assert_eq!(migrated.gpu_count, 1);
+        assert_eq!(migrated.region, crate::migrations::v4::UNKNOWN_REGION);
+        assert_eq!(migrated.reputation, pool.reputation);
+        assert_eq!(
+            ComputePoolScheduler::tasks(0).unwrap().requirements,
+            TaskRequirements { min_vram: 16, ..Default::default() }
+        );
+        assert_eq!(
+            ComputePoolScheduler::indexed_pool(0).unwrap().bucket.profile.region,
+            crate::migrations::v4::UNKNOWN_REGION
+        );
+        assert_eq!(crate::PoolIndex::<Test>::iter().count(), 1);
+    });
+}
+
+fn register_shard_pools(count: u64) {
+    for owner in 1..=count {
+        register_profiled_pool(owner, b"RTX-4090", 24, 8, true, *b"US", 100);
+    }
+}
+
+fn submit_sharded(shards_m: u32, shards_n: u32) -> Result<u64, sp_runtime::DispatchError> {
+    let task_id = ComputePoolScheduler::next_task_id();
+    ComputePoolScheduler::submit_sharded_task(
+        RuntimeOrigin::signed(5),
+        TaskDimensions { m: 4, n: 8, k: 8 },
+        reqs(),
+        TaskPriority::Normal,
+        shards_m,
+        shards_n,
+    )?;
+    Ok(task_id)
+}
+
+fn prove_shard(shard_id: u64, proof_hash: [u8; 32], result: bool) {
+    let pool_id = ComputePoolScheduler::tasks(shard_id).unwrap().pool_id;
+    let owner = ComputePoolScheduler::pools(pool_id).unwrap().owner;
+    assert_ok!(ComputePoolScheduler::submit_proof(
+        RuntimeOrigin::signed(owner), shard_id, proof_hash,
+    ));
+    assert_ok!(ComputePoolScheduler::verify_proof(RuntimeOrigin::signed(99), shard_id, result));
+}
+
+#[test]
+fn sharded_tasks_are_spread_over_pools_and_split_the_reward() {
+    new_test_ext().execute_with(|| {
+        register_shard_pools(3);
+        assert_eq!(submit_sharded(3, 1), Ok(0));
+        assert_eq!(ComputePoolScheduler::next_task_id(), 4);
+        assert_eq!(ComputePoolScheduler::task_shards(0).into_inner(), vec![1, 2, 3]);
+
+        let task = ComputePoolScheduler::tasks(0).unwrap();
+        assert_eq!(task.status, TaskStatus::Computing);
+        assert_eq!(task.reward_amount, Some(130));
+
+        // Rows 2, 1 and 1 of 4; the last shard takes the rounding remainder
+        let shards: Vec<_> = (1..=3).map(|id| ComputePoolScheduler::tasks(id).unwrap()).collect();
+        let rows: Vec<_> = shards.iter().map(|shard| shard.dimensions.m).collect();
+        assert_eq!(rows, vec![2, 1, 1]);
+        let rewards: Vec<_> = shards.iter().map(|shard| shard.reward_amount).collect();
+        assert_eq!(rewards, vec![Some(65), Some(32), Some(33)]);
+        let mut pools: Vec<_> = shards.iter().map(|shard| shard.pool_id).collect();
+        pools.sort();
+        assert_eq!(pools, vec![0, 1, 2]);
+        for shard in &shards {
+            assert_eq!(shard.status, TaskStatus::Computing);
+            assert_eq!(ComputePoolScheduler::shard_parent(shard.task_id), Some(0));
+        }
+        System::assert_has_event(crate::Event::TaskSharded { task_id: 0, shards: 3 }.into());
+    });
+}
+
+#[test]
+fn sharded_task_completes_once_every_shard_verifies() {
+    new_test_ext().execute_with(|| {
+        register_shard_pools(2);
+        assert_eq!(submit_sharded(1, 2), Ok(0));
+
+        prove_shard(1, [1u8; 32], true);
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Computing);
+
+        prove_shard(2, [2u8; 32], true);
+        let result_hash = vec![[1u8; 32], [2u8; 32]].using_encoded(sp_io::hashing::blake2_256);
+        let task = ComputePoolScheduler::tasks(0).unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.proof_hash, Some(result_hash));
+        assert_eq!(task.verification_result, Some(true));
+        System::assert_has_event(
+            crate::Event::ShardedTaskCompleted { task_id: 0, result_hash }.into(),
+        );
+    });
+}
+
+#[test]
+fn failed_shards_are_reassigned_to_another_pool() {
+    new_test_ext().execute_with(|| {
+        register_shard_pools(3);
+        assert_eq!(submit_sharded(2, 1), Ok(0));
+        let failed_pool = ComputePoolScheduler::tasks(1).unwrap().pool_id;
+        let sibling_pool = ComputePoolScheduler::tasks(2).unwrap().pool_id;
+
+        prove_shard(1, [1u8; 32], false);
+        assert_eq!(ComputePoolScheduler::tasks(1).unwrap().status, TaskStatus::Failed);
+        // Without a deadline a failed shard leaves the task waiting for a reassignment
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Computing);
+
+        assert_ok!(ComputePoolScheduler::reassign_shard(RuntimeOrigin::signed(5), 1));
+        System::assert_has_event(crate::Event::ShardReassigned { shard_id: 1, task_id: 0 }.into());
+        let shard = ComputePoolScheduler::tasks(1).unwrap();
+        assert_eq!(shard.status, TaskStatus::Computing);
+        assert_eq!(shard.reward_amount, Some(65));
+        assert_eq!(shard.proof_hash, None);
+        assert!(shard.pool_id != failed_pool && shard.pool_id != sibling_pool);
+
+        prove_shard(1, [3u8; 32], true);
+        prove_shard(2, [4u8; 32], true);
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Completed);
+    });
+}
+
+#[test]
+fn sharded_task_fails_once_a_shard_cannot_be_reassigned() {
+    new_test_ext().execute_with(|| {
+        register_shard_pools(2);
+        assert_eq!(submit_sharded(2, 1), Ok(0));
+
+        // No pool has the VRAM the task asks for anymore
+        for pool_id in 0..2 {
+            let owner = ComputePoolScheduler::pools(pool_id).unwrap().owner;
+            assert_ok!(ComputePoolScheduler::update_pool_config(
+                RuntimeOrigin::signed(owner),
+                pool_id,
+                b"RTX-4090".to_vec().try_into().unwrap(),
+                4,
+                8,
+                true,
+                130,
+                *b"US",
+                100,
+            ));
+        }
+        prove_shard(1, [1u8; 32], false);
+
+        let task = ComputePoolScheduler::tasks(0).unwrap();
+        assert_eq!(task.status, TaskStatus::Failed);
+        assert_eq!(task.verification_result, Some(false));
+        assert_noop!(
+            ComputePoolScheduler::reassign_shard(RuntimeOrigin::signed(5), 1),
+            Error::<Test>::InvalidTaskState
+        );
+
+        // The task stays failed even if its last shard verifies
+        prove_shard(2, [2u8; 32], true);
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Failed);
+    });
+}
+
+#[test]
+fn shards_of_a_finished_sharded_task_cannot_be_disputed() {
+    new_test_ext().execute_with(|| {
+        register_shard_pools(2);
+        // While its sharded task runs, a verified shard can still be disputed
+        assert_eq!(submit_sharded(1, 2), Ok(0));
+        prove_shard(1, [1u8; 32], true);
+        assert_ok!(ComputePoolScheduler::dispute_verification(RuntimeOrigin::signed(5), 1));
+        assert_eq!(ComputePoolScheduler::tasks(1).unwrap().status, TaskStatus::Failed);
+        assert_eq!(ComputePoolScheduler::tasks(0).unwrap().status, TaskStatus::Computing);
+
+        assert_eq!(submit_sharded(1, 2), Ok(3));
+        prove_shard(4, [1u8; 32], true);
+        prove_shard(5, [2u8; 32], true);
+        let completed = ComputePoolScheduler::tasks(3).unwrap();
+        assert_eq!(completed.status, TaskStatus::Completed);
+        assert_noop!(
+            ComputePoolScheduler::dispute_verification(RuntimeOrigin::signed(5), 4),
+            Error::<Test>::ShardedTaskFinished
+        );
+        assert_eq!(ComputePoolScheduler::tasks(3).unwrap(), completed);
+    });
+}
+
+#[test]
+fn invalid_shard_requests_are_rejected() {
+    new_test_ext().execute_with(|| {
+        register_shard_pools(2);
+        assert_noop!(submit_sharded(1, 1), Error::<Test>::InvalidShardGrid);
+        assert_noop!(submit_sharded(5, 1), Error::<Test>::InvalidShardGrid);
+        assert_noop!(submit_sharded(3, 2), Error::<Test>::InvalidShardGrid);
+
+        assert_eq!(submit_sharded(2, 1), Ok(0));
+        assert_noop!(
+            ComputePoolScheduler::submit_proof(RuntimeOrigin::signed(1), 0, [1u8; 32]),
+            Error::<Test>::ShardedTask
+        );
+        assert_noop!(
+            ComputePoolScheduler::dispute_verification(RuntimeOrigin::signed(5), 0),
+            Error::<Test>::ShardedTask
+        );
+        assert_noop!(
+            ComputePoolScheduler::reassign_shard(RuntimeOrigin::signed(5), 0),
+            Error::<Test>::NotAShard
+        );
+        assert_noop!(
+            ComputePoolScheduler::reassign_shard(RuntimeOrigin::signed(5), 1),
+            Error::<Test>::InvalidTaskState
+        );
+        assert_noop!(
+            ComputePoolScheduler::reassign_shard(RuntimeOrigin::signed(4), 1),
+            Error::<Test>::NotTaskUser
+        );
+    });
+}
+
+fn order_context(order_id: u64) -> dbc_support::traits::TaskOrderContext<AccountId, Balance> {
+    dbc_support::traits::TaskOrderContext {
+        order_id,
+        customer: 2,
+        model_id: b"llama3-70b".to_vec(),
+        input_tokens: 1_000,
+        output_tokens: 500,
+        agreed_price: 340,
+    }
+}
+
+#[test]
+fn schedule_compute_uses_preferred_miner_pool() {
+    use dbc_support::traits::TaskComputeScheduler;
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        assert_ok!(ComputePoolScheduler::register_pool(
+            RuntimeOrigin::signed(4), gpu_model(), 48, 8, true, 130, *b"US", 50,
+        ));
+
+        let (task_id, miner, price) = ComputePoolScheduler::schedule_compute(
+            &order_context(7),
+            Some(&4),
+            (1_000, 500, 1),
+            &TaskRequirements::default(),
+        )
+        .unwrap();
+        assert_eq!(miner, 4);
+        assert_eq!(price, 340);
+
+        let task = ComputePoolScheduler::tasks(task_id).unwrap();
+        assert_eq!(task.pool_id, 1);
+        assert_eq!(task.user, 2);
+        assert_eq!(task.status, TaskStatus::Computing);
+        assert_eq!(task.reward_amount, None);
+        assert_eq!(ComputePoolScheduler::task_order_context(task_id), Some(order_context(7)));
+
+        // A miner without a pool cannot take the order
+        assert!(ComputePoolScheduler::schedule_compute(
+            &order_context(8),
+            Some(&5),
+            (1, 1, 1),
+            &TaskRequirements::default(),
+        )
+        .is_err());
+    });
+}
+
+#[test]
+fn completed_order_task_passes_context_to_handler() {
+    use dbc_support::traits::TaskComputeScheduler;
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        let (task_id, _, _) = ComputePoolScheduler::schedule_compute(
+            &order_context(7),
+            Some(&1),
+            (1_000, 500, 1),
+            &TaskRequirements::default(),
+        )
+        .unwrap();
+        assert_ok!(ComputePoolScheduler::submit_proof(
+            RuntimeOrigin::signed(1), task_id, [42u8; 32],
+        ));
+        assert_ok!(ComputePoolScheduler::verify_proof(
+            RuntimeOrigin::signed(3), task_id, true,
+        ));
+
+        assert!(ComputePoolScheduler::is_task_completed(task_id));
+        assert_eq!(ComputePoolScheduler::rewards(task_id), None);
+        assert_eq!(ComputePoolScheduler::task_order_context(task_id), None);
+        COMPLETED_ORDERS.with(|c| assert_eq!(*c.borrow(), vec![(task_id, Some(7))]));
+    });
+}
+
+#[test]
+fn order_tasks_wait_in_the_queue_of_a_busy_pool() {
+    use dbc_support::traits::TaskComputeScheduler;
+    new_test_ext().execute_with(|| {
+        setup_default_pool();
+        fill_pool();
+        let reserved = Balances::reserved_balance(2);
+
+        // The order learns its miner even though the task waits for a free slot
+        let (task_id, miner, _) = ComputePoolScheduler::schedule_compute(
+            &order_context(7),
+            None,
+            (1_000, 500, 1),
+            &TaskRequirements::default(),
+        )
+        .unwrap();
+        assert_eq!((task_id, miner), (16, 1));
+        assert_eq!(ComputePoolScheduler::tasks(16).unwrap().status, TaskStatus::Pending);
+        assert_eq!(
+            ComputePoolScheduler::pool_queue(0).into_inner(),
+            vec![(TaskPriority::Normal, 16)]
+        );
+
+        // A finished task frees the slot for the order task, which escrows nothing
+        assert_ok!(ComputePoolScheduler::submit_proof(RuntimeOrigin::signed(1), 0, [1u8; 32]));
+        assert_ok!(ComputePoolScheduler::verify_proof(RuntimeOrigin::signed(3), 0, false));
+        let task = ComputePoolScheduler::tasks(16).unwrap();
+        assert_eq!(task.status, TaskStatus::Computing);
+        assert_eq!(task.pool_id, 0);
+        assert_eq!(task.reward_amount, None);
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::ComputePoolScheduler(crate::Event::TaskAssigned {
+                task_id: 16,
+                pool_id: 0,
+                ..
+            })
+        )));
+        assert!(Balances::reserved_balance(2) < reserved);
+
+        // A failed order task drops its context
+        assert_ok!(ComputePoolScheduler::submit_proof(RuntimeOrigin::signed(1), 16, [2u8; 32]));
+        assert_ok!(ComputePoolScheduler::verify_proof(RuntimeOrigin::signed(3), 16, false));
+        assert_eq!(ComputePoolScheduler::tasks(16).unwrap().status, TaskStatus::Failed);
+        assert_eq!(ComputePoolScheduler::task_order_context(16), None);
+        COMPLETED_ORDERS.with(|c| assert!(c.borrow().is_empty()));
     });
 }
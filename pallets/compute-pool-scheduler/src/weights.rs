@@ -38,15 +38,23 @@ use core::marker::PhantomData;
 pub trait WeightInfo {
 	fn register_pool() -> Weight;
 	fn update_pool_config() -> Weight;
-	fn deregister_pool() -> Weight;
+	fn deregister_pool(n: u32, ) -> Weight;
 	fn submit_task() -> Weight;
 	fn submit_proof() -> Weight;
 	fn verify_proof() -> Weight;
 	fn claim_reward() -> Weight;
 	fn dispute_verification() -> Weight;
-	fn on_initialize() -> Weight;
+	fn on_initialize(n: u32, ) -> Weight;
 	fn stake_to_pool() -> Weight;
 	fn unstake_from_pool() -> Weight;
+	fn withdraw_unbonded() -> Weight;
+	fn claim_delegator_rewards() -> Weight;
+	fn set_pool_commission() -> Weight;
+	fn cancel_queued_task() -> Weight;
+	fn submit_sharded_task(s: u32, ) -> Weight;
+	fn reassign_shard() -> Weight;
+	fn time_out_task() -> Weight;
+	fn fill_pool_slots() -> Weight;
 }
 
 /// Weights for `pallet_compute_pool_scheduler` using the Substrate node and recommended hardware.
@@ -64,67 +72,135 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler MinerReputation (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:404 w:4)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:4)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
 	fn register_pool() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `107`
-		//  Estimated: `3593`
-		// Minimum execution time: 42_336_000 picoseconds.
-		Weight::from_parts(50_671_000, 3593)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(6_u64))
+		//  Measured:  `61519`
+		//  Estimated: `109335`
+		// Minimum execution time: 240_336_000 picoseconds.
+		Weight::from_parts(265_089_000, 109335)
+			.saturating_add(T::DbWeight::get().reads(469_u64))
+			.saturating_add(T::DbWeight::get().writes(63_u64))
 	}
 	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:404 w:4)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:4)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
 	fn update_pool_config() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `317`
-		//  Estimated: `3782`
-		// Minimum execution time: 19_559_000 picoseconds.
-		Weight::from_parts(20_125_000, 3782)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `61729`
+		//  Estimated: `109524`
+		// Minimum execution time: 217_559_000 picoseconds.
+		Weight::from_parts(234_543_000, 109524)
+			.saturating_add(T::DbWeight::get().reads(468_u64))
+			.saturating_add(T::DbWeight::get().writes(59_u64))
 	}
 	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
+	/// Storage: System Account (r:65 w:65)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:64 w:64)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler PoolByOwner (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolByOwner (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler PoolTasks (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
-	fn deregister_pool() -> Weight {
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 64]`.
+	fn deregister_pool(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `434`
-		//  Estimated: `3899`
-		// Minimum execution time: 49_146_000 picoseconds.
-		Weight::from_parts(53_928_000, 3899)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+		//  Measured:  `1299 + n * (1554 ±0)`
+		//  Estimated: `45613 + n * (3109 ±0)`
+		// Minimum execution time: 53_654_000 picoseconds.
+		Weight::from_parts(66_314_000, 45613)
+			// Standard Error: 8_004
+			.saturating_add(Weight::from_parts(17_906_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3109).saturating_mul(n.into()))
 	}
 	/// Storage: ComputePoolScheduler NextTaskId (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler NextTaskId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:0)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:258 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2306 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler Pools (r:2 w:1)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
-	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:2 w:1)
 	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
-	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
-	/// Storage: ComputePoolScheduler Tasks (r:0 w:1)
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:2)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
 	fn submit_task() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `507`
-		//  Estimated: `6447`
-		// Minimum execution time: 96_510_000 picoseconds.
-		Weight::from_parts(98_884_000, 6447)
-			.saturating_add(T::DbWeight::get().reads(6_u64))
-			.saturating_add(T::DbWeight::get().writes(7_u64))
+		//  Measured:  `412396`
+		//  Estimated: `1038254`
+		// Minimum execution time: 12_481_905_000 picoseconds.
+		Weight::from_parts(12_874_316_000, 1038254)
+			.saturating_add(T::DbWeight::get().reads(2576_u64))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
 	}
 	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
@@ -146,27 +222,61 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
 	/// Storage: AgentAttestation Attestations (r:0 w:1)
 	/// Proof: AgentAttestation Attestations (max_values: None, max_size: Some(436), added: 2911, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
 	fn submit_proof() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `725`
 		//  Estimated: `4190`
 		// submit_proof now only sets ProofSubmitted status (lighter than before)
 		Weight::from_parts(30_000_000, 4190)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
-	/// verify_proof: independent verification of submitted proof
-	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
-	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
-	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
-	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Storage: ComputePoolScheduler Tasks (r:405 w:5)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:3 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:4 w:2)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler MinerReputation (r:1 w:1)
-	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler ProofSubmittedAt (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:5 w:5)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:2)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:2)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ShardParent (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskOrderContexts (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskOrderContexts (max_values: None, max_size: None, mode: Measured)
 	fn verify_proof() -> Weight {
-		// Placeholder — will be regenerated by benchmarks
-		Weight::from_parts(119_131_000, 4190)
-			.saturating_add(T::DbWeight::get().reads(8_u64))
-			.saturating_add(T::DbWeight::get().writes(10_u64))
+		// Proof Size summary in bytes:
+		//  Measured:  `36112`
+		//  Estimated: `108337`
+		// Minimum execution time: 303_460_000 picoseconds.
+		Weight::from_parts(312_846_000, 108337)
+			.saturating_add(T::DbWeight::get().reads(450_u64))
+			.saturating_add(T::DbWeight::get().writes(45_u64))
 	}
 	/// Storage: ComputePoolScheduler Tasks (r:1 w:0)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
@@ -176,16 +286,20 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:2 w:2)
+	/// Storage: System Account (r:3 w:3)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
 	fn claim_reward() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `994`
 		//  Estimated: `6196`
 		// Minimum execution time: 86_151_000 picoseconds.
 		Weight::from_parts(88_712_000, 6196)
-			.saturating_add(T::DbWeight::get().reads(6_u64))
-			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
 	}
 	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
@@ -195,60 +309,316 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:2 w:2)
+	/// Storage: System Account (r:3 w:3)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: ComputePoolScheduler Rewards (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
 	fn dispute_verification() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `986`
-		//  Estimated: `6196`
+		//  Estimated: `47910`
 		// Minimum execution time: 99_616_000 picoseconds.
-		Weight::from_parts(101_852_000, 6196)
-			.saturating_add(T::DbWeight::get().reads(6_u64))
-			.saturating_add(T::DbWeight::get().writes(7_u64))
+		Weight::from_parts(112_852_000, 47910)
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(16_u64))
 	}
-	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:0)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1001 w:0)
 	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
-	fn on_initialize() -> Weight {
+	/// Storage: ComputePoolScheduler Tasks (r:1000 w:0)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:1000 w:0)
+	/// Proof Skipped: ComputePoolScheduler ProofSubmittedAt (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[0, 1000]`.
+	fn on_initialize(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `164`
-		//  Estimated: `3629`
+		//  Measured:  `164 + n * (612 ±0)`
+		//  Estimated: `3629 + n * (6219 ±0)`
 		// Minimum execution time: 6_044_000 picoseconds.
 		Weight::from_parts(6_558_000, 3629)
+			// Standard Error: 4_118
+			.saturating_add(Weight::from_parts(14_932_000, 0).saturating_mul(n.into()))
 			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 6219).saturating_mul(n.into()))
 	}
 	/// Storage: ComputePoolScheduler Pools (r:1 w:0)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn stake_to_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 69_341_000 picoseconds.
+		Weight::from_parts(71_486_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
 	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
-	fn stake_to_pool() -> Weight {
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn unstake_from_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 62_959_000 picoseconds.
+		Weight::from_parts(64_907_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_unbonded() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `390`
-		//  Estimated: `3855`
-		// Minimum execution time: 44_610_000 picoseconds.
-		Weight::from_parts(45_222_000, 3855)
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 56_467_000 picoseconds.
+		Weight::from_parts(58_214_000, 6196)
 			.saturating_add(T::DbWeight::get().reads(4_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
 	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_delegator_rewards() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 52_991_000 picoseconds.
+		Weight::from_parts(54_630_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: ComputePoolScheduler Pools (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	fn set_pool_commission() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1204`
+		//  Estimated: `3614`
+		// Minimum execution time: 20_833_000 picoseconds.
+		Weight::from_parts(21_478_000, 3614)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	fn cancel_queued_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1260`
+		//  Estimated: `3782`
+		// Minimum execution time: 46_430_000 picoseconds.
+		Weight::from_parts(47_866_000, 3782)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: ComputePoolScheduler NextTaskId (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler NextTaskId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:16)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler Pools (r:33 w:16)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:32 w:16)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:16 w:16)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:16 w:16)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:168 w:32)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:168 w:32)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:16 w:33)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ShardParent (r:0 w:16)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:16)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `s` is `[2, 16]`.
+	fn submit_sharded_task(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `15940 + s * (3906 ±0)`
+		//  Estimated: `47820 + s * (7812 ±0)`
+		// Minimum execution time: 166_067_000 picoseconds.
+		Weight::from_parts(171_204_000, 47820)
+			// Standard Error: 43_056
+			.saturating_add(Weight::from_parts(96_318_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((14_u64).saturating_mul(s.into())))
+			// Shards skip the pools earlier shards took, up to `s` index entries each
+			.saturating_add(Weight::from_parts(2_904_000, 2603).saturating_mul(s.saturating_mul(s).into()))
+			.saturating_add(T::DbWeight::get().reads((3 * s as u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((18_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 7812).saturating_mul(s.into()))
+	}
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:17 w:1)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolIndex (r:19 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:19 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	fn reassign_shard() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `34854`
+		//  Estimated: `104562`
+		// Minimum execution time: 236_599_000 picoseconds.
+		Weight::from_parts(243_917_000, 104562)
+			.saturating_add(T::DbWeight::get().reads(65_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: ComputePoolScheduler Tasks (r:18 w:2)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler MinerReputation (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler ProofSubmittedAt (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskOrderContexts (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskOrderContexts (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Rewards (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:3 w:3)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
-	fn unstake_from_pool() -> Weight {
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
+	fn time_out_task() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `493`
-		//  Estimated: `3958`
-		// Minimum execution time: 45_550_000 picoseconds.
-		Weight::from_parts(46_126_000, 3958)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+		//  Measured:  `21734`
+		//  Estimated: `72914`
+		// Minimum execution time: 168_903_000 picoseconds.
+		Weight::from_parts(176_245_000, 72914)
+			.saturating_add(T::DbWeight::get().reads(38_u64))
+			.saturating_add(T::DbWeight::get().writes(21_u64))
+	}
+	/// Storage: ComputePoolScheduler Pools (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:4 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:404 w:4)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:4 w:4)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ShardParent (r:3 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	fn fill_pool_slots() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `33918`
+		//  Estimated: `101294`
+		// Minimum execution time: 254_118_000 picoseconds.
+		Weight::from_parts(261_407_000, 101294)
+			.saturating_add(T::DbWeight::get().reads(431_u64))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
 	}
 }
 
@@ -266,67 +636,135 @@ impl WeightInfo for () {
 	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler MinerReputation (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:404 w:4)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:4)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
 	fn register_pool() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `107`
-		//  Estimated: `3593`
-		// Minimum execution time: 42_336_000 picoseconds.
-		Weight::from_parts(50_671_000, 3593)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(6_u64))
+		//  Measured:  `61519`
+		//  Estimated: `109335`
+		// Minimum execution time: 240_336_000 picoseconds.
+		Weight::from_parts(265_089_000, 109335)
+			.saturating_add(RocksDbWeight::get().reads(469_u64))
+			.saturating_add(RocksDbWeight::get().writes(63_u64))
 	}
 	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:404 w:4)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:4)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
 	fn update_pool_config() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `317`
-		//  Estimated: `3782`
-		// Minimum execution time: 19_559_000 picoseconds.
-		Weight::from_parts(20_125_000, 3782)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `61729`
+		//  Estimated: `109524`
+		// Minimum execution time: 217_559_000 picoseconds.
+		Weight::from_parts(234_543_000, 109524)
+			.saturating_add(RocksDbWeight::get().reads(468_u64))
+			.saturating_add(RocksDbWeight::get().writes(59_u64))
 	}
 	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
+	/// Storage: System Account (r:65 w:65)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:64 w:64)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler PoolByOwner (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolByOwner (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler PoolTasks (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
-	fn deregister_pool() -> Weight {
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 64]`.
+	fn deregister_pool(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `434`
-		//  Estimated: `3899`
-		// Minimum execution time: 49_146_000 picoseconds.
-		Weight::from_parts(53_928_000, 3899)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+		//  Measured:  `1299 + n * (1554 ±0)`
+		//  Estimated: `45613 + n * (3109 ±0)`
+		// Minimum execution time: 53_654_000 picoseconds.
+		Weight::from_parts(66_314_000, 45613)
+			// Standard Error: 8_004
+			.saturating_add(Weight::from_parts(17_906_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3109).saturating_mul(n.into()))
 	}
 	/// Storage: ComputePoolScheduler NextTaskId (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler NextTaskId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:0)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:258 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2306 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler Pools (r:2 w:1)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
-	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:2 w:1)
 	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
-	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
-	/// Storage: ComputePoolScheduler Tasks (r:0 w:1)
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:2)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
 	fn submit_task() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `507`
-		//  Estimated: `6447`
-		// Minimum execution time: 96_510_000 picoseconds.
-		Weight::from_parts(98_884_000, 6447)
-			.saturating_add(RocksDbWeight::get().reads(6_u64))
-			.saturating_add(RocksDbWeight::get().writes(7_u64))
+		//  Measured:  `412396`
+		//  Estimated: `1038254`
+		// Minimum execution time: 12_481_905_000 picoseconds.
+		Weight::from_parts(12_874_316_000, 1038254)
+			.saturating_add(RocksDbWeight::get().reads(2576_u64))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
 	}
 	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
@@ -346,18 +784,58 @@ impl WeightInfo for () {
 	/// Proof: AgentAttestation AttesterTaskCount (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
 	/// Storage: ComputePoolScheduler Rewards (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
 	fn submit_proof() -> Weight {
 		// submit_proof now only sets ProofSubmitted status (lighter than before)
 		Weight::from_parts(30_000_000, 4190)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
-	/// verify_proof: independent verification of submitted proof
+	/// Storage: ComputePoolScheduler Tasks (r:405 w:5)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:3 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:4 w:2)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler MinerReputation (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler ProofSubmittedAt (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:5 w:5)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:2)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:2)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ShardParent (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskOrderContexts (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskOrderContexts (max_values: None, max_size: None, mode: Measured)
 	fn verify_proof() -> Weight {
-		// Placeholder — will be regenerated by benchmarks
-		Weight::from_parts(119_131_000, 4190)
-			.saturating_add(RocksDbWeight::get().reads(8_u64))
-			.saturating_add(RocksDbWeight::get().writes(10_u64))
+		// Proof Size summary in bytes:
+		//  Measured:  `36112`
+		//  Estimated: `108337`
+		// Minimum execution time: 303_460_000 picoseconds.
+		Weight::from_parts(312_846_000, 108337)
+			.saturating_add(RocksDbWeight::get().reads(450_u64))
+			.saturating_add(RocksDbWeight::get().writes(45_u64))
 	}
 	/// Storage: ComputePoolScheduler Tasks (r:1 w:0)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
@@ -367,16 +845,20 @@ impl WeightInfo for () {
 	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:2 w:2)
+	/// Storage: System Account (r:3 w:3)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
 	fn claim_reward() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `994`
 		//  Estimated: `6196`
 		// Minimum execution time: 86_151_000 picoseconds.
 		Weight::from_parts(88_712_000, 6196)
-			.saturating_add(RocksDbWeight::get().reads(6_u64))
-			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
 	}
 	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
@@ -386,59 +868,315 @@ impl WeightInfo for () {
 	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:2 w:2)
+	/// Storage: System Account (r:3 w:3)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: ComputePoolScheduler Rewards (r:0 w:1)
 	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
 	fn dispute_verification() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `986`
-		//  Estimated: `6196`
+		//  Estimated: `47910`
 		// Minimum execution time: 99_616_000 picoseconds.
-		Weight::from_parts(101_852_000, 6196)
-			.saturating_add(RocksDbWeight::get().reads(6_u64))
-			.saturating_add(RocksDbWeight::get().writes(7_u64))
+		Weight::from_parts(112_852_000, 47910)
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(16_u64))
 	}
-	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:0)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1001 w:0)
 	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
-	fn on_initialize() -> Weight {
+	/// Storage: ComputePoolScheduler Tasks (r:1000 w:0)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:1000 w:0)
+	/// Proof Skipped: ComputePoolScheduler ProofSubmittedAt (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[0, 1000]`.
+	fn on_initialize(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `164`
-		//  Estimated: `3629`
+		//  Measured:  `164 + n * (612 ±0)`
+		//  Estimated: `3629 + n * (6219 ±0)`
 		// Minimum execution time: 6_044_000 picoseconds.
 		Weight::from_parts(6_558_000, 3629)
+			// Standard Error: 4_118
+			.saturating_add(Weight::from_parts(14_932_000, 0).saturating_mul(n.into()))
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 6219).saturating_mul(n.into()))
 	}
 	/// Storage: ComputePoolScheduler Pools (r:1 w:0)
 	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn stake_to_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 69_341_000 picoseconds.
+		Weight::from_parts(71_486_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
 	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
-	fn stake_to_pool() -> Weight {
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn unstake_from_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 62_959_000 picoseconds.
+		Weight::from_parts(64_907_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_unbonded() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `390`
-		//  Estimated: `3855`
-		// Minimum execution time: 44_610_000 picoseconds.
-		Weight::from_parts(45_222_000, 3855)
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 56_467_000 picoseconds.
+		Weight::from_parts(58_214_000, 6196)
 			.saturating_add(RocksDbWeight::get().reads(4_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
 	/// Storage: ComputePoolScheduler PoolStakes (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler PoolStakes (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_delegator_rewards() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2065`
+		//  Estimated: `6196`
+		// Minimum execution time: 52_991_000 picoseconds.
+		Weight::from_parts(54_630_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: ComputePoolScheduler Pools (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
+	fn set_pool_commission() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1204`
+		//  Estimated: `3614`
+		// Minimum execution time: 20_833_000 picoseconds.
+		Weight::from_parts(21_478_000, 3614)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	fn cancel_queued_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1260`
+		//  Estimated: `3782`
+		// Minimum execution time: 46_430_000 picoseconds.
+		Weight::from_parts(47_866_000, 3782)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: ComputePoolScheduler NextTaskId (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler NextTaskId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:16)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler Pools (r:33 w:16)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:32 w:16)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:16 w:16)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:16 w:16)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:168 w:32)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:168 w:32)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:16 w:33)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ShardParent (r:0 w:16)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:16)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `s` is `[2, 16]`.
+	fn submit_sharded_task(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `15940 + s * (3906 ±0)`
+		//  Estimated: `47820 + s * (7812 ±0)`
+		// Minimum execution time: 166_067_000 picoseconds.
+		Weight::from_parts(171_204_000, 47820)
+			// Standard Error: 43_056
+			.saturating_add(Weight::from_parts(96_318_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((14_u64).saturating_mul(s.into())))
+			// Shards skip the pools earlier shards took, up to `s` index entries each
+			.saturating_add(Weight::from_parts(2_904_000, 2603).saturating_mul(s.saturating_mul(s).into()))
+			.saturating_add(RocksDbWeight::get().reads((3 * s as u64).saturating_mul(s.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((18_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 7812).saturating_mul(s.into()))
+	}
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:17 w:1)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolIndex (r:19 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:19 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	fn reassign_shard() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `34854`
+		//  Estimated: `104562`
+		// Minimum execution time: 236_599_000 picoseconds.
+		Weight::from_parts(243_917_000, 104562)
+			.saturating_add(RocksDbWeight::get().reads(65_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	/// Storage: ComputePoolScheduler Tasks (r:18 w:2)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler MinerReputation (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler MinerReputation (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ProofSubmittedAt (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler ProofSubmittedAt (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskOrderContexts (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskOrderContexts (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Rewards (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler Rewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:3 w:3)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler StakePools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler StakePools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: ComputePoolScheduler TotalPoolStake (r:1 w:1)
 	/// Proof Skipped: ComputePoolScheduler TotalPoolStake (max_values: None, max_size: None, mode: Measured)
-	fn unstake_from_pool() -> Weight {
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ShardParent (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskShards (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler TaskShards (max_values: None, max_size: None, mode: Measured)
+	fn time_out_task() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `493`
-		//  Estimated: `3958`
-		// Minimum execution time: 45_550_000 picoseconds.
-		Weight::from_parts(46_126_000, 3958)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+		//  Measured:  `21734`
+		//  Estimated: `72914`
+		// Minimum execution time: 168_903_000 picoseconds.
+		Weight::from_parts(176_245_000, 72914)
+			.saturating_add(RocksDbWeight::get().reads(38_u64))
+			.saturating_add(RocksDbWeight::get().writes(21_u64))
+	}
+	/// Storage: ComputePoolScheduler Pools (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:4 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolQueue (r:4 w:4)
+	/// Proof Skipped: ComputePoolScheduler PoolQueue (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler GlobalQueue (r:4 w:0)
+	/// Proof Skipped: ComputePoolScheduler GlobalQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:404 w:4)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:4 w:4)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:1)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler ShardParent (r:3 w:0)
+	/// Proof Skipped: ComputePoolScheduler ShardParent (max_values: None, max_size: None, mode: Measured)
+	fn fill_pool_slots() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `33918`
+		//  Estimated: `101294`
+		// Minimum execution time: 254_118_000 picoseconds.
+		Weight::from_parts(261_407_000, 101294)
+			.saturating_add(RocksDbWeight::get().reads(431_u64))
+			.saturating_add(RocksDbWeight::get().writes(17_u64))
 	}
 }
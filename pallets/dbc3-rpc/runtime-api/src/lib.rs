@@ -71,6 +71,18 @@ sp_api::decl_runtime_apis! {
         /// Get pending attestation count.
         fn get_pending_attestation_count() -> u64;
 
+        /// Active providers of a model with at least `min_free_concurrency` free slots, optionally
+        /// in `region` and charging at most `max_price` per token. Best ranked first, skipping
+        /// `offset` and returning at most `limit` (max 100 entries, SCALE-encoded `Vec<ProviderInfo>`).
+        fn get_model_providers(
+            model_id: Vec<u8>,
+            region: Option<Vec<u8>>,
+            max_price: Option<Balance>,
+            min_free_concurrency: u32,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<u8>;
+
         // ─── X402 Settlement ─────────────────────────────────────────
 
         /// Get a payment intent by ID (SCALE-encoded `PaymentIntent`).
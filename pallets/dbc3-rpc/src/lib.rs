@@ -69,6 +69,19 @@ pub trait Dbc3RpcApi<BlockHash, AccountId> {
     #[method(name = "dbc3_getPendingAttestationCount")]
     fn get_pending_attestation_count(&self, at: Option<BlockHash>) -> RpcResult<u64>;
 
+    /// `max_price` is a decimal string, as balances do not fit JSON numbers
+    #[method(name = "dbc3_getModelProviders")]
+    fn get_model_providers(
+        &self,
+        model_id: String,
+        region: Option<String>,
+        max_price: Option<String>,
+        min_free_concurrency: u32,
+        offset: u32,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<u8>>;
+
     // === X402 Settlement ===
     #[method(name = "dbc3_getPaymentIntent")]
     fn get_payment_intent(&self, intent_id: u64, at: Option<BlockHash>) -> RpcResult<Option<Vec<u8>>>;
@@ -107,6 +120,14 @@ impl<C, M> Dbc3Storage<C, M> {
     }
 }
 
+fn invalid_params(message: String) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        ErrorCode::InvalidParams.code(),
+        message,
+        None::<()>,
+    )))
+}
+
 fn map_err(e: impl std::fmt::Debug) -> JsonRpseeError {
     JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
         ErrorCode::InternalError.code(),
@@ -222,6 +243,33 @@ where
         api.get_pending_attestation_count(at_hash).map_err(map_err)
     }
 
+    fn get_model_providers(
+        &self,
+        model_id: String,
+        region: Option<String>,
+        max_price: Option<String>,
+        min_free_concurrency: u32,
+        offset: u32,
+        limit: u32,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Vec<u8>> {
+        let max_price = max_price
+            .map(|price| price.parse::<u128>().map_err(|e| invalid_params(format!("max_price: {e}"))))
+            .transpose()?;
+        let api = self.client.runtime_api();
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+        api.get_model_providers(
+            at_hash,
+            model_id.into_bytes(),
+            region.map(String::into_bytes),
+            max_price,
+            min_free_concurrency,
+            offset,
+            limit,
+        )
+        .map_err(map_err)
+    }
+
     fn get_payment_intent(&self, intent_id: u64, at: Option<Block::Hash>) -> RpcResult<Option<Vec<u8>>> {
         let api = self.client.runtime_api();
         let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
@@ -1,7 +1,8 @@
 use crate::{MachineId, RentOrderId};
 use frame_support::{
     dispatch::{Decode, Encode, TypeInfo},
-    RuntimeDebug,
+    traits::ConstU32,
+    BoundedVec, RuntimeDebug,
 };
 use sp_core::H160;
 use sp_std::vec::Vec;
@@ -256,38 +257,92 @@ pub trait GNOps {
 // DBC 3.0 Cross-Pallet Integration Traits
 // ============================================================
 
+/// Billing context of the task-mode order a compute task was created for.
+/// Carried from task-mode through the scheduler and attestation so that
+/// settlement moves the customer's funds to the miner that did the work.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct TaskOrderContext<AccountId, Balance> {
+    /// Originating `TaskOrder` id in pallet-task-mode
+    pub order_id: u64,
+    pub customer: AccountId,
+    pub model_id: Vec<u8>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Amount the customer agreed to pay the miner for this order
+    pub agreed_price: Balance,
+}
+
+/// ISO 3166-1 alpha-2 country code, e.g. `*b"DE"`
+pub type RegionCode = [u8; 2];
+
+/// GPU models a task can list in its requirements
+pub type MaxTaskGpuModels = ConstU32<16>;
+
+/// GPU model name as listed in task requirements
+pub type GpuModelName = BoundedVec<u8, ConstU32<128>>;
+
+/// Resources a compute task needs from the pool that runs it.
+/// Empty or `None` fields accept any pool.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
+pub struct TaskRequirements<Balance, BlockNumber> {
+    /// Minimum GPU memory, in the unit pools register their memory in
+    pub min_vram: u32,
+    /// GPU models the task can run on, matched exactly against the pool's model
+    pub gpu_models: BoundedVec<GpuModelName, MaxTaskGpuModels>,
+    /// Minimum number of GPUs in the pool
+    pub gpu_count: u32,
+    pub nvlink_required: bool,
+    pub region: Option<RegionCode>,
+    /// Highest price per task the customer accepts
+    pub max_price: Option<Balance>,
+    /// Last block at which the task may still be computing
+    pub deadline: Option<BlockNumber>,
+}
+
 /// Trait for task-mode to request compute scheduling from the pool scheduler.
 /// Implemented by pallet-compute-pool-scheduler.
 pub trait TaskComputeScheduler {
     type AccountId;
     type Balance;
+    type BlockNumber;
 
-    /// Schedule a compute task on the best available pool.
+    /// Schedule a compute task for an order, on the pool of `preferred_miner`
+    /// if given, otherwise on the best available pool meeting `requirements`.
+    /// A busy pool queues the task until it has a free slot.
     /// Returns (scheduler_task_id, assigned_miner, estimated_cost).
     fn schedule_compute(
-        user: &Self::AccountId,
-        model_id: &[u8],
+        order: &TaskOrderContext<Self::AccountId, Self::Balance>,
+        preferred_miner: Option<&Self::AccountId>,
         dimensions: (u32, u32, u32),
+        requirements: &TaskRequirements<Self::Balance, Self::BlockNumber>,
     ) -> Result<(u64, Self::AccountId, Self::Balance), &'static str>;
 
     /// Check if a scheduled task is completed.
     fn is_task_completed(scheduler_task_id: u64) -> bool;
+
+    /// Make `miner` able to accept scheduled orders in benchmarks.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn prepare_benchmark_miner(_miner: &Self::AccountId) {}
+
+    /// Force a scheduled task into its completed state in benchmarks.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn complete_benchmark_task(_scheduler_task_id: u64) {}
 }
 
 /// Trait for scheduler to notify the attestation system when a task completes.
 /// Implemented by pallet-agent-attestation.
 pub trait TaskCompletionHandler {
     type AccountId;
+    type Balance;
 
     /// Called when a compute task is completed with a valid proof.
-    /// Creates an attestation entry automatically.
+    /// Creates an attestation entry automatically, linked to `order` when the
+    /// task was scheduled for a task-mode order.
     fn on_task_completed(
         attester: &Self::AccountId,
         task_id: u64,
         result_hash: sp_core::H256,
-        model_id: &[u8],
-        input_tokens: u64,
-        output_tokens: u64,
+        order: Option<&TaskOrderContext<Self::AccountId, Self::Balance>>,
     ) -> Result<u64, &'static str>;
 }
 
@@ -298,13 +353,54 @@ pub trait AttestationSettler {
     type Balance;
 
     /// Trigger settlement for a confirmed attestation.
-    /// Moves funds from merchant to miner.
+    /// Moves `agreed_price` of the order customer's reserved funds to the miner.
     fn settle_for_attestation(
-        merchant: &Self::AccountId,
+        order: &TaskOrderContext<Self::AccountId, Self::Balance>,
         miner: &Self::AccountId,
-        amount: Self::Balance,
         attestation_id: u64,
     ) -> Result<u64, &'static str>;
+
+    /// Refund an order whose attestation was slashed.
+    /// Releases everything reserved from the customer for the order and returns the amount.
+    fn refund_for_attestation(
+        order: &TaskOrderContext<Self::AccountId, Self::Balance>,
+        attestation_id: u64,
+    ) -> Result<Self::Balance, &'static str>;
+}
+
+/// Trait for settlement to close the originating task-mode order.
+/// Implemented by pallet-task-mode.
+pub trait TaskOrderSettlementHandler {
+    type AccountId;
+    type Balance;
+
+    /// Called before the miner payout of an order is moved, so the order owner can reject
+    /// a stale or already settled order. Settles the rest of the order (burn share, era
+    /// accounting).
+    fn on_order_settled(
+        order_id: u64,
+        miner: &Self::AccountId,
+        settled_amount: Self::Balance,
+    ) -> Result<(), &'static str>;
+
+    /// Called when the work of an order was rejected, e.g. its attestation was slashed.
+    /// Returns everything reserved for the order to the customer and closes the order.
+    fn on_order_refunded(order_id: u64) -> Result<Self::Balance, &'static str>;
+
+    /// Whether a payment channel may bill metered usage against task definition `task_id`.
+    fn is_billable_task(task_id: u64) -> bool;
+
+    /// Called when a payment channel closes with `amount` owed to the miner.
+    /// Books the usage as a settled order of `task_id` and moves the burn share out of the
+    /// customer's reserve. Returns the order id and the miner payout left for the caller to move.
+    fn on_channel_settled(
+        task_id: u64,
+        customer: &Self::AccountId,
+        miner: &Self::AccountId,
+        input_tokens: u64,
+        output_tokens: u64,
+        amount: Self::Balance,
+    ) -> Result<(u64, Self::Balance), &'static str>;
 }
 
 /// Trait for task-mode to notify settlement about billing.
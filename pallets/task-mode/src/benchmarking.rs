@@ -9,6 +9,12 @@ use frame_system::RawOrigin;
 use codec::Encode;
 use sp_std::vec::Vec;
 
+/// Register a compute pool for `miner` so its orders can be scheduled.
+fn setup_miner<T: Config>(miner: &T::AccountId) {
+    use dbc_support::traits::TaskComputeScheduler;
+    T::ComputeScheduler::prepare_benchmark_miner(miner);
+}
+
 fn setup_task_definition<T: Config>(admin: T::AccountId) -> u64 {
     let task_id = NextTaskId::<T>::get();
     TaskMode::<T>::create_task_definition(
@@ -62,12 +68,15 @@ frame_benchmarking::v1::benchmarks! {
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         seed_dbc_price::<T>();
         let task_id = setup_task_definition::<T>(customer.clone());
+        setup_miner::<T>(&miner);
         let _ = T::Currency::deposit_creating(&customer, 1_000_000_000_000_000_000u128);
-    }: _(RawOrigin::Signed(customer), task_id, miner.clone(), 1_000u64, 1_000u64)
+        // Leaving the miner unset takes the heavier scheduled path
+    }: _(RawOrigin::Signed(customer), task_id, None, 1_000u64, 1_000u64)
     verify {
         assert_eq!(NextOrderId::<T>::get(), 1);
         let order = TaskOrders::<T>::get(0).unwrap();
         assert_eq!(order.miner, miner);
+        assert!(OrderComputeTasks::<T>::contains_key(0));
     }
 
     mark_order_completed {
@@ -76,10 +85,11 @@ frame_benchmarking::v1::benchmarks! {
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         seed_dbc_price::<T>();
         let task_id = setup_task_definition::<T>(customer.clone());
+        setup_miner::<T>(&miner);
         let _ = T::Currency::deposit_creating(&customer, 1_000_000_000_000_000_000u128);
         let _ = T::Currency::deposit_creating(&miner, 1_000_000_000_000_000_000u128);
         TaskMode::<T>::create_task_order(
-            RawOrigin::Signed(customer).into(), task_id, miner.clone(), 1_000, 1_000
+            RawOrigin::Signed(customer).into(), task_id, Some(miner.clone()), 1_000, 1_000
         ).expect("setup: create_task_order failed");
     }: _(RawOrigin::Signed(miner), 0u64, [7u8; 32])
     verify {
@@ -93,13 +103,14 @@ frame_benchmarking::v1::benchmarks! {
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         seed_dbc_price::<T>();
         let task_id = setup_task_definition::<T>(customer.clone());
+        setup_miner::<T>(&miner);
         let _ = T::Currency::deposit_creating(&customer, 1_000_000_000_000_000_000u128);
         let _ = T::Currency::deposit_creating(&miner, 1_000_000_000_000_000_000u128);
         // Fund treasury account so repatriate_reserved can succeed
         let treasury = T::TreasuryAccount::get();
         let _ = T::Currency::deposit_creating(&treasury, 1_000_000_000_000u128);
         TaskMode::<T>::create_task_order(
-            RawOrigin::Signed(customer.clone()).into(), task_id, miner.clone(), 1_000, 1_000
+            RawOrigin::Signed(customer.clone()).into(), task_id, Some(miner.clone()), 1_000, 1_000
         ).expect("setup: create_task_order failed");
         TaskMode::<T>::mark_order_completed(
             RawOrigin::Signed(miner).into(), 0, [7u8; 32]
@@ -116,9 +127,10 @@ frame_benchmarking::v1::benchmarks! {
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         seed_dbc_price::<T>();
         let task_id = setup_task_definition::<T>(customer.clone());
+        setup_miner::<T>(&miner);
         let _ = T::Currency::deposit_creating(&customer, 1_000_000_000_000_000_000u128);
         TaskMode::<T>::create_task_order(
-            RawOrigin::Signed(customer.clone()).into(), task_id, miner.clone(), 1_000, 1_000
+            RawOrigin::Signed(customer.clone()).into(), task_id, Some(miner.clone()), 1_000, 1_000
         ).expect("setup: create_task_order failed");
         // Advance block past OrderTimeout
         let timeout: frame_system::pallet_prelude::BlockNumberFor<T> = T::OrderTimeout::get();
@@ -128,4 +140,23 @@ frame_benchmarking::v1::benchmarks! {
         let order = TaskOrders::<T>::get(0).unwrap();
         assert!(matches!(order.status, TaskOrderStatus::Settled));
     }
+
+    sync_order_status {
+        let customer: T::AccountId = whitelisted_caller();
+        let miner: T::AccountId = frame_benchmarking::v1::account("miner", 0, 0);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        seed_dbc_price::<T>();
+        let task_id = setup_task_definition::<T>(customer.clone());
+        setup_miner::<T>(&miner);
+        let _ = T::Currency::deposit_creating(&customer, 1_000_000_000_000_000_000u128);
+        TaskMode::<T>::create_task_order(
+            RawOrigin::Signed(customer.clone()).into(), task_id, None, 1_000, 1_000
+        ).expect("setup: create_task_order failed");
+        let scheduler_task_id = OrderComputeTasks::<T>::get(0).unwrap();
+        <T::ComputeScheduler as dbc_support::traits::TaskComputeScheduler>::complete_benchmark_task(scheduler_task_id);
+    }: _(RawOrigin::Signed(customer), 0u64)
+    verify {
+        let order = TaskOrders::<T>::get(0).unwrap();
+        assert!(matches!(order.status, TaskOrderStatus::Completed));
+    }
 }
@@ -14,7 +14,7 @@ pub mod weights;
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::traits::StorageVersion;
-    use dbc_support::traits::DbcPrice;
+    use dbc_support::traits::{DbcPrice, TaskComputeScheduler, TaskOrderContext, TaskRequirements};
     use frame_support::{
         dispatch::DispatchResult,
         pallet_prelude::*,
@@ -114,7 +114,8 @@ pub mod pallet {
         /// Compute scheduler for task execution
         type ComputeScheduler: dbc_support::traits::TaskComputeScheduler<
             AccountId = Self::AccountId,
-            Balance = BalanceOf<Self>
+            Balance = BalanceOf<Self>,
+            BlockNumber = BlockNumberFor<Self>
         >;
     }
 
@@ -159,6 +160,11 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Scheduler task id that executes an order
+    #[pallet::storage]
+    #[pallet::getter(fn order_compute_task)]
+    pub type OrderComputeTasks<T: Config> = StorageMap<_, Blake2_128Concat, u64, u64>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -170,6 +176,15 @@ pub mod pallet {
             miner: T::AccountId,
             total_dbc: BalanceOf<T>,
         },
+        TaskOrderScheduled {
+            order_id: u64,
+            scheduler_task_id: u64,
+            miner: T::AccountId,
+        },
+        TaskOrderComputeCompleted {
+            order_id: u64,
+            scheduler_task_id: u64,
+        },
         TaskOrderCompleted {
             order_id: u64,
             attestation_hash: [u8; 32],
@@ -184,6 +199,11 @@ pub mod pallet {
             customer: T::AccountId,
             refunded: BalanceOf<T>,
         },
+        TaskOrderRefunded {
+            order_id: u64,
+            customer: T::AccountId,
+            refunded: BalanceOf<T>,
+        },
     }
 
     #[pallet::error]
@@ -198,6 +218,9 @@ pub mod pallet {
         ArithmeticOverflow,
         NotAuthorized,
         OrderNotExpired,
+        ComputeSchedulingFailed,
+        ComputeTaskNotFound,
+        ComputeTaskNotCompleted,
     }
 
 
@@ -303,12 +326,14 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Create an order for `task_id`. When `miner` is `None` the compute scheduler
+        /// picks a pool and the order is assigned to that pool's owner.
         #[pallet::call_index(2)]
         #[pallet::weight(T::WeightInfo::create_task_order())]
         pub fn create_task_order(
             origin: OriginFor<T>,
             task_id: u64,
-            miner: T::AccountId,
+            miner: Option<T::AccountId>,
             input_tokens: u64,
             output_tokens: u64,
         ) -> DispatchResult {
@@ -346,6 +371,30 @@ pub mod pallet {
             let next_order_id = order_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
             NextOrderId::<T>::put(next_order_id);
 
+            // Orders naming their miner are executed off-chain by that miner as before
+            let (miner, scheduler_task_id) = match miner {
+                Some(miner) => (miner, None),
+                None => {
+                    let context = TaskOrderContext {
+                        order_id,
+                        customer: customer.clone(),
+                        model_id: task.model_id.to_vec(),
+                        input_tokens,
+                        output_tokens,
+                        agreed_price: miner_payout,
+                    };
+                    let (scheduler_task_id, miner, _) = T::ComputeScheduler::schedule_compute(
+                        &context,
+                        None,
+                        Self::order_dimensions(input_tokens, output_tokens),
+                        &TaskRequirements::default(),
+                    )
+                    .map_err(|_| Error::<T>::ComputeSchedulingFailed)?;
+                    OrderComputeTasks::<T>::insert(order_id, scheduler_task_id);
+                    (miner, Some(scheduler_task_id))
+                },
+            };
+
             TaskOrders::<T>::insert(
                 order_id,
                 TaskOrder {
@@ -375,9 +424,12 @@ pub mod pallet {
             Self::deposit_event(Event::TaskOrderCreated {
                 order_id,
                 customer,
-                miner,
+                miner: miner.clone(),
                 total_dbc: total_dbc_charged,
             });
+            if let Some(scheduler_task_id) = scheduler_task_id {
+                Self::deposit_event(Event::TaskOrderScheduled { order_id, scheduler_task_id, miner });
+            }
             Ok(())
         }
 
@@ -452,25 +504,7 @@ pub mod pallet {
 
             order.status = TaskOrderStatus::Settled;
             TaskOrders::<T>::insert(order_id, &order);
-
-            let era = Self::block_to_era(order.created_at);
-            EraStats::<T>::mutate(era, |stats| {
-                stats.total_charged = stats.total_charged.saturating_add(order.total_dbc_charged);
-                stats.total_burned = stats.total_burned.saturating_add(order.dbc_burned);
-                stats.total_miner_payout = stats.total_miner_payout.saturating_add(order.miner_payout);
-                stats.completed_orders = stats.completed_orders.saturating_add(1);
-            });
-
-            MinerTaskStats::<T>::mutate(era, &order.miner, |(total_payout, count)| {
-                *total_payout = total_payout.saturating_add(order.miner_payout);
-                *count = count.saturating_add(1);
-            });
-
-            Self::deposit_event(Event::TaskOrderSettled {
-                order_id,
-                burned: order.dbc_burned,
-                miner_payout: order.miner_payout,
-            });
+            Self::record_settlement(&order);
 
             Ok(())
         }
@@ -513,9 +547,67 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Move an in-progress order to `Completed` once its scheduler task has completed.
+        /// Anyone can call this; the scheduler is the source of truth for completion.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::sync_order_status())]
+        pub fn sync_order_status(origin: OriginFor<T>, order_id: u64) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let scheduler_task_id =
+                OrderComputeTasks::<T>::get(order_id).ok_or(Error::<T>::ComputeTaskNotFound)?;
+            TaskOrders::<T>::try_mutate(order_id, |maybe_order| -> DispatchResult {
+                let order = maybe_order.as_mut().ok_or(Error::<T>::TaskOrderNotFound)?;
+                ensure!(
+                    matches!(order.status, TaskOrderStatus::InProgress),
+                    Error::<T>::InvalidOrderStatus
+                );
+                ensure!(
+                    T::ComputeScheduler::is_task_completed(scheduler_task_id),
+                    Error::<T>::ComputeTaskNotCompleted
+                );
+                order.status = TaskOrderStatus::Completed;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::TaskOrderComputeCompleted { order_id, scheduler_task_id });
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
+        /// Token-priced inference has no matrix shape; the token counts stand in
+        /// for `m` and `n` so the scheduler can still size the task.
+        pub(crate) fn order_dimensions(input_tokens: u64, output_tokens: u64) -> (u32, u32, u32) {
+            let clamp = |tokens: u64| tokens.clamp(1, u32::MAX as u64) as u32;
+            (clamp(input_tokens), clamp(output_tokens), 1)
+        }
+
+        /// Update era and miner statistics for an order that was just settled.
+        pub(crate) fn record_settlement(
+            order: &TaskOrder<T::AccountId, BlockNumberFor<T>, BalanceOf<T>>,
+        ) {
+            let era = Self::block_to_era(order.created_at);
+            EraStats::<T>::mutate(era, |stats| {
+                stats.total_charged = stats.total_charged.saturating_add(order.total_dbc_charged);
+                stats.total_burned = stats.total_burned.saturating_add(order.dbc_burned);
+                stats.total_miner_payout = stats.total_miner_payout.saturating_add(order.miner_payout);
+                stats.completed_orders = stats.completed_orders.saturating_add(1);
+            });
+
+            MinerTaskStats::<T>::mutate(era, &order.miner, |(total_payout, count)| {
+                *total_payout = total_payout.saturating_add(order.miner_payout);
+                *count = count.saturating_add(1);
+            });
+
+            Self::deposit_event(Event::TaskOrderSettled {
+                order_id: order.order_id,
+                burned: order.dbc_burned,
+                miner_payout: order.miner_payout,
+            });
+        }
+
         fn calculate_order_usd_value(
             input_tokens: u64,
             output_tokens: u64,
@@ -541,7 +633,7 @@ pub mod pallet {
             u64::try_from(total).map_err(|_| Error::<T>::ArithmeticOverflow)
         }
 
-        fn calculate_revenue_split(total: BalanceOf<T>) -> Result<(BalanceOf<T>, BalanceOf<T>), Error<T>> {
+        pub(crate) fn calculate_revenue_split(total: BalanceOf<T>) -> Result<(BalanceOf<T>, BalanceOf<T>), Error<T>> {
             let burned = T::BurnPercentage::get() * total;
             let miner_percent_cut = T::MinerPayoutPercentage::get() * total;
 
@@ -645,3 +737,134 @@ impl<T: Config> dbc_support::traits::TaskBillingProvider for Pallet<T> {
         (burn_amount, miner_amount)
     }
 }
+
+// ============================================================
+// Cross-Pallet Integration: TaskOrderSettlementHandler Implementation
+// ============================================================
+
+impl<T: Config> dbc_support::traits::TaskOrderSettlementHandler for Pallet<T> {
+    type AccountId = T::AccountId;
+    type Balance = BalanceOf<T>;
+
+    fn on_order_settled(
+        order_id: u64,
+        miner: &Self::AccountId,
+        settled_amount: Self::Balance,
+    ) -> Result<(), &'static str> {
+        use frame_support::traits::{BalanceStatus, ReservableCurrency};
+        use sp_runtime::traits::Zero;
+
+        let mut order = pallet::TaskOrders::<T>::get(order_id).ok_or("Task order not found")?;
+        if !matches!(order.status, TaskOrderStatus::InProgress | TaskOrderStatus::Completed) {
+            return Err("Task order not open")
+        }
+        if &order.miner != miner {
+            return Err("Miner does not match task order")
+        }
+        if settled_amount != order.miner_payout {
+            return Err("Settled amount does not match miner payout")
+        }
+
+        // The settler moves the miner payout once this returns; only the burn share is
+        // settled here.
+        let remainder = T::Currency::repatriate_reserved(
+            &order.customer,
+            &T::TreasuryAccount::get(),
+            order.dbc_burned,
+            BalanceStatus::Free,
+        )
+        .map_err(|_| "Failed to transfer burn share")?;
+        if !remainder.is_zero() {
+            return Err("Insufficient reserved balance for burn share")
+        }
+
+        order.status = TaskOrderStatus::Settled;
+        pallet::TaskOrders::<T>::insert(order_id, &order);
+        Pallet::<T>::record_settlement(&order);
+
+        Ok(())
+    }
+
+    fn on_order_refunded(order_id: u64) -> Result<Self::Balance, &'static str> {
+        use frame_support::traits::ReservableCurrency;
+
+        let mut order = pallet::TaskOrders::<T>::get(order_id).ok_or("Task order not found")?;
+        if !matches!(order.status, TaskOrderStatus::InProgress | TaskOrderStatus::Completed) {
+            return Err("Task order not open")
+        }
+
+        T::Currency::unreserve(&order.customer, order.total_dbc_charged);
+
+        let customer = order.customer.clone();
+        let refunded = order.total_dbc_charged;
+        order.status = TaskOrderStatus::Settled;
+        pallet::TaskOrders::<T>::insert(order_id, &order);
+
+        Pallet::<T>::deposit_event(pallet::Event::TaskOrderRefunded { order_id, customer, refunded });
+        Ok(refunded)
+    }
+
+    fn is_billable_task(task_id: u64) -> bool {
+        pallet::TaskDefinitions::<T>::get(task_id).map_or(false, |task| task.is_active)
+    }
+
+    fn on_channel_settled(
+        task_id: u64,
+        customer: &Self::AccountId,
+        miner: &Self::AccountId,
+        input_tokens: u64,
+        output_tokens: u64,
+        amount: Self::Balance,
+    ) -> Result<(u64, Self::Balance), &'static str> {
+        use frame_support::traits::{BalanceStatus, ReservableCurrency};
+        use sp_runtime::traits::Zero;
+
+        if !pallet::TaskDefinitions::<T>::contains_key(task_id) {
+            return Err("Task definition not found")
+        }
+        let (dbc_burned, miner_payout) =
+            Pallet::<T>::calculate_revenue_split(amount).map_err(|_| "Revenue split overflow")?;
+
+        // The whole channel deposit is reserved; only the burn share is moved here.
+        let remainder = T::Currency::repatriate_reserved(
+            customer,
+            &T::TreasuryAccount::get(),
+            dbc_burned,
+            BalanceStatus::Free,
+        )
+        .map_err(|_| "Failed to transfer burn share")?;
+        if !remainder.is_zero() {
+            return Err("Insufficient reserved balance for burn share")
+        }
+
+        let order_id = pallet::NextOrderId::<T>::get();
+        let next_order_id = order_id.checked_add(1).ok_or("Order ID overflow")?;
+        pallet::NextOrderId::<T>::put(next_order_id);
+
+        let order = TaskOrder {
+            order_id,
+            task_id,
+            customer: customer.clone(),
+            miner: miner.clone(),
+            input_tokens,
+            output_tokens,
+            dbc_price_snapshot: T::DbcPriceProvider::get_dbc_price().unwrap_or_default(),
+            total_dbc_charged: amount,
+            dbc_burned,
+            miner_payout,
+            created_at: <frame_system::Pallet<T>>::block_number(),
+            status: TaskOrderStatus::Settled,
+            attestation_hash: None,
+        };
+        pallet::TaskOrders::<T>::insert(order_id, &order);
+        Pallet::<T>::deposit_event(pallet::Event::TaskOrderCreated {
+            order_id,
+            customer: customer.clone(),
+            miner: miner.clone(),
+            total_dbc: amount,
+        });
+        Pallet::<T>::record_settlement(&order);
+
+        Ok((order_id, miner_payout))
+    }
+}
@@ -140,24 +140,34 @@ impl crate::Config for Test {
     type ComputeScheduler = MockComputeScheduler;
 }
 
+thread_local! {
+    pub static LAST_SCHEDULED_ORDER: RefCell<Option<dbc_support::traits::TaskOrderContext<u64, u128>>> =
+        RefCell::new(None);
+    pub static COMPUTE_TASK_COMPLETED: RefCell<bool> = RefCell::new(false);
+}
+
 // Mock implementation for TaskComputeScheduler
 pub struct MockComputeScheduler;
 
 impl dbc_support::traits::TaskComputeScheduler for MockComputeScheduler {
     type AccountId = u64;
     type Balance = u128;
+    type BlockNumber = u64;
 
     fn schedule_compute(
-        _user: &Self::AccountId,
-        _model_id: &[u8],
+        order: &dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>,
+        preferred_miner: Option<&Self::AccountId>,
         _dimensions: (u32, u32, u32),
+        _requirements: &dbc_support::traits::TaskRequirements<Self::Balance, Self::BlockNumber>,
     ) -> Result<(u64, Self::AccountId, Self::Balance), &'static str> {
-        // Return mock values for testing
-        Ok((1, 1, 1000))
+        // Echo the order back so tests can check what was handed to the scheduler
+        LAST_SCHEDULED_ORDER.with(|o| *o.borrow_mut() = Some(order.clone()));
+        let miner = preferred_miner.copied().unwrap_or(2);
+        Ok((order.order_id + 100, miner, order.agreed_price))
     }
 
     fn is_task_completed(_scheduler_task_id: u64) -> bool {
-        true
+        COMPUTE_TASK_COMPLETED.with(|c| *c.borrow())
     }
 }
 
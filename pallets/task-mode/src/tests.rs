@@ -1,7 +1,11 @@
 use crate::{
-    mock::{new_test_ext, DBCPriceOCW, RuntimeOrigin, System, TaskMode, Test},
-    EraStats, MinerTaskStats, TaskOrderStatus,
+    mock::{
+        new_test_ext, DBCPriceOCW, RuntimeOrigin, System, TaskMode, Test, COMPUTE_TASK_COMPLETED,
+        LAST_SCHEDULED_ORDER,
+    },
+    EraStats, MinerTaskStats, OrderComputeTasks, TaskOrderStatus,
 };
+use dbc_support::traits::TaskOrderSettlementHandler;
 use frame_support::{assert_noop, assert_ok};
 
 fn create_default_task() {
@@ -20,7 +24,7 @@ fn create_default_order() {
     assert_ok!(TaskMode::create_task_order(
         RuntimeOrigin::signed(1),
         0,
-        2,
+        Some(2),
         1_000,
         500,
     ));
@@ -107,7 +111,7 @@ fn create_task_order_fails_for_inactive_task() {
         ));
 
         assert_noop!(
-            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, 2, 100, 100),
+            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(2), 100, 100),
             crate::Error::<Test>::TaskDefinitionInactive
         );
     });
@@ -119,7 +123,7 @@ fn token_limit_enforced() {
         create_default_task();
 
         assert_noop!(
-            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, 2, 9_000, 2_000),
+            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(2), 9_000, 2_000),
             crate::Error::<Test>::TokenCountExceedsLimit
         );
     });
@@ -132,7 +136,7 @@ fn price_oracle_failure_rejected() {
         DBCPriceOCW::set_price(None);
 
         assert_noop!(
-            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, 2, 100, 100),
+            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(2), 100, 100),
             crate::Error::<Test>::PriceOracleUnavailable
         );
 
@@ -140,7 +144,7 @@ fn price_oracle_failure_rejected() {
         DBCPriceOCW::set_multiplier(None);
 
         assert_noop!(
-            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, 2, 100, 100),
+            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(2), 100, 100),
             crate::Error::<Test>::PriceOracleUnavailable
         );
     });
@@ -153,7 +157,7 @@ fn create_task_order_insufficient_balance_rejected() {
         DBCPriceOCW::set_multiplier(Some(10_000_000_000));
 
         assert_noop!(
-            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, 2, 100_000, 100_000),
+            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(2), 100_000, 100_000),
             crate::Error::<Test>::TokenCountExceedsLimit
         );
 
@@ -167,7 +171,7 @@ fn create_task_order_insufficient_balance_rejected() {
         ));
 
         assert_noop!(
-            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, 2, 100_000, 100_000),
+            TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(2), 100_000, 100_000),
             crate::Error::<Test>::InsufficientBalance
         );
     });
@@ -275,7 +279,7 @@ fn reward_split_70_30_and_miner_reward_share_works() {
         assert_ok!(TaskMode::create_task_order(
             RuntimeOrigin::signed(1),
             0,
-            2,
+            Some(2),
             1_000,
             500,
         ));
@@ -295,7 +299,7 @@ fn reward_split_70_30_and_miner_reward_share_works() {
         assert_ok!(TaskMode::create_task_order(
             RuntimeOrigin::signed(1),
             0,
-            3,
+            Some(3),
             500,
             500,
         ));
@@ -346,3 +350,189 @@ fn settle_allows_attestation_override() {
         assert_eq!(order.attestation_hash, Some([4u8; 32]));
     });
 }
+
+#[test]
+fn create_task_order_hands_order_context_to_scheduler() {
+    new_test_ext().execute_with(|| {
+        create_default_task();
+        assert_ok!(TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, None, 1_000, 500));
+
+        let ctx = LAST_SCHEDULED_ORDER.with(|o| o.borrow().clone()).expect("order scheduled");
+        assert_eq!(ctx.order_id, 0);
+        assert_eq!(ctx.customer, 1);
+        assert_eq!(ctx.model_id, b"llama3-70b".to_vec());
+        assert_eq!(ctx.input_tokens, 1_000);
+        assert_eq!(ctx.output_tokens, 500);
+        assert_eq!(ctx.agreed_price, 34_000_000);
+
+        assert_eq!(OrderComputeTasks::<Test>::get(0), Some(100));
+    });
+}
+
+#[test]
+fn create_task_order_with_miner_bypasses_scheduler() {
+    new_test_ext().execute_with(|| {
+        create_default_task();
+        assert_ok!(TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, Some(3), 1_000, 500));
+
+        assert!(LAST_SCHEDULED_ORDER.with(|o| o.borrow().is_none()));
+        assert_eq!(OrderComputeTasks::<Test>::get(0), None);
+        let order = TaskMode::task_order_of(0).expect("order exists");
+        assert_eq!(order.miner, 3);
+        assert!(matches!(order.status, TaskOrderStatus::InProgress));
+        assert_noop!(
+            TaskMode::sync_order_status(RuntimeOrigin::signed(3), 0),
+            crate::Error::<Test>::ComputeTaskNotFound
+        );
+    });
+}
+
+#[test]
+fn on_order_settled_burns_remainder_and_settles_order() {
+    new_test_ext().execute_with(|| {
+        create_default_task();
+        create_default_order();
+
+        // Settler moves the miner payout out of the customer's reserve first
+        assert_ok!(<Test as crate::Config>::Currency::repatriate_reserved(
+            &1,
+            &2,
+            34_000_000,
+            frame_support::traits::BalanceStatus::Free,
+        ));
+
+        let treasury_before = <Test as crate::Config>::Currency::free_balance(99);
+        assert_ok!(<TaskMode as TaskOrderSettlementHandler>::on_order_settled(0, &2, 34_000_000));
+
+        let order = TaskMode::task_order_of(0).expect("order exists");
+        assert!(matches!(order.status, TaskOrderStatus::Settled));
+        assert_eq!(<Test as crate::Config>::Currency::reserved_balance(1), 0);
+        assert_eq!(
+            <Test as crate::Config>::Currency::free_balance(99),
+            treasury_before + 6_000_000
+        );
+        assert_eq!(MinerTaskStats::<Test>::get(0, 2), (34_000_000, 1));
+
+        // Already settled orders cannot be settled twice
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_order_settled(0, &2, 34_000_000).is_err());
+    });
+}
+
+#[test]
+fn on_order_settled_rejects_wrong_miner_or_amount() {
+    new_test_ext().execute_with(|| {
+        create_default_task();
+        create_default_order();
+
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_order_settled(0, &3, 34_000_000).is_err());
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_order_settled(0, &2, 1).is_err());
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_order_settled(7, &2, 34_000_000).is_err());
+    });
+}
+
+#[test]
+fn on_order_refunded_releases_the_whole_charge() {
+    new_test_ext().execute_with(|| {
+        create_default_task();
+        create_default_order();
+        assert_eq!(<Test as crate::Config>::Currency::reserved_balance(1), 40_000_000);
+
+        assert_eq!(<TaskMode as TaskOrderSettlementHandler>::on_order_refunded(0), Ok(40_000_000));
+        assert_eq!(<Test as crate::Config>::Currency::reserved_balance(1), 0);
+        assert!(matches!(TaskMode::task_order_of(0).unwrap().status, TaskOrderStatus::Settled));
+        System::assert_last_event(
+            crate::Event::TaskOrderRefunded { order_id: 0, customer: 1, refunded: 40_000_000 }.into(),
+        );
+
+        // Closed orders cannot be refunded or paid out again
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_order_refunded(0).is_err());
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_order_settled(0, &2, 34_000_000).is_err());
+    });
+}
+
+#[test]
+fn on_channel_settled_books_a_settled_order() {
+    new_test_ext().execute_with(|| {
+        assert!(!<TaskMode as TaskOrderSettlementHandler>::is_billable_task(0));
+        create_default_task();
+        assert!(<TaskMode as TaskOrderSettlementHandler>::is_billable_task(0));
+
+        // The payment channel keeps its whole deposit reserved until it settles
+        assert_ok!(<Test as crate::Config>::Currency::reserve(&1, 20_000_000));
+        let treasury_before = <Test as crate::Config>::Currency::free_balance(99);
+        let (order_id, miner_payout) = <TaskMode as TaskOrderSettlementHandler>::on_channel_settled(
+            0, &1, &2, 1_200, 800, 10_000_000,
+        )
+        .expect("channel usage booked");
+
+        assert_eq!(order_id, 0);
+        assert_eq!(miner_payout, 8_500_000);
+        assert_eq!(
+            <Test as crate::Config>::Currency::free_balance(99),
+            treasury_before + 1_500_000
+        );
+        assert_eq!(<Test as crate::Config>::Currency::reserved_balance(1), 18_500_000);
+
+        let order = TaskMode::task_order_of(0).expect("order exists");
+        assert!(matches!(order.status, TaskOrderStatus::Settled));
+        assert_eq!((order.input_tokens, order.output_tokens), (1_200, 800));
+        assert_eq!(order.total_dbc_charged, 10_000_000);
+        assert_eq!(EraStats::<Test>::get(0).completed_orders, 1);
+        assert_eq!(MinerTaskStats::<Test>::get(0, 2), (8_500_000, 1));
+        assert_eq!(TaskMode::next_order_id(), 1);
+
+        assert!(<TaskMode as TaskOrderSettlementHandler>::on_channel_settled(7, &1, &2, 0, 0, 1).is_err());
+    });
+}
+
+#[test]
+fn create_task_order_without_miner_uses_scheduled_pool_owner() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        create_default_task();
+        assert_ok!(TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, None, 1_000, 500));
+
+        // The mock scheduler assigns unrouted orders to account 2
+        let order = TaskMode::task_order_of(0).expect("order exists");
+        assert_eq!(order.miner, 2);
+        assert!(matches!(order.status, TaskOrderStatus::InProgress));
+        System::assert_has_event(
+            crate::Event::TaskOrderScheduled { order_id: 0, scheduler_task_id: 100, miner: 2 }.into(),
+        );
+    });
+}
+
+#[test]
+fn sync_order_status_follows_scheduler_task() {
+    new_test_ext().execute_with(|| {
+        create_default_task();
+        assert_ok!(TaskMode::create_task_order(RuntimeOrigin::signed(1), 0, None, 1_000, 500));
+
+        assert_noop!(
+            TaskMode::sync_order_status(RuntimeOrigin::signed(3), 0),
+            crate::Error::<Test>::ComputeTaskNotCompleted
+        );
+
+        COMPUTE_TASK_COMPLETED.with(|c| *c.borrow_mut() = true);
+        assert_ok!(TaskMode::sync_order_status(RuntimeOrigin::signed(3), 0));
+        let order = TaskMode::task_order_of(0).expect("order exists");
+        assert!(matches!(order.status, TaskOrderStatus::Completed));
+
+        // Once completed the order settles as usual
+        assert_noop!(
+            TaskMode::sync_order_status(RuntimeOrigin::signed(3), 0),
+            crate::Error::<Test>::InvalidOrderStatus
+        );
+        assert_ok!(TaskMode::settle_task_order(RuntimeOrigin::signed(1), 0, None));
+    });
+}
+
+#[test]
+fn sync_order_status_requires_scheduled_order() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TaskMode::sync_order_status(RuntimeOrigin::signed(3), 5),
+            crate::Error::<Test>::ComputeTaskNotFound
+        );
+    });
+}
@@ -42,6 +42,7 @@ pub trait WeightInfo {
 	fn mark_order_completed() -> Weight;
 	fn settle_task_order() -> Weight;
 	fn cancel_expired_order() -> Weight;
+	fn sync_order_status() -> Weight;
 }
 
 /// Weights for `pallet_task_mode` using the Substrate node and recommended hardware.
@@ -77,16 +78,40 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: TaskMode NextOrderId (r:1 w:1)
 	/// Proof: TaskMode NextOrderId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler NextTaskId (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler NextTaskId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:0)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskOrderContexts (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskOrderContexts (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:2)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: TaskMode OrderComputeTasks (r:0 w:1)
+	/// Proof: TaskMode OrderComputeTasks (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
 	/// Storage: TaskMode TaskOrders (r:0 w:1)
 	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
 	fn create_task_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `329`
-		//  Estimated: `5104`
-		// Minimum execution time: 52_150_000 picoseconds.
-		Weight::from_parts(61_052_000, 5104)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(2_u64))
+		//  Measured:  `1347`
+		//  Estimated: `14020`
+		// Minimum execution time: 161_452_000 picoseconds.
+		Weight::from_parts(172_318_000, 14020)
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(16_u64))
 	}
 	/// Storage: TaskMode TaskOrders (r:1 w:1)
 	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
@@ -129,6 +154,21 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: TaskMode OrderComputeTasks (r:1 w:0)
+	/// Proof: TaskMode OrderComputeTasks (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:1 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	fn sync_order_status() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1229`
+		//  Estimated: `3687`
+		// Minimum execution time: 25_498_000 picoseconds.
+		Weight::from_parts(27_418_000, 3687)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests.
@@ -163,16 +203,40 @@ impl WeightInfo for () {
 	/// Proof Skipped: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: TaskMode NextOrderId (r:1 w:1)
 	/// Proof: TaskMode NextOrderId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler NextTaskId (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler NextTaskId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedProfiles (r:1 w:0)
+	/// Proof: ComputePoolScheduler IndexedProfiles (max_values: Some(1), max_size: Some(12035), added: 12530, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler PoolTierHeads (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolTierHeads (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolIndex (r:2 w:2)
+	/// Proof Skipped: ComputePoolScheduler PoolIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Pools (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler ActiveTaskCount (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler ActiveTaskCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler PoolTasks (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler PoolTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler IndexedPools (r:1 w:1)
+	/// Proof Skipped: ComputePoolScheduler IndexedPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskOrderContexts (r:2 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskOrderContexts (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler TaskEscrowStore (r:0 w:1)
+	/// Proof Skipped: ComputePoolScheduler TaskEscrowStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:2)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: TaskMode OrderComputeTasks (r:0 w:1)
+	/// Proof: TaskMode OrderComputeTasks (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
 	/// Storage: TaskMode TaskOrders (r:0 w:1)
 	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
 	fn create_task_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `329`
-		//  Estimated: `5104`
-		// Minimum execution time: 52_150_000 picoseconds.
-		Weight::from_parts(61_052_000, 5104)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(2_u64))
+		//  Measured:  `1347`
+		//  Estimated: `14020`
+		// Minimum execution time: 161_452_000 picoseconds.
+		Weight::from_parts(172_318_000, 14020)
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(16_u64))
 	}
 	/// Storage: TaskMode TaskOrders (r:1 w:1)
 	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
@@ -215,4 +279,19 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: TaskMode OrderComputeTasks (r:1 w:0)
+	/// Proof: TaskMode OrderComputeTasks (max_values: None, max_size: Some(32), added: 2507, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:1 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	/// Storage: ComputePoolScheduler Tasks (r:1 w:0)
+	/// Proof Skipped: ComputePoolScheduler Tasks (max_values: None, max_size: None, mode: Measured)
+	fn sync_order_status() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1229`
+		//  Estimated: `3687`
+		// Minimum execution time: 25_498_000 picoseconds.
+		Weight::from_parts(27_418_000, 3687)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
@@ -22,5 +22,11 @@ sp_api::decl_runtime_apis! {
 
         /// Get number of pending payment intents
         fn get_pending_intents_count() -> u64;
+
+        /// Query a registered facilitator, returns SCALE-encoded Facilitator or None
+        fn get_facilitator(account: AccountId) -> Option<Vec<u8>>;
+
+        /// Query the dashboard of a facilitator, returns SCALE-encoded FacilitatorStats
+        fn get_facilitator_stats(account: AccountId) -> Vec<u8>;
     }
 }
@@ -33,6 +33,14 @@ pub trait X402SettlementRpcApi<BlockHash, AccountId> {
     /// Get number of pending payment intents
     #[method(name = "x402_getPendingIntentsCount")]
     fn get_pending_intents_count(&self, at: Option<BlockHash>) -> RpcResult<u64>;
+
+    /// Get a registered facilitator (returns hex-encoded SCALE bytes)
+    #[method(name = "x402_getFacilitator")]
+    fn get_facilitator(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<Option<String>>;
+
+    /// Get the settled volume, fees and failures of a facilitator (returns hex-encoded SCALE bytes)
+    #[method(name = "x402_getFacilitatorStats")]
+    fn get_facilitator_stats(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<String>;
 }
 
 pub struct X402Settlement<C, M> {
@@ -126,4 +134,34 @@ where
                 CallError::Custom(ErrorObject::owned(1, "Runtime error", Some(e.to_string()))).into()
             })
     }
+
+    fn get_facilitator(
+        &self,
+        account: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Option<String>> {
+        let api = self.client.runtime_api();
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.get_facilitator(at_hash, account)
+            .map(|opt| opt.map(|bytes| format!("0x{}", hex::encode(bytes))))
+            .map_err(|e| {
+                CallError::Custom(ErrorObject::owned(1, "Runtime error", Some(e.to_string()))).into()
+            })
+    }
+
+    fn get_facilitator_stats(
+        &self,
+        account: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<String> {
+        let api = self.client.runtime_api();
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.get_facilitator_stats(at_hash, account)
+            .map(|bytes| format!("0x{}", hex::encode(bytes)))
+            .map_err(|e| {
+                CallError::Custom(ErrorObject::owned(1, "Runtime error", Some(e.to_string()))).into()
+            })
+    }
 }
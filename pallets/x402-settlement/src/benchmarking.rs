@@ -3,13 +3,94 @@
 use super::*;
 use frame_benchmarking::vec;
 use crate::Pallet as X402Settlement;
-use frame_benchmarking::v1::whitelisted_caller;
+use frame_benchmarking::v1::{account, whitelisted_caller, BenchmarkError};
 use frame_support::traits::Get;
-use frame_support::traits::{Currency, ReservableCurrency};
+use frame_support::traits::{Currency, EnsureOrigin, Hooks, ReservableCurrency};
 use frame_system::RawOrigin;
 use sp_core::H256;
 use frame_system::pallet_prelude::BlockNumberFor;
 use frame_support::BoundedVec;
+use sp_runtime::{KeyTypeId, Perbill};
+use pallet_evm::AddressMapping;
+
+/// Register an active facilitator that charges a fee, bonding the minimum stake.
+fn setup_facilitator<T: Config>() -> T::AccountId {
+    let facilitator: T::AccountId = account("facilitator", 0, 0);
+    let _ = T::Currency::deposit_creating(&facilitator, 10_000_000_000_000_000_000u128);
+    T::Currency::reserve(&facilitator, T::MinFacilitatorStake::get()).expect("reserve failed");
+    Facilitators::<T>::insert(&facilitator, Facilitator::<T> {
+        key: FacilitatorKey::Sr25519([1u8; 32]),
+        stake: T::MinFacilitatorStake::get(),
+        fee_rate: Perbill::from_percent(1),
+        active_from: 0u32.into(),
+        active_until: None,
+    });
+    facilitator
+}
+
+/// Hold `amount` for an intent of `merchant`: reserved DBC, or a USD-pegged benchmark asset
+/// when `convert_to_dbc` is given
+fn hold_funds<T: Config>(
+    merchant: &T::AccountId,
+    amount: BalanceOf<T>,
+    convert_to_dbc: Option<bool>,
+) -> PaymentAsset<T::AssetId> {
+    let _ = T::Currency::deposit_creating(merchant, 10_000_000_000_000_000_000u128);
+    match convert_to_dbc {
+        None => {
+            T::Currency::reserve(merchant, amount).expect("reserve failed");
+            PaymentAsset::Native
+        },
+        Some(convert_to_dbc) => {
+            // The merchant keeps more than the minimum balance of the asset on top of the hold
+            let id = T::BenchmarkHelper::fund_asset(merchant, amount.saturating_mul(2));
+            UsdPeggedAssets::<T>::insert(id, true);
+            X402Settlement::<T>::hold_asset(id, merchant, amount).expect("hold failed");
+            PaymentAsset::Asset { id, convert_to_dbc }
+        },
+    }
+}
+
+/// Fill the expiry queues of the blocks from `expires_at`, so a new intent is carried over as
+/// far as it can be
+fn fill_expiry_queues<T: Config>(expires_at: BlockNumberFor<T>) {
+    let full: BoundedVec<u64, T::MaxExpiriesPerBlock> =
+        vec![u64::MAX; T::MaxExpiriesPerBlock::get() as usize].try_into().unwrap();
+    let mut at = expires_at;
+    for _ in 0..crate::MAX_EXPIRY_CARRY_OVER {
+        ExpiryQueue::<T>::insert(at, full.clone());
+        at += 1u32.into();
+    }
+}
+
+const CHANNEL_DEPOSIT: u128 = 1_000_000_000_000_000_000;
+
+/// Insert channel 0 from a funded customer to a funded miner, with the deposit reserved
+fn setup_channel<T: Config>(
+    voucher: ChannelVoucher<BalanceOf<T>>,
+    status: ChannelStatus<BlockNumberFor<T>>,
+) -> (T::AccountId, T::AccountId) {
+    let customer: T::AccountId = account("customer", 0, 0);
+    let miner: T::AccountId = account("miner", 0, 0);
+    let _ = T::Currency::deposit_creating(&customer, 10_000_000_000_000_000_000u128);
+    let _ = T::Currency::deposit_creating(&miner, 10_000_000_000_000_000_000u128);
+    T::Currency::reserve(&customer, CHANNEL_DEPOSIT).expect("reserve failed");
+    PaymentChannels::<T>::insert(0, PaymentChannel::<T> {
+        customer: customer.clone(),
+        miner: miner.clone(),
+        task_id: T::BenchmarkHelper::billable_task(),
+        deposit: CHANNEL_DEPOSIT,
+        voucher_key: FacilitatorKey::Sr25519([9u8; 32]),
+        voucher,
+        status,
+        opened_at: 1u32.into(),
+    });
+    (customer, miner)
+}
+
+fn channel_voucher(amount: u128) -> ChannelVoucher<u128> {
+    ChannelVoucher { amount, input_tokens: 1_000, output_tokens: 2_000 }
+}
 
 /// Set up a payment intent in Pending status with proper fund reservation.
 /// Directly inserts storage to avoid calling submit_payment_intent.
@@ -17,9 +98,10 @@ fn setup_pending_intent<T: Config>(
     merchant: T::AccountId,
     miner: T::AccountId,
     amount: BalanceOf<T>,
+    convert_to_dbc: Option<bool>,
+    facilitator: T::AccountId,
 ) -> u64 {
-    let _ = T::Currency::deposit_creating(&merchant, 10_000_000_000_000_000_000u128);
-    T::Currency::reserve(&merchant, amount).expect("reserve failed");
+    let asset = hold_funds::<T>(&merchant, amount, convert_to_dbc);
 
     let intent_id = NextIntentId::<T>::get();
     let block: BlockNumberFor<T> = 1u32.into();
@@ -30,8 +112,10 @@ fn setup_pending_intent<T: Config>(
         merchant: merchant.clone(),
         miner: miner.clone(),
         amount,
+        asset,
         nonce: 1,
         replay_fingerprint: H256::from_low_u64_be(1),
+        facilitator: facilitator.clone(),
         facilitator_signature: sig,
         status: PaymentIntentStatus::Pending,
         created_at: block,
@@ -39,6 +123,7 @@ fn setup_pending_intent<T: Config>(
         settled_at: None,
         expires_at: block + T::PaymentIntentTTL::get(),
     });
+    OpenIntents::<T>::mutate(&facilitator, |count| *count += 1);
 
     NextIntentId::<T>::put(intent_id + 1);
     intent_id
@@ -49,9 +134,10 @@ fn setup_verified_intent<T: Config>(
     merchant: T::AccountId,
     miner: T::AccountId,
     amount: BalanceOf<T>,
+    convert_to_dbc: Option<bool>,
+    facilitator: T::AccountId,
 ) -> u64 {
-    let _ = T::Currency::deposit_creating(&merchant, 10_000_000_000_000_000_000u128);
-    T::Currency::reserve(&merchant, amount).expect("reserve failed");
+    let asset = hold_funds::<T>(&merchant, amount, convert_to_dbc);
 
     let intent_id = NextIntentId::<T>::get();
     let block: BlockNumberFor<T> = 1u32.into();
@@ -62,8 +148,10 @@ fn setup_verified_intent<T: Config>(
         merchant: merchant.clone(),
         miner: miner.clone(),
         amount,
+        asset,
         nonce: 1,
         replay_fingerprint: H256::from_low_u64_be(1),
+        facilitator: facilitator.clone(),
         facilitator_signature: sig,
         status: PaymentIntentStatus::Verified,
         created_at: block,
@@ -71,6 +159,7 @@ fn setup_verified_intent<T: Config>(
         settled_at: None,
         expires_at: block + T::PaymentIntentTTL::get(),
     });
+    OpenIntents::<T>::mutate(&facilitator, |count| *count += 1);
 
     NextIntentId::<T>::put(intent_id + 1);
     intent_id
@@ -86,9 +175,12 @@ frame_benchmarking::v1::benchmarks! {
         let amount: BalanceOf<T> = 1_000u128;
         let nonce: u64 = 1;
         let replay_fingerprint = H256::from_low_u64_be(1);
+        let facilitator = setup_facilitator::<T>();
         // Dummy signature — signature verification is bypassed in runtime-benchmarks
         let sig: sp_std::vec::Vec<u8> = vec![0u8; 64];
-    }: _(RawOrigin::Signed(merchant), miner, amount, nonce, replay_fingerprint, sig)
+        let expires_at: BlockNumberFor<T> = 1u32.into();
+        fill_expiry_queues::<T>(expires_at + T::PaymentIntentTTL::get());
+    }: _(RawOrigin::Signed(merchant), miner, amount, nonce, replay_fingerprint, facilitator, sig)
     verify {
         assert_eq!(NextIntentId::<T>::get(), 1);
     }
@@ -97,8 +189,8 @@ frame_benchmarking::v1::benchmarks! {
         let merchant: T::AccountId = whitelisted_caller();
         let miner: T::AccountId = frame_benchmarking::v1::account("miner", 0, 0);
         frame_system::Pallet::<T>::set_block_number(1u32.into());
-        let intent_id = setup_pending_intent::<T>(merchant, miner, 1_000u128);
-        let facilitator = T::FacilitatorAccount::get();
+        let facilitator = setup_facilitator::<T>();
+        let intent_id = setup_pending_intent::<T>(merchant, miner, 1_000u128, None, facilitator.clone());
     }: _(RawOrigin::Signed(facilitator), intent_id)
     verify {
         let intent = PaymentIntents::<T>::get(intent_id).unwrap();
@@ -110,7 +202,10 @@ frame_benchmarking::v1::benchmarks! {
         let miner: T::AccountId = frame_benchmarking::v1::account("miner", 0, 0);
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         let _ = T::Currency::deposit_creating(&miner, 10_000_000_000_000_000_000u128);
-        let intent_id = setup_verified_intent::<T>(merchant.clone(), miner.clone(), 1_000u128);
+        let _ = T::Currency::deposit_creating(&T::ConversionAccount::get(), 10_000_000_000_000_000_000u128);
+        // Worst case: the facilitator takes a fee and the asset is converted to DBC
+        let facilitator = setup_facilitator::<T>();
+        let intent_id = setup_verified_intent::<T>(merchant.clone(), miner.clone(), 1_000_000u128, Some(true), facilitator);
         // Advance past settlement delay
         let settle_block: BlockNumberFor<T> = 1u32.into();
         let settle_block = settle_block + T::SettlementDelay::get();
@@ -125,11 +220,215 @@ frame_benchmarking::v1::benchmarks! {
         let merchant: T::AccountId = whitelisted_caller();
         let miner: T::AccountId = frame_benchmarking::v1::account("miner", 0, 0);
         frame_system::Pallet::<T>::set_block_number(1u32.into());
-        let intent_id = setup_pending_intent::<T>(merchant, miner, 1_000u128);
-        let facilitator = T::FacilitatorAccount::get();
+        let facilitator = setup_facilitator::<T>();
+        // Worst case: a verified intent slashes the facilitator and releases a held asset
+        let intent_id = setup_verified_intent::<T>(merchant, miner, 1_000u128, Some(false), facilitator.clone());
     }: _(RawOrigin::Signed(facilitator), intent_id)
     verify {
         let intent = PaymentIntents::<T>::get(intent_id).unwrap();
         assert!(matches!(intent.status, PaymentIntentStatus::Failed));
     }
+
+    register_facilitator {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let facilitator: T::AccountId = account("facilitator", 0, 0);
+        let _ = T::Currency::deposit_creating(&facilitator, 10_000_000_000_000_000_000u128);
+        let stake = T::MinFacilitatorStake::get();
+    }: _<T::RuntimeOrigin>(origin, facilitator.clone(), FacilitatorKey::Secp256k1([2u8; 33]), stake, Perbill::from_percent(1), 0u32.into(), None)
+    verify {
+        assert!(Facilitators::<T>::contains_key(&facilitator));
+    }
+
+    rotate_facilitator_key {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let facilitator = setup_facilitator::<T>();
+    }: _<T::RuntimeOrigin>(origin, facilitator.clone(), FacilitatorKey::Secp256k1([2u8; 33]))
+    verify {
+        let info = Facilitators::<T>::get(&facilitator).unwrap();
+        assert_eq!(info.key, FacilitatorKey::Secp256k1([2u8; 33]));
+    }
+
+    set_facilitator_terms {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let facilitator = setup_facilitator::<T>();
+    }: _<T::RuntimeOrigin>(origin, facilitator.clone(), Perbill::from_percent(2), 1u32.into(), Some(100u32.into()))
+    verify {
+        let info = Facilitators::<T>::get(&facilitator).unwrap();
+        assert_eq!(info.fee_rate, Perbill::from_percent(2));
+    }
+
+    remove_facilitator {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let facilitator = setup_facilitator::<T>();
+    }: _<T::RuntimeOrigin>(origin, facilitator.clone())
+    verify {
+        assert!(!Facilitators::<T>::contains_key(&facilitator));
+    }
+
+    // The wallet signature is checked for real, unlike the facilitator signature above
+    submit_eip712_payment_intent {
+        let wallet = sp_io::crypto::ecdsa_generate(KeyTypeId(*b"x402"), None);
+        let sign = |digest: &[u8; 32]| {
+            sp_io::crypto::ecdsa_sign_prehashed(KeyTypeId(*b"x402"), &wallet, digest)
+                .expect("key was generated")
+                .0
+        };
+        let address = eip712::recover_signer(&[0u8; 32], &sign(&[0u8; 32])).expect("signature recovers");
+        let merchant = T::AddressMapping::into_account_id(address);
+        let _ = T::Currency::deposit_creating(&merchant, 10_000_000_000_000_000_000u128);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+
+        let intent = Eip712PaymentIntent::<T> {
+            merchant: address,
+            miner: account("miner", 0, 0),
+            amount: 1_000u128,
+            nonce: 1,
+            replay_fingerprint: H256::from_low_u64_be(1),
+            expiry: 100u32.into(),
+        };
+        let signature = sign(&X402Settlement::<T>::eip712_digest(&intent));
+        let facilitator = setup_facilitator::<T>();
+        let expires_at: BlockNumberFor<T> = 1u32.into();
+        fill_expiry_queues::<T>((expires_at + T::PaymentIntentTTL::get()).min(intent.expiry));
+    }: _(RawOrigin::Signed(facilitator), intent, signature)
+    verify {
+        assert_eq!(NextIntentId::<T>::get(), 1);
+    }
+
+    submit_asset_payment_intent {
+        let merchant: T::AccountId = whitelisted_caller();
+        let miner: T::AccountId = account("miner", 0, 0);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let _ = T::Currency::deposit_creating(&merchant, 10_000_000_000_000_000_000u128);
+        let asset = T::BenchmarkHelper::fund_asset(&merchant, 1_000_000u128);
+        UsdPeggedAssets::<T>::insert(asset, true);
+        // Worst case: the miner is paid in DBC, so the conversion account's liquidity is checked
+        let _ = T::Currency::deposit_creating(&T::ConversionAccount::get(), 10_000_000_000_000_000_000u128);
+        let facilitator = setup_facilitator::<T>();
+        // Dummy signature — signature verification is bypassed in runtime-benchmarks
+        let sig: sp_std::vec::Vec<u8> = vec![0u8; 64];
+        let expires_at: BlockNumberFor<T> = 1u32.into();
+        fill_expiry_queues::<T>(expires_at + T::PaymentIntentTTL::get());
+    }: _(RawOrigin::Signed(merchant), asset, true, miner, 1_000u128, 1, H256::from_low_u64_be(1), facilitator, sig)
+    verify {
+        assert_eq!(NextIntentId::<T>::get(), 1);
+    }
+
+    open_channel {
+        let customer: T::AccountId = whitelisted_caller();
+        let miner: T::AccountId = account("miner", 0, 0);
+        let task_id = T::BenchmarkHelper::billable_task();
+        let _ = T::Currency::deposit_creating(&customer, 10_000_000_000_000_000_000u128);
+    }: _(RawOrigin::Signed(customer), miner, task_id, CHANNEL_DEPOSIT, FacilitatorKey::Sr25519([9u8; 32]))
+    verify {
+        assert_eq!(NextChannelId::<T>::get(), 1);
+    }
+
+    close_channel {
+        let (_, miner) = setup_channel::<T>(Default::default(), ChannelStatus::Open);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        // Dummy signature — signature verification is bypassed in runtime-benchmarks
+        let sig: sp_std::vec::Vec<u8> = vec![0u8; 64];
+    }: _(RawOrigin::Signed(miner), 0, channel_voucher(CHANNEL_DEPOSIT / 2), sig)
+    verify {
+        let channel = PaymentChannels::<T>::get(0).unwrap();
+        assert!(matches!(channel.status, ChannelStatus::Closing { .. }));
+    }
+
+    dispute_channel {
+        let (_, miner) = setup_channel::<T>(
+            channel_voucher(CHANNEL_DEPOSIT / 4),
+            ChannelStatus::Closing { settles_at: 100u32.into() },
+        );
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let sig: sp_std::vec::Vec<u8> = vec![0u8; 64];
+    }: _(RawOrigin::Signed(miner), 0, channel_voucher(CHANNEL_DEPOSIT / 2), sig)
+    verify {
+        assert_eq!(PaymentChannels::<T>::get(0).unwrap().voucher.amount, CHANNEL_DEPOSIT / 2);
+    }
+
+    // Booking the usage as a task order is part of the worst case
+    settle_channel {
+        setup_channel::<T>(
+            channel_voucher(CHANNEL_DEPOSIT / 2),
+            ChannelStatus::Closing { settles_at: 1u32.into() },
+        );
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), 0)
+    verify {
+        let channel = PaymentChannels::<T>::get(0).unwrap();
+        assert!(matches!(channel.status, ChannelStatus::Settled { order_id: Some(_) }));
+    }
+
+    // Worst case: the fee is paid, the settled part converted and the rest returned in the asset
+    finalize_partial_settlement {
+        let merchant: T::AccountId = whitelisted_caller();
+        let miner: T::AccountId = account("miner", 0, 0);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let _ = T::Currency::deposit_creating(&miner, 10_000_000_000_000_000_000u128);
+        let _ = T::Currency::deposit_creating(&T::ConversionAccount::get(), 10_000_000_000_000_000_000u128);
+        let facilitator = setup_facilitator::<T>();
+        let intent_id = setup_verified_intent::<T>(merchant.clone(), miner, 1_000_000u128, Some(true), facilitator);
+        let settle_block: BlockNumberFor<T> = 1u32.into();
+        frame_system::Pallet::<T>::set_block_number(settle_block + T::SettlementDelay::get());
+        // Dummy signature — signature verification is bypassed in runtime-benchmarks
+        let sig: sp_std::vec::Vec<u8> = vec![0u8; 64];
+    }: _(RawOrigin::Signed(merchant), intent_id, 600_000u128, H256::from_low_u64_be(1), sig)
+    verify {
+        assert_eq!(SettlementReceipts::<T>::get(intent_id).unwrap().refunded, 400_000);
+    }
+
+    refund_payment_intent {
+        let merchant: T::AccountId = whitelisted_caller();
+        let miner: T::AccountId = account("miner", 0, 0);
+        let facilitator = setup_facilitator::<T>();
+        let intent_id = setup_verified_intent::<T>(merchant.clone(), miner, 1_000u128, Some(false), facilitator);
+        let expired: BlockNumberFor<T> = 1u32.into();
+        frame_system::Pallet::<T>::set_block_number(expired + T::PaymentIntentTTL::get());
+    }: _(RawOrigin::Signed(merchant), intent_id)
+    verify {
+        let intent = PaymentIntents::<T>::get(intent_id).unwrap();
+        assert!(matches!(intent.status, PaymentIntentStatus::Failed));
+    }
+
+    // Worst case: every queued intent is pending and releases a held asset
+    on_initialize {
+        let n in 0 .. T::MaxExpiriesPerBlock::get();
+
+        let miner: T::AccountId = account("miner", 0, 0);
+        let facilitator = setup_facilitator::<T>();
+        let mut queue: BoundedVec<u64, T::MaxExpiriesPerBlock> = Default::default();
+        for i in 0..n {
+            let merchant: T::AccountId = account("merchant", i, 0);
+            let intent_id = setup_pending_intent::<T>(merchant, miner.clone(), 1_000u128, Some(false), facilitator.clone());
+            queue.try_push(intent_id).map_err(|_| "queue full")?;
+        }
+        let now: BlockNumberFor<T> = 1u32.into();
+        let now = now + T::PaymentIntentTTL::get();
+        ExpiryQueue::<T>::insert(now, queue);
+        frame_system::Pallet::<T>::set_block_number(now);
+    }: {
+        X402Settlement::<T>::on_initialize(now);
+    }
+    verify {
+        assert!(!ExpiryQueue::<T>::contains_key(now));
+        for intent_id in 0..n as u64 {
+            let intent = PaymentIntents::<T>::get(intent_id).ok_or("intent removed")?;
+            assert!(matches!(intent.status, PaymentIntentStatus::Failed));
+        }
+    }
+
+    set_usd_pegged_asset {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let asset = T::BenchmarkHelper::fund_asset(&whitelisted_caller(), 1u128);
+    }: _<T::RuntimeOrigin>(origin, asset, true)
+    verify {
+        assert!(UsdPeggedAssets::<T>::get(asset));
+    }
 }
@@ -0,0 +1,86 @@
+//! EIP-712 typed-data hashing of payment intents signed by EVM wallets.
+//!
+//! The domain is `EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)`
+//! with name [`DOMAIN_NAME`] and version [`DOMAIN_VERSION`]. The signed message is
+//!
+//! ```text
+//! PaymentIntent(address merchant,bytes miner,uint256 amount,uint64 nonce,bytes32 replayFingerprint,uint64 expiry)
+//! ```
+//!
+//! where `miner` is the SCALE encoding of the miner account and `expiry` is the last block
+//! (exclusive) the intent may be submitted in.
+
+use codec::Encode;
+use sp_core::{H160, H256, U256};
+use sp_io::hashing::keccak_256;
+use sp_std::vec::Vec;
+
+pub const DOMAIN_NAME: &[u8] = b"DBC x402";
+pub const DOMAIN_VERSION: &[u8] = b"1";
+
+const DOMAIN_TYPE: &[u8] =
+    b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const PAYMENT_INTENT_TYPE: &[u8] =
+    b"PaymentIntent(address merchant,bytes miner,uint256 amount,uint64 nonce,bytes32 replayFingerprint,uint64 expiry)";
+
+fn word(value: U256) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+fn address_word(address: H160) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[12..].copy_from_slice(address.as_bytes());
+    out
+}
+
+pub fn domain_separator(chain_id: u64, verifying_contract: H160) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(5 * 32);
+    buf.extend_from_slice(&keccak_256(DOMAIN_TYPE));
+    buf.extend_from_slice(&keccak_256(DOMAIN_NAME));
+    buf.extend_from_slice(&keccak_256(DOMAIN_VERSION));
+    buf.extend_from_slice(&word(chain_id.into()));
+    buf.extend_from_slice(&address_word(verifying_contract));
+    keccak_256(&buf)
+}
+
+pub fn payment_intent_hash<AccountId: Encode>(
+    merchant: H160,
+    miner: &AccountId,
+    amount: u128,
+    nonce: u64,
+    replay_fingerprint: H256,
+    expiry: u64,
+) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(7 * 32);
+    buf.extend_from_slice(&keccak_256(PAYMENT_INTENT_TYPE));
+    buf.extend_from_slice(&address_word(merchant));
+    buf.extend_from_slice(&keccak_256(&miner.encode()));
+    buf.extend_from_slice(&word(amount.into()));
+    buf.extend_from_slice(&word(nonce.into()));
+    buf.extend_from_slice(replay_fingerprint.as_bytes());
+    buf.extend_from_slice(&word(expiry.into()));
+    keccak_256(&buf)
+}
+
+/// Digest signed by `eth_signTypedData_v4`
+pub fn digest(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 66];
+    buf[0] = 0x19;
+    buf[1] = 0x01;
+    buf[2..34].copy_from_slice(domain_separator);
+    buf[34..].copy_from_slice(struct_hash);
+    keccak_256(&buf)
+}
+
+/// Address that produced the 65-byte `r || s || v` signature of `digest`
+pub fn recover_signer(digest: &[u8; 32], signature: &[u8; 65]) -> Option<H160> {
+    let mut sig = *signature;
+    // Wallets encode the recovery id as 27/28
+    if sig[64] >= 27 {
+        sig[64] -= 27;
+    }
+    let public = sp_io::crypto::secp256k1_ecdsa_recover(&sig, digest).ok()?;
+    Some(H160::from_slice(&keccak_256(&public)[12..]))
+}
@@ -4,8 +4,15 @@ pub use pallet::*;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod eip712;
+pub mod migrations;
 pub mod weights;
 
+pub(crate) const LOG_TARGET: &str = "runtime::x402-settlement";
+
+/// Blocks after `expires_at` an intent can be carried over to when the expiry queues are full
+pub const MAX_EXPIRY_CARRY_OVER: u32 = 8;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -18,14 +25,23 @@ pub mod pallet {
         traits::EnsureOrigin,
         dispatch::DispatchResult,
         pallet_prelude::*,
-        traits::{Currency, ReservableCurrency},
+        traits::{
+            fungibles::{self, Mutate as _},
+            tokens::{Fortitude, Preservation},
+            BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency,
+        },
         BoundedVec,
     };
     use frame_system::pallet_prelude::*;
     use sp_std::vec::Vec;
-    use sp_core::H256;
-    use crate::weights::WeightInfo;
-    use sp_runtime::traits::{SaturatedConversion, Saturating};
+    use sp_core::{H160, H256};
+    use crate::{eip712, weights::WeightInfo};
+    use dbc_support::traits::{DbcPrice, TaskOrderSettlementHandler};
+    use pallet_evm::AddressMapping;
+    use sp_runtime::{
+        traits::{One, SaturatedConversion, Saturating, Zero},
+        Perbill,
+    };
 
 
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -36,6 +52,20 @@ pub mod pallet {
         Failed,
     }
 
+    /// What a payment intent is paid in
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum PaymentAsset<AssetId> {
+        /// Native DBC, reserved from the merchant
+        Native,
+        /// An asset held in the merchant's account until the intent settles or fails
+        Asset {
+            id: AssetId,
+            /// Pay the miner in DBC at the oracle price instead of the asset. Only for assets
+            /// in `UsdPeggedAssets`.
+            convert_to_dbc: bool,
+        },
+    }
+
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
     pub struct PaymentIntent<T: Config> {
@@ -43,8 +73,11 @@ pub mod pallet {
         pub merchant: T::AccountId,
         pub miner: T::AccountId,
         pub amount: BalanceOf<T>,
+        pub asset: PaymentAsset<T::AssetId>,
         pub nonce: u64,
         pub replay_fingerprint: H256,
+        /// Facilitator whose key signed the intent
+        pub facilitator: T::AccountId,
         pub facilitator_signature: BoundedVec<u8, T::MaxSignatureLen>,
         pub status: PaymentIntentStatus,
         pub created_at: BlockNumberFor<T>,
@@ -53,19 +86,134 @@ pub mod pallet {
         pub expires_at: BlockNumberFor<T>,
     }
 
+    /// Payment intent authorized by an EVM wallet with an EIP-712 signature
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Eip712PaymentIntent<T: Config> {
+        /// Signing wallet, paying from the account `AddressMapping` maps it to
+        pub merchant: H160,
+        pub miner: T::AccountId,
+        pub amount: BalanceOf<T>,
+        pub nonce: u64,
+        pub replay_fingerprint: H256,
+        /// Block from which the signed intent can no longer be submitted
+        pub expiry: BlockNumberFor<T>,
+    }
+
+    /// Public key a facilitator signs payment intents, or a customer channel vouchers, with
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum FacilitatorKey {
+        Sr25519([u8; 32]),
+        Ed25519([u8; 32]),
+        /// Compressed public key
+        Secp256k1([u8; 33]),
+    }
+
+    /// A facilitator registered by `AdminOrigin`
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Facilitator<T: Config> {
+        pub key: FacilitatorKey,
+        /// Reserved from the facilitator account while registered, less what was slashed
+        pub stake: BalanceOf<T>,
+        /// Share of each settled intent paid to the facilitator
+        pub fee_rate: Perbill,
+        /// First block the facilitator may sign intents
+        pub active_from: BlockNumberFor<T>,
+        /// Block from which the facilitator may no longer sign intents
+        pub active_until: Option<BlockNumberFor<T>>,
+    }
+
+    /// Per-facilitator dashboard of intents it signed
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+    pub struct FacilitatorStats<Balance> {
+        pub submitted: u64,
+        pub settled: u64,
+        /// Total amount of settled native intents
+        pub volume: Balance,
+        /// Fees taken from native intents
+        pub fees: Balance,
+        /// Intents failed by the facilitator
+        pub failed: u64,
+        pub expired: u64,
+    }
+
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-    pub struct SettlementReceipt<AccountId, Balance> {
+    pub struct SettlementReceipt<AccountId, Balance, AssetId> {
         pub intent_id: u64,
         pub merchant: AccountId,
         pub miner: AccountId,
+        /// Paid out of the intent, including the facilitator fee
         pub amount: Balance,
+        /// Returned to the merchant when the intent settled for less than its amount
+        pub refunded: Balance,
+        /// Asset the merchant paid in, native DBC when `None`
+        pub asset: Option<AssetId>,
+        /// DBC price, in millionths of a USD, the miner was paid at when converted to DBC
+        pub exchange_rate: Option<Balance>,
         pub settled_at: u64,
         pub tx_hash: H256,
     }
 
+    /// Cumulative usage of a payment channel, signed off-chain by the customer's voucher key
+    #[derive(Clone, Copy, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ChannelVoucher<Balance> {
+        /// Total owed to the miner since the channel was opened
+        pub amount: Balance,
+        pub input_tokens: u64,
+        pub output_tokens: u64,
+    }
+
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum ChannelStatus<BlockNumber> {
+        Open,
+        /// A higher voucher may still replace the submitted one until `settles_at`
+        Closing { settles_at: BlockNumber },
+        /// Paid out, booked as task order `order_id` when anything was owed
+        Settled { order_id: Option<u64> },
+    }
+
+    /// Deposit a customer streams to a miner through off-chain vouchers
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct PaymentChannel<T: Config> {
+        pub customer: T::AccountId,
+        pub miner: T::AccountId,
+        /// Task definition in pallet-task-mode the usage is billed against
+        pub task_id: u64,
+        /// Reserved from the customer until the channel settles
+        pub deposit: BalanceOf<T>,
+        /// Key the customer signs vouchers with
+        pub voucher_key: FacilitatorKey,
+        /// Highest voucher submitted on-chain
+        pub voucher: ChannelVoucher<BalanceOf<T>>,
+        pub status: ChannelStatus<BlockNumberFor<T>>,
+        pub opened_at: BlockNumberFor<T>,
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    pub trait BenchmarkHelper<AssetId, AccountId, Balance> {
+        /// Give `who` `amount` of an asset, creating it if needed, and make the DBC price available
+        fn fund_asset(who: &AccountId, amount: Balance) -> AssetId;
+        /// Task definition payment channels can bill against, created if needed
+        fn billable_task() -> u64;
+    }
+    #[cfg(feature = "runtime-benchmarks")]
+    impl<AssetId: From<u32>, AccountId, Balance> BenchmarkHelper<AssetId, AccountId, Balance> for () {
+        fn fund_asset(_who: &AccountId, _amount: Balance) -> AssetId {
+            0u32.into()
+        }
+        fn billable_task() -> u64 {
+            0
+        }
+    }
+
     pub(crate) type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+    pub type SettlementReceiptOf<T> =
+        SettlementReceipt<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as Config>::AssetId>;
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
@@ -73,14 +221,16 @@ pub mod pallet {
         type Currency: ReservableCurrency<Self::AccountId, Balance = u128>;
 
         #[pallet::constant]
-        type FacilitatorAccount: Get<Self::AccountId>;
+        type MaxSignatureLen: Get<u32>;
 
+        /// Minimum stake bonded by a registered facilitator
         #[pallet::constant]
-        type MaxSignatureLen: Get<u32>;
+        type MinFacilitatorStake: Get<BalanceOf<Self>>;
 
-        /// Sr25519 public key of the facilitator for signature verification
+        /// Slashed from a facilitator's stake for each intent it verified that then failed or
+        /// expired unsettled
         #[pallet::constant]
-        type FacilitatorPublicKey: Get<[u8; 32]>;
+        type FacilitatorSlash: Get<BalanceOf<Self>>;
 
         #[pallet::constant]
         type SettlementDelay: Get<BlockNumberFor<Self>>;
@@ -90,11 +240,56 @@ pub mod pallet {
 
         type WeightInfo: WeightInfo;
 
-        /// Origin that can finalize settlements
+        /// Identifier of the assets payment intents can be denominated in
+        type AssetId: Member + Parameter + Copy + MaxEncodedLen;
+
+        /// Assets, such as stablecoins, that payment intents can be denominated in. Their
+        /// `Freezer` must be this pallet, so the assets held for open intents stay frozen.
+        type Assets: fungibles::Mutate<Self::AccountId, AssetId = Self::AssetId, Balance = BalanceOf<Self>>
+            + fungibles::metadata::Inspect<Self::AccountId>;
+
+        /// Price oracle for intents converted to DBC
+        type DbcPrice: DbcPrice<Balance = BalanceOf<Self>>;
+
+        /// Takes the asset of a converted intent and pays the miner in DBC. Intents asking for
+        /// conversion are only accepted while it holds their value in DBC.
+        type ConversionAccount: Get<Self::AccountId>;
+
+        /// Maps the EVM wallet that signed an EIP-712 payment intent to its account
+        type AddressMapping: AddressMapping<Self::AccountId>;
+
+        /// Chain id of the EIP-712 signing domain
+        type ChainId: Get<u64>;
+
+        /// Verifying contract of the EIP-712 signing domain
+        #[pallet::constant]
+        type Eip712VerifyingContract: Get<H160>;
+
+        /// Origin that manages the facilitator registry
         type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Blocks a closing payment channel waits for a higher voucher before it can settle
+        #[pallet::constant]
+        type ChannelDisputeWindow: Get<BlockNumberFor<Self>>;
+
+        /// Max pending intents expired automatically in the same block. Intents past this cap are
+        /// carried over to the following blocks.
+        #[pallet::constant]
+        type MaxExpiriesPerBlock: Get<u32>;
+
+        /// Handler notified when a task order has been paid out to its miner, or a payment
+        /// channel has settled
+        type OnOrderSettled: dbc_support::traits::TaskOrderSettlementHandler<
+            AccountId = Self::AccountId,
+            Balance = BalanceOf<Self>,
+        >;
+
+        /// Helper trait for benchmarks.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: BenchmarkHelper<Self::AssetId, Self::AccountId, BalanceOf<Self>>;
     }
 
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
@@ -122,12 +317,66 @@ pub mod pallet {
     #[pallet::storage]
     #[pallet::getter(fn settlement_receipt_of)]
     pub type SettlementReceipts<T: Config> =
-        StorageMap<_, Blake2_128Concat, u64, SettlementReceipt<T::AccountId, BalanceOf<T>>>;
+        StorageMap<_, Blake2_128Concat, u64, SettlementReceiptOf<T>>;
+
+    /// Settlement receipt issued for each task order
+    #[pallet::storage]
+    #[pallet::getter(fn order_receipt_of)]
+    pub type OrderReceipts<T: Config> = StorageMap<_, Blake2_128Concat, u64, u64>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn facilitator_of)]
+    pub type Facilitators<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, Facilitator<T>>;
+
+    /// Pending and verified intents signed by each facilitator, which keep its stake bonded
+    #[pallet::storage]
+    #[pallet::getter(fn open_intents)]
+    pub type OpenIntents<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Kept after a facilitator is removed
+    #[pallet::storage]
+    #[pallet::getter(fn facilitator_stats)]
+    pub type FacilitatorStatsOf<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, FacilitatorStats<BalanceOf<T>>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_channel_id)]
+    pub type NextChannelId<T> = StorageValue<_, u64, ValueQuery>;
 
-    /// Track pending intent IDs to avoid unbounded iteration in on_initialize
     #[pallet::storage]
-    pub type PendingIntentIds<T: Config> =
-        StorageValue<_, BoundedVec<u64, ConstU32<10000>>, ValueQuery>;
+    #[pallet::getter(fn payment_channel_of)]
+    pub type PaymentChannels<T: Config> = StorageMap<_, Blake2_128Concat, u64, PaymentChannel<T>>;
+
+    /// Assets held in a merchant's account for its open asset intents. `pallet_assets` sees them
+    /// as frozen through the `FrozenBalance` impl of this pallet.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_hold)]
+    pub type AssetHolds<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// Assets worth one USD per whole unit, which intents can be converted to DBC from
+    #[pallet::storage]
+    #[pallet::getter(fn is_usd_pegged)]
+    pub type UsdPeggedAssets<T: Config> = StorageMap<_, Blake2_128Concat, T::AssetId, bool, ValueQuery>;
+
+    /// Pending intents expired in `on_initialize`, keyed by the block they are due in. That is
+    /// `expires_at`, or a later block if the queue of `expires_at` was full.
+    #[pallet::storage]
+    #[pallet::getter(fn expiry_queue)]
+    pub type ExpiryQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<u64, T::MaxExpiriesPerBlock>,
+        ValueQuery,
+    >;
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -137,7 +386,9 @@ pub mod pallet {
             merchant: T::AccountId,
             miner: T::AccountId,
             amount: BalanceOf<T>,
+            asset: PaymentAsset<T::AssetId>,
             nonce: u64,
+            facilitator: T::AccountId,
         },
         PaymentIntentVerified {
             intent_id: u64,
@@ -158,6 +409,77 @@ pub mod pallet {
             merchant: T::AccountId,
             amount: BalanceOf<T>,
         },
+        /// Part or all of an intent went back to the merchant, with the facilitator's
+        /// justification for a partial settlement
+        PaymentIntentRefunded {
+            intent_id: u64,
+            merchant: T::AccountId,
+            amount: BalanceOf<T>,
+            justification: Option<H256>,
+        },
+        /// The asset of a settled intent was exchanged for DBC paid to the miner
+        PaymentConverted {
+            intent_id: u64,
+            asset: T::AssetId,
+            amount: BalanceOf<T>,
+            dbc_amount: BalanceOf<T>,
+            exchange_rate: BalanceOf<T>,
+        },
+        FacilitatorFeePaid {
+            intent_id: u64,
+            facilitator: T::AccountId,
+            fee: BalanceOf<T>,
+        },
+        FacilitatorRegistered {
+            facilitator: T::AccountId,
+            stake: BalanceOf<T>,
+            fee_rate: Perbill,
+        },
+        FacilitatorKeyRotated {
+            facilitator: T::AccountId,
+        },
+        FacilitatorTermsUpdated {
+            facilitator: T::AccountId,
+            fee_rate: Perbill,
+            active_from: BlockNumberFor<T>,
+            active_until: Option<BlockNumberFor<T>>,
+        },
+        FacilitatorRemoved {
+            facilitator: T::AccountId,
+            stake: BalanceOf<T>,
+        },
+        /// An intent the facilitator verified failed or expired unsettled
+        FacilitatorSlashed {
+            facilitator: T::AccountId,
+            intent_id: u64,
+            amount: BalanceOf<T>,
+        },
+        ChannelOpened {
+            channel_id: u64,
+            customer: T::AccountId,
+            miner: T::AccountId,
+            deposit: BalanceOf<T>,
+        },
+        ChannelClosing {
+            channel_id: u64,
+            amount: BalanceOf<T>,
+            settles_at: BlockNumberFor<T>,
+        },
+        /// A higher voucher replaced the one a closing channel was submitted with
+        ChannelVoucherReplaced {
+            channel_id: u64,
+            amount: BalanceOf<T>,
+        },
+        ChannelSettled {
+            channel_id: u64,
+            order_id: Option<u64>,
+            amount: BalanceOf<T>,
+            refunded: BalanceOf<T>,
+        },
+        UsdPeggedAssetSet {
+            asset: T::AssetId,
+            pegged: bool,
+        },
     }
 
     #[pallet::error]
@@ -173,6 +495,30 @@ pub mod pallet {
         ArithmeticOverflow,
         PaymentIntentExpired,
         TooManyPendingIntents,
+        FacilitatorAlreadyRegistered,
+        FacilitatorNotFound,
+        FacilitatorNotActive,
+        InsufficientFacilitatorStake,
+        InvalidActiveWindow,
+        AuthorizationExpired,
+        InvalidPayerSignature,
+        DbcPriceUnavailable,
+        ConversionFailed,
+        TaskNotBillable,
+        ChannelNotFound,
+        ChannelNotOpen,
+        ChannelNotClosing,
+        InvalidVoucherSignature,
+        VoucherExceedsDeposit,
+        VoucherNotHigher,
+        DisputeWindowOpen,
+        DisputeWindowClosed,
+        SettledAmountExceedsIntent,
+        InvalidJustificationSignature,
+        AssetNotUsdPegged,
+        PaymentIntentNotExpired,
+        InsufficientConversionLiquidity,
+        FacilitatorHasOpenIntents,
     }
 
 
@@ -196,45 +542,19 @@ pub mod pallet {
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
         fn on_initialize(now: BlockNumberFor<T>) -> Weight {
-            let mut expired_ids = sp_std::vec::Vec::new();
-            let pending = PendingIntentIds::<T>::get();
-            let total_checked = pending.len() as u64;
-
-            for id in &pending {
-                if let Some(intent) = PaymentIntents::<T>::get(id) {
-                    if now >= intent.expires_at && matches!(intent.status, PaymentIntentStatus::Pending) {
-                        T::Currency::unreserve(&intent.merchant, intent.amount);
-                        PaymentIntents::<T>::mutate(id, |maybe| {
-                            if let Some(ref mut i) = maybe {
-                                i.status = PaymentIntentStatus::Failed;
-                            }
-                        });
-                        Self::deposit_event(Event::PaymentIntentExpired {
-                            intent_id: *id,
-                            merchant: intent.merchant,
-                            amount: intent.amount,
-                        });
-                        expired_ids.push(*id);
-                    }
-                }
-            }
-
-            // Remove expired intents from pending list
-            if !expired_ids.is_empty() {
-                PendingIntentIds::<T>::mutate(|ids| {
-                    ids.retain(|id| !expired_ids.contains(id));
-                });
+            let due = ExpiryQueue::<T>::take(now);
+            for id in due.iter() {
+                // Verified, settled or failed intents are skipped
+                Self::expire_intent(*id);
             }
-
-            let expired_count = expired_ids.len() as u64;
-            T::DbWeight::get().reads(total_checked.saturating_add(1))
-                .saturating_add(T::DbWeight::get().writes(expired_count.saturating_mul(2).saturating_add(1)))
+            T::WeightInfo::on_initialize(due.len() as u32)
         }
     }
 
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// Submit a payment intent signed by `facilitator`, which must be registered and active
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::submit_payment_intent())]
         pub fn submit_payment_intent(
@@ -243,80 +563,20 @@ pub mod pallet {
             amount: BalanceOf<T>,
             nonce: u64,
             replay_fingerprint: H256,
+            facilitator: T::AccountId,
             facilitator_signature: Vec<u8>,
         ) -> DispatchResult {
             let merchant = ensure_signed(origin)?;
-
-            // Check nonce
-            ensure!(
-                !NonceUsed::<T>::contains_key((merchant.clone(), nonce)),
-                Error::<T>::InvalidNonce
-            );
-
-            // Check replay fingerprint
-            ensure!(
-                !ReplayFingerprintUsed::<T>::contains_key(replay_fingerprint),
-                Error::<T>::ReplayFingerprintUsed
-            );
-
-            // Verify facilitator signature
-            let signature_bytes = facilitator_signature
-                .try_into()
-                .map_err(|_| Error::<T>::ArithmeticOverflow)?;
-            ensure!(
-                Self::verify_facilitator_signature(
-                    &merchant,
-                    &miner,
-                    amount,
-                    nonce,
-                    replay_fingerprint,
-                    &signature_bytes
-                ),
-                Error::<T>::InvalidFacilitatorSignature
-            );
-
-            // Reserve merchant balance
-            T::Currency::reserve(&merchant, amount)
-                .map_err(|_| Error::<T>::InsufficientBalance)?;
-
-            let intent_id = NextIntentId::<T>::get();
-            let next_intent_id = intent_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
-            NextIntentId::<T>::put(next_intent_id);
-
-            // Mark nonce and replay fingerprint as used
-            NonceUsed::<T>::insert((merchant.clone(), nonce), true);
-            ReplayFingerprintUsed::<T>::insert(replay_fingerprint, true);
-
-            PendingIntentIds::<T>::try_mutate(|ids| {
-                ids.try_push(intent_id).map_err(|_| Error::<T>::TooManyPendingIntents)
-            })?;
-            PaymentIntents::<T>::insert(
-                intent_id,
-                PaymentIntent {
-                    intent_id,
-                    merchant: merchant.clone(),
-                    miner: miner.clone(),
-                    amount,
-                    nonce,
-                    replay_fingerprint,
-                    facilitator_signature: signature_bytes,
-                    status: PaymentIntentStatus::Pending,
-                    created_at: <frame_system::Pallet<T>>::block_number(),
-                    verified_at: None,
-                    settled_at: None,
-                    expires_at: <frame_system::Pallet<T>>::block_number().saturating_add(T::PaymentIntentTTL::get()),
-                },
-            );
-
-            Self::deposit_event(Event::PaymentIntentSubmitted {
-                intent_id,
+            Self::submit_signed(
                 merchant,
                 miner,
                 amount,
+                PaymentAsset::Native,
                 nonce,
-            });
-
-            Ok(())
+                replay_fingerprint,
+                facilitator,
+                facilitator_signature,
+            )
         }
 
         #[pallet::call_index(1)]
@@ -326,13 +586,15 @@ pub mod pallet {
             intent_id: u64,
         ) -> DispatchResult {
             let facilitator = ensure_signed(origin)?;
+            // A removed facilitator can no longer verify the intents it signed
             ensure!(
-                facilitator == T::FacilitatorAccount::get(),
+                Facilitators::<T>::contains_key(&facilitator),
                 Error::<T>::NotAuthorized
             );
 
             PaymentIntents::<T>::try_mutate(intent_id, |maybe_intent| -> DispatchResult {
                 let intent = maybe_intent.as_mut().ok_or(Error::<T>::PaymentIntentNotFound)?;
+                ensure!(intent.facilitator == facilitator, Error::<T>::NotAuthorized);
                 ensure!(
                     matches!(intent.status, PaymentIntentStatus::Pending),
                     Error::<T>::InvalidPaymentIntentStatus
@@ -345,9 +607,6 @@ pub mod pallet {
                 intent.status = PaymentIntentStatus::Verified;
                 intent.verified_at = Some(<frame_system::Pallet<T>>::block_number());
 
-                // Remove from pending tracking (no longer Pending status)
-                PendingIntentIds::<T>::mutate(|ids| ids.retain(|id| *id != intent_id));
-
                 Ok(())
             })?;
 
@@ -366,66 +625,9 @@ pub mod pallet {
             intent_id: u64,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-
-            let mut intent = PaymentIntents::<T>::get(intent_id).ok_or(Error::<T>::PaymentIntentNotFound)?;
-            ensure!(
-                matches!(intent.status, PaymentIntentStatus::Verified),
-                Error::<T>::InvalidPaymentIntentStatus
-            );
-            ensure!(
-                <frame_system::Pallet<T>>::block_number() < intent.expires_at,
-                Error::<T>::PaymentIntentExpired
-            );
-
-            // Check authorization: merchant, miner, or facilitator can finalize
-            let facilitator = T::FacilitatorAccount::get();
-            ensure!(
-                caller == intent.merchant || caller == intent.miner || caller == facilitator,
-                Error::<T>::NotAuthorized
-            );
-
-            // Check settlement delay
-            let current_block = <frame_system::Pallet<T>>::block_number();
-            let verified_at = intent.verified_at.ok_or(Error::<T>::InvalidPaymentIntentStatus)?;
-            let delay_blocks = T::SettlementDelay::get();
-            ensure!(
-                current_block >= verified_at.saturating_add(delay_blocks),
-                Error::<T>::SettlementDelayNotMet
-            );
-
-            // Transfer funds from merchant to miner
-            T::Currency::repatriate_reserved(
-                &intent.merchant,
-                &intent.miner,
-                intent.amount,
-                frame_support::traits::BalanceStatus::Free,
-            )
-            .map_err(|_| Error::<T>::InsufficientBalance)?;
-
-            // Update intent status
-            intent.status = PaymentIntentStatus::Settled;
-            intent.settled_at = Some(current_block);
-            PaymentIntents::<T>::insert(intent_id, &intent);
-
-            // Create settlement receipt
-            let receipt = SettlementReceipt {
-                intent_id,
-                merchant: intent.merchant.clone(),
-                miner: intent.miner.clone(),
-                amount: intent.amount,
-                settled_at: current_block.saturated_into(),
-                tx_hash: H256::from_low_u64_be(intent_id),
-            };
-            SettlementReceipts::<T>::insert(intent_id, receipt);
-
-            Self::deposit_event(Event::PaymentIntentSettled {
-                intent_id,
-                merchant: intent.merchant,
-                miner: intent.miner,
-                amount: intent.amount,
-            });
-
-            Ok(())
+            let intent = PaymentIntents::<T>::get(intent_id).ok_or(Error::<T>::PaymentIntentNotFound)?;
+            let amount = intent.amount;
+            Self::settle(caller, intent, amount, None)
         }
 
         #[pallet::call_index(3)]
@@ -435,29 +637,32 @@ pub mod pallet {
             intent_id: u64,
         ) -> DispatchResult {
             let facilitator = ensure_signed(origin)?;
-            ensure!(
-                facilitator == T::FacilitatorAccount::get(),
-                Error::<T>::NotAuthorized
-            );
 
-            PaymentIntents::<T>::try_mutate(intent_id, |maybe_intent| -> DispatchResult {
+            let verified = PaymentIntents::<T>::try_mutate(intent_id, |maybe_intent| {
                 let intent = maybe_intent.as_mut().ok_or(Error::<T>::PaymentIntentNotFound)?;
+                ensure!(intent.facilitator == facilitator, Error::<T>::NotAuthorized);
                 ensure!(
                     matches!(intent.status, PaymentIntentStatus::Pending | PaymentIntentStatus::Verified),
                     Error::<T>::InvalidPaymentIntentStatus
                 );
 
-                // Release reserved funds back to merchant
-                T::Currency::unreserve(&intent.merchant, intent.amount);
+                // Release held funds back to merchant
+                Self::release_funds(intent, intent.amount)?;
 
+                let verified = matches!(intent.status, PaymentIntentStatus::Verified);
                 intent.status = PaymentIntentStatus::Failed;
 
-                // Remove from pending tracking
-                PendingIntentIds::<T>::mutate(|ids| ids.retain(|id| *id != intent_id));
-
-                Ok(())
+                Ok::<_, DispatchError>(verified)
             })?;
 
+            Self::close_intent(&facilitator);
+            if verified {
+                Self::slash_facilitator(&facilitator, intent_id);
+            }
+            FacilitatorStatsOf::<T>::mutate(&facilitator, |stats| {
+                stats.failed = stats.failed.saturating_add(1);
+            });
+
             Self::deposit_event(Event::PaymentIntentFailed {
                 intent_id,
                 reason: DispatchError::Other("Payment intent failed by facilitator"),
@@ -465,49 +670,947 @@ pub mod pallet {
 
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        fn verify_facilitator_signature(
-            merchant: &T::AccountId,
-            miner: &T::AccountId,
-            amount: BalanceOf<T>,
-            nonce: u64,
-            replay_fingerprint: H256,
-            signature_bytes: &BoundedVec<u8, T::MaxSignatureLen>,
-        ) -> bool {
-            // Bypass signature verification during benchmarks
-            #[cfg(feature = "runtime-benchmarks")]
-            {
-                let _ = (merchant, miner, amount, nonce, replay_fingerprint, signature_bytes);
-                return true;
-            }
+        /// Register `facilitator`, reserving `stake` from its account
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::register_facilitator())]
+        pub fn register_facilitator(
+            origin: OriginFor<T>,
+            facilitator: T::AccountId,
+            key: FacilitatorKey,
+            stake: BalanceOf<T>,
+            fee_rate: Perbill,
+            active_from: BlockNumberFor<T>,
+            active_until: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
 
-            #[cfg(not(feature = "runtime-benchmarks"))]
-            {
-                // Sr25519 signature must be exactly 64 bytes
-                if signature_bytes.len() != 64 {
-                    return false;
-                }
+            ensure!(
+                !Facilitators::<T>::contains_key(&facilitator),
+                Error::<T>::FacilitatorAlreadyRegistered
+            );
+            ensure!(
+                stake >= T::MinFacilitatorStake::get(),
+                Error::<T>::InsufficientFacilitatorStake
+            );
+            ensure!(
+                active_until.map_or(true, |until| until > active_from),
+                Error::<T>::InvalidActiveWindow
+            );
+            T::Currency::reserve(&facilitator, stake)
+                .map_err(|_| Error::<T>::InsufficientBalance)?;
 
-                // Build the message: SCALE-encode all payment parameters
-                let mut message = Vec::new();
-                merchant.encode_to(&mut message);
-                miner.encode_to(&mut message);
-                amount.encode_to(&mut message);
-                nonce.encode_to(&mut message);
-                replay_fingerprint.encode_to(&mut message);
+            Facilitators::<T>::insert(
+                &facilitator,
+                Facilitator { key, stake, fee_rate, active_from, active_until },
+            );
+
+            Self::deposit_event(Event::FacilitatorRegistered { facilitator, stake, fee_rate });
+            Ok(())
+        }
+
+        /// Replace the key `facilitator` signs intents with. Pending intents keep their
+        /// already verified signature.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::rotate_facilitator_key())]
+        pub fn rotate_facilitator_key(
+            origin: OriginFor<T>,
+            facilitator: T::AccountId,
+            key: FacilitatorKey,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            Facilitators::<T>::try_mutate(&facilitator, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::FacilitatorNotFound)?;
+                info.key = key;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::FacilitatorKeyRotated { facilitator });
+            Ok(())
+        }
+
+        /// Change the fee rate and active window of `facilitator`
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_facilitator_terms())]
+        pub fn set_facilitator_terms(
+            origin: OriginFor<T>,
+            facilitator: T::AccountId,
+            fee_rate: Perbill,
+            active_from: BlockNumberFor<T>,
+            active_until: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                active_until.map_or(true, |until| until > active_from),
+                Error::<T>::InvalidActiveWindow
+            );
+
+            Facilitators::<T>::try_mutate(&facilitator, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::FacilitatorNotFound)?;
+                info.fee_rate = fee_rate;
+                info.active_from = active_from;
+                info.active_until = active_until;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::FacilitatorTermsUpdated {
+                facilitator,
+                fee_rate,
+                active_from,
+                active_until,
+            });
+            Ok(())
+        }
+
+        /// Remove `facilitator` and return its stake. The stake answers for the intents it
+        /// verified, so none of the intents it signed may still be pending or verified.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::remove_facilitator())]
+        pub fn remove_facilitator(
+            origin: OriginFor<T>,
+            facilitator: T::AccountId,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                OpenIntents::<T>::get(&facilitator) == 0,
+                Error::<T>::FacilitatorHasOpenIntents
+            );
+
+            let info = Facilitators::<T>::take(&facilitator).ok_or(Error::<T>::FacilitatorNotFound)?;
+            T::Currency::unreserve(&facilitator, info.stake);
+
+            Self::deposit_event(Event::FacilitatorRemoved { facilitator, stake: info.stake });
+            Ok(())
+        }
+
+        /// Submit a payment intent authorized by the EIP-712 signature of an EVM wallet. The
+        /// caller relays it as the intent's facilitator and must be registered and active.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::submit_eip712_payment_intent())]
+        pub fn submit_eip712_payment_intent(
+            origin: OriginFor<T>,
+            intent: Eip712PaymentIntent<T>,
+            signature: [u8; 65],
+        ) -> DispatchResult {
+            let facilitator = ensure_signed(origin)?;
+
+            let facilitator_info = Facilitators::<T>::get(&facilitator).ok_or(Error::<T>::FacilitatorNotFound)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(Self::is_active(&facilitator_info, now), Error::<T>::FacilitatorNotActive);
+            ensure!(now < intent.expiry, Error::<T>::AuthorizationExpired);
+
+            let digest = Self::eip712_digest(&intent);
+            ensure!(
+                eip712::recover_signer(&digest, &signature) == Some(intent.merchant),
+                Error::<T>::InvalidPayerSignature
+            );
+            let merchant = T::AddressMapping::into_account_id(intent.merchant);
+            let signature_bytes = signature
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::ArithmeticOverflow)?;
+
+            Self::open_intent(
+                merchant,
+                intent.miner,
+                intent.amount,
+                PaymentAsset::Native,
+                intent.nonce,
+                intent.replay_fingerprint,
+                facilitator,
+                signature_bytes,
+                now.saturating_add(T::PaymentIntentTTL::get()).min(intent.expiry),
+            )
+        }
+
+        /// Submit a payment intent denominated in `asset`, which stays frozen in the merchant's
+        /// account until the intent settles or fails. With `convert_to_dbc` the miner is paid in
+        /// DBC at the oracle price of settlement, which `ConversionAccount` must hold enough DBC
+        /// for when the intent is submitted. The facilitator signs the intent message followed
+        /// by `(asset, convert_to_dbc)`.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::submit_asset_payment_intent())]
+        #[allow(clippy::too_many_arguments)]
+        pub fn submit_asset_payment_intent(
+            origin: OriginFor<T>,
+            asset: T::AssetId,
+            convert_to_dbc: bool,
+            miner: T::AccountId,
+            amount: BalanceOf<T>,
+            nonce: u64,
+            replay_fingerprint: H256,
+            facilitator: T::AccountId,
+            facilitator_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let merchant = ensure_signed(origin)?;
+            Self::submit_signed(
+                merchant,
+                miner,
+                amount,
+                PaymentAsset::Asset { id: asset, convert_to_dbc },
+                nonce,
+                replay_fingerprint,
+                facilitator,
+                facilitator_signature,
+            )
+        }
+
+        /// Open a payment channel to `miner`, reserving `deposit` from the caller. Usage is
+        /// billed against task definition `task_id` through vouchers signed with `voucher_key`.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::open_channel())]
+        pub fn open_channel(
+            origin: OriginFor<T>,
+            miner: T::AccountId,
+            task_id: u64,
+            deposit: BalanceOf<T>,
+            voucher_key: FacilitatorKey,
+        ) -> DispatchResult {
+            let customer = ensure_signed(origin)?;
+            ensure!(T::OnOrderSettled::is_billable_task(task_id), Error::<T>::TaskNotBillable);
+
+            let channel_id = NextChannelId::<T>::get();
+            let next_id = channel_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
+
+            T::Currency::reserve(&customer, deposit).map_err(|_| Error::<T>::InsufficientBalance)?;
+            NextChannelId::<T>::put(next_id);
+
+            PaymentChannels::<T>::insert(
+                channel_id,
+                PaymentChannel {
+                    customer: customer.clone(),
+                    miner: miner.clone(),
+                    task_id,
+                    deposit,
+                    voucher_key,
+                    voucher: Default::default(),
+                    status: ChannelStatus::Open,
+                    opened_at: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::ChannelOpened { channel_id, customer, miner, deposit });
+            Ok(())
+        }
+
+        /// Start closing a channel with the latest voucher. Either party may close; the customer
+        /// closes with zero usage by signing an empty voucher.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::close_channel())]
+        pub fn close_channel(
+            origin: OriginFor<T>,
+            channel_id: u64,
+            voucher: ChannelVoucher<BalanceOf<T>>,
+            signature: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let settles_at = PaymentChannels::<T>::try_mutate(channel_id, |maybe_channel| {
+                let channel = maybe_channel.as_mut().ok_or(Error::<T>::ChannelNotFound)?;
+                ensure!(who == channel.customer || who == channel.miner, Error::<T>::NotAuthorized);
+                ensure!(matches!(channel.status, ChannelStatus::Open), Error::<T>::ChannelNotOpen);
+                Self::ensure_valid_voucher(channel_id, channel, &voucher, signature)?;
+
+                let settles_at = <frame_system::Pallet<T>>::block_number()
+                    .saturating_add(T::ChannelDisputeWindow::get());
+                channel.voucher = voucher;
+                channel.status = ChannelStatus::Closing { settles_at };
+                Ok::<_, DispatchError>(settles_at)
+            })?;
+
+            Self::deposit_event(Event::ChannelClosing { channel_id, amount: voucher.amount, settles_at });
+            Ok(())
+        }
+
+        /// Replace the voucher of a closing channel with a higher one before the dispute
+        /// window ends
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::dispute_channel())]
+        pub fn dispute_channel(
+            origin: OriginFor<T>,
+            channel_id: u64,
+            voucher: ChannelVoucher<BalanceOf<T>>,
+            signature: Vec<u8>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            PaymentChannels::<T>::try_mutate(channel_id, |maybe_channel| -> DispatchResult {
+                let channel = maybe_channel.as_mut().ok_or(Error::<T>::ChannelNotFound)?;
+                let settles_at = match channel.status {
+                    ChannelStatus::Closing { settles_at } => settles_at,
+                    _ => return Err(Error::<T>::ChannelNotClosing.into()),
+                };
+                ensure!(
+                    <frame_system::Pallet<T>>::block_number() < settles_at,
+                    Error::<T>::DisputeWindowClosed
+                );
+                ensure!(voucher.amount > channel.voucher.amount, Error::<T>::VoucherNotHigher);
+                Self::ensure_valid_voucher(channel_id, channel, &voucher, signature)?;
+
+                channel.voucher = voucher;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::ChannelVoucherReplaced { channel_id, amount: voucher.amount });
+            Ok(())
+        }
+
+        /// Pay the miner the voucher amount of a channel whose dispute window has ended and
+        /// refund the rest of the deposit. The usage is booked as a settled task order.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::settle_channel())]
+        pub fn settle_channel(origin: OriginFor<T>, channel_id: u64) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let mut channel = PaymentChannels::<T>::get(channel_id).ok_or(Error::<T>::ChannelNotFound)?;
+            let settles_at = match channel.status {
+                ChannelStatus::Closing { settles_at } => settles_at,
+                _ => return Err(Error::<T>::ChannelNotClosing.into()),
+            };
+            ensure!(<frame_system::Pallet<T>>::block_number() >= settles_at, Error::<T>::DisputeWindowOpen);
+
+            let amount = channel.voucher.amount;
+            let order_id = if amount.is_zero() {
+                None
+            } else {
+                let (order_id, miner_payout) = T::OnOrderSettled::on_channel_settled(
+                    channel.task_id,
+                    &channel.customer,
+                    &channel.miner,
+                    channel.voucher.input_tokens,
+                    channel.voucher.output_tokens,
+                    amount,
+                )
+                .map_err(DispatchError::Other)?;
+                let remainder = T::Currency::repatriate_reserved(
+                    &channel.customer,
+                    &channel.miner,
+                    miner_payout,
+                    BalanceStatus::Free,
+                )?;
+                ensure!(remainder.is_zero(), Error::<T>::InsufficientBalance);
+                Some(order_id)
+            };
+
+            let refunded = channel.deposit.saturating_sub(amount);
+            T::Currency::unreserve(&channel.customer, refunded);
+
+            channel.status = ChannelStatus::Settled { order_id };
+            PaymentChannels::<T>::insert(channel_id, channel);
+
+            Self::deposit_event(Event::ChannelSettled { channel_id, order_id, amount, refunded });
+            Ok(())
+        }
+
+        /// Settle a verified intent for `settled_amount` of its amount, refunding the rest to the
+        /// merchant. The facilitator signs `partial_settlement_message` over the amount and the
+        /// hash of its off-chain `justification`, e.g. a delivery report.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::finalize_partial_settlement())]
+        pub fn finalize_partial_settlement(
+            origin: OriginFor<T>,
+            intent_id: u64,
+            settled_amount: BalanceOf<T>,
+            justification: H256,
+            facilitator_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let intent = PaymentIntents::<T>::get(intent_id).ok_or(Error::<T>::PaymentIntentNotFound)?;
+            ensure!(settled_amount <= intent.amount, Error::<T>::SettledAmountExceedsIntent);
+
+            let facilitator_info =
+                Facilitators::<T>::get(&intent.facilitator).ok_or(Error::<T>::FacilitatorNotFound)?;
+            let signature_bytes = facilitator_signature
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidJustificationSignature)?;
+            ensure!(
+                Self::verify_signature(
+                    &facilitator_info.key,
+                    &Self::partial_settlement_message(intent_id, settled_amount, justification),
+                    &signature_bytes,
+                ),
+                Error::<T>::InvalidJustificationSignature
+            );
+
+            Self::settle(caller, intent, settled_amount, Some(justification))
+        }
+
+        /// Refund a verified intent that was not finalized before it expired. Anyone can call this.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::refund_payment_intent())]
+        pub fn refund_payment_intent(origin: OriginFor<T>, intent_id: u64) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let intent = PaymentIntents::<T>::try_mutate(intent_id, |maybe_intent| {
+                let intent = maybe_intent.as_mut().ok_or(Error::<T>::PaymentIntentNotFound)?;
+                ensure!(
+                    matches!(intent.status, PaymentIntentStatus::Verified),
+                    Error::<T>::InvalidPaymentIntentStatus
+                );
+                ensure!(
+                    <frame_system::Pallet<T>>::block_number() >= intent.expires_at,
+                    Error::<T>::PaymentIntentNotExpired
+                );
+
+                Self::release_funds(intent, intent.amount)?;
+                intent.status = PaymentIntentStatus::Failed;
+                Ok::<_, DispatchError>(intent.clone())
+            })?;
+
+            Self::close_intent(&intent.facilitator);
+            Self::slash_facilitator(&intent.facilitator, intent_id);
+            FacilitatorStatsOf::<T>::mutate(&intent.facilitator, |stats| {
+                stats.expired = stats.expired.saturating_add(1);
+            });
+
+            Self::deposit_event(Event::PaymentIntentRefunded {
+                intent_id,
+                merchant: intent.merchant,
+                amount: intent.amount,
+                justification: None,
+            });
+            Ok(())
+        }
+
+        /// Allow or disallow intents in `asset` to be converted to DBC, valuing one whole unit
+        /// of it at one USD
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::set_usd_pegged_asset())]
+        pub fn set_usd_pegged_asset(origin: OriginFor<T>, asset: T::AssetId, pegged: bool) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            if pegged {
+                UsdPeggedAssets::<T>::insert(asset, true);
+            } else {
+                UsdPeggedAssets::<T>::remove(asset);
+            }
+            Self::deposit_event(Event::UsdPeggedAssetSet { asset, pegged });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Message a facilitator signs to settle intent `intent_id` for `settled_amount`
+        pub fn partial_settlement_message(intent_id: u64, settled_amount: BalanceOf<T>, justification: H256) -> Vec<u8> {
+            (b"x402/partial", intent_id, settled_amount, justification).encode()
+        }
+
+        /// Message a customer signs to authorize `voucher` on channel `channel_id`
+        pub fn channel_voucher_message(channel_id: u64, voucher: &ChannelVoucher<BalanceOf<T>>) -> Vec<u8> {
+            (b"x402/channel", channel_id, voucher).encode()
+        }
+
+        fn ensure_valid_voucher(
+            channel_id: u64,
+            channel: &PaymentChannel<T>,
+            voucher: &ChannelVoucher<BalanceOf<T>>,
+            signature: Vec<u8>,
+        ) -> DispatchResult {
+            ensure!(voucher.amount <= channel.deposit, Error::<T>::VoucherExceedsDeposit);
+            let signature_bytes = signature.try_into().map_err(|_| Error::<T>::InvalidVoucherSignature)?;
+            ensure!(
+                Self::verify_signature(
+                    &channel.voucher_key,
+                    &Self::channel_voucher_message(channel_id, voucher),
+                    &signature_bytes,
+                ),
+                Error::<T>::InvalidVoucherSignature
+            );
+            Ok(())
+        }
+
+        /// Whether `facilitator` may sign intents at block `now`
+        pub fn is_active(facilitator: &Facilitator<T>, now: BlockNumberFor<T>) -> bool {
+            now >= facilitator.active_from && facilitator.active_until.map_or(true, |until| now < until)
+        }
+
+        /// EIP-712 digest an EVM wallet signs to authorize `intent`
+        pub fn eip712_digest(intent: &Eip712PaymentIntent<T>) -> [u8; 32] {
+            let domain_separator =
+                eip712::domain_separator(T::ChainId::get(), T::Eip712VerifyingContract::get());
+            let struct_hash = eip712::payment_intent_hash(
+                intent.merchant,
+                &intent.miner,
+                intent.amount,
+                intent.nonce,
+                intent.replay_fingerprint,
+                intent.expiry.saturated_into(),
+            );
+            eip712::digest(&domain_separator, &struct_hash)
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn submit_signed(
+            merchant: T::AccountId,
+            miner: T::AccountId,
+            amount: BalanceOf<T>,
+            asset: PaymentAsset<T::AssetId>,
+            nonce: u64,
+            replay_fingerprint: H256,
+            facilitator: T::AccountId,
+            facilitator_signature: Vec<u8>,
+        ) -> DispatchResult {
+            let facilitator_info = Facilitators::<T>::get(&facilitator).ok_or(Error::<T>::FacilitatorNotFound)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(Self::is_active(&facilitator_info, now), Error::<T>::FacilitatorNotActive);
+
+            // Verify facilitator signature
+            let signature_bytes = facilitator_signature
+                .try_into()
+                .map_err(|_| Error::<T>::ArithmeticOverflow)?;
+            let message = Self::intent_message(&merchant, &miner, amount, &asset, nonce, replay_fingerprint);
+            ensure!(
+                Self::verify_signature(&facilitator_info.key, &message, &signature_bytes),
+                Error::<T>::InvalidFacilitatorSignature
+            );
+
+            Self::open_intent(
+                merchant,
+                miner,
+                amount,
+                asset,
+                nonce,
+                replay_fingerprint,
+                facilitator,
+                signature_bytes,
+                now.saturating_add(T::PaymentIntentTTL::get()),
+            )
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn open_intent(
+            merchant: T::AccountId,
+            miner: T::AccountId,
+            amount: BalanceOf<T>,
+            asset: PaymentAsset<T::AssetId>,
+            nonce: u64,
+            replay_fingerprint: H256,
+            facilitator: T::AccountId,
+            facilitator_signature: BoundedVec<u8, T::MaxSignatureLen>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            // Check nonce
+            ensure!(
+                !NonceUsed::<T>::contains_key((merchant.clone(), nonce)),
+                Error::<T>::InvalidNonce
+            );
+
+            // Check replay fingerprint
+            ensure!(
+                !ReplayFingerprintUsed::<T>::contains_key(replay_fingerprint),
+                Error::<T>::ReplayFingerprintUsed
+            );
+
+            // Hold merchant funds
+            match asset {
+                PaymentAsset::Native =>
+                    T::Currency::reserve(&merchant, amount).map_err(|_| Error::<T>::InsufficientBalance)?,
+                PaymentAsset::Asset { id, convert_to_dbc } => {
+                    if convert_to_dbc {
+                        Self::ensure_conversion_liquidity(id, amount)?;
+                    }
+                    Self::hold_asset(id, &merchant, amount)?;
+                },
+            }
 
-                // Construct sr25519 signature from bytes
-                let mut sig_bytes = [0u8; 64];
-                sig_bytes.copy_from_slice(&signature_bytes[..64]);
-                let signature = sp_core::sr25519::Signature(sig_bytes);
+            let intent_id = NextIntentId::<T>::get();
+            let next_intent_id = intent_id.checked_add(1).ok_or(Error::<T>::ArithmeticOverflow)?;
+            NextIntentId::<T>::put(next_intent_id);
 
-                // Get facilitator's sr25519 public key
-                let pubkey = sp_core::sr25519::Public(T::FacilitatorPublicKey::get());
+            // Mark nonce and replay fingerprint as used
+            NonceUsed::<T>::insert((merchant.clone(), nonce), true);
+            ReplayFingerprintUsed::<T>::insert(replay_fingerprint, true);
 
-                // Verify cryptographic signature
-                sp_io::crypto::sr25519_verify(&signature, &message, &pubkey)
+            Self::schedule_expiry(intent_id, expires_at)?;
+            PaymentIntents::<T>::insert(
+                intent_id,
+                PaymentIntent {
+                    intent_id,
+                    merchant: merchant.clone(),
+                    miner: miner.clone(),
+                    amount,
+                    asset,
+                    nonce,
+                    replay_fingerprint,
+                    facilitator: facilitator.clone(),
+                    facilitator_signature,
+                    status: PaymentIntentStatus::Pending,
+                    created_at: <frame_system::Pallet<T>>::block_number(),
+                    verified_at: None,
+                    settled_at: None,
+                    expires_at,
+                },
+            );
+
+            OpenIntents::<T>::mutate(&facilitator, |count| *count = count.saturating_add(1));
+            FacilitatorStatsOf::<T>::mutate(&facilitator, |stats| {
+                stats.submitted = stats.submitted.saturating_add(1);
+            });
+
+            Self::deposit_event(Event::PaymentIntentSubmitted {
+                intent_id,
+                merchant,
+                miner,
+                amount,
+                asset,
+                nonce,
+                facilitator,
+            });
+
+            Ok(())
+        }
+
+        /// Hold `amount` of `asset` in the account of `who`. It stays there, frozen, and the
+        /// account must keep the minimum balance of the asset on top of it.
+        pub(crate) fn hold_asset(asset: T::AssetId, who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+            let free = <T::Assets as fungibles::Inspect<T::AccountId>>::reducible_balance(
+                asset,
+                who,
+                Preservation::Preserve,
+                Fortitude::Polite,
+            );
+            ensure!(free >= amount, Error::<T>::InsufficientBalance);
+            AssetHolds::<T>::mutate(asset, who, |held| *held = held.saturating_add(amount));
+            Ok(())
+        }
+
+        /// Check that `ConversionAccount` can pay out `amount` of `asset` in DBC at the current
+        /// oracle price and stay alive
+        fn ensure_conversion_liquidity(asset: T::AssetId, amount: BalanceOf<T>) -> DispatchResult {
+            ensure!(UsdPeggedAssets::<T>::get(asset), Error::<T>::AssetNotUsdPegged);
+            let (dbc_amount, _) = Self::dbc_value(asset, amount).ok_or(Error::<T>::DbcPriceUnavailable)?;
+            let liquidity = T::Currency::free_balance(&T::ConversionAccount::get())
+                .saturating_sub(T::Currency::minimum_balance());
+            ensure!(liquidity >= dbc_amount, Error::<T>::InsufficientConversionLiquidity);
+            Ok(())
+        }
+
+        /// Release `amount` of `asset` held in the account of `who`
+        pub(crate) fn release_asset(asset: T::AssetId, who: &T::AccountId, amount: BalanceOf<T>) {
+            AssetHolds::<T>::mutate_exists(asset, who, |held| {
+                *held = held.map(|h| h.saturating_sub(amount)).filter(|h| !h.is_zero());
+            });
+        }
+
+        /// Pay out `settled_amount` of a verified intent once the settlement delay has passed
+        /// and refund the rest to the merchant
+        fn settle(
+            caller: T::AccountId,
+            mut intent: PaymentIntent<T>,
+            settled_amount: BalanceOf<T>,
+            justification: Option<H256>,
+        ) -> DispatchResult {
+            let intent_id = intent.intent_id;
+            ensure!(
+                matches!(intent.status, PaymentIntentStatus::Verified),
+                Error::<T>::InvalidPaymentIntentStatus
+            );
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() < intent.expires_at,
+                Error::<T>::PaymentIntentExpired
+            );
+
+            // Check authorization: merchant, miner, or facilitator can finalize
+            ensure!(
+                caller == intent.merchant || caller == intent.miner || caller == intent.facilitator,
+                Error::<T>::NotAuthorized
+            );
+
+            // Check settlement delay
+            let current_block = <frame_system::Pallet<T>>::block_number();
+            let verified_at = intent.verified_at.ok_or(Error::<T>::InvalidPaymentIntentStatus)?;
+            let delay_blocks = T::SettlementDelay::get();
+            ensure!(
+                current_block >= verified_at.saturating_add(delay_blocks),
+                Error::<T>::SettlementDelayNotMet
+            );
+
+            // The facilitator takes its fee at the current rate, the miner gets the rest.
+            // A removed facilitator takes no fee.
+            let fee = Facilitators::<T>::get(&intent.facilitator)
+                .map(|f| f.fee_rate * settled_amount)
+                .unwrap_or_default();
+            let exchange_rate = Self::pay_out(&intent, settled_amount, fee)?;
+            let refunded = intent.amount.saturating_sub(settled_amount);
+            if refunded > 0 {
+                Self::release_funds(&intent, refunded)?;
+            }
+
+            // Update intent status
+            intent.status = PaymentIntentStatus::Settled;
+            intent.settled_at = Some(current_block);
+            PaymentIntents::<T>::insert(intent_id, &intent);
+
+            // Create settlement receipt
+            let receipt = SettlementReceipt {
+                intent_id,
+                merchant: intent.merchant.clone(),
+                miner: intent.miner.clone(),
+                amount: settled_amount,
+                refunded,
+                asset: match intent.asset {
+                    PaymentAsset::Native => None,
+                    PaymentAsset::Asset { id, .. } => Some(id),
+                },
+                exchange_rate,
+                settled_at: current_block.saturated_into(),
+                tx_hash: H256::from_low_u64_be(intent_id),
+            };
+            SettlementReceipts::<T>::insert(intent_id, receipt);
+
+            Self::close_intent(&intent.facilitator);
+            FacilitatorStatsOf::<T>::mutate(&intent.facilitator, |stats| {
+                stats.settled = stats.settled.saturating_add(1);
+                if intent.asset == PaymentAsset::Native {
+                    stats.volume = stats.volume.saturating_add(settled_amount);
+                    stats.fees = stats.fees.saturating_add(fee);
+                }
+            });
+            if fee > 0 {
+                Self::deposit_event(Event::FacilitatorFeePaid {
+                    intent_id,
+                    facilitator: intent.facilitator.clone(),
+                    fee,
+                });
+            }
+            if refunded > 0 {
+                Self::deposit_event(Event::PaymentIntentRefunded {
+                    intent_id,
+                    merchant: intent.merchant.clone(),
+                    amount: refunded,
+                    justification,
+                });
+            }
+
+            Self::deposit_event(Event::PaymentIntentSettled {
+                intent_id,
+                merchant: intent.merchant,
+                miner: intent.miner,
+                amount: settled_amount,
+            });
+
+            Ok(())
+        }
+
+        /// Queue `intent_id` for expiry in the first block from `expires_at` whose queue has room
+        pub(crate) fn schedule_expiry(intent_id: u64, expires_at: BlockNumberFor<T>) -> DispatchResult {
+            let mut at = expires_at;
+            for _ in 0..=crate::MAX_EXPIRY_CARRY_OVER {
+                if ExpiryQueue::<T>::mutate(at, |queue| queue.try_push(intent_id)).is_ok() {
+                    return Ok(())
+                }
+                at = at.saturating_add(One::one());
+            }
+            Err(Error::<T>::TooManyPendingIntents.into())
+        }
+
+        /// Fail a pending intent that has reached its expiry block and return its funds
+        fn expire_intent(intent_id: u64) {
+            let intent = match PaymentIntents::<T>::get(intent_id) {
+                Some(intent) if matches!(intent.status, PaymentIntentStatus::Pending) => intent,
+                _ => return,
+            };
+            if let Err(e) = Self::release_funds(&intent, intent.amount) {
+                log::warn!(target: crate::LOG_TARGET, "failed to release expired intent {}: {:?}", intent_id, e);
+            }
+            PaymentIntents::<T>::mutate(intent_id, |maybe| {
+                if let Some(ref mut i) = maybe {
+                    i.status = PaymentIntentStatus::Failed;
+                }
+            });
+            Self::close_intent(&intent.facilitator);
+            FacilitatorStatsOf::<T>::mutate(&intent.facilitator, |stats| {
+                stats.expired = stats.expired.saturating_add(1);
+            });
+            Self::deposit_event(Event::PaymentIntentExpired {
+                intent_id,
+                merchant: intent.merchant,
+                amount: intent.amount,
+            });
+        }
+
+        /// Count an intent signed by `facilitator` as settled or failed
+        fn close_intent(facilitator: &T::AccountId) {
+            OpenIntents::<T>::mutate_exists(facilitator, |count| {
+                *count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+            });
+        }
+
+        /// Slash `FacilitatorSlash` from the stake of `facilitator`, which verified `intent_id`
+        /// before it failed or expired unsettled. The slashed funds are burned.
+        fn slash_facilitator(facilitator: &T::AccountId, intent_id: u64) {
+            let amount = Facilitators::<T>::mutate(facilitator, |maybe_info| {
+                let info = match maybe_info {
+                    Some(info) => info,
+                    None => return 0,
+                };
+                let due = T::FacilitatorSlash::get().min(info.stake);
+                let (_imbalance, unslashed) = T::Currency::slash_reserved(facilitator, due);
+                let slashed = due.saturating_sub(unslashed);
+                info.stake = info.stake.saturating_sub(slashed);
+                slashed
+            });
+            if amount > 0 {
+                Self::deposit_event(Event::FacilitatorSlashed {
+                    facilitator: facilitator.clone(),
+                    intent_id,
+                    amount,
+                });
+            }
+        }
+
+        /// Return `amount` of the funds held for `intent` to its merchant
+        fn release_funds(intent: &PaymentIntent<T>, amount: BalanceOf<T>) -> DispatchResult {
+            match intent.asset {
+                PaymentAsset::Native => {
+                    T::Currency::unreserve(&intent.merchant, amount);
+                },
+                PaymentAsset::Asset { id, .. } => Self::release_asset(id, &intent.merchant, amount),
+            }
+            Ok(())
+        }
+
+        /// Pay `settled_amount` of a settling intent, `fee` to its facilitator and the rest to its
+        /// miner, returning the exchange rate when the miner is paid in DBC for an asset
+        fn pay_out(
+            intent: &PaymentIntent<T>,
+            settled_amount: BalanceOf<T>,
+            fee: BalanceOf<T>,
+        ) -> Result<Option<BalanceOf<T>>, DispatchError> {
+            let rest = settled_amount.saturating_sub(fee);
+            match intent.asset {
+                PaymentAsset::Native => {
+                    if fee > 0 {
+                        T::Currency::repatriate_reserved(
+                            &intent.merchant,
+                            &intent.facilitator,
+                            fee,
+                            frame_support::traits::BalanceStatus::Free,
+                        )
+                        .map_err(|_| Error::<T>::InsufficientBalance)?;
+                    }
+
+                    // Transfer funds from merchant to miner
+                    T::Currency::repatriate_reserved(
+                        &intent.merchant,
+                        &intent.miner,
+                        rest,
+                        frame_support::traits::BalanceStatus::Free,
+                    )
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                    Ok(None)
+                },
+                PaymentAsset::Asset { id, convert_to_dbc } => {
+                    // The settled amount is released and paid out of the merchant's account
+                    let merchant = &intent.merchant;
+                    Self::release_asset(id, merchant, settled_amount);
+                    if fee > 0 {
+                        T::Assets::transfer(id, merchant, &intent.facilitator, fee, Preservation::Expendable)?;
+                    }
+                    if !convert_to_dbc {
+                        T::Assets::transfer(id, merchant, &intent.miner, rest, Preservation::Expendable)?;
+                        return Ok(None)
+                    }
+
+                    let (dbc_amount, exchange_rate) =
+                        Self::dbc_value(id, rest).ok_or(Error::<T>::DbcPriceUnavailable)?;
+                    let conversion_account = T::ConversionAccount::get();
+                    T::Assets::transfer(id, merchant, &conversion_account, rest, Preservation::Expendable)?;
+                    T::Currency::transfer(
+                        &conversion_account,
+                        &intent.miner,
+                        dbc_amount,
+                        ExistenceRequirement::KeepAlive,
+                    )
+                    .map_err(|_| Error::<T>::ConversionFailed)?;
+
+                    Self::deposit_event(Event::PaymentConverted {
+                        intent_id: intent.intent_id,
+                        asset: id,
+                        amount: rest,
+                        dbc_amount,
+                        exchange_rate,
+                    });
+                    Ok(Some(exchange_rate))
+                },
+            }
+        }
+
+        /// DBC worth `amount` of a USD-pegged `asset` at the oracle price, valuing one whole unit
+        /// of the asset at one USD, together with the DBC price used. `None` for other assets.
+        pub fn dbc_value(asset: T::AssetId, amount: BalanceOf<T>) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
+            if !UsdPeggedAssets::<T>::get(asset) {
+                return None
+            }
+            let decimals = <T::Assets as fungibles::metadata::Inspect<T::AccountId>>::decimals(asset);
+            // The oracle takes values in millionths of a USD
+            let value = amount.checked_mul(1_000_000)?.checked_div(10u128.checked_pow(decimals.into())?)?;
+            let dbc_amount = T::DbcPrice::get_dbc_amount_by_value(value.try_into().ok()?)?;
+            Some((dbc_amount, T::DbcPrice::get_dbc_price()?))
+        }
+
+        /// Message a facilitator signs for a payment intent. Asset intents append the encoded
+        /// `(asset, convert_to_dbc)`.
+        pub fn intent_message(
+            merchant: &T::AccountId,
+            miner: &T::AccountId,
+            amount: BalanceOf<T>,
+            asset: &PaymentAsset<T::AssetId>,
+            nonce: u64,
+            replay_fingerprint: H256,
+        ) -> Vec<u8> {
+            // SCALE-encode all payment parameters
+            let mut message = Vec::new();
+            merchant.encode_to(&mut message);
+            miner.encode_to(&mut message);
+            amount.encode_to(&mut message);
+            nonce.encode_to(&mut message);
+            replay_fingerprint.encode_to(&mut message);
+            if let PaymentAsset::Asset { id, convert_to_dbc } = asset {
+                (id, convert_to_dbc).encode_to(&mut message);
+            }
+            message
+        }
+
+        fn verify_signature(
+            key: &FacilitatorKey,
+            message: &[u8],
+            signature_bytes: &BoundedVec<u8, T::MaxSignatureLen>,
+        ) -> bool {
+            // Bypass signature verification during benchmarks
+            #[cfg(feature = "runtime-benchmarks")]
+            {
+                let _ = (key, message, signature_bytes);
+                return true;
+            }
+
+            #[cfg(not(feature = "runtime-benchmarks"))]
+            {
+                match key {
+                    FacilitatorKey::Sr25519(public) => <[u8; 64]>::try_from(&signature_bytes[..]).map_or(false, |sig| {
+                        sp_io::crypto::sr25519_verify(
+                            &sp_core::sr25519::Signature(sig),
+                            message,
+                            &sp_core::sr25519::Public(*public),
+                        )
+                    }),
+                    FacilitatorKey::Ed25519(public) => <[u8; 64]>::try_from(&signature_bytes[..]).map_or(false, |sig| {
+                        sp_io::crypto::ed25519_verify(
+                            &sp_core::ed25519::Signature(sig),
+                            message,
+                            &sp_core::ed25519::Public(*public),
+                        )
+                    }),
+                    // Recoverable signature over the blake2_256 hash of the message
+                    FacilitatorKey::Secp256k1(public) => <[u8; 65]>::try_from(&signature_bytes[..]).map_or(false, |sig| {
+                        sp_io::crypto::ecdsa_verify(
+                            &sp_core::ecdsa::Signature(sig),
+                            message,
+                            &sp_core::ecdsa::Public(*public),
+                        )
+                    }),
+                }
             }
         }
 
@@ -515,7 +1618,7 @@ pub mod pallet {
             PaymentIntents::<T>::get(intent_id)
         }
 
-        pub fn get_settlement_receipt(intent_id: u64) -> Option<SettlementReceipt<T::AccountId, BalanceOf<T>>> {
+        pub fn get_settlement_receipt(intent_id: u64) -> Option<SettlementReceiptOf<T>> {
             SettlementReceipts::<T>::get(intent_id)
         }
 
@@ -534,39 +1637,109 @@ impl<T: pallet::Config> dbc_support::traits::AttestationSettler for pallet::Pall
     type Balance = pallet::BalanceOf<T>;
 
     fn settle_for_attestation(
-        merchant: &Self::AccountId,
+        order: &dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>,
         miner: &Self::AccountId,
-        amount: Self::Balance,
+        attestation_id: u64,
+    ) -> Result<u64, &'static str> {
+        use sp_runtime::DispatchError;
+
+        // Either the miner is paid and the order is closed, or nothing changes
+        frame_support::storage::transactional::with_storage_layer(|| {
+            Self::settle_order(order, miner, attestation_id).map_err(DispatchError::Other)
+        })
+        .map_err(|e| match e {
+            DispatchError::Other(reason) => reason,
+            _ => "Settlement failed",
+        })
+    }
+
+    fn refund_for_attestation(
+        order: &dbc_support::traits::TaskOrderContext<Self::AccountId, Self::Balance>,
         _attestation_id: u64,
+    ) -> Result<Self::Balance, &'static str> {
+        use dbc_support::traits::TaskOrderSettlementHandler;
+
+        if pallet::OrderReceipts::<T>::contains_key(order.order_id) {
+            return Err("Order already settled");
+        }
+        // The customer's reserve is held by the order owner, which releases all of it
+        T::OnOrderSettled::on_order_refunded(order.order_id)
+    }
+}
+
+impl<T: pallet::Config> pallet::Pallet<T> {
+    fn settle_order(
+        order: &dbc_support::traits::TaskOrderContext<T::AccountId, pallet::BalanceOf<T>>,
+        miner: &T::AccountId,
+        attestation_id: u64,
     ) -> Result<u64, &'static str> {
-        use frame_support::traits::{Currency, ExistenceRequirement};
+        use codec::Encode;
+        use dbc_support::traits::TaskOrderSettlementHandler;
+        use frame_support::traits::{BalanceStatus, ReservableCurrency};
+        use sp_runtime::traits::Zero;
+
+        if pallet::OrderReceipts::<T>::contains_key(order.order_id) {
+            return Err("Order already settled");
+        }
+
+        // Let the order owner validate and close the order before any funds move, so a
+        // stale or already settled order cannot draw on the customer's other reserves.
+        T::OnOrderSettled::on_order_settled(order.order_id, miner, order.agreed_price)?;
 
         let intent_id = pallet::NextIntentId::<T>::get();
         let next_id = intent_id.checked_add(1).ok_or("Intent ID overflow")?;
         pallet::NextIntentId::<T>::put(next_id);
 
-        // Direct transfer instead of reserve + repatriate to avoid double-charging
-        T::Currency::transfer(merchant, miner, amount, ExistenceRequirement::KeepAlive)
-            .map_err(|_| "Failed to transfer to miner")?;
+        // The agreed price was reserved from the customer when the order was created
+        let remainder = T::Currency::repatriate_reserved(
+            &order.customer,
+            miner,
+            order.agreed_price,
+            BalanceStatus::Free,
+        )
+        .map_err(|_| "Failed to transfer to miner")?;
+        if !remainder.is_zero() {
+            return Err("Insufficient reserved balance");
+        }
 
         let now = frame_system::Pallet::<T>::block_number();
         let receipt = pallet::SettlementReceipt {
             intent_id,
-            merchant: merchant.clone(),
+            merchant: order.customer.clone(),
             miner: miner.clone(),
-            amount,
+            amount: order.agreed_price,
+            refunded: 0,
+            asset: None,
+            exchange_rate: None,
             settled_at: sp_runtime::traits::SaturatedConversion::saturated_into(now),
-            tx_hash: sp_core::H256::from_low_u64_be(intent_id),
+            tx_hash: sp_core::H256::from(sp_io::hashing::blake2_256(
+                &(order.order_id, attestation_id).encode(),
+            )),
         };
         pallet::SettlementReceipts::<T>::insert(intent_id, receipt);
+        pallet::OrderReceipts::<T>::insert(order.order_id, intent_id);
 
         pallet::Pallet::<T>::deposit_event(pallet::Event::PaymentIntentSettled {
             intent_id,
-            merchant: merchant.clone(),
+            merchant: order.customer.clone(),
             miner: miner.clone(),
-            amount,
+            amount: order.agreed_price,
         });
 
         Ok(intent_id)
     }
 }
+
+/// Freezes the assets held for open asset intents in their merchants' accounts
+impl<T: pallet::Config> pallet_assets::FrozenBalance<T::AssetId, T::AccountId, pallet::BalanceOf<T>>
+    for pallet::Pallet<T>
+{
+    fn frozen_balance(asset: T::AssetId, who: &T::AccountId) -> Option<pallet::BalanceOf<T>> {
+        use sp_runtime::traits::Zero;
+        Some(pallet::AssetHolds::<T>::get(asset, who)).filter(|held| !held.is_zero())
+    }
+
+    fn died(asset: T::AssetId, who: &T::AccountId) {
+        pallet::AssetHolds::<T>::remove(asset, who);
+    }
+}
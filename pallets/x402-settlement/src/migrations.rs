@@ -0,0 +1,434 @@
+use crate::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_core::H256;
+use sp_runtime::Perbill;
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+pub mod v2 {
+    use super::*;
+
+    #[derive(Decode)]
+    struct OldPaymentIntent<T: Config> {
+        intent_id: u64,
+        merchant: T::AccountId,
+        miner: T::AccountId,
+        amount: BalanceOf<T>,
+        nonce: u64,
+        replay_fingerprint: H256,
+        facilitator_signature: BoundedVec<u8, T::MaxSignatureLen>,
+        status: PaymentIntentStatus,
+        created_at: BlockNumberFor<T>,
+        verified_at: Option<BlockNumberFor<T>>,
+        settled_at: Option<BlockNumberFor<T>>,
+        expires_at: BlockNumberFor<T>,
+    }
+
+    #[derive(Encode, Decode)]
+    pub(crate) struct PaymentIntent<T: Config> {
+        pub intent_id: u64,
+        pub merchant: T::AccountId,
+        pub miner: T::AccountId,
+        pub amount: BalanceOf<T>,
+        pub nonce: u64,
+        pub replay_fingerprint: H256,
+        pub facilitator: T::AccountId,
+        pub facilitator_signature: BoundedVec<u8, T::MaxSignatureLen>,
+        pub status: PaymentIntentStatus,
+        pub created_at: BlockNumberFor<T>,
+        pub verified_at: Option<BlockNumberFor<T>>,
+        pub settled_at: Option<BlockNumberFor<T>>,
+        pub expires_at: BlockNumberFor<T>,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type PaymentIntents<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, u64, PaymentIntent<T>>;
+
+    /// Moves from the single facilitator configured by runtime constants to the registry.
+    /// `LegacyAccount` is registered with its sr25519 `LegacyKey`, no stake and no fee, and
+    /// becomes the facilitator of every existing intent.
+    pub struct Migration<T, LegacyAccount, LegacyKey>(PhantomData<(T, LegacyAccount, LegacyKey)>);
+    impl<T, LegacyAccount, LegacyKey> OnRuntimeUpgrade for Migration<T, LegacyAccount, LegacyKey>
+    where
+        T: Config,
+        LegacyAccount: Get<T::AccountId>,
+        LegacyKey: Get<[u8; 32]>,
+    {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 1 {
+                log::info!(target: LOG_TARGET, "v2 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>(LegacyAccount::get(), FacilitatorKey::Sr25519(LegacyKey::get()))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 1, "this migration can be deleted");
+            Ok((PaymentIntents::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let intents: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration needs to be removed");
+            ensure!(
+                PaymentIntents::<T>::iter().count() as u64 == intents,
+                "payment intents were lost"
+            );
+            ensure!(
+                Facilitators::<T>::contains_key(LegacyAccount::get()),
+                "legacy facilitator is not registered"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>(legacy: T::AccountId, key: FacilitatorKey) -> Weight {
+        let now = <frame_system::Pallet<T>>::block_number();
+        if !Facilitators::<T>::contains_key(&legacy) {
+            Facilitators::<T>::insert(
+                &legacy,
+                Facilitator {
+                    key,
+                    stake: 0,
+                    fee_rate: Perbill::zero(),
+                    active_from: now,
+                    active_until: None,
+                },
+            );
+        }
+
+        let mut translated = 0u64;
+        PaymentIntents::<T>::translate(|_id, old: OldPaymentIntent<T>| {
+            translated += 1;
+            Some(PaymentIntent {
+                intent_id: old.intent_id,
+                merchant: old.merchant,
+                miner: old.miner,
+                amount: old.amount,
+                nonce: old.nonce,
+                replay_fingerprint: old.replay_fingerprint,
+                facilitator: legacy.clone(),
+                facilitator_signature: old.facilitator_signature,
+                status: old.status,
+                created_at: old.created_at,
+                verified_at: old.verified_at,
+                settled_at: old.settled_at,
+                expires_at: old.expires_at,
+            })
+        });
+
+        StorageVersion::new(2).put::<Pallet<T>>();
+
+        log::info!(target: LOG_TARGET, "v2 migration moved {} payment intents to the facilitator registry", translated);
+        T::DbWeight::get().reads_writes(translated + 2, translated + 2)
+    }
+}
+
+pub mod v3 {
+    use super::*;
+
+    #[derive(Decode)]
+    struct OldSettlementReceipt<AccountId, Balance> {
+        intent_id: u64,
+        merchant: AccountId,
+        miner: AccountId,
+        amount: Balance,
+        settled_at: u64,
+        tx_hash: H256,
+    }
+
+    /// Settlement receipt as of storage version 3
+    #[derive(Encode, Decode)]
+    pub(crate) struct SettlementReceipt<AccountId, Balance, AssetId> {
+        pub intent_id: u64,
+        pub merchant: AccountId,
+        pub miner: AccountId,
+        pub amount: Balance,
+        pub asset: Option<AssetId>,
+        pub exchange_rate: Option<Balance>,
+        pub settled_at: u64,
+        pub tx_hash: H256,
+    }
+
+    #[frame_support::storage_alias]
+    pub(crate) type SettlementReceipts<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        u64,
+        SettlementReceipt<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as Config>::AssetId>,
+    >;
+
+    /// Payment intents and receipts gain an asset. Everything before is native DBC.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 2 {
+                log::info!(target: LOG_TARGET, "v3 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration can be deleted");
+            let intents = PaymentIntents::<T>::iter_keys().count() as u64;
+            let receipts = SettlementReceipts::<T>::iter_keys().count() as u64;
+            Ok((intents, receipts).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (intents, receipts): (u64, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration needs to be removed");
+            ensure!(PaymentIntents::<T>::iter().count() as u64 == intents, "payment intents were lost");
+            ensure!(
+                SettlementReceipts::<T>::iter().count() as u64 == receipts,
+                "settlement receipts were lost"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut intents = 0u64;
+        PaymentIntents::<T>::translate(|_id, old: v2::PaymentIntent<T>| {
+            intents += 1;
+            Some(PaymentIntent {
+                intent_id: old.intent_id,
+                merchant: old.merchant,
+                miner: old.miner,
+                amount: old.amount,
+                asset: PaymentAsset::Native,
+                nonce: old.nonce,
+                replay_fingerprint: old.replay_fingerprint,
+                facilitator: old.facilitator,
+                facilitator_signature: old.facilitator_signature,
+                status: old.status,
+                created_at: old.created_at,
+                verified_at: old.verified_at,
+                settled_at: old.settled_at,
+                expires_at: old.expires_at,
+            })
+        });
+
+        let mut receipts = 0u64;
+        SettlementReceipts::<T>::translate(|_id, old: OldSettlementReceipt<T::AccountId, BalanceOf<T>>| {
+            receipts += 1;
+            Some(SettlementReceipt {
+                intent_id: old.intent_id,
+                merchant: old.merchant,
+                miner: old.miner,
+                amount: old.amount,
+                asset: None,
+                exchange_rate: None,
+                settled_at: old.settled_at,
+                tx_hash: old.tx_hash,
+            })
+        });
+
+        StorageVersion::new(3).put::<Pallet<T>>();
+
+        log::info!(target: LOG_TARGET, "v3 migration moved {} payment intents and {} receipts", intents, receipts);
+        T::DbWeight::get().reads_writes(intents + receipts + 1, intents + receipts + 1)
+    }
+}
+
+pub mod v4 {
+    use super::*;
+
+    /// Settlement receipts record the part of an intent refunded to the merchant. Every
+    /// intent settled before paid out its full amount.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 3 {
+                log::info!(target: LOG_TARGET, "v4 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration can be deleted");
+            Ok((SettlementReceipts::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let receipts: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 4, "this migration needs to be removed");
+            ensure!(
+                SettlementReceipts::<T>::iter().count() as u64 == receipts,
+                "settlement receipts were lost"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut receipts = 0u64;
+        SettlementReceipts::<T>::translate(
+            |_id, old: v3::SettlementReceipt<T::AccountId, BalanceOf<T>, T::AssetId>| {
+                receipts += 1;
+                Some(SettlementReceipt {
+                    intent_id: old.intent_id,
+                    merchant: old.merchant,
+                    miner: old.miner,
+                    amount: old.amount,
+                    refunded: 0,
+                    asset: old.asset,
+                    exchange_rate: old.exchange_rate,
+                    settled_at: old.settled_at,
+                    tx_hash: old.tx_hash,
+                })
+            },
+        );
+
+        StorageVersion::new(4).put::<Pallet<T>>();
+
+        log::info!(target: LOG_TARGET, "v4 migration moved {} receipts", receipts);
+        T::DbWeight::get().reads_writes(receipts + 1, receipts + 1)
+    }
+}
+
+pub mod v5 {
+    use super::*;
+    use sp_runtime::traits::{One, Saturating};
+
+    #[frame_support::storage_alias]
+    pub(crate) type PendingIntentIds<T: Config> =
+        StorageValue<Pallet<T>, BoundedVec<u64, ConstU32<10000>>, ValueQuery>;
+
+    /// Moves pending intents from the single list scanned by `on_initialize` to the expiry
+    /// queue of the block they expire in. Intents already past expiry are queued for the
+    /// upgrade block.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 4 {
+                log::info!(target: LOG_TARGET, "v5 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 4, "this migration can be deleted");
+            let pending = PendingIntentIds::<T>::get()
+                .into_iter()
+                .filter(|id| {
+                    PaymentIntents::<T>::get(id)
+                        .map_or(false, |intent| matches!(intent.status, PaymentIntentStatus::Pending))
+                })
+                .count() as u64;
+            Ok(pending.encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let pending: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(Pallet::<T>::on_chain_storage_version() == 5, "this migration needs to be removed");
+            ensure!(!PendingIntentIds::<T>::exists(), "pending intent ids were not removed");
+            ensure!(
+                ExpiryQueue::<T>::iter_values().map(|queue| queue.len() as u64).sum::<u64>() == pending,
+                "pending intents were lost"
+            );
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut reads = 2u64;
+        let mut writes = 2u64;
+        let mut moved = 0u64;
+
+        for id in PendingIntentIds::<T>::take() {
+            reads += 1;
+            let intent = match PaymentIntents::<T>::get(id) {
+                Some(intent) if matches!(intent.status, PaymentIntentStatus::Pending) => intent,
+                _ => continue,
+            };
+            // Unlike new intents, existing ones are carried over as far as it takes to queue them
+            let mut at = intent.expires_at.max(now);
+            loop {
+                reads += 1;
+                if ExpiryQueue::<T>::mutate(at, |queue| queue.try_push(id)).is_ok() {
+                    writes += 1;
+                    break
+                }
+                at = at.saturating_add(One::one());
+            }
+            moved += 1;
+        }
+
+        StorageVersion::new(5).put::<Pallet<T>>();
+
+        log::info!(target: LOG_TARGET, "v5 migration queued {} pending intents for expiry", moved);
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+}
+
+pub mod v6 {
+    use super::*;
+
+    /// Counts the pending and verified intents of every facilitator, which it must be free of
+    /// before it can be removed.
+    pub struct Migration<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() != 5 {
+                log::info!(target: LOG_TARGET, "v6 migration skipped, remove it from the runtime");
+                return T::DbWeight::get().reads(1)
+            }
+            migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 5, "this migration can be deleted");
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            ensure!(Pallet::<T>::on_chain_storage_version() == 6, "this migration needs to be removed");
+            let open = PaymentIntents::<T>::iter_values()
+                .filter(|intent| {
+                    matches!(intent.status, PaymentIntentStatus::Pending | PaymentIntentStatus::Verified)
+                })
+                .count() as u64;
+            let counted: u64 = OpenIntents::<T>::iter_values().map(u64::from).sum();
+            ensure!(open == counted, "open intents were miscounted");
+            Ok(())
+        }
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut read = 0u64;
+        let mut counted = 0u64;
+        for intent in PaymentIntents::<T>::iter_values() {
+            read += 1;
+            if matches!(intent.status, PaymentIntentStatus::Pending | PaymentIntentStatus::Verified) {
+                OpenIntents::<T>::mutate(&intent.facilitator, |count| *count = count.saturating_add(1));
+                counted += 1;
+            }
+        }
+
+        StorageVersion::new(6).put::<Pallet<T>>();
+
+        log::info!(target: LOG_TARGET, "v6 migration counted {} open intents", counted);
+        T::DbWeight::get().reads_writes(read + counted + 1, counted + 1)
+    }
+}
@@ -1,9 +1,9 @@
 use crate as pallet_x402_settlement;
 use frame_support::{
     parameter_types,
-    traits::{ConstU16, ConstU32},
+    traits::{AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32},
 };
-use sp_core::H256;
+use sp_core::{H160, H256};
 use sp_runtime::{
     generic::Header,
     traits::{BlakeTwo256, IdentityLookup},
@@ -12,6 +12,15 @@ use sp_runtime::{
 pub type AccountId = u64;
 pub type BlockNumber = u64;
 
+/// Facilitator registered at genesis, signing with `FacilitatorPublicKeyValue`
+pub const FACILITATOR: AccountId = 100;
+/// Swaps converted assets for DBC
+pub const CONVERSION_ACCOUNT: AccountId = 400;
+/// Stablecoin with 6 decimals, valued at one USD
+pub const USDC: u32 = 7;
+/// Task definition payment channels may bill against
+pub const BILLABLE_TASK: u64 = 0;
+
 frame_support::construct_runtime!(
     pub enum Test where
         Block = Block,
@@ -20,6 +29,7 @@ frame_support::construct_runtime!(
     {
         System: frame_system,
         Balances: pallet_balances,
+        Assets: pallet_assets,
         X402Settlement: pallet_x402_settlement,
     }
 );
@@ -31,7 +41,6 @@ parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const ExistentialDeposit: u128 = 1;
     pub const MaxLocks: u32 = 50;
-    pub const FacilitatorAccount: AccountId = 100;
     pub const MaxSignatureLen: u32 = 256;
     pub FacilitatorPublicKeyValue: [u8; 32] = {
         use sp_core::Pair;
@@ -39,6 +48,26 @@ parameter_types! {
     };
     pub const SettlementDelay: BlockNumber = 10;
     pub const PaymentIntentTTL: BlockNumber = 100;
+    pub const ChannelDisputeWindow: BlockNumber = 20;
+    pub const MaxExpiriesPerBlock: u32 = 4;
+    pub const MinFacilitatorStake: u128 = 1_000;
+    pub const FacilitatorSlash: u128 = 400;
+    pub const ConversionAccount: AccountId = CONVERSION_ACCOUNT;
+    pub const MinLockAmount: u128 = 1;
+    pub const MaxLockDuration: BlockNumber = 1_000;
+    pub const ChainId: u64 = 19880818;
+    pub Eip712VerifyingContract: H160 = H160::from_low_u64_be(2100);
+}
+
+/// Maps an EVM address to the account in its low 8 bytes
+pub struct TestAddressMapping;
+
+impl pallet_evm::AddressMapping<AccountId> for TestAddressMapping {
+    fn into_account_id(address: H160) -> AccountId {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&address.as_bytes()[12..]);
+        AccountId::from_be_bytes(bytes)
+    }
 }
 
 impl frame_system::Config for Test {
@@ -84,16 +113,132 @@ impl pallet_balances::Config for Test {
     type HoldIdentifier = ();
 }
 
+impl pallet_assets::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = u128;
+    type AssetId = u32;
+    type AssetIdParameter = u32;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+    type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+    type AssetDeposit = ConstU128<1>;
+    type AssetAccountDeposit = ConstU128<1>;
+    type MetadataDepositBase = ConstU128<1>;
+    type MetadataDepositPerByte = ConstU128<1>;
+    type ApprovalDeposit = ConstU128<1>;
+    type StringLimit = ConstU32<50>;
+    type Freezer = X402Settlement;
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = ();
+    type RemoveItemsLimit = ConstU32<5>;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+    type MinLockAmount = MinLockAmount;
+    type MaxLockDuration = MaxLockDuration;
+}
+
 impl crate::pallet::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type Currency = Balances;
-    type FacilitatorAccount = FacilitatorAccount;
     type MaxSignatureLen = MaxSignatureLen;
-    type FacilitatorPublicKey = FacilitatorPublicKeyValue;
+    type MinFacilitatorStake = MinFacilitatorStake;
+    type FacilitatorSlash = FacilitatorSlash;
     type SettlementDelay = SettlementDelay;
     type PaymentIntentTTL = PaymentIntentTTL;
+    type ChannelDisputeWindow = ChannelDisputeWindow;
+    type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+    type AssetId = u32;
+    type Assets = Assets;
+    type DbcPrice = MockDbcPrice;
+    type ConversionAccount = ConversionAccount;
+    type AddressMapping = TestAddressMapping;
+    type ChainId = ChainId;
+    type Eip712VerifyingContract = Eip712VerifyingContract;
     type WeightInfo = ();
-    type AdminOrigin = frame_system::EnsureSigned<AccountId>;
+    type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+    type OnOrderSettled = MockOrderSettlementHandler;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+}
+
+thread_local! {
+    pub static SETTLED_ORDERS: std::cell::RefCell<Vec<(u64, AccountId, u128)>> =
+        std::cell::RefCell::new(Vec::new());
+    pub static REJECT_ORDER_SETTLEMENT: std::cell::RefCell<bool> = std::cell::RefCell::new(false);
+    pub static REFUNDED_ORDERS: std::cell::RefCell<Vec<u64>> = std::cell::RefCell::new(Vec::new());
+    /// (task_id, customer, miner, input_tokens, output_tokens, amount) of settled channels
+    pub static SETTLED_CHANNELS: std::cell::RefCell<Vec<(u64, AccountId, AccountId, u64, u64, u128)>> =
+        std::cell::RefCell::new(Vec::new());
+    /// DBC price in millionths of a USD
+    pub static DBC_PRICE: std::cell::RefCell<Option<u128>> = std::cell::RefCell::new(Some(10_000));
+}
+
+/// Prices DBC like `dbc-price-ocw`, at `DBC_PRICE`
+pub struct MockDbcPrice;
+
+impl dbc_support::traits::DbcPrice for MockDbcPrice {
+    type Balance = u128;
+
+    fn get_dbc_price() -> Option<u128> {
+        DBC_PRICE.with(|p| *p.borrow())
+    }
+
+    fn get_dbc_amount_by_value(value: u64) -> Option<u128> {
+        (value as u128).checked_mul(1_000_000_000_000_000)?.checked_div(Self::get_dbc_price()?)
+    }
+
+    fn get_dlc_amount_by_value(_value: u64) -> Option<u128> {
+        None
+    }
+}
+
+// Mock implementation for TaskOrderSettlementHandler
+pub struct MockOrderSettlementHandler;
+
+impl dbc_support::traits::TaskOrderSettlementHandler for MockOrderSettlementHandler {
+    type AccountId = AccountId;
+    type Balance = u128;
+
+    fn on_order_settled(
+        order_id: u64,
+        miner: &Self::AccountId,
+        settled_amount: Self::Balance,
+    ) -> Result<(), &'static str> {
+        if REJECT_ORDER_SETTLEMENT.with(|r| *r.borrow()) {
+            return Err("Task order not open");
+        }
+        SETTLED_ORDERS.with(|s| s.borrow_mut().push((order_id, *miner, settled_amount)));
+        Ok(())
+    }
+
+    fn on_order_refunded(order_id: u64) -> Result<Self::Balance, &'static str> {
+        if REJECT_ORDER_SETTLEMENT.with(|r| *r.borrow()) {
+            return Err("Task order not open");
+        }
+        REFUNDED_ORDERS.with(|r| r.borrow_mut().push(order_id));
+        Ok(0)
+    }
+
+    fn is_billable_task(task_id: u64) -> bool {
+        task_id == BILLABLE_TASK
+    }
+
+    /// Books channels as orders 1000, 1001, ... and leaves the whole amount to the miner
+    fn on_channel_settled(
+        task_id: u64,
+        customer: &Self::AccountId,
+        miner: &Self::AccountId,
+        input_tokens: u64,
+        output_tokens: u64,
+        amount: Self::Balance,
+    ) -> Result<(u64, Self::Balance), &'static str> {
+        SETTLED_CHANNELS.with(|s| {
+            let mut settled = s.borrow_mut();
+            settled.push((task_id, *customer, *miner, input_tokens, output_tokens, amount));
+            Ok((999 + settled.len() as u64, amount))
+        })
+    }
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -107,14 +252,34 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
             (2, 1_000_000_000_000),
             (3, 1_000_000_000_000),
             (100, 1_000_000_000_000),
+            (CONVERSION_ACCOUNT, 1_000_000_000_000_000_000),
         ],
     }
     .assimilate_storage(&mut t)
     .expect("balances storage assimilates");
 
+    pallet_assets::GenesisConfig::<Test> {
+        assets: vec![(USDC, 1, true, 1)],
+        metadata: vec![(USDC, b"USD Coin".to_vec(), b"USDC".to_vec(), 6)],
+        accounts: vec![(USDC, 1, 1_000_000_000)],
+    }
+    .assimilate_storage(&mut t)
+    .expect("assets storage assimilates");
+
     let mut ext = sp_io::TestExternalities::new(t);
     ext.execute_with(|| {
         System::set_block_number(1);
+        X402Settlement::register_facilitator(
+            RuntimeOrigin::root(),
+            FACILITATOR,
+            crate::FacilitatorKey::Sr25519(FacilitatorPublicKeyValue::get()),
+            MinFacilitatorStake::get(),
+            sp_runtime::Perbill::zero(),
+            0,
+            None,
+        )
+        .expect("facilitator registers");
+        X402Settlement::set_usd_pegged_asset(RuntimeOrigin::root(), USDC, true).expect("USDC is pegged");
     });
     ext
 }
\ No newline at end of file
@@ -1,19 +1,30 @@
-use crate::pallet::PaymentIntentStatus;
-use crate::mock::{new_test_ext, RuntimeOrigin, System, Test, X402Settlement, Balances};
+use crate::pallet::{
+    ChannelStatus, ChannelVoucher, Eip712PaymentIntent, Error, FacilitatorKey, PaymentAsset, PaymentIntentStatus,
+};
+use crate::mock::{
+    new_test_ext, Assets, Balances, RuntimeOrigin, System, Test, X402Settlement, BILLABLE_TASK, CONVERSION_ACCOUNT,
+    DBC_PRICE, FACILITATOR, FacilitatorSlash, MinFacilitatorStake, SETTLED_CHANNELS, USDC,
+};
 use frame_support::{assert_noop, assert_ok, traits::{Currency, Hooks}};
-use sp_core::H256;
+use sp_core::{H160, H256};
+use sp_runtime::Perbill;
 use codec::Encode;
-/// Generate a valid facilitator sr25519 signature for testing.
-fn make_facilitator_sig(merchant: u64, miner: u64, amount: u128, nonce: u64, fingerprint: H256) -> Vec<u8> {
-    use sp_core::Pair;
-    let pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]); // Must match FacilitatorPublicKeyValue in mock
+/// Message a facilitator signs for a payment intent.
+fn intent_message(merchant: u64, miner: u64, amount: u128, nonce: u64, fingerprint: H256) -> Vec<u8> {
     let mut message = Vec::new();
     merchant.encode_to(&mut message);
     miner.encode_to(&mut message);
     amount.encode_to(&mut message);
     nonce.encode_to(&mut message);
     fingerprint.encode_to(&mut message);
-    pair.sign(&message).0.to_vec()
+    message
+}
+
+/// Generate a valid facilitator sr25519 signature for testing.
+fn make_facilitator_sig(merchant: u64, miner: u64, amount: u128, nonce: u64, fingerprint: H256) -> Vec<u8> {
+    use sp_core::Pair;
+    let pair = sp_core::sr25519::Pair::from_seed(&[1u8; 32]); // Must match FacilitatorPublicKeyValue in mock
+    pair.sign(&intent_message(merchant, miner, amount, nonce, fingerprint)).0.to_vec()
 }
 
 fn create_default_payment_intent() -> u64 {
@@ -24,6 +35,7 @@ fn create_default_payment_intent() -> u64 {
         1_000_000,
         1,
         H256::from_low_u64_be(12345),
+        FACILITATOR,
         sig,
     ));
     0 // First intent ID
@@ -66,6 +78,7 @@ fn submit_payment_intent_fails_with_duplicate_nonce() {
                 500_000,
                 1, // Same nonce
                 H256::from_low_u64_be(67890),
+                FACILITATOR,
                 sig2,
             ),
             crate::pallet::Error::<Test>::InvalidNonce
@@ -86,6 +99,7 @@ fn submit_payment_intent_fails_with_duplicate_replay_fingerprint() {
                 500_000,
                 1,
                 H256::from_low_u64_be(12345), // Same fingerprint
+                FACILITATOR,
                 sig2,
             ),
             crate::pallet::Error::<Test>::ReplayFingerprintUsed
@@ -108,6 +122,7 @@ fn submit_payment_intent_fails_with_insufficient_balance() {
                 current_balance + 1, // More than available balance
                 1,
                 H256::from_low_u64_be(12345),
+                FACILITATOR,
                 sig,
             ),
             crate::pallet::Error::<Test>::InsufficientBalance
@@ -127,6 +142,7 @@ fn submit_payment_intent_fails_with_invalid_signature() {
                 1_000_000,
                 1,
                 H256::from_low_u64_be(12345),
+                FACILITATOR,
                 b"invalid_short".to_vec(), // Too short (< 32 bytes after try_into)
             ),
             crate::pallet::Error::<Test>::InvalidFacilitatorSignature
@@ -216,6 +232,7 @@ fn finalize_settlement_works() {
         assert_eq!(receipt.merchant, 1);
         assert_eq!(receipt.miner, 3);
         assert_eq!(receipt.amount, 1_000_000);
+        assert_eq!(receipt.refunded, 0);
         assert_eq!(receipt.settled_at, 15);
     });
 }
@@ -358,11 +375,1107 @@ fn fail_settled_payment_intent_fails() {
 fn payment_intent_expires_on_initialize() {
     new_test_ext().execute_with(|| {
         let intent_id = create_default_payment_intent();
-        System::set_block_number(102);
-        X402Settlement::on_initialize(102);
+        System::set_block_number(101);
+        X402Settlement::on_initialize(101);
         let intent = X402Settlement::payment_intent_of(intent_id).expect("intent exists");
         assert!(matches!(intent.status, PaymentIntentStatus::Failed));
         assert_eq!(Balances::reserved_balance(1), 0);
         assert_eq!(Balances::free_balance(1), 1_000_000_000_000);
     });
 }
+
+#[test]
+fn expiry_queue_carries_over_full_blocks() {
+    new_test_ext().execute_with(|| {
+        // Four intents fit in the queue of block 101, the fifth is carried over to block 102
+        for nonce in 1..=5 {
+            let sig = make_facilitator_sig(1, 3, 1_000_000, nonce, H256::from_low_u64_be(nonce));
+            assert_ok!(submit(FACILITATOR, nonce, sig));
+        }
+        assert_eq!(X402Settlement::expiry_queue(101).into_inner(), vec![0, 1, 2, 3]);
+        assert_eq!(X402Settlement::expiry_queue(102).into_inner(), vec![4]);
+
+        // Verified intents keep their slot but are not expired
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+
+        System::set_block_number(101);
+        X402Settlement::on_initialize(101);
+        assert!(!crate::ExpiryQueue::<Test>::contains_key(101));
+        assert!(matches!(X402Settlement::payment_intent_of(0).unwrap().status, PaymentIntentStatus::Verified));
+        for id in 1..4 {
+            assert!(matches!(X402Settlement::payment_intent_of(id).unwrap().status, PaymentIntentStatus::Failed));
+        }
+        assert!(matches!(X402Settlement::payment_intent_of(4).unwrap().status, PaymentIntentStatus::Pending));
+
+        System::set_block_number(102);
+        X402Settlement::on_initialize(102);
+        assert!(matches!(X402Settlement::payment_intent_of(4).unwrap().status, PaymentIntentStatus::Failed));
+        assert_eq!(X402Settlement::facilitator_stats(FACILITATOR).expired, 4);
+        assert_eq!(Balances::reserved_balance(1), 1_000_000);
+    });
+}
+
+#[test]
+fn intents_are_rejected_when_the_carry_over_is_exhausted() {
+    new_test_ext().execute_with(|| {
+        let slots = 4 * (crate::MAX_EXPIRY_CARRY_OVER as u64 + 1);
+        for nonce in 1..=slots {
+            let sig = make_facilitator_sig(1, 3, 1, nonce, H256::from_low_u64_be(nonce));
+            assert_ok!(X402Settlement::submit_payment_intent(
+                RuntimeOrigin::signed(1),
+                3,
+                1,
+                nonce,
+                H256::from_low_u64_be(nonce),
+                FACILITATOR,
+                sig,
+            ));
+        }
+        assert_eq!(X402Settlement::expiry_queue(101 + crate::MAX_EXPIRY_CARRY_OVER as u64).len(), 4);
+
+        let nonce = slots + 1;
+        let sig = make_facilitator_sig(1, 3, 1, nonce, H256::from_low_u64_be(nonce));
+        assert_noop!(
+            X402Settlement::submit_payment_intent(
+                RuntimeOrigin::signed(1),
+                3,
+                1,
+                nonce,
+                H256::from_low_u64_be(nonce),
+                FACILITATOR,
+                sig,
+            ),
+            Error::<Test>::TooManyPendingIntents
+        );
+    });
+}
+
+fn order_context(order_id: u64, agreed_price: u128) -> dbc_support::traits::TaskOrderContext<u64, u128> {
+    dbc_support::traits::TaskOrderContext {
+        order_id,
+        customer: 1,
+        model_id: b"llama3-70b".to_vec(),
+        input_tokens: 1_000,
+        output_tokens: 500,
+        agreed_price,
+    }
+}
+
+#[test]
+fn settle_for_attestation_pays_miner_from_customer_reserve() {
+    use dbc_support::traits::AttestationSettler;
+    use frame_support::traits::ReservableCurrency;
+    use crate::mock::SETTLED_ORDERS;
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::reserve(&1, 500_000));
+        let miner_before = Balances::free_balance(3);
+
+        let receipt_id =
+            <X402Settlement as AttestationSettler>::settle_for_attestation(&order_context(4, 500_000), &3, 11)
+                .expect("settles");
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(3), miner_before + 500_000);
+        let receipt = X402Settlement::settlement_receipt_of(receipt_id).expect("receipt exists");
+        assert_eq!(receipt.merchant, 1);
+        assert_eq!(receipt.miner, 3);
+        assert_eq!(receipt.amount, 500_000);
+        assert_eq!(X402Settlement::order_receipt_of(4), Some(receipt_id));
+        SETTLED_ORDERS.with(|s| assert_eq!(*s.borrow(), vec![(4, 3, 500_000)]));
+
+        // The same order can only be paid once
+        assert!(<X402Settlement as AttestationSettler>::settle_for_attestation(&order_context(4, 500_000), &3, 12)
+            .is_err());
+    });
+}
+
+#[test]
+fn settle_for_attestation_rolls_back_when_order_is_rejected() {
+    use dbc_support::traits::AttestationSettler;
+    use frame_support::traits::ReservableCurrency;
+    use crate::mock::REJECT_ORDER_SETTLEMENT;
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::reserve(&1, 500_000));
+        REJECT_ORDER_SETTLEMENT.with(|r| *r.borrow_mut() = true);
+
+        assert!(<X402Settlement as AttestationSettler>::settle_for_attestation(&order_context(4, 500_000), &3, 11)
+            .is_err());
+        assert_eq!(Balances::reserved_balance(1), 500_000);
+        assert_eq!(X402Settlement::order_receipt_of(4), None);
+        assert_eq!(X402Settlement::next_intent_id(), 0);
+    });
+}
+
+#[test]
+fn refund_for_attestation_hands_open_orders_back_to_task_mode() {
+    use dbc_support::traits::AttestationSettler;
+    use frame_support::traits::ReservableCurrency;
+    use crate::mock::REFUNDED_ORDERS;
+    new_test_ext().execute_with(|| {
+        assert_ok!(<X402Settlement as AttestationSettler>::refund_for_attestation(&order_context(4, 500_000), 11));
+        REFUNDED_ORDERS.with(|r| assert_eq!(*r.borrow(), vec![4]));
+
+        // A paid order is not refunded
+        assert_ok!(Balances::reserve(&1, 500_000));
+        assert_ok!(<X402Settlement as AttestationSettler>::settle_for_attestation(&order_context(5, 500_000), &3, 12));
+        assert!(<X402Settlement as AttestationSettler>::refund_for_attestation(&order_context(5, 500_000), 12)
+            .is_err());
+        REFUNDED_ORDERS.with(|r| assert_eq!(*r.borrow(), vec![4]));
+    });
+}
+
+#[test]
+fn settle_for_attestation_requires_reserved_price() {
+    use dbc_support::traits::AttestationSettler;
+    use frame_support::traits::ReservableCurrency;
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::reserve(&1, 100_000));
+        let miner_before = Balances::free_balance(3);
+
+        assert!(<X402Settlement as AttestationSettler>::settle_for_attestation(&order_context(4, 500_000), &3, 11)
+            .is_err());
+        assert_eq!(Balances::reserved_balance(1), 100_000);
+        assert_eq!(Balances::free_balance(3), miner_before);
+    });
+}
+
+fn register(facilitator: u64, key: FacilitatorKey) {
+    let _ = Balances::deposit_creating(&facilitator, 1_000_000);
+    assert_ok!(X402Settlement::register_facilitator(
+        RuntimeOrigin::root(),
+        facilitator,
+        key,
+        1_000,
+        Perbill::zero(),
+        0,
+        None,
+    ));
+}
+
+fn submit(facilitator: u64, nonce: u64, sig: Vec<u8>) -> frame_support::dispatch::DispatchResult {
+    X402Settlement::submit_payment_intent(
+        RuntimeOrigin::signed(1),
+        3,
+        1_000_000,
+        nonce,
+        H256::from_low_u64_be(nonce),
+        facilitator,
+        sig,
+    )
+}
+
+#[test]
+fn register_facilitator_works() {
+    new_test_ext().execute_with(|| {
+        let _ = Balances::deposit_creating(&200, 1_000_000);
+        let key = FacilitatorKey::Sr25519([7u8; 32]);
+        assert_noop!(
+            X402Settlement::register_facilitator(RuntimeOrigin::signed(200), 200, key.clone(), 1_000, Perbill::zero(), 0, None),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            X402Settlement::register_facilitator(RuntimeOrigin::root(), 200, key.clone(), 999, Perbill::zero(), 0, None),
+            Error::<Test>::InsufficientFacilitatorStake
+        );
+        assert_noop!(
+            X402Settlement::register_facilitator(RuntimeOrigin::root(), 200, key.clone(), 1_000, Perbill::zero(), 10, Some(10)),
+            Error::<Test>::InvalidActiveWindow
+        );
+
+        assert_ok!(X402Settlement::register_facilitator(
+            RuntimeOrigin::root(),
+            200,
+            key.clone(),
+            5_000,
+            Perbill::from_percent(2),
+            0,
+            Some(50),
+        ));
+        let info = X402Settlement::facilitator_of(200).expect("registered");
+        assert_eq!(info.key, key);
+        assert_eq!(info.fee_rate, Perbill::from_percent(2));
+        assert_eq!(info.active_until, Some(50));
+        assert_eq!(Balances::reserved_balance(200), 5_000);
+
+        assert_noop!(
+            X402Settlement::register_facilitator(RuntimeOrigin::root(), 200, key, 1_000, Perbill::zero(), 0, None),
+            Error::<Test>::FacilitatorAlreadyRegistered
+        );
+
+        assert_ok!(X402Settlement::remove_facilitator(RuntimeOrigin::root(), 200));
+        assert!(X402Settlement::facilitator_of(200).is_none());
+        assert_eq!(Balances::reserved_balance(200), 0);
+    });
+}
+
+#[test]
+fn submit_requires_registered_active_facilitator() {
+    new_test_ext().execute_with(|| {
+        let sig = make_facilitator_sig(1, 3, 1_000_000, 1, H256::from_low_u64_be(1));
+        assert_noop!(submit(200, 1, sig.clone()), Error::<Test>::FacilitatorNotFound);
+
+        assert_ok!(X402Settlement::set_facilitator_terms(RuntimeOrigin::root(), FACILITATOR, Perbill::zero(), 5, Some(20)));
+        assert_noop!(submit(FACILITATOR, 1, sig.clone()), Error::<Test>::FacilitatorNotActive);
+        System::set_block_number(20);
+        assert_noop!(submit(FACILITATOR, 1, sig.clone()), Error::<Test>::FacilitatorNotActive);
+        System::set_block_number(19);
+        assert_ok!(submit(FACILITATOR, 1, sig));
+        assert_eq!(X402Settlement::payment_intent_of(0).unwrap().facilitator, FACILITATOR);
+    });
+}
+
+#[test]
+fn facilitators_sign_with_any_supported_key_type() {
+    use sp_core::Pair;
+    new_test_ext().execute_with(|| {
+        let ed = sp_core::ed25519::Pair::from_seed(&[2u8; 32]);
+        let ecdsa = sp_core::ecdsa::Pair::from_seed(&[3u8; 32]);
+        register(200, FacilitatorKey::Ed25519(ed.public().0));
+        register(201, FacilitatorKey::Secp256k1(ecdsa.public().0));
+
+        let message = intent_message(1, 3, 1_000_000, 1, H256::from_low_u64_be(1));
+        // Signed by another facilitator's key
+        assert_noop!(
+            submit(201, 1, ed.sign(&message).0.to_vec()),
+            Error::<Test>::InvalidFacilitatorSignature
+        );
+        assert_ok!(submit(200, 1, ed.sign(&message).0.to_vec()));
+
+        let message = intent_message(1, 3, 1_000_000, 2, H256::from_low_u64_be(2));
+        assert_ok!(submit(201, 2, ecdsa.sign(&message).0.to_vec()));
+        assert_eq!(X402Settlement::payment_intent_of(1).unwrap().facilitator, 201);
+    });
+}
+
+#[test]
+fn rotated_key_replaces_the_old_one() {
+    use sp_core::Pair;
+    new_test_ext().execute_with(|| {
+        let new_pair = sp_core::sr25519::Pair::from_seed(&[9u8; 32]);
+        assert_ok!(X402Settlement::rotate_facilitator_key(
+            RuntimeOrigin::root(),
+            FACILITATOR,
+            FacilitatorKey::Sr25519(new_pair.public().0),
+        ));
+
+        let old_sig = make_facilitator_sig(1, 3, 1_000_000, 1, H256::from_low_u64_be(1));
+        assert_noop!(submit(FACILITATOR, 1, old_sig), Error::<Test>::InvalidFacilitatorSignature);
+        let message = intent_message(1, 3, 1_000_000, 1, H256::from_low_u64_be(1));
+        assert_ok!(submit(FACILITATOR, 1, new_pair.sign(&message).0.to_vec()));
+    });
+}
+
+#[test]
+fn only_the_signing_facilitator_handles_an_intent() {
+    new_test_ext().execute_with(|| {
+        register(200, FacilitatorKey::Sr25519([7u8; 32]));
+        let intent_id = create_default_payment_intent();
+
+        assert_noop!(
+            X402Settlement::verify_settlement(RuntimeOrigin::signed(200), intent_id),
+            Error::<Test>::NotAuthorized
+        );
+        assert_noop!(
+            X402Settlement::fail_payment_intent(RuntimeOrigin::signed(200), intent_id),
+            Error::<Test>::NotAuthorized
+        );
+
+        assert_ok!(X402Settlement::fail_payment_intent(RuntimeOrigin::signed(FACILITATOR), intent_id));
+        assert_eq!(Balances::reserved_balance(1), 0);
+    });
+}
+
+#[test]
+fn facilitator_stake_stays_bonded_while_its_intents_are_open() {
+    new_test_ext().execute_with(|| {
+        let intent_id = create_default_payment_intent();
+        assert_eq!(X402Settlement::open_intents(FACILITATOR), 1);
+        assert_noop!(
+            X402Settlement::remove_facilitator(RuntimeOrigin::root(), FACILITATOR),
+            Error::<Test>::FacilitatorHasOpenIntents
+        );
+
+        // Failing an intent that was never verified costs nothing
+        assert_ok!(X402Settlement::fail_payment_intent(RuntimeOrigin::signed(FACILITATOR), intent_id));
+        assert_eq!(X402Settlement::open_intents(FACILITATOR), 0);
+        assert_eq!(X402Settlement::facilitator_of(FACILITATOR).unwrap().stake, MinFacilitatorStake::get());
+        assert_ok!(X402Settlement::remove_facilitator(RuntimeOrigin::root(), FACILITATOR));
+        assert_eq!(Balances::reserved_balance(FACILITATOR), 0);
+    });
+}
+
+#[test]
+fn failed_or_expired_verified_intents_slash_the_facilitator() {
+    new_test_ext().execute_with(|| {
+        let stake = MinFacilitatorStake::get();
+        let issuance = Balances::total_issuance();
+
+        // Failed after it was verified
+        let intent_id = create_default_payment_intent();
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), intent_id));
+        assert_ok!(X402Settlement::fail_payment_intent(RuntimeOrigin::signed(FACILITATOR), intent_id));
+        System::assert_has_event(
+            crate::Event::<Test>::FacilitatorSlashed {
+                facilitator: FACILITATOR,
+                intent_id,
+                amount: FacilitatorSlash::get(),
+            }
+            .into(),
+        );
+        assert_eq!(X402Settlement::facilitator_of(FACILITATOR).unwrap().stake, stake - FacilitatorSlash::get());
+        assert_eq!(Balances::reserved_balance(FACILITATOR), stake - FacilitatorSlash::get());
+        assert_eq!(Balances::total_issuance(), issuance - FacilitatorSlash::get());
+
+        // Verified but expired before it was finalized: the slash takes what is left
+        let sig = make_facilitator_sig(1, 3, 1_000_000, 2, H256::from_low_u64_be(2));
+        assert_ok!(submit(FACILITATOR, 2, sig));
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 1));
+        let sig = make_facilitator_sig(1, 3, 1_000_000, 3, H256::from_low_u64_be(3));
+        assert_ok!(submit(FACILITATOR, 3, sig));
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 2));
+        System::set_block_number(101);
+        assert_ok!(X402Settlement::refund_payment_intent(RuntimeOrigin::signed(2), 1));
+        assert_ok!(X402Settlement::refund_payment_intent(RuntimeOrigin::signed(2), 2));
+        assert_eq!(X402Settlement::facilitator_of(FACILITATOR).unwrap().stake, 0);
+        assert_eq!(Balances::reserved_balance(FACILITATOR), 0);
+        assert_eq!(X402Settlement::open_intents(FACILITATOR), 0);
+    });
+}
+
+#[test]
+fn facilitator_fee_and_dashboard_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(X402Settlement::set_facilitator_terms(
+            RuntimeOrigin::root(),
+            FACILITATOR,
+            Perbill::from_percent(10),
+            0,
+            None,
+        ));
+        let facilitator_before = Balances::free_balance(FACILITATOR);
+        let miner_before = Balances::free_balance(3);
+
+        // Settled
+        let intent_id = create_default_payment_intent();
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), intent_id));
+        System::set_block_number(15);
+        assert_ok!(X402Settlement::finalize_settlement(RuntimeOrigin::signed(3), intent_id));
+        assert_eq!(Balances::free_balance(FACILITATOR), facilitator_before + 100_000);
+        assert_eq!(Balances::free_balance(3), miner_before + 900_000);
+        System::assert_has_event(
+            crate::Event::<Test>::FacilitatorFeePaid { intent_id, facilitator: FACILITATOR, fee: 100_000 }.into(),
+        );
+
+        // Failed by the facilitator
+        let sig = make_facilitator_sig(1, 3, 1_000_000, 2, H256::from_low_u64_be(2));
+        assert_ok!(submit(FACILITATOR, 2, sig));
+        assert_ok!(X402Settlement::fail_payment_intent(RuntimeOrigin::signed(FACILITATOR), 1));
+
+        // Expired
+        let sig = make_facilitator_sig(1, 3, 1_000_000, 3, H256::from_low_u64_be(3));
+        assert_ok!(submit(FACILITATOR, 3, sig));
+        System::set_block_number(115);
+        X402Settlement::on_initialize(115);
+
+        let stats = X402Settlement::facilitator_stats(FACILITATOR);
+        assert_eq!(stats.submitted, 3);
+        assert_eq!(stats.settled, 1);
+        assert_eq!(stats.volume, 1_000_000);
+        assert_eq!(stats.fees, 100_000);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.expired, 1);
+    });
+}
+
+#[test]
+fn migration_v2_assigns_legacy_facilitator() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        // Intent encoded without the facilitator field
+        let old = (
+            7u64,
+            1u64,
+            3u64,
+            1_000_000u128,
+            1u64,
+            H256::from_low_u64_be(1),
+            vec![0u8; 64],
+            PaymentIntentStatus::Pending,
+            1u64,
+            Option::<u64>::None,
+            Option::<u64>::None,
+            101u64,
+        );
+        frame_support::storage::unhashed::put_raw(
+            &crate::PaymentIntents::<Test>::hashed_key_for(7),
+            &old.encode(),
+        );
+        StorageVersion::new(1).put::<X402Settlement>();
+
+        crate::migrations::v2::migrate::<Test>(500, FacilitatorKey::Sr25519([5u8; 32]));
+
+        let intent = crate::migrations::v2::PaymentIntents::<Test>::get(7).expect("intent migrated");
+        assert_eq!(intent.facilitator, 500);
+        assert_eq!(intent.amount, 1_000_000);
+        assert_eq!(intent.expires_at, 101);
+        let legacy = X402Settlement::facilitator_of(500).expect("legacy facilitator registered");
+        assert_eq!(legacy.key, FacilitatorKey::Sr25519([5u8; 32]));
+        assert_eq!(legacy.stake, 0);
+        assert_eq!(X402Settlement::on_chain_storage_version(), 2);
+    });
+}
+
+#[test]
+fn migration_v3_makes_existing_payments_native() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        let old_intent = (
+            7u64,
+            1u64,
+            3u64,
+            1_000_000u128,
+            1u64,
+            H256::from_low_u64_be(1),
+            FACILITATOR,
+            vec![0u8; 64],
+            PaymentIntentStatus::Settled,
+            1u64,
+            Some(2u64),
+            Some(12u64),
+            101u64,
+        );
+        frame_support::storage::unhashed::put_raw(
+            &crate::PaymentIntents::<Test>::hashed_key_for(7),
+            &old_intent.encode(),
+        );
+        let old_receipt = (7u64, 1u64, 3u64, 1_000_000u128, 12u64, H256::from_low_u64_be(7));
+        frame_support::storage::unhashed::put_raw(
+            &crate::SettlementReceipts::<Test>::hashed_key_for(7),
+            &old_receipt.encode(),
+        );
+        StorageVersion::new(2).put::<X402Settlement>();
+
+        crate::migrations::v3::migrate::<Test>();
+
+        let intent = X402Settlement::payment_intent_of(7).expect("intent migrated");
+        assert_eq!(intent.asset, PaymentAsset::Native);
+        assert_eq!(intent.facilitator, FACILITATOR);
+        assert_eq!(intent.settled_at, Some(12));
+        let receipt = crate::migrations::v3::SettlementReceipts::<Test>::get(7).expect("receipt migrated");
+        assert_eq!(receipt.asset, None);
+        assert_eq!(receipt.exchange_rate, None);
+        assert_eq!(receipt.tx_hash, H256::from_low_u64_be(7));
+        assert_eq!(X402Settlement::on_chain_storage_version(), 3);
+
+        crate::migrations::v4::migrate::<Test>();
+
+        let receipt = X402Settlement::settlement_receipt_of(7).expect("receipt migrated");
+        assert_eq!(receipt.amount, 1_000_000);
+        assert_eq!(receipt.refunded, 0);
+        assert_eq!(receipt.tx_hash, H256::from_low_u64_be(7));
+        assert_eq!(X402Settlement::on_chain_storage_version(), 4);
+    });
+}
+
+#[test]
+fn migration_v5_queues_pending_intents_by_expiry() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        for nonce in 1..=3 {
+            let sig = make_facilitator_sig(1, 3, 1_000_000, nonce, H256::from_low_u64_be(nonce));
+            assert_ok!(submit(FACILITATOR, nonce, sig));
+        }
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 1));
+        // Intent 2 expired while the old list was not swept yet
+        crate::PaymentIntents::<Test>::mutate(2, |intent| intent.as_mut().unwrap().expires_at = 30);
+        let _ = crate::ExpiryQueue::<Test>::clear(u32::MAX, None);
+        let pending: frame_support::BoundedVec<u64, frame_support::traits::ConstU32<10_000>> =
+            vec![0, 1, 2].try_into().unwrap();
+        crate::migrations::v5::PendingIntentIds::<Test>::put(pending);
+        StorageVersion::new(4).put::<X402Settlement>();
+
+        System::set_block_number(50);
+        crate::migrations::v5::migrate::<Test>();
+
+        assert!(!crate::migrations::v5::PendingIntentIds::<Test>::exists());
+        assert_eq!(X402Settlement::expiry_queue(101).into_inner(), vec![0]);
+        assert_eq!(X402Settlement::expiry_queue(50).into_inner(), vec![2]);
+        assert_eq!(X402Settlement::on_chain_storage_version(), 5);
+
+        X402Settlement::on_initialize(50);
+        assert!(matches!(X402Settlement::payment_intent_of(2).unwrap().status, PaymentIntentStatus::Failed));
+    });
+}
+
+#[test]
+fn migration_v6_counts_open_intents() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    new_test_ext().execute_with(|| {
+        for nonce in 1..=3 {
+            let sig = make_facilitator_sig(1, 3, 1_000_000, nonce, H256::from_low_u64_be(nonce));
+            assert_ok!(submit(FACILITATOR, nonce, sig));
+        }
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 1));
+        assert_ok!(X402Settlement::fail_payment_intent(RuntimeOrigin::signed(FACILITATOR), 2));
+        crate::OpenIntents::<Test>::remove(FACILITATOR);
+        StorageVersion::new(5).put::<X402Settlement>();
+
+        crate::migrations::v6::migrate::<Test>();
+
+        assert_eq!(X402Settlement::open_intents(FACILITATOR), 2);
+        assert_eq!(X402Settlement::on_chain_storage_version(), 6);
+    });
+}
+
+/// EVM address of an ecdsa wallet
+fn wallet_address(wallet: &sp_core::ecdsa::Pair) -> H160 {
+    let sig = wallet.sign_prehashed(&[0u8; 32]);
+    crate::eip712::recover_signer(&[0u8; 32], &sig.0).expect("signature recovers")
+}
+
+fn eip712_intent(merchant: H160, nonce: u64, expiry: u64) -> Eip712PaymentIntent<Test> {
+    Eip712PaymentIntent {
+        merchant,
+        miner: 3,
+        amount: 1_000_000,
+        nonce,
+        replay_fingerprint: H256::from_low_u64_be(1_000 + nonce),
+        expiry,
+    }
+}
+
+fn sign_eip712(wallet: &sp_core::ecdsa::Pair, intent: &Eip712PaymentIntent<Test>) -> [u8; 65] {
+    wallet.sign_prehashed(&X402Settlement::eip712_digest(intent)).0
+}
+
+#[test]
+fn eip712_payment_intent_works() {
+    use pallet_evm::AddressMapping;
+    use sp_core::Pair;
+    new_test_ext().execute_with(|| {
+        let wallet = sp_core::ecdsa::Pair::from_seed(&[7u8; 32]);
+        let address = wallet_address(&wallet);
+        let merchant = crate::mock::TestAddressMapping::into_account_id(address);
+        let _ = Balances::deposit_creating(&merchant, 10_000_000);
+
+        let intent = eip712_intent(address, 1, 50);
+        assert_ok!(X402Settlement::submit_eip712_payment_intent(
+            RuntimeOrigin::signed(FACILITATOR),
+            intent.clone(),
+            sign_eip712(&wallet, &intent),
+        ));
+
+        let stored = X402Settlement::payment_intent_of(0).expect("intent exists");
+        assert_eq!(stored.merchant, merchant);
+        assert_eq!(stored.miner, 3);
+        assert_eq!(stored.facilitator, FACILITATOR);
+        // The signed expiry comes before the intent TTL
+        assert_eq!(stored.expires_at, 50);
+        assert_eq!(Balances::reserved_balance(merchant), 1_000_000);
+        assert!(X402Settlement::is_nonce_used(&merchant, 1));
+
+        // Wallets may encode the recovery id as 27/28
+        let intent = eip712_intent(address, 2, 500);
+        let mut sig = sign_eip712(&wallet, &intent);
+        sig[64] += 27;
+        assert_ok!(X402Settlement::submit_eip712_payment_intent(
+            RuntimeOrigin::signed(FACILITATOR),
+            intent,
+            sig,
+        ));
+        assert_eq!(X402Settlement::payment_intent_of(1).expect("intent exists").expires_at, 101);
+
+        // The intent then follows the usual verify and settle flow
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+        System::set_block_number(12);
+        assert_ok!(X402Settlement::finalize_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+        assert_eq!(Balances::reserved_balance(merchant), 1_000_000);
+        assert_eq!(Balances::free_balance(3), 1_000_000_000_000 + 1_000_000);
+    });
+}
+
+#[test]
+fn eip712_payment_intent_checks_signer_and_expiry() {
+    use sp_core::Pair;
+    new_test_ext().execute_with(|| {
+        let wallet = sp_core::ecdsa::Pair::from_seed(&[7u8; 32]);
+        let address = wallet_address(&wallet);
+
+        // Signed for another amount
+        let intent = eip712_intent(address, 1, 50);
+        let sig = sign_eip712(&wallet, &intent);
+        let mut tampered = intent.clone();
+        tampered.amount = 2_000_000;
+        assert_noop!(
+            X402Settlement::submit_eip712_payment_intent(RuntimeOrigin::signed(FACILITATOR), tampered, sig),
+            Error::<Test>::InvalidPayerSignature
+        );
+
+        // Signed by a wallet other than the merchant
+        let other = sp_core::ecdsa::Pair::from_seed(&[8u8; 32]);
+        assert_noop!(
+            X402Settlement::submit_eip712_payment_intent(
+                RuntimeOrigin::signed(FACILITATOR),
+                intent.clone(),
+                sign_eip712(&other, &intent),
+            ),
+            Error::<Test>::InvalidPayerSignature
+        );
+
+        // Only registered facilitators relay intents
+        assert_noop!(
+            X402Settlement::submit_eip712_payment_intent(RuntimeOrigin::signed(2), intent.clone(), sig),
+            Error::<Test>::FacilitatorNotFound
+        );
+
+        System::set_block_number(50);
+        assert_noop!(
+            X402Settlement::submit_eip712_payment_intent(RuntimeOrigin::signed(FACILITATOR), intent, sig),
+            Error::<Test>::AuthorizationExpired
+        );
+    });
+}
+
+/// Facilitator signature over an asset intent of merchant 1 paying miner 3
+fn make_asset_sig(amount: u128, nonce: u64, convert_to_dbc: bool) -> Vec<u8> {
+    use sp_core::Pair;
+    let mut message = intent_message(1, 3, amount, nonce, H256::from_low_u64_be(nonce));
+    (USDC, convert_to_dbc).encode_to(&mut message);
+    sp_core::sr25519::Pair::from_seed(&[1u8; 32]).sign(&message).0.to_vec()
+}
+
+fn submit_asset(amount: u128, nonce: u64, convert_to_dbc: bool) -> frame_support::dispatch::DispatchResult {
+    X402Settlement::submit_asset_payment_intent(
+        RuntimeOrigin::signed(1),
+        USDC,
+        convert_to_dbc,
+        3,
+        amount,
+        nonce,
+        H256::from_low_u64_be(nonce),
+        FACILITATOR,
+        make_asset_sig(amount, nonce, convert_to_dbc),
+    )
+}
+
+#[test]
+fn asset_payment_intent_settles_in_the_asset() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(X402Settlement::set_facilitator_terms(
+            RuntimeOrigin::root(),
+            FACILITATOR,
+            Perbill::from_percent(10),
+            0,
+            None,
+        ));
+        // The native message does not authorize an asset intent
+        assert_noop!(
+            X402Settlement::submit_asset_payment_intent(
+                RuntimeOrigin::signed(1),
+                USDC,
+                false,
+                3,
+                5_000_000,
+                1,
+                H256::from_low_u64_be(1),
+                FACILITATOR,
+                make_facilitator_sig(1, 3, 5_000_000, 1, H256::from_low_u64_be(1)),
+            ),
+            Error::<Test>::InvalidFacilitatorSignature
+        );
+
+        assert_ok!(submit_asset(5_000_000, 1, false));
+        let intent = X402Settlement::payment_intent_of(0).expect("intent exists");
+        assert_eq!(intent.asset, PaymentAsset::Asset { id: USDC, convert_to_dbc: false });
+        // The asset stays with the merchant, frozen
+        assert_eq!(Assets::balance(USDC, 1), 1_000_000_000);
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 5_000_000);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert!(Assets::transfer(RuntimeOrigin::signed(1), USDC, 2, 995_000_000).is_err());
+
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+        System::set_block_number(15);
+        assert_ok!(X402Settlement::finalize_settlement(RuntimeOrigin::signed(3), 0));
+
+        assert_eq!(Assets::balance(USDC, 3), 4_500_000);
+        assert_eq!(Assets::balance(USDC, FACILITATOR), 500_000);
+        assert_eq!(Assets::balance(USDC, 1), 995_000_000);
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 0);
+        let receipt = X402Settlement::settlement_receipt_of(0).expect("receipt exists");
+        assert_eq!(receipt.asset, Some(USDC));
+        assert_eq!(receipt.exchange_rate, None);
+
+        // Native volume and fees only count DBC
+        let stats = X402Settlement::facilitator_stats(FACILITATOR);
+        assert_eq!(stats.settled, 1);
+        assert_eq!(stats.volume, 0);
+        assert_eq!(stats.fees, 0);
+    });
+}
+
+#[test]
+fn asset_payment_intent_converts_to_dbc() {
+    new_test_ext().execute_with(|| {
+        let miner_before = Balances::free_balance(3);
+        let conversion_before = Balances::free_balance(CONVERSION_ACCOUNT);
+
+        assert_ok!(submit_asset(5_000_000, 1, true));
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+        System::set_block_number(15);
+        assert_ok!(X402Settlement::finalize_settlement(RuntimeOrigin::signed(3), 0));
+
+        // 5 USDC at 0.01 USD per DBC
+        let dbc_amount = 500 * 1_000_000_000_000_000;
+        assert_eq!(Balances::free_balance(3), miner_before + dbc_amount);
+        assert_eq!(Balances::free_balance(CONVERSION_ACCOUNT), conversion_before - dbc_amount);
+        assert_eq!(Assets::balance(USDC, CONVERSION_ACCOUNT), 5_000_000);
+        assert_eq!(Assets::balance(USDC, 3), 0);
+
+        let receipt = X402Settlement::settlement_receipt_of(0).expect("receipt exists");
+        assert_eq!(receipt.asset, Some(USDC));
+        assert_eq!(receipt.exchange_rate, Some(10_000));
+        System::assert_has_event(
+            crate::Event::<Test>::PaymentConverted {
+                intent_id: 0,
+                asset: USDC,
+                amount: 5_000_000,
+                dbc_amount,
+                exchange_rate: 10_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn asset_payment_intents_return_the_asset_when_not_settled() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(submit_asset(5_000_000, 1, true));
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+        System::set_block_number(15);
+
+        // No conversion without a DBC price
+        DBC_PRICE.with(|p| *p.borrow_mut() = None);
+        assert_noop!(
+            X402Settlement::finalize_settlement(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::DbcPriceUnavailable
+        );
+
+        assert_ok!(X402Settlement::fail_payment_intent(RuntimeOrigin::signed(FACILITATOR), 0));
+        assert_eq!(Assets::balance(USDC, 1), 1_000_000_000);
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 0);
+
+        assert_ok!(submit_asset(5_000_000, 2, false));
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 5_000_000);
+        System::set_block_number(115);
+        X402Settlement::on_initialize(115);
+        assert!(matches!(
+            X402Settlement::payment_intent_of(1).expect("intent exists").status,
+            PaymentIntentStatus::Failed
+        ));
+        assert_eq!(Assets::balance(USDC, 1), 1_000_000_000);
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 0);
+    });
+}
+
+#[test]
+fn only_usd_pegged_assets_convert_to_dbc() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            X402Settlement::set_usd_pegged_asset(RuntimeOrigin::signed(1), USDC, false),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(X402Settlement::set_usd_pegged_asset(RuntimeOrigin::root(), USDC, false));
+        assert!(!X402Settlement::is_usd_pegged(USDC));
+        assert_eq!(X402Settlement::dbc_value(USDC, 5_000_000), None);
+
+        assert_noop!(submit_asset(5_000_000, 1, true), Error::<Test>::AssetNotUsdPegged);
+        // Paying the miner in the asset itself needs no price
+        assert_ok!(submit_asset(5_000_000, 1, false));
+    });
+}
+
+#[test]
+fn conversion_needs_dbc_in_the_conversion_account() {
+    new_test_ext().execute_with(|| {
+        // 5 USDC at 0.01 USD per DBC, and the account keeps the existential deposit of 1
+        let dbc_amount = 500 * 1_000_000_000_000_000;
+        Balances::make_free_balance_be(&CONVERSION_ACCOUNT, dbc_amount);
+        assert_noop!(submit_asset(5_000_000, 1, true), Error::<Test>::InsufficientConversionLiquidity);
+        DBC_PRICE.with(|p| *p.borrow_mut() = None);
+        assert_noop!(submit_asset(5_000_000, 1, true), Error::<Test>::DbcPriceUnavailable);
+        DBC_PRICE.with(|p| *p.borrow_mut() = Some(10_000));
+
+        Balances::make_free_balance_be(&CONVERSION_ACCOUNT, dbc_amount + 1);
+        assert_ok!(submit_asset(5_000_000, 1, true));
+    });
+}
+
+#[test]
+fn asset_holds_cannot_exceed_the_free_balance() {
+    new_test_ext().execute_with(|| {
+        // The merchant keeps the minimum balance of 1 on top of what is held
+        assert_noop!(submit_asset(1_000_000_000, 1, false), Error::<Test>::InsufficientBalance);
+        assert_ok!(submit_asset(600_000_000, 1, false));
+        assert_noop!(submit_asset(400_000_000, 2, false), Error::<Test>::InsufficientBalance);
+        assert_ok!(submit_asset(399_999_999, 2, false));
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 999_999_999);
+    });
+}
+
+fn voucher_pair() -> sp_core::sr25519::Pair {
+    use sp_core::Pair;
+    sp_core::sr25519::Pair::from_seed(&[9u8; 32])
+}
+
+fn voucher(amount: u128, tokens: u64) -> ChannelVoucher<u128> {
+    ChannelVoucher { amount, input_tokens: tokens, output_tokens: tokens * 2 }
+}
+
+fn sign_voucher(channel_id: u64, voucher: &ChannelVoucher<u128>) -> Vec<u8> {
+    use sp_core::Pair;
+    voucher_pair().sign(&X402Settlement::channel_voucher_message(channel_id, voucher)).0.to_vec()
+}
+
+/// Channel 0 from customer 1 to miner 3 with a deposit of 1_000_000
+fn open_default_channel() {
+    use sp_core::Pair;
+    assert_ok!(X402Settlement::open_channel(
+        RuntimeOrigin::signed(1),
+        3,
+        BILLABLE_TASK,
+        1_000_000,
+        FacilitatorKey::Sr25519(voucher_pair().public().0),
+    ));
+}
+
+#[test]
+fn payment_channel_settles_the_highest_voucher() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            X402Settlement::open_channel(RuntimeOrigin::signed(1), 3, 5, 1_000_000, FacilitatorKey::Sr25519([9u8; 32])),
+            Error::<Test>::TaskNotBillable
+        );
+        open_default_channel();
+        assert_eq!(Balances::reserved_balance(1), 1_000_000);
+        assert_eq!(X402Settlement::next_channel_id(), 1);
+
+        let first = voucher(300_000, 100);
+        assert_ok!(X402Settlement::close_channel(RuntimeOrigin::signed(3), 0, first, sign_voucher(0, &first)));
+        let channel = X402Settlement::payment_channel_of(0).expect("channel exists");
+        assert_eq!(channel.status, ChannelStatus::Closing { settles_at: 21 });
+        assert_noop!(
+            X402Settlement::settle_channel(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::DisputeWindowOpen
+        );
+
+        // Only a higher voucher signed with the channel key within the deposit wins
+        let lower = voucher(200_000, 50);
+        assert_noop!(
+            X402Settlement::dispute_channel(RuntimeOrigin::signed(3), 0, lower, sign_voucher(0, &lower)),
+            Error::<Test>::VoucherNotHigher
+        );
+        let too_high = voucher(1_000_001, 500);
+        assert_noop!(
+            X402Settlement::dispute_channel(RuntimeOrigin::signed(3), 0, too_high, sign_voucher(0, &too_high)),
+            Error::<Test>::VoucherExceedsDeposit
+        );
+        let latest = voucher(500_000, 200);
+        assert_noop!(
+            X402Settlement::dispute_channel(RuntimeOrigin::signed(3), 0, latest, sign_voucher(1, &latest)),
+            Error::<Test>::InvalidVoucherSignature
+        );
+        assert_ok!(X402Settlement::dispute_channel(RuntimeOrigin::signed(3), 0, latest, sign_voucher(0, &latest)));
+
+        System::set_block_number(21);
+        let later = voucher(600_000, 300);
+        assert_noop!(
+            X402Settlement::dispute_channel(RuntimeOrigin::signed(3), 0, later, sign_voucher(0, &later)),
+            Error::<Test>::DisputeWindowClosed
+        );
+
+        let customer_before = Balances::free_balance(1);
+        let miner_before = Balances::free_balance(3);
+        assert_ok!(X402Settlement::settle_channel(RuntimeOrigin::signed(2), 0));
+
+        assert_eq!(Balances::free_balance(3), miner_before + 500_000);
+        assert_eq!(Balances::free_balance(1), customer_before + 500_000);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        let channel = X402Settlement::payment_channel_of(0).expect("channel exists");
+        assert_eq!(channel.status, ChannelStatus::Settled { order_id: Some(1000) });
+        SETTLED_CHANNELS.with(|s| assert_eq!(*s.borrow(), vec![(BILLABLE_TASK, 1, 3, 200, 400, 500_000)]));
+        System::assert_last_event(
+            crate::Event::<Test>::ChannelSettled {
+                channel_id: 0,
+                order_id: Some(1000),
+                amount: 500_000,
+                refunded: 500_000,
+            }
+            .into(),
+        );
+
+        assert_noop!(
+            X402Settlement::settle_channel(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::ChannelNotClosing
+        );
+    });
+}
+
+#[test]
+fn payment_channel_closes_without_usage() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        open_default_channel();
+
+        let empty = voucher(0, 0);
+        assert_noop!(
+            X402Settlement::close_channel(RuntimeOrigin::signed(2), 0, empty, sign_voucher(0, &empty)),
+            Error::<Test>::NotAuthorized
+        );
+        assert_ok!(X402Settlement::close_channel(RuntimeOrigin::signed(1), 0, empty, sign_voucher(0, &empty)));
+        assert_noop!(
+            X402Settlement::close_channel(RuntimeOrigin::signed(1), 0, empty, sign_voucher(0, &empty)),
+            Error::<Test>::ChannelNotOpen
+        );
+
+        System::set_block_number(21);
+        assert_ok!(X402Settlement::settle_channel(RuntimeOrigin::signed(1), 0));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000_000_000_000);
+        let channel = X402Settlement::payment_channel_of(0).expect("channel exists");
+        assert_eq!(channel.status, ChannelStatus::Settled { order_id: None });
+        SETTLED_CHANNELS.with(|s| assert!(s.borrow().is_empty()));
+    });
+}
+
+fn sign_partial_settlement(intent_id: u64, settled_amount: u128, justification: H256) -> Vec<u8> {
+    use sp_core::Pair;
+    let message = X402Settlement::partial_settlement_message(intent_id, settled_amount, justification);
+    sp_core::sr25519::Pair::from_seed(&[1u8; 32]).sign(&message).0.to_vec()
+}
+
+#[test]
+fn partial_settlement_refunds_the_rest() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(X402Settlement::set_facilitator_terms(
+            RuntimeOrigin::root(),
+            FACILITATOR,
+            Perbill::from_percent(10),
+            0,
+            None,
+        ));
+        let intent_id = create_default_payment_intent();
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), intent_id));
+        System::set_block_number(15);
+        let report = H256::from_low_u64_be(77);
+
+        assert_noop!(
+            X402Settlement::finalize_partial_settlement(
+                RuntimeOrigin::signed(3),
+                intent_id,
+                1_000_001,
+                report,
+                sign_partial_settlement(intent_id, 1_000_001, report),
+            ),
+            Error::<Test>::SettledAmountExceedsIntent
+        );
+        // The facilitator signature covers the amount
+        assert_noop!(
+            X402Settlement::finalize_partial_settlement(
+                RuntimeOrigin::signed(3),
+                intent_id,
+                600_000,
+                report,
+                sign_partial_settlement(intent_id, 700_000, report),
+            ),
+            Error::<Test>::InvalidJustificationSignature
+        );
+
+        let miner_before = Balances::free_balance(3);
+        let facilitator_before = Balances::free_balance(FACILITATOR);
+        assert_ok!(X402Settlement::finalize_partial_settlement(
+            RuntimeOrigin::signed(3),
+            intent_id,
+            600_000,
+            report,
+            sign_partial_settlement(intent_id, 600_000, report),
+        ));
+
+        assert_eq!(Balances::free_balance(3), miner_before + 540_000);
+        assert_eq!(Balances::free_balance(FACILITATOR), facilitator_before + 60_000);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000_000_000_000 - 600_000);
+
+        let receipt = X402Settlement::settlement_receipt_of(intent_id).expect("receipt exists");
+        assert_eq!(receipt.amount, 600_000);
+        assert_eq!(receipt.refunded, 400_000);
+        let stats = X402Settlement::facilitator_stats(FACILITATOR);
+        assert_eq!(stats.volume, 600_000);
+        assert_eq!(stats.fees, 60_000);
+        System::assert_has_event(
+            crate::Event::<Test>::PaymentIntentRefunded {
+                intent_id,
+                merchant: 1,
+                amount: 400_000,
+                justification: Some(report),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn partial_settlement_of_an_asset_intent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(submit_asset(5_000_000, 1, false));
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), 0));
+        System::set_block_number(15);
+        let report = H256::from_low_u64_be(77);
+        assert_ok!(X402Settlement::finalize_partial_settlement(
+            RuntimeOrigin::signed(1),
+            0,
+            2_000_000,
+            report,
+            sign_partial_settlement(0, 2_000_000, report),
+        ));
+
+        assert_eq!(Assets::balance(USDC, 3), 2_000_000);
+        assert_eq!(Assets::balance(USDC, 1), 998_000_000);
+        assert_eq!(X402Settlement::asset_hold(USDC, 1), 0);
+    });
+}
+
+#[test]
+fn verified_intents_are_refunded_after_expiry() {
+    new_test_ext().execute_with(|| {
+        let intent_id = create_default_payment_intent();
+        assert_ok!(X402Settlement::verify_settlement(RuntimeOrigin::signed(FACILITATOR), intent_id));
+
+        assert_noop!(
+            X402Settlement::refund_payment_intent(RuntimeOrigin::signed(2), intent_id),
+            Error::<Test>::PaymentIntentNotExpired
+        );
+
+        // Verified intents are no longer swept by on_initialize
+        System::set_block_number(101);
+        X402Settlement::on_initialize(101);
+        assert_noop!(
+            X402Settlement::finalize_settlement(RuntimeOrigin::signed(1), intent_id),
+            Error::<Test>::PaymentIntentExpired
+        );
+        assert_eq!(Balances::reserved_balance(1), 1_000_000);
+
+        assert_ok!(X402Settlement::refund_payment_intent(RuntimeOrigin::signed(2), intent_id));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1_000_000_000_000);
+        let intent = X402Settlement::payment_intent_of(intent_id).expect("intent exists");
+        assert!(matches!(intent.status, PaymentIntentStatus::Failed));
+        assert_eq!(X402Settlement::facilitator_stats(FACILITATOR).expired, 1);
+
+        assert_noop!(
+            X402Settlement::refund_payment_intent(RuntimeOrigin::signed(2), intent_id),
+            Error::<Test>::InvalidPaymentIntentStatus
+        );
+    });
+}
@@ -40,133 +40,741 @@ pub trait WeightInfo {
 	fn verify_settlement() -> Weight;
 	fn finalize_settlement() -> Weight;
 	fn fail_payment_intent() -> Weight;
+	fn register_facilitator() -> Weight;
+	fn rotate_facilitator_key() -> Weight;
+	fn set_facilitator_terms() -> Weight;
+	fn remove_facilitator() -> Weight;
+	fn submit_eip712_payment_intent() -> Weight;
+	fn submit_asset_payment_intent() -> Weight;
+	fn open_channel() -> Weight;
+	fn close_channel() -> Weight;
+	fn dispute_channel() -> Weight;
+	fn settle_channel() -> Weight;
+	fn finalize_partial_settlement() -> Weight;
+	fn refund_payment_intent() -> Weight;
+	fn on_initialize(n: u32, ) -> Weight;
+	fn set_usd_pegged_asset() -> Weight;
 }
 
 /// Weights for `pallet_x402_settlement` using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
 	/// Storage: X402Settlement NonceUsed (r:1 w:1)
 	/// Proof: X402Settlement NonceUsed (max_values: None, max_size: Some(57), added: 2532, mode: MaxEncodedLen)
 	/// Storage: X402Settlement ReplayFingerprintUsed (r:1 w:1)
 	/// Proof: X402Settlement ReplayFingerprintUsed (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
 	/// Storage: X402Settlement NextIntentId (r:1 w:1)
 	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	/// Storage: X402Settlement PendingIntentIds (r:1 w:1)
-	/// Proof: X402Settlement PendingIntentIds (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ExpiryQueue (r:9 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
 	/// Storage: X402Settlement PaymentIntents (r:0 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn submit_payment_intent() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `114`
-		//  Estimated: `81487`
-		// Minimum execution time: 48_882_000 picoseconds.
-		Weight::from_parts(49_726_000, 81487)
-			.saturating_add(T::DbWeight::get().reads(4_u64))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+		//  Measured:  `1462`
+		//  Estimated: `41823`
+		// Minimum execution time: 57_134_000 picoseconds.
+		Weight::from_parts(58_902_000, 41823)
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
 	}
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
 	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
-	/// Storage: X402Settlement PendingIntentIds (r:1 w:1)
-	/// Proof: X402Settlement PendingIntentIds (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
 	fn verify_settlement() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `399`
-		//  Estimated: `81487`
-		// Minimum execution time: 26_718_000 picoseconds.
-		Weight::from_parts(27_299_000, 81487)
+		//  Measured:  `512`
+		//  Estimated: `3804`
+		// Minimum execution time: 20_735_000 picoseconds.
+		Weight::from_parts(21_377_000, 3804)
 			.saturating_add(T::DbWeight::get().reads(2_u64))
-			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
 	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
-	/// Storage: System Account (r:1 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:3 w:3)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Assets Metadata (r:1 w:0)
+	/// Proof: Assets Metadata (max_values: None, max_size: Some(140), added: 2615, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
 	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
-	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(190), added: 2665, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn finalize_settlement() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `506`
-		//  Estimated: `3766`
-		// Minimum execution time: 56_064_000 picoseconds.
-		Weight::from_parts(57_575_000, 3766)
-			.saturating_add(T::DbWeight::get().reads(2_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+		//  Measured:  `8941`
+		//  Estimated: `26824`
+		// Minimum execution time: 127_463_000 picoseconds.
+		Weight::from_parts(131_406_000, 26824)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
 	}
 	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
-	/// Storage: X402Settlement PendingIntentIds (r:1 w:1)
-	/// Proof: X402Settlement PendingIntentIds (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	fn fail_payment_intent() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `399`
-		//  Estimated: `81487`
-		// Minimum execution time: 39_775_000 picoseconds.
-		Weight::from_parts(42_071_000, 81487)
+		//  Measured:  `2634`
+		//  Estimated: `8893`
+		// Minimum execution time: 74_906_000 picoseconds.
+		Weight::from_parts(77_201_000, 8893)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn register_facilitator() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1197`
+		//  Estimated: `3593`
+		// Minimum execution time: 33_180_000 picoseconds.
+		Weight::from_parts(34_207_000, 3593)
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	fn rotate_facilitator_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1192`
+		//  Estimated: `3576`
+		// Minimum execution time: 17_392_000 picoseconds.
+		Weight::from_parts(17_930_000, 3576)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	fn set_facilitator_terms() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1192`
+		//  Estimated: `3576`
+		// Minimum execution time: 17_667_000 picoseconds.
+		Weight::from_parts(18_214_000, 3576)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:0)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn remove_facilitator() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1197`
+		//  Estimated: `3593`
+		// Minimum execution time: 32_060_000 picoseconds.
+		Weight::from_parts(33_052_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NonceUsed (r:1 w:1)
+	/// Proof: X402Settlement NonceUsed (max_values: None, max_size: Some(57), added: 2532, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ReplayFingerprintUsed (r:1 w:1)
+	/// Proof: X402Settlement ReplayFingerprintUsed (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ExpiryQueue (r:9 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentIntents (r:0 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn submit_eip712_payment_intent() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1462`
+		//  Estimated: `41823`
+		// Minimum execution time: 114_583_000 picoseconds.
+		Weight::from_parts(118_127_000, 41823)
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NonceUsed (r:1 w:1)
+	/// Proof: X402Settlement NonceUsed (max_values: None, max_size: Some(57), added: 2532, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ReplayFingerprintUsed (r:1 w:1)
+	/// Proof: X402Settlement ReplayFingerprintUsed (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: X402Settlement UsdPeggedAssets (r:1 w:0)
+	/// Proof: X402Settlement UsdPeggedAssets (max_values: None, max_size: Some(21), added: 2496, mode: MaxEncodedLen)
+	/// Storage: Assets Metadata (r:1 w:0)
+	/// Proof: Assets Metadata (max_values: None, max_size: Some(140), added: 2615, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof Skipped: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:0)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:1 w:0)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement AssetHolds (r:1 w:1)
+	/// Proof: X402Settlement AssetHolds (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ExpiryQueue (r:9 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentIntents (r:0 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn submit_asset_payment_intent() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4318`
+		//  Estimated: `55338`
+		// Minimum execution time: 88_907_000 picoseconds.
+		Weight::from_parts(91_532_000, 55338)
+			.saturating_add(T::DbWeight::get().reads(22_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: TaskMode TaskDefinitions (r:1 w:0)
+	/// Proof: TaskMode TaskDefinitions (max_values: None, max_size: Some(1639), added: 4114, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextChannelId (r:1 w:1)
+	/// Proof: X402Settlement NextChannelId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentChannels (r:0 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	fn open_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1705`
+		//  Estimated: `5117`
+		// Minimum execution time: 40_616_000 picoseconds.
+		Weight::from_parts(41_873_000, 5117)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: X402Settlement PaymentChannels (r:1 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	fn close_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1211`
+		//  Estimated: `3633`
+		// Minimum execution time: 37_067_000 picoseconds.
+		Weight::from_parts(38_214_000, 3633)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement PaymentChannels (r:1 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	fn dispute_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1211`
+		//  Estimated: `3633`
+		// Minimum execution time: 37_734_000 picoseconds.
+		Weight::from_parts(38_902_000, 3633)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement PaymentChannels (r:1 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskDefinitions (r:1 w:0)
+	/// Proof: TaskMode TaskDefinitions (max_values: None, max_size: Some(1639), added: 4114, mode: MaxEncodedLen)
+	/// Storage: System Account (r:3 w:3)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TaskMode NextOrderId (r:1 w:1)
+	/// Proof: TaskMode NextOrderId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: TaskMode EraStats (r:1 w:1)
+	/// Proof: TaskMode EraStats (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TaskMode MinerTaskStats (r:1 w:1)
+	/// Proof: TaskMode MinerTaskStats (max_values: None, max_size: Some(84), added: 2559, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:0 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	fn settle_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `5147`
+		//  Estimated: `15441`
+		// Minimum execution time: 93_634_000 picoseconds.
+		Weight::from_parts(96_530_000, 15441)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Assets Metadata (r:1 w:0)
+	/// Proof: Assets Metadata (max_values: None, max_size: Some(140), added: 2615, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(190), added: 2665, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn finalize_partial_settlement() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `9811`
+		//  Estimated: `29433`
+		// Minimum execution time: 171_610_000 picoseconds.
+		Weight::from_parts(176_918_000, 29433)
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn refund_payment_intent() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2969`
+		//  Estimated: `8909`
+		// Minimum execution time: 67_530_000 picoseconds.
+		Weight::from_parts(69_784_000, 8909)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: X402Settlement ExpiryQueue (r:1 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentIntents (r:256 w:256)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:257 w:257)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 256]`.
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn on_initialize(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4139 + n * (2711 ±0)`
+		//  Estimated: `12418 + n * (5423 ±0)`
+		// Minimum execution time: 5_963_000 picoseconds.
+		Weight::from_parts(6_148_000, 12418)
+			// Standard Error: 13_950
+			.saturating_add(Weight::from_parts(31_207_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5423).saturating_mul(n.into()))
+	}
+	/// Storage: X402Settlement UsdPeggedAssets (r:0 w:1)
+	/// Proof: X402Settlement UsdPeggedAssets (max_values: None, max_size: Some(21), added: 2496, mode: MaxEncodedLen)
+	fn set_usd_pegged_asset() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_874_000 picoseconds.
+		Weight::from_parts(10_262_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests.
 impl WeightInfo for () {
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
 	/// Storage: X402Settlement NonceUsed (r:1 w:1)
 	/// Proof: X402Settlement NonceUsed (max_values: None, max_size: Some(57), added: 2532, mode: MaxEncodedLen)
 	/// Storage: X402Settlement ReplayFingerprintUsed (r:1 w:1)
 	/// Proof: X402Settlement ReplayFingerprintUsed (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
 	/// Storage: X402Settlement NextIntentId (r:1 w:1)
 	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	/// Storage: X402Settlement PendingIntentIds (r:1 w:1)
-	/// Proof: X402Settlement PendingIntentIds (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ExpiryQueue (r:9 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
 	/// Storage: X402Settlement PaymentIntents (r:0 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn submit_payment_intent() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `114`
-		//  Estimated: `81487`
-		// Minimum execution time: 48_882_000 picoseconds.
-		Weight::from_parts(49_726_000, 81487)
-			.saturating_add(RocksDbWeight::get().reads(4_u64))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+		//  Measured:  `1462`
+		//  Estimated: `41823`
+		// Minimum execution time: 57_134_000 picoseconds.
+		Weight::from_parts(58_902_000, 41823)
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
 	}
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
 	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
-	/// Storage: X402Settlement PendingIntentIds (r:1 w:1)
-	/// Proof: X402Settlement PendingIntentIds (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
 	fn verify_settlement() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `399`
-		//  Estimated: `81487`
-		// Minimum execution time: 26_718_000 picoseconds.
-		Weight::from_parts(27_299_000, 81487)
+		//  Measured:  `512`
+		//  Estimated: `3804`
+		// Minimum execution time: 20_735_000 picoseconds.
+		Weight::from_parts(21_377_000, 3804)
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
-			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
 	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
-	/// Storage: System Account (r:1 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:3 w:3)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Assets Metadata (r:1 w:0)
+	/// Proof: Assets Metadata (max_values: None, max_size: Some(140), added: 2615, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
 	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
-	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(152), added: 2627, mode: MaxEncodedLen)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(190), added: 2665, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	fn finalize_settlement() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `506`
-		//  Estimated: `3766`
-		// Minimum execution time: 56_064_000 picoseconds.
-		Weight::from_parts(57_575_000, 3766)
-			.saturating_add(RocksDbWeight::get().reads(2_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+		//  Measured:  `8941`
+		//  Estimated: `26824`
+		// Minimum execution time: 127_463_000 picoseconds.
+		Weight::from_parts(131_406_000, 26824)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
 	}
 	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
-	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(301), added: 2776, mode: MaxEncodedLen)
-	/// Storage: X402Settlement PendingIntentIds (r:1 w:1)
-	/// Proof: X402Settlement PendingIntentIds (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	fn fail_payment_intent() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `399`
-		//  Estimated: `81487`
-		// Minimum execution time: 39_775_000 picoseconds.
-		Weight::from_parts(42_071_000, 81487)
+		//  Measured:  `2634`
+		//  Estimated: `8893`
+		// Minimum execution time: 74_906_000 picoseconds.
+		Weight::from_parts(77_201_000, 8893)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn register_facilitator() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1197`
+		//  Estimated: `3593`
+		// Minimum execution time: 33_180_000 picoseconds.
+		Weight::from_parts(34_207_000, 3593)
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	fn rotate_facilitator_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1192`
+		//  Estimated: `3576`
+		// Minimum execution time: 17_392_000 picoseconds.
+		Weight::from_parts(17_930_000, 3576)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	fn set_facilitator_terms() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1192`
+		//  Estimated: `3576`
+		// Minimum execution time: 17_667_000 picoseconds.
+		Weight::from_parts(18_214_000, 3576)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:0)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn remove_facilitator() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1197`
+		//  Estimated: `3593`
+		// Minimum execution time: 32_060_000 picoseconds.
+		Weight::from_parts(33_052_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NonceUsed (r:1 w:1)
+	/// Proof: X402Settlement NonceUsed (max_values: None, max_size: Some(57), added: 2532, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ReplayFingerprintUsed (r:1 w:1)
+	/// Proof: X402Settlement ReplayFingerprintUsed (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ExpiryQueue (r:9 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentIntents (r:0 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn submit_eip712_payment_intent() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1462`
+		//  Estimated: `41823`
+		// Minimum execution time: 114_583_000 picoseconds.
+		Weight::from_parts(118_127_000, 41823)
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NonceUsed (r:1 w:1)
+	/// Proof: X402Settlement NonceUsed (max_values: None, max_size: Some(57), added: 2532, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ReplayFingerprintUsed (r:1 w:1)
+	/// Proof: X402Settlement ReplayFingerprintUsed (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: X402Settlement UsdPeggedAssets (r:1 w:0)
+	/// Proof: X402Settlement UsdPeggedAssets (max_values: None, max_size: Some(21), added: 2496, mode: MaxEncodedLen)
+	/// Storage: Assets Metadata (r:1 w:0)
+	/// Proof: Assets Metadata (max_values: None, max_size: Some(140), added: 2615, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof Skipped: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:0)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:1 w:0)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement AssetHolds (r:1 w:1)
+	/// Proof: X402Settlement AssetHolds (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextIntentId (r:1 w:1)
+	/// Proof: X402Settlement NextIntentId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: X402Settlement ExpiryQueue (r:9 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentIntents (r:0 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn submit_asset_payment_intent() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4318`
+		//  Estimated: `55338`
+		// Minimum execution time: 88_907_000 picoseconds.
+		Weight::from_parts(91_532_000, 55338)
+			.saturating_add(RocksDbWeight::get().reads(22_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: TaskMode TaskDefinitions (r:1 w:0)
+	/// Proof: TaskMode TaskDefinitions (max_values: None, max_size: Some(1639), added: 4114, mode: MaxEncodedLen)
+	/// Storage: X402Settlement NextChannelId (r:1 w:1)
+	/// Proof: X402Settlement NextChannelId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentChannels (r:0 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	fn open_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1705`
+		//  Estimated: `5117`
+		// Minimum execution time: 40_616_000 picoseconds.
+		Weight::from_parts(41_873_000, 5117)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: X402Settlement PaymentChannels (r:1 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	fn close_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1211`
+		//  Estimated: `3633`
+		// Minimum execution time: 37_067_000 picoseconds.
+		Weight::from_parts(38_214_000, 3633)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement PaymentChannels (r:1 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	fn dispute_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1211`
+		//  Estimated: `3633`
+		// Minimum execution time: 37_734_000 picoseconds.
+		Weight::from_parts(38_902_000, 3633)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: X402Settlement PaymentChannels (r:1 w:1)
+	/// Proof: X402Settlement PaymentChannels (max_values: None, max_size: Some(168), added: 2643, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskDefinitions (r:1 w:0)
+	/// Proof: TaskMode TaskDefinitions (max_values: None, max_size: Some(1639), added: 4114, mode: MaxEncodedLen)
+	/// Storage: System Account (r:3 w:3)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TaskMode NextOrderId (r:1 w:1)
+	/// Proof: TaskMode NextOrderId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: TaskMode EraStats (r:1 w:1)
+	/// Proof: TaskMode EraStats (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: TaskMode MinerTaskStats (r:1 w:1)
+	/// Proof: TaskMode MinerTaskStats (max_values: None, max_size: Some(84), added: 2559, mode: MaxEncodedLen)
+	/// Storage: TaskMode TaskOrders (r:0 w:1)
+	/// Proof: TaskMode TaskOrders (max_values: None, max_size: Some(222), added: 2697, mode: MaxEncodedLen)
+	fn settle_channel() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `5147`
+		//  Estimated: `15441`
+		// Minimum execution time: 93_634_000 picoseconds.
+		Weight::from_parts(96_530_000, 15441)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:0)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Assets Metadata (r:1 w:0)
+	/// Proof: Assets Metadata (max_values: None, max_size: Some(140), added: 2615, mode: MaxEncodedLen)
+	/// Storage: DBCPriceOCW AvgPrice (r:1 w:0)
+	/// Proof: DBCPriceOCW AvgPrice (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement SettlementReceipts (r:0 w:1)
+	/// Proof: X402Settlement SettlementReceipts (max_values: None, max_size: Some(190), added: 2665, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn finalize_partial_settlement() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `9811`
+		//  Estimated: `29433`
+		// Minimum execution time: 171_610_000 picoseconds.
+		Weight::from_parts(176_918_000, 29433)
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	/// Storage: X402Settlement PaymentIntents (r:1 w:1)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: X402Settlement Facilitators (r:1 w:1)
+	/// Proof: X402Settlement Facilitators (max_values: None, max_size: Some(111), added: 2586, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn refund_payment_intent() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2969`
+		//  Estimated: `8909`
+		// Minimum execution time: 67_530_000 picoseconds.
+		Weight::from_parts(69_784_000, 8909)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: X402Settlement ExpiryQueue (r:1 w:1)
+	/// Proof: X402Settlement ExpiryQueue (max_values: None, max_size: Some(2062), added: 4537, mode: MaxEncodedLen)
+	/// Storage: X402Settlement PaymentIntents (r:256 w:256)
+	/// Proof: X402Settlement PaymentIntents (max_values: None, max_size: Some(339), added: 2814, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:257 w:257)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: X402Settlement FacilitatorStatsOf (r:1 w:1)
+	/// Proof: X402Settlement FacilitatorStatsOf (max_values: None, max_size: Some(112), added: 2587, mode: MaxEncodedLen)
+	/// The range of component `n` is `[0, 256]`.
+	/// Storage: X402Settlement OpenIntents (r:1 w:1)
+	/// Proof: X402Settlement OpenIntents (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn on_initialize(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4139 + n * (2711 ±0)`
+		//  Estimated: `12418 + n * (5423 ±0)`
+		// Minimum execution time: 5_963_000 picoseconds.
+		Weight::from_parts(6_148_000, 12418)
+			// Standard Error: 13_950
+			.saturating_add(Weight::from_parts(31_207_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5423).saturating_mul(n.into()))
+	}
+	/// Storage: X402Settlement UsdPeggedAssets (r:0 w:1)
+	/// Proof: X402Settlement UsdPeggedAssets (max_values: None, max_size: Some(21), added: 2496, mode: MaxEncodedLen)
+	fn set_usd_pegged_asset() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_874_000 picoseconds.
+		Weight::from_parts(10_262_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
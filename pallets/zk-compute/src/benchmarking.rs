@@ -3,17 +3,21 @@
 use super::*;
 use frame_benchmarking::vec;
 use crate::Pallet as ZkCompute;
-use frame_benchmarking::v1::whitelisted_caller;
+use frame_benchmarking::v1::{whitelisted_caller, BenchmarkError};
 use frame_support::traits::Currency;
+use frame_support::{traits::UnfilteredDispatchable, unsigned::ValidateUnsigned};
 use frame_system::RawOrigin;
-use sp_runtime::traits::One;
+use sp_runtime::{traits::One, transaction_validity::TransactionSource, RuntimeAppPublic};
 
 fn setup_pending_task<T: Config>(miner: T::AccountId) -> T::TaskId {
     let _ = T::Currency::deposit_creating(&miner, T::SubmissionDeposit::get() + T::BaseReward::get() + T::SubmissionDeposit::get());
+    ZkCompute::<T>::register_hash_commitment_circuit();
     let task_id = NextTaskId::<T>::get();
     let _ = ZkCompute::<T>::submit_proof(
         RawOrigin::Signed(miner).into(),
+        HASH_COMMITMENT_CIRCUIT,
         vec![1u8; 8],
+        vec![],
         (8, 8, 8),
         120,
         1,
@@ -21,12 +25,63 @@ fn setup_pending_task<T: Config>(miner: T::AccountId) -> T::TaskId {
     task_id
 }
 
+/// A BN254 verifying key with `inputs` public inputs and `proofs` valid Groth16 proofs for it,
+/// each binding the dimensions `(8, 8, 8)`. The setup trapdoor is known here, so every proof
+/// is built by solving the verification equation for `C` instead of running a prover.
+fn groth16_fixture(inputs: u32, proofs: u32) -> (Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>) {
+    use ark_bn254::{Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, Field, PrimeField};
+
+    fn fq(value: Fq) -> Vec<u8> {
+        value.into_bigint().to_bytes_be()
+    }
+    fn g1(point: G1Projective) -> Vec<u8> {
+        let point = point.into_affine();
+        [fq(point.x), fq(point.y)].concat()
+    }
+    fn g2(point: G2Projective) -> Vec<u8> {
+        let point = point.into_affine();
+        [fq(point.x.c1), fq(point.x.c0), fq(point.y.c1), fq(point.y.c0)].concat()
+    }
+
+    let (g1_gen, g2_gen) = (G1Affine::generator(), G2Affine::generator());
+    let (alpha, beta, gamma, delta) = (Fr::from(3u64), Fr::from(5u64), Fr::from(7u64), Fr::from(11u64));
+    let ic = (0..=inputs).map(|j| Fr::from(13u64 + j as u64)).collect::<Vec<_>>();
+
+    let mut vk = [g1(g1_gen * alpha), g2(g2_gen * beta), g2(g2_gen * gamma), g2(g2_gen * delta)].concat();
+    ic.iter().for_each(|coeff| vk.extend(g1(g1_gen * coeff)));
+
+    let delta_inv = delta.inverse().expect("delta is not zero");
+    let fixtures = (0..proofs)
+        .map(|p| {
+            let values = (0..inputs)
+                .map(|j| if j < 3 { Fr::from(8u64) } else { Fr::from(p as u64 + j as u64) })
+                .collect::<Vec<_>>();
+            let public_inputs = values
+                .iter()
+                .flat_map(|value| value.into_bigint().to_bytes_be())
+                .collect::<Vec<_>>();
+
+            // e(A, B) = e(alpha, beta) * e(IC(x), gamma) * e(C, delta) in the exponent
+            let ic_x = values.iter().zip(&ic[1..]).fold(ic[0], |acc, (x, coeff)| acc + *x * coeff);
+            let (a, b) = (Fr::from(17u64 + p as u64), Fr::from(19u64 + p as u64));
+            let c = (a * b - alpha * beta - ic_x * gamma) * delta_inv;
+            let proof = [g1(g1_gen * a), g2(g2_gen * b), g1(g1_gen * c)].concat();
+            (proof, public_inputs)
+        })
+        .collect();
+    (vk, fixtures)
+}
+
 frame_benchmarking::v1::benchmarks! {
     submit_proof {
         let miner: T::AccountId = whitelisted_caller();
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         let _ = T::Currency::deposit_creating(&miner, T::SubmissionDeposit::get() + T::BaseReward::get());
-    }: _(RawOrigin::Signed(miner), vec![1u8; 8], (8u32, 8u32, 8u32), 120u32, 42u64)
+        ZkCompute::<T>::register_hash_commitment_circuit();
+        let public_inputs = vec![0u8; T::MaxPublicInputsSize::get() as usize];
+    }: _(RawOrigin::Signed(miner), HASH_COMMITMENT_CIRCUIT, vec![1u8; 8], public_inputs, (8u32, 8u32, 8u32), 120u32, 42u64)
     verify {
         assert_eq!(NextTaskId::<T>::get(), One::one());
     }
@@ -64,20 +119,116 @@ frame_benchmarking::v1::benchmarks! {
     }
 
     submit_verification_unsigned {
+        let k in 1 .. T::MaxAuthorities::get();
         let miner: T::AccountId = whitelisted_caller();
-        let verifier: T::AccountId = frame_benchmarking::v1::account("verifier", 0, 0);
         frame_system::Pallet::<T>::set_block_number(1u32.into());
         let task_id = setup_pending_task::<T>(miner.clone());
-    }: {
-        ZkCompute::<T>::submit_verification_unsigned(
-            frame_system::RawOrigin::None.into(),
+
+        let keys = (0..k).map(|_| T::AuthorityId::generate_pair(None)).collect::<Vec<_>>();
+        ZkCompute::<T>::set_authorities(keys.clone().into_iter());
+        let generic: GenericPublicOf<T> = keys[keys.len() - 1].clone().into();
+        let public: T::Public = generic.into();
+        let payload = VerificationPayload {
             task_id,
-            true,
-        ).expect("submit_verification_unsigned failed");
+            verified: true,
+            block_number: frame_system::Pallet::<T>::block_number(),
+            public: public.clone(),
+        };
+        let signature = <T::AuthorityCrypto as AppCrypto<T::Public, T::Signature>>::sign(
+            &payload.encode(),
+            public,
+        ).expect("authority key is in the keystore");
+        let call = Call::<T>::submit_verification_unsigned { payload, signature };
+    }: {
+        ZkCompute::<T>::validate_unsigned(TransactionSource::InBlock, &call)
+            .map_err(<&str>::from)?;
+        call.dispatch_bypass_filter(RawOrigin::None.into())?;
     }
     verify {
         let task = Tasks::<T>::get(task_id).unwrap();
         assert!(matches!(task.status, ZkVerificationStatus::Verified));
     }
 
+    register_verification_key {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        ZkCompute::<T>::register_hash_commitment_circuit();
+        let key = vec![1u8; T::MaxVerificationKeySize::get() as usize];
+    }: _<T::RuntimeOrigin>(origin, HASH_COMMITMENT_CIRCUIT, ProvingSystem::HashCommitment, key, 10u32.into())
+    verify {
+        assert_eq!(LatestKeyVersion::<T>::get(HASH_COMMITMENT_CIRCUIT), Some(1));
+    }
+
+    deprecate_verification_key {
+        let origin = T::AdminOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        ZkCompute::<T>::register_hash_commitment_circuit();
+    }: _<T::RuntimeOrigin>(origin, HASH_COMMITMENT_CIRCUIT, 0, 10u32.into())
+    verify {
+        let key = VerificationKeys::<T>::get(HASH_COMMITMENT_CIRCUIT, 0).unwrap();
+        assert_eq!(key.deprecation_block, Some(10u32.into()));
+    }
+
+    verify_tasks_batch {
+        let n in 1 .. T::MaxVerificationBatch::get();
+        let verifier: T::AccountId = frame_benchmarking::v1::account("verifier", 0, 0);
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let task_ids = (0..n)
+            .map(|i| setup_pending_task::<T>(frame_benchmarking::v1::account("miner", i, 0)))
+            .collect::<Vec<_>>();
+        let _ = T::Currency::deposit_creating(&ZkCompute::<T>::account_id(), T::SubmissionDeposit::get() + T::BaseReward::get());
+    }: _(RawOrigin::Signed(verifier), task_ids.clone())
+    verify {
+        for task_id in task_ids {
+            let task = Tasks::<T>::get(task_id).unwrap();
+            assert!(!matches!(task.status, ZkVerificationStatus::Pending));
+        }
+    }
+
+    verify_groth16_proof {
+        let i in 3 .. groth16::max_public_inputs::<T>();
+        let (vk, mut proofs) = groth16_fixture(i, 1);
+        let (proof, public_inputs) = proofs.remove(0);
+    }: {
+        assert!(groth16::Groth16Verifier::<T>::verify(&vk, &proof, &public_inputs, (8, 8, 8)));
+    }
+
+    verify_groth16_batch {
+        let n in 1 .. T::MaxVerificationBatch::get();
+        let i in 3 .. groth16::max_public_inputs::<T>();
+        let (vk, proofs) = groth16_fixture(i, n);
+        let batch = proofs
+            .iter()
+            .map(|(proof, public_inputs)| BatchProof { proof, public_inputs, dimensions: (8, 8, 8) })
+            .collect::<Vec<_>>();
+    }: {
+        assert!(groth16::Groth16Verifier::<T>::verify_batch(&vk, &batch));
+    }
+
+    prune_settled_task {
+        let miner: T::AccountId = whitelisted_caller();
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let task_id = setup_pending_task::<T>(miner);
+        ZkCompute::<T>::schedule_prune(task_id);
+        let now = frame_system::Pallet::<T>::block_number() + T::TaskRetentionPeriod::get();
+    }: {
+        ZkCompute::<T>::prune_settled_tasks(now, Weight::MAX);
+    }
+    verify {
+        assert!(Tasks::<T>::get(task_id).is_none());
+    }
+
+    expire_overdue_task {
+        let miner: T::AccountId = whitelisted_caller();
+        frame_system::Pallet::<T>::set_block_number(1u32.into());
+        let task_id = setup_pending_task::<T>(miner);
+        let now = frame_system::Pallet::<T>::block_number() + T::VerificationTimeout::get() + One::one();
+    }: {
+        ZkCompute::<T>::expire_overdue_tasks(now, Weight::MAX);
+    }
+    verify {
+        assert_eq!(Tasks::<T>::get(task_id).unwrap().status, ZkVerificationStatus::Expired);
+    }
 }
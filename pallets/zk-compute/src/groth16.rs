@@ -0,0 +1,264 @@
+//! Native Groth16 verifier over BN254 for proofs produced by circom/snarkjs circuits.
+//!
+//! All group elements and scalars use the big-endian encoding of the Ethereum `ecPairing`
+//! precompile (EIP-197), i.e. the calldata layout of the snarkjs Solidity verifier:
+//!
+//! - G1 point: `x || y`, 64 bytes.
+//! - G2 point: `x.c1 || x.c0 || y.c1 || y.c0`, 128 bytes.
+//! - Scalar: 32 bytes, canonical (below the BN254 scalar field modulus).
+//!
+//! A verifying key is `alpha_g1 || beta_g2 || gamma_g2 || delta_g2 || ic[0] || .. || ic[n]`,
+//! where `n` is the number of public inputs. A task proof is `a_g1 || b_g2 || c_g1`, and the
+//! first three of the task's public inputs must be its `m`, `n` and `k` so the proof is bound
+//! to the claimed dimensions.
+//!
+//! Proofs sharing a verifying key can be checked together with a random linear combination:
+//! `n` proofs cost `n + 3` Miller loops and one final exponentiation instead of `n` full
+//! four-pairing verifications.
+
+use crate::{weights::WeightInfo, BatchProof, Config, VerifyZkProof};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{BigInt, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, VerifyingKey};
+use frame_support::pallet_prelude::*;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub const SCALAR_SIZE: usize = 32;
+pub const G1_SIZE: usize = 64;
+pub const G2_SIZE: usize = 128;
+pub const PROOF_SIZE: usize = 2 * G1_SIZE + G2_SIZE;
+/// Verifying key without its `ic` points.
+pub const VERIFYING_KEY_BASE_SIZE: usize = G1_SIZE + 3 * G2_SIZE;
+
+/// `VerifyZkProof` backend for circuits registered with `ProvingSystem::Groth16Bn254`.
+pub struct Groth16Verifier<T>(PhantomData<T>);
+
+impl<T: Config> VerifyZkProof for Groth16Verifier<T> {
+	fn verify(
+		verification_key: &[u8],
+		proof: &[u8],
+		public_inputs: &[u8],
+		dimensions: (u32, u32, u32),
+	) -> bool {
+		if verification_key.len() > T::MaxVerificationKeySize::get() as usize ||
+			public_inputs.len() > T::MaxPublicInputsSize::get() as usize
+		{
+			return false
+		}
+		if !binds_dimensions(public_inputs, dimensions) {
+			return false
+		}
+		verify(verification_key, proof, public_inputs)
+	}
+
+	fn is_valid_key(verification_key: &[u8]) -> bool {
+		decode_verifying_key(verification_key).is_some()
+	}
+
+	fn verification_weight() -> Weight {
+		T::WeightInfo::verify_groth16_proof(max_public_inputs::<T>())
+	}
+
+	fn verify_batch(verification_key: &[u8], proofs: &[BatchProof]) -> bool {
+		if verification_key.len() > T::MaxVerificationKeySize::get() as usize {
+			return false
+		}
+		let within_limits = proofs.iter().all(|batch_proof| {
+			batch_proof.public_inputs.len() <= T::MaxPublicInputsSize::get() as usize &&
+				binds_dimensions(batch_proof.public_inputs, batch_proof.dimensions)
+		});
+		if !within_limits {
+			return false
+		}
+		let proofs = proofs
+			.iter()
+			.map(|batch_proof| (batch_proof.proof, batch_proof.public_inputs))
+			.collect::<Vec<_>>();
+		verify_batch(verification_key, &proofs)
+	}
+
+	fn batch_verification_weight(proofs: u32) -> Weight {
+		T::WeightInfo::verify_groth16_batch(proofs, max_public_inputs::<T>())
+	}
+}
+
+/// Public inputs a task proof can carry within `MaxPublicInputsSize`.
+pub fn max_public_inputs<T: Config>() -> u32 {
+	T::MaxPublicInputsSize::get() / SCALAR_SIZE as u32
+}
+
+/// Verify a Groth16 `proof` of `public_inputs` against the encoded `verifying_key`.
+pub fn verify(verifying_key: &[u8], proof: &[u8], public_inputs: &[u8]) -> bool {
+	let vk = match decode_verifying_key(verifying_key) {
+		Some(vk) => vk,
+		None => return false,
+	};
+	let (proof, inputs) = match (decode_proof(proof), decode_public_inputs(public_inputs)) {
+		(Some(proof), Some(inputs)) => (proof, inputs),
+		_ => return false,
+	};
+	if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+		return false
+	}
+	let pvk: PreparedVerifyingKey<Bn254> = ark_groth16::prepare_verifying_key(&vk);
+	Groth16::<Bn254>::verify_proof(&pvk, &proof, &inputs).unwrap_or(false)
+}
+
+/// Verify `(proof, public_inputs)` pairs against one verifying key at once. Returns `false`
+/// if any proof is invalid.
+///
+/// For challenges `r_i` this checks
+/// `prod e(r_i * A_i, B_i) = e(sum(r_i) * alpha, beta) * e(sum(r_i * IC(x_i)), gamma) * e(sum(r_i * C_i), delta)`.
+/// The challenges are derived from the key and every proof and input, so a prover cannot
+/// pick proofs whose errors cancel out.
+pub fn verify_batch(verifying_key: &[u8], proofs: &[(&[u8], &[u8])]) -> bool {
+	let vk = match decode_verifying_key(verifying_key) {
+		Some(vk) => vk,
+		None => return false,
+	};
+	let mut decoded = Vec::with_capacity(proofs.len());
+	for (proof, public_inputs) in proofs {
+		match (decode_proof(proof), decode_public_inputs(public_inputs)) {
+			(Some(proof), Some(inputs)) if inputs.len() + 1 == vk.gamma_abc_g1.len() =>
+				decoded.push((proof, inputs)),
+			_ => return false,
+		}
+	}
+
+	// `ic_coeffs[0]` accumulates `sum(r_i)`, `ic_coeffs[j + 1]` accumulates `sum(r_i * x_ij)`.
+	let mut ic_coeffs = sp_std::vec![Fr::zero(); vk.gamma_abc_g1.len()];
+	let mut c_acc = G1Projective::zero();
+	let mut g1 = Vec::with_capacity(decoded.len() + 3);
+	let mut g2 = Vec::with_capacity(decoded.len() + 3);
+	for ((proof, inputs), r) in decoded.iter().zip(batch_challenges(verifying_key, proofs)) {
+		g1.push((proof.a * r).into_affine());
+		g2.push(proof.b);
+		ic_coeffs[0] += r;
+		for (coeff, input) in ic_coeffs[1..].iter_mut().zip(inputs) {
+			*coeff += r * input;
+		}
+		c_acc += proof.c * r;
+	}
+	let ic_acc = vk
+		.gamma_abc_g1
+		.iter()
+		.zip(&ic_coeffs)
+		.fold(G1Projective::zero(), |acc, (point, coeff)| acc + *point * coeff);
+
+	g1.push((-(vk.alpha_g1 * ic_coeffs[0])).into_affine());
+	g2.push(vk.beta_g2);
+	g1.push((-ic_acc).into_affine());
+	g2.push(vk.gamma_g2);
+	g1.push((-c_acc).into_affine());
+	g2.push(vk.delta_g2);
+
+	Bn254::multi_pairing(g1, g2).is_zero()
+}
+
+/// Fiat-Shamir challenges for `verify_batch`, one per proof.
+fn batch_challenges(verifying_key: &[u8], proofs: &[(&[u8], &[u8])]) -> Vec<Fr> {
+	let mut transcript = verifying_key.to_vec();
+	for (proof, public_inputs) in proofs {
+		transcript.extend_from_slice(proof);
+		transcript.extend_from_slice(public_inputs);
+	}
+	let seed = sp_io::hashing::blake2_256(&transcript);
+	(0..proofs.len() as u32)
+		.map(|i| {
+			let challenge = sp_io::hashing::blake2_256(&(seed, i).encode());
+			Fr::from_le_bytes_mod_order(&challenge)
+		})
+		.collect()
+}
+
+/// Decode a verifying key, rejecting points that are off-curve or outside the prime subgroup.
+pub fn decode_verifying_key(bytes: &[u8]) -> Option<VerifyingKey<Bn254>> {
+	if bytes.len() < VERIFYING_KEY_BASE_SIZE + G1_SIZE ||
+		(bytes.len() - VERIFYING_KEY_BASE_SIZE) % G1_SIZE != 0
+	{
+		return None
+	}
+	let (alpha, rest) = bytes.split_at(G1_SIZE);
+	let (beta, rest) = rest.split_at(G2_SIZE);
+	let (gamma, rest) = rest.split_at(G2_SIZE);
+	let (delta, ic) = rest.split_at(G2_SIZE);
+
+	Some(VerifyingKey {
+		alpha_g1: decode_g1(alpha)?,
+		beta_g2: decode_g2(beta)?,
+		gamma_g2: decode_g2(gamma)?,
+		delta_g2: decode_g2(delta)?,
+		gamma_abc_g1: ic.chunks(G1_SIZE).map(decode_g1).collect::<Option<Vec<_>>>()?,
+	})
+}
+
+fn decode_proof(bytes: &[u8]) -> Option<Proof<Bn254>> {
+	if bytes.len() != PROOF_SIZE {
+		return None
+	}
+	let (a, rest) = bytes.split_at(G1_SIZE);
+	let (b, c) = rest.split_at(G2_SIZE);
+	Some(Proof { a: decode_g1(a)?, b: decode_g2(b)?, c: decode_g1(c)? })
+}
+
+fn decode_public_inputs(bytes: &[u8]) -> Option<Vec<Fr>> {
+	if bytes.len() % SCALAR_SIZE != 0 {
+		return None
+	}
+	bytes.chunks(SCALAR_SIZE).map(|chunk| Fr::from_bigint(be_bigint(chunk))).collect()
+}
+
+/// The first three public inputs must be the task dimensions `m`, `n`, `k`.
+fn binds_dimensions(public_inputs: &[u8], (m, n, k): (u32, u32, u32)) -> bool {
+	if public_inputs.len() < 3 * SCALAR_SIZE {
+		return false
+	}
+	public_inputs
+		.chunks(SCALAR_SIZE)
+		.zip([m, n, k])
+		.all(|(input, dim)| input == encode_u32_scalar(dim).as_slice())
+}
+
+/// Big-endian scalar encoding of a small integer public input.
+pub fn encode_u32_scalar(value: u32) -> [u8; SCALAR_SIZE] {
+	let mut scalar = [0u8; SCALAR_SIZE];
+	scalar[SCALAR_SIZE - 4..].copy_from_slice(&value.to_be_bytes());
+	scalar
+}
+
+fn decode_fq(bytes: &[u8]) -> Option<Fq> {
+	Fq::from_bigint(be_bigint(bytes))
+}
+
+fn decode_g1(bytes: &[u8]) -> Option<G1Affine> {
+	let (x, y) = bytes.split_at(SCALAR_SIZE);
+	let (x, y) = (decode_fq(x)?, decode_fq(y)?);
+	if x.is_zero() && y.is_zero() {
+		return Some(G1Affine::identity())
+	}
+	let point = G1Affine::new_unchecked(x, y);
+	(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()).then_some(point)
+}
+
+fn decode_g2(bytes: &[u8]) -> Option<G2Affine> {
+	let mut limbs = bytes.chunks(SCALAR_SIZE).map(decode_fq);
+	let (x_c1, x_c0, y_c1, y_c0) = (limbs.next()??, limbs.next()??, limbs.next()??, limbs.next()??);
+	let (x, y) = (Fq2::new(x_c0, x_c1), Fq2::new(y_c0, y_c1));
+	if x.is_zero() && y.is_zero() {
+		return Some(G2Affine::identity())
+	}
+	let point = G2Affine::new_unchecked(x, y);
+	(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()).then_some(point)
+}
+
+/// Read a 32-byte big-endian integer into little-endian `u64` limbs.
+fn be_bigint(bytes: &[u8]) -> BigInt<4> {
+	let mut limbs = [0u64; 4];
+	for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks(8)) {
+		let mut word = [0u8; 8];
+		word.copy_from_slice(chunk);
+		*limb = u64::from_be_bytes(word);
+	}
+	BigInt::new(limbs)
+}
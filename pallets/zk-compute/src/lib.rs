@@ -1,6 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub mod groth16;
+pub mod migrations;
 pub mod weights;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -12,31 +14,120 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-use frame_support::pallet_prelude::*;
-use sp_runtime::traits::AtLeast32BitUnsigned;
+use frame_support::{pallet_prelude::*, traits::OneSessionHandler, WeakBoundedVec};
+use frame_system::offchain::{AppCrypto, SignedPayload, SigningTypes};
+use sp_runtime::{traits::AtLeast32BitUnsigned, KeyTypeId};
 use sp_std::vec::Vec;
 
+/// Key type used by validators to sign off-chain zk verification results.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"zkvf");
+
+pub mod sr25519 {
+	mod app_sr25519 {
+		use sp_runtime::app_crypto::{app_crypto, sr25519};
+		app_crypto!(sr25519, crate::KEY_TYPE);
+	}
+
+	use sp_runtime::{MultiSignature, MultiSigner};
+
+	/// Session key validators use to sign zk verification payloads.
+	pub type AuthorityId = app_sr25519::Public;
+	pub type AuthoritySignature = app_sr25519::Signature;
+
+	/// Maps `AuthorityId` onto the runtime's `MultiSigner`/`MultiSignature` signing types.
+	pub struct AuthorityCrypto;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for AuthorityCrypto {
+		type RuntimeAppPublic = AuthorityId;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Proving-system backend checking a task proof against a registered verification key.
 pub trait VerifyZkProof {
-	fn verify(proof: &[u8], dimensions: (u32, u32, u32)) -> bool;
+	fn verify(
+		verification_key: &[u8],
+		proof: &[u8],
+		public_inputs: &[u8],
+		dimensions: (u32, u32, u32),
+	) -> bool;
+
+	/// Whether `verification_key` is well formed for this backend.
+	fn is_valid_key(_verification_key: &[u8]) -> bool {
+		true
+	}
+
+	/// Worst-case weight of one on-chain `verify` call, charged on top of `verify_task`.
+	fn verification_weight() -> Weight {
+		Weight::zero()
+	}
+
+	/// Check several proofs against the same key at once. Returns `false` if any of them is
+	/// invalid, without saying which; callers fall back to `verify` to find out.
+	fn verify_batch(verification_key: &[u8], proofs: &[BatchProof]) -> bool {
+		proofs.iter().all(|batch_proof| {
+			Self::verify(
+				verification_key,
+				batch_proof.proof,
+				batch_proof.public_inputs,
+				batch_proof.dimensions,
+			)
+		})
+	}
+
+	/// Worst-case weight of one `verify_batch` call over `proofs` proofs.
+	fn batch_verification_weight(proofs: u32) -> Weight {
+		Self::verification_weight().saturating_mul(proofs as u64)
+	}
+}
+
+/// One task proof of a batch checked against a shared verification key.
+pub struct BatchProof<'a> {
+	pub proof: &'a [u8],
+	pub public_inputs: &'a [u8],
+	pub dimensions: (u32, u32, u32),
+}
+
+/// Verification verdict signed by a session authority's off-chain worker.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct VerificationPayload<Public, TaskId, BlockNumber> {
+	pub task_id: TaskId,
+	pub verified: bool,
+	pub block_number: BlockNumber,
+	pub public: Public,
+}
+
+impl<T: Config> SignedPayload<T>
+	for VerificationPayload<T::Public, T::TaskId, T::BlockNumber>
+{
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
 }
 
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::traits::StorageVersion;
 	use super::*;
-	use frame_system::offchain::SubmitTransaction;
-	use sp_runtime::transaction_validity::{
-		InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
+	use frame_system::offchain::{CreateSignedTransaction, SendUnsignedTransaction, Signer};
+	use sp_runtime::{
+		offchain::storage::{StorageRetrievalError, StorageValueRef},
+		transaction_validity::{
+			InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
+		},
+		RuntimeAppPublic,
 	};
 	use crate::weights::WeightInfo;
 	use frame_support::{
-		dispatch::DispatchResult,
+		dispatch::{DispatchResult, DispatchResultWithPostInfo},
 		traits::{
 			BalanceStatus, Currency, ExistenceRequirement::AllowDeath, ReservableCurrency,
 		},
 		transactional, PalletId,
 	};
 	use frame_system::pallet_prelude::*;
+	use sp_std::{collections::btree_map::BTreeMap, vec};
 	use sp_runtime::traits::{
 		AccountIdConversion, CheckedAdd, One, SaturatedConversion, Saturating, Zero,
 	};
@@ -45,26 +136,79 @@ pub mod pallet {
 	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 	pub type BoundedProofOf<T> = BoundedVec<u8, <T as Config>::MaxProofSize>;
+	pub type BoundedPublicInputsOf<T> = BoundedVec<u8, <T as Config>::MaxPublicInputsSize>;
+	pub type BoundedVerificationKeyOf<T> = BoundedVec<u8, <T as Config>::MaxVerificationKeySize>;
 	pub type ZkTaskOf<T> = ZkTask<
 		TaskIdOf<T>,
 		<T as frame_system::Config>::AccountId,
 		<T as frame_system::Config>::BlockNumber,
 		BalanceOf<T>,
 		BoundedProofOf<T>,
+		BoundedPublicInputsOf<T>,
+	>;
+	pub type VerificationKeyInfoOf<T> = VerificationKeyInfo<
+		<T as frame_system::Config>::BlockNumber,
+		BoundedVerificationKeyOf<T>,
+	>;
+
+	pub type CircuitId = u32;
+	pub type KeyVersion = u32;
+
+	/// Built-in circuit for hash-commitment proofs, registered at genesis.
+	pub const HASH_COMMITMENT_CIRCUIT: CircuitId = 0;
+	pub type VerificationPayloadOf<T> = VerificationPayload<
+		<T as SigningTypes>::Public,
+		TaskIdOf<T>,
+		<T as frame_system::Config>::BlockNumber,
 	>;
+	pub(crate) type GenericPublicOf<T> = <<T as Config>::AuthorityCrypto as AppCrypto<
+		<T as SigningTypes>::Public,
+		<T as SigningTypes>::Signature,
+	>>::GenericPublic;
 
 	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	pub enum ZkVerificationStatus {
 		Pending,
 		Verified,
 		Failed,
+		/// Not verified within `VerificationTimeout`; the submission deposit was refunded.
+		Expired,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ProvingSystem {
+		/// Checked by `Config::HashCommitmentVerifier`.
+		HashCommitment,
+		/// Checked by the native `groth16::Groth16Verifier`.
+		Groth16Bn254,
+	}
+
+	/// One version of a circuit's verification key.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct VerificationKeyInfo<BlockNumber, Key> {
+		pub proving_system: ProvingSystem,
+		pub key: Key,
+		/// First block at which new proofs may use this key.
+		pub activation_block: BlockNumber,
+		/// First block at which new proofs may no longer use this key.
+		pub deprecation_block: Option<BlockNumber>,
+	}
+
+	impl<BlockNumber: PartialOrd, Key> VerificationKeyInfo<BlockNumber, Key> {
+		pub fn is_active_at(&self, now: &BlockNumber) -> bool {
+			self.activation_block <= *now &&
+				self.deprecation_block.as_ref().map_or(true, |deprecation| now < deprecation)
+		}
 	}
 
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-	pub struct ZkTask<TaskId, AccountId, BlockNumber, Balance, Proof> {
+	pub struct ZkTask<TaskId, AccountId, BlockNumber, Balance, Proof, PublicInputs> {
 		pub task_id: TaskId,
 		pub miner: AccountId,
+		pub circuit_id: CircuitId,
+		pub key_version: KeyVersion,
 		pub proof: Proof,
+		pub public_inputs: PublicInputs,
 		pub dimensions: (u32, u32, u32),
 		pub status: ZkVerificationStatus,
 		pub base_reward: Balance,
@@ -75,7 +219,7 @@ pub mod pallet {
 	}
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config + frame_system::offchain::SendTransactionTypes<Call<Self>> {
+	pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
 		type Currency: ReservableCurrency<Self::AccountId>;
@@ -88,7 +232,25 @@ pub mod pallet {
 			+ MaxEncodedLen
 			+ TypeInfo;
 
-		type ZkVerifier: VerifyZkProof;
+		/// Backend for circuits registered with `ProvingSystem::HashCommitment`.
+		type HashCommitmentVerifier: VerifyZkProof;
+
+		/// Origin allowed to register and deprecate verification keys.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Session key of the authorities allowed to report off-chain verification results.
+		type AuthorityId: Member
+			+ Parameter
+			+ RuntimeAppPublic
+			+ Ord
+			+ MaybeSerializeDeserialize
+			+ MaxEncodedLen;
+
+		/// Signs and verifies `VerificationPayload`s with `AuthorityId` keys.
+		type AuthorityCrypto: AppCrypto<Self::Public, Self::Signature, RuntimeAppPublic = Self::AuthorityId>;
+
+		#[pallet::constant]
+		type MaxAuthorities: Get<u32>;
 
 		#[pallet::constant]
 		type MaxProofSize: Get<u32>;
@@ -102,10 +264,17 @@ pub mod pallet {
 		type MaxVerifiedTasks: Get<u32>;
 		#[pallet::constant]
 		type MaxPendingPerMiner: Get<u32>;
+		/// Blocks a failed or claimed task is kept before `on_idle` prunes it.
+		#[pallet::constant]
+		type TaskRetentionPeriod: Get<Self::BlockNumber>;
+		/// Maximum number of tasks one `verify_tasks_batch` call may verify.
+		#[pallet::constant]
+		type MaxVerificationBatch: Get<u32>;
 		#[pallet::constant]
 		type BaseReward: Get<BalanceOf<Self>>;
 		#[pallet::constant]
 		type SubmissionDeposit: Get<BalanceOf<Self>>;
+		/// Blocks a task may stay pending before it expires.
 		#[pallet::constant]
 		type VerificationTimeout: Get<Self::BlockNumber>;
 		#[pallet::constant]
@@ -124,8 +293,10 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 	const UNSIGNED_TXS_PRIORITY: u64 = 100;
+	/// Blocks an off-chain worker waits before re-sending a verdict for the same task.
+	const OCW_RESUBMIT_INTERVAL: u32 = 5;
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -139,15 +310,38 @@ pub mod pallet {
 	#[pallet::getter(fn tasks)]
 	pub type Tasks<T: Config> = StorageMap<_, Blake2_128Concat, T::TaskId, ZkTaskOf<T>>;
 
+	/// Tasks awaiting verification, at most `MaxPendingTasks`.
+	#[pallet::storage]
+	pub type PendingTasks<T: Config> = CountedStorageMap<_, Twox64Concat, T::TaskId, ()>;
+
+	/// Lowest task id that may still be pending. Tasks are submitted in id order, so every
+	/// overdue pending task sits between this cursor and `NextTaskId`.
+	#[pallet::storage]
+	pub type ExpiryCursor<T: Config> = StorageValue<_, T::TaskId, ValueQuery>;
+
+	/// Most recently verified tasks, a ring buffer of `MaxVerifiedTasks` slots.
 	#[pallet::storage]
-	#[pallet::getter(fn pending_tasks)]
-	pub type PendingTasks<T: Config> =
-		StorageValue<_, BoundedVec<T::TaskId, T::MaxPendingTasks>, ValueQuery>;
+	pub type VerifiedTasks<T: Config> = StorageMap<_, Twox64Concat, u32, T::TaskId>;
 
+	/// Number of tasks ever recorded in `VerifiedTasks`; the next slot is this value
+	/// modulo `MaxVerifiedTasks`.
 	#[pallet::storage]
-	#[pallet::getter(fn verified_tasks)]
-	pub type VerifiedTasks<T: Config> =
-		StorageValue<_, BoundedVec<T::TaskId, T::MaxVerifiedTasks>, ValueQuery>;
+	pub type VerifiedTasksCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Settled tasks and the block from which their record may be pruned, in the order they
+	/// were settled.
+	#[pallet::storage]
+	pub type PruneQueue<T: Config> =
+		StorageMap<_, Twox64Concat, u64, (T::TaskId, T::BlockNumber)>;
+
+	/// `(head, tail)` of `PruneQueue`: the live entries are `head..tail`.
+	#[pallet::storage]
+	pub type PruneQueueBounds<T: Config> = StorageValue<_, (u64, u64), ValueQuery>;
+
+	/// `(next, end)` task ids the v4 migration has yet to check for pruning. Tasks settled
+	/// before the upgrade are queued from `on_idle`, a few per block, until `next` reaches `end`.
+	#[pallet::storage]
+	pub type LegacyPruneScan<T: Config> = StorageValue<_, (T::TaskId, T::TaskId), OptionQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn miner_score)]
@@ -162,6 +356,29 @@ pub mod pallet {
 	#[pallet::getter(fn miner_pending_count)]
 	pub type MinerPendingCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
+	/// Verification keys by circuit and version.
+	#[pallet::storage]
+	#[pallet::getter(fn verification_key)]
+	pub type VerificationKeys<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CircuitId,
+		Twox64Concat,
+		KeyVersion,
+		VerificationKeyInfoOf<T>,
+	>;
+
+	/// Newest registered key version of each circuit.
+	#[pallet::storage]
+	#[pallet::getter(fn latest_key_version)]
+	pub type LatestKeyVersion<T: Config> = StorageMap<_, Twox64Concat, CircuitId, KeyVersion>;
+
+	/// Verification keys of the current session's authorities.
+	#[pallet::storage]
+	#[pallet::getter(fn authorities)]
+	pub type Authorities<T: Config> =
+		StorageValue<_, WeakBoundedVec<T::AuthorityId, T::MaxAuthorities>, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -171,7 +388,29 @@ pub mod pallet {
 			nonce: u64,
 			dimensions: (u32, u32, u32),
 		},
+		VerificationKeyRegistered {
+			circuit_id: CircuitId,
+			version: KeyVersion,
+			proving_system: ProvingSystem,
+			activation_block: T::BlockNumber,
+		},
+		VerificationKeyDeprecated {
+			circuit_id: CircuitId,
+			version: KeyVersion,
+			deprecation_block: T::BlockNumber,
+		},
 		ProofVerifiedByOcw { task_id: T::TaskId, verified: bool },
+		TasksBatchVerified {
+			verifier: T::AccountId,
+			verified: u32,
+			failed: u32,
+		},
+		TasksPruned { count: u32 },
+		TaskExpired {
+			task_id: T::TaskId,
+			miner: T::AccountId,
+			refunded: BalanceOf<T>,
+		},
 		ProofVerified {
 			task_id: T::TaskId,
 			verifier: T::AccountId,
@@ -207,10 +446,20 @@ pub mod pallet {
 		ArithmeticOverflow,
 		NonceAlreadyUsed,
 		TooManyPendingTasks,
+		/// No longer returned since `VerifiedTasks` became a ring buffer.
 		TooManyVerifiedTasks,
 		TooManyPendingTasksForMiner,
 		InsufficientMinerScore,
 		BalanceTransferFailed,
+		VerificationKeyTooLarge,
+		PublicInputsTooLarge,
+		InvalidVerificationKey,
+		VerificationKeyNotFound,
+		NoActiveVerificationKey,
+		KeyVersionPending,
+		KeyVersionOverflow,
+		EmptyBatch,
+		BatchTooLarge,
 	}
 
 
@@ -228,13 +477,21 @@ pub mod pallet {
 
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
-        fn build(&self) {}
+        fn build(&self) {
+            Pallet::<T>::register_hash_commitment_circuit();
+        }
     }
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn offchain_worker(_block_number: BlockNumberFor<T>) {
-			let _ = Self::ocw_verify_pending_tasks();
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let mut used = Self::expire_overdue_tasks(now, remaining_weight);
+			used.saturating_accrue(Self::prune_settled_tasks(now, remaining_weight.saturating_sub(used)));
+			used.saturating_add(Self::scan_legacy_tasks(remaining_weight.saturating_sub(used)))
+		}
+
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let _ = Self::ocw_verify_pending_tasks(block_number);
 		}
 	}
 
@@ -245,7 +502,9 @@ pub mod pallet {
 		#[transactional]
 		pub fn submit_proof(
 			origin: OriginFor<T>,
+			circuit_id: CircuitId,
 			proof: Vec<u8>,
+			public_inputs: Vec<u8>,
 			dimensions: (u32, u32, u32),
 			multiplier_q100: u32,
 			nonce: u64,
@@ -265,20 +524,30 @@ pub mod pallet {
 				pending_count < T::MaxPendingPerMiner::get(),
 				Error::<T>::TooManyPendingTasksForMiner
 			);
+			ensure!(
+				PendingTasks::<T>::count() < T::MaxPendingTasks::get(),
+				Error::<T>::TooManyPendingTasks
+			);
 
 			let bounded_proof =
 				BoundedProofOf::<T>::try_from(proof).map_err(|_| Error::<T>::ProofTooLarge)?;
+			let public_inputs = BoundedPublicInputsOf::<T>::try_from(public_inputs)
+				.map_err(|_| Error::<T>::PublicInputsTooLarge)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			let key_version = Self::active_key_version(circuit_id, now)?;
 
 			T::Currency::reserve(&miner, T::SubmissionDeposit::get())?;
 
 			let task_id = NextTaskId::<T>::get();
 			let next_task_id = task_id.checked_add(&One::one()).ok_or(Error::<T>::TaskIdOverflow)?;
-			let now = <frame_system::Pallet<T>>::block_number();
 
 			let task = ZkTask {
 				task_id,
 				miner: miner.clone(),
+				circuit_id,
+				key_version,
 				proof: bounded_proof,
+				public_inputs,
 				dimensions,
 				status: ZkVerificationStatus::Pending,
 				base_reward: T::BaseReward::get(),
@@ -288,10 +557,7 @@ pub mod pallet {
 				reward_claimed: false,
 			};
 
-			PendingTasks::<T>::try_mutate(|queue| {
-				queue.try_push(task_id).map_err(|_| Error::<T>::TooManyPendingTasks)
-			})?;
-
+			PendingTasks::<T>::insert(task_id, ());
 			Tasks::<T>::insert(task_id, task);
 			NextTaskId::<T>::put(next_task_id);
 			UsedNonces::<T>::insert(&miner, nonce, true);
@@ -302,7 +568,7 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(T::WeightInfo::verify_task())]
+		#[pallet::weight(T::WeightInfo::verify_task().saturating_add(Pallet::<T>::max_verification_weight()))]
 		#[transactional]
 		pub fn verify_task(origin: OriginFor<T>, task_id: T::TaskId) -> DispatchResult {
 			let verifier = ensure_signed(origin)?;
@@ -310,52 +576,15 @@ pub mod pallet {
 			ensure!(task.status == ZkVerificationStatus::Pending, Error::<T>::InvalidTaskStatus);
 
 			let now = <frame_system::Pallet<T>>::block_number();
-			let deadline = task
-				.submitted_at
-				.checked_add(&T::VerificationTimeout::get())
-				.ok_or(Error::<T>::ArithmeticOverflow)?;
-
-			let timed_out = now > deadline;
-			let verified = if timed_out {
-				false
-			} else {
-				T::ZkVerifier::verify(task.proof.as_ref(), task.dimensions)
-			};
-
-			task.status = if verified {
-				ZkVerificationStatus::Verified
-			} else {
-				ZkVerificationStatus::Failed
-			};
-			Tasks::<T>::insert(task_id, &task);
-
-			Self::remove_from_pending(task_id);
-			let miner_pending = MinerPendingCount::<T>::get(&task.miner);
-			MinerPendingCount::<T>::insert(&task.miner, miner_pending.saturating_sub(1));
-
-			if verified {
-				VerifiedTasks::<T>::try_mutate(|history| {
-					history.try_push(task_id).map_err(|_| Error::<T>::TooManyVerifiedTasks)
-				})?;
-				Self::increase_score(&task.miner);
-			} else {
-				Self::decrease_score(&task.miner);
-				let moved = T::Currency::repatriate_reserved(
-					&task.miner,
-					&Self::account_id(),
-					task.submission_deposit,
-					BalanceStatus::Free,
-				)?;
-				let slashed = task.submission_deposit.saturating_sub(moved);
-				if !slashed.is_zero() {
-					Self::deposit_event(Event::DepositSlashed {
-						task_id,
-						miner: task.miner.clone(),
-						amount: slashed,
-					});
-				}
+			if Self::is_overdue(&task, now) {
+				// Not the miner's fault: refund instead of slashing.
+				Self::expire_task(task_id, &mut task);
+				return Ok(())
 			}
 
+			let verified = Self::verify_proof(&task);
+			Self::settle_verdict(task_id, &mut task, verified)?;
+
 			Self::deposit_event(Event::ProofVerified {
 				task_id,
 				verifier,
@@ -382,6 +611,7 @@ pub mod pallet {
 
 			task.reward_claimed = true;
 			Tasks::<T>::insert(task_id, task.clone());
+			Self::schedule_prune(task_id);
 
 			Self::deposit_event(Event::RewardClaimed {
 				task_id,
@@ -390,51 +620,193 @@ pub mod pallet {
 			});
 			Ok(())
 		}
-		/// Submit ZK verification result from off-chain worker (unsigned transaction)
+		/// Submit ZK verification result from off-chain worker (unsigned transaction
+		/// carrying a payload signed by a session authority)
 		#[pallet::call_index(3)]
-		#[pallet::weight(T::WeightInfo::submit_verification_unsigned())]
+		#[pallet::weight(T::WeightInfo::submit_verification_unsigned(T::MaxAuthorities::get()))]
 		#[transactional]
 		pub fn submit_verification_unsigned(
 			origin: OriginFor<T>,
-			task_id: T::TaskId,
-			verified: bool,
+			payload: VerificationPayloadOf<T>,
+			_signature: T::Signature,
 		) -> DispatchResult {
 			ensure_none(origin)?;
+			let task_id = payload.task_id;
+			let verified = payload.verified;
 			let mut task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
-			ensure!(task.status == ZkVerificationStatus::Pending, Error::<T>::InvalidTaskStatus);
+			// Another authority's verdict for this task already landed.
+			if task.status != ZkVerificationStatus::Pending {
+				return Ok(())
+			}
 
-			task.status = if verified {
-				ZkVerificationStatus::Verified
-			} else {
-				ZkVerificationStatus::Failed
+			Self::settle_verdict(task_id, &mut task, verified)?;
+
+			Self::deposit_event(Event::ProofVerifiedByOcw {
+				task_id,
+				verified,
+			});
+
+			Ok(())
+		}
+
+		/// Register a new version of `circuit_id`'s verification key. It is used for new
+		/// proofs from `activation_block` on, superseding earlier versions.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::register_verification_key())]
+		pub fn register_verification_key(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			proving_system: ProvingSystem,
+			key: Vec<u8>,
+			activation_block: T::BlockNumber,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let key = BoundedVerificationKeyOf::<T>::try_from(key)
+				.map_err(|_| Error::<T>::VerificationKeyTooLarge)?;
+			let key_valid = match proving_system {
+				ProvingSystem::HashCommitment => T::HashCommitmentVerifier::is_valid_key(&key),
+				ProvingSystem::Groth16Bn254 => groth16::Groth16Verifier::<T>::is_valid_key(&key),
 			};
-			Tasks::<T>::insert(task_id, &task);
+			ensure!(key_valid, Error::<T>::InvalidVerificationKey);
 
-			Self::remove_from_pending(task_id);
-			let miner_pending = MinerPendingCount::<T>::get(&task.miner);
-			MinerPendingCount::<T>::insert(&task.miner, miner_pending.saturating_sub(1));
+			let now = <frame_system::Pallet<T>>::block_number();
+			let version = match LatestKeyVersion::<T>::get(circuit_id) {
+				Some(latest) => {
+					// At most one version may be waiting for activation.
+					let latest_key = VerificationKeys::<T>::get(circuit_id, latest)
+						.ok_or(Error::<T>::VerificationKeyNotFound)?;
+					ensure!(latest_key.activation_block <= now, Error::<T>::KeyVersionPending);
+					latest.checked_add(1).ok_or(Error::<T>::KeyVersionOverflow)?
+				},
+				None => 0,
+			};
+			let activation_block = activation_block.max(now);
+
+			VerificationKeys::<T>::insert(
+				circuit_id,
+				version,
+				VerificationKeyInfo {
+					proving_system,
+					key,
+					activation_block,
+					deprecation_block: None,
+				},
+			);
+			LatestKeyVersion::<T>::insert(circuit_id, version);
 
-			if verified {
-				let _ = VerifiedTasks::<T>::try_mutate(|history| {
-					history.try_push(task_id)
-				});
-				Self::increase_score(&task.miner);
-			} else {
-				Self::decrease_score(&task.miner);
-				let _ = T::Currency::repatriate_reserved(
-					&task.miner,
-					&Self::account_id(),
-					task.submission_deposit,
-					BalanceStatus::Free,
+			Self::deposit_event(Event::VerificationKeyRegistered {
+				circuit_id,
+				version,
+				proving_system,
+				activation_block,
+			});
+			Ok(())
+		}
+
+		/// Stop accepting new proofs against a key version from `deprecation_block` on.
+		/// Tasks already submitted against it are still verified with it.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::deprecate_verification_key())]
+		pub fn deprecate_verification_key(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			version: KeyVersion,
+			deprecation_block: T::BlockNumber,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let deprecation_block = deprecation_block.max(<frame_system::Pallet<T>>::block_number());
+			VerificationKeys::<T>::try_mutate(circuit_id, version, |maybe_key| {
+				let key = maybe_key.as_mut().ok_or(Error::<T>::VerificationKeyNotFound)?;
+				key.deprecation_block = Some(deprecation_block);
+				Ok::<(), DispatchError>(())
+			})?;
+
+			Self::deposit_event(Event::VerificationKeyDeprecated {
+				circuit_id,
+				version,
+				deprecation_block,
+			});
+			Ok(())
+		}
+
+		/// Verify several pending tasks in one call. Tasks sharing a key version are checked
+		/// as one batch; if a batch fails, its proofs are re-checked one by one so only the
+		/// failing miners are slashed. Tasks that are no longer pending are skipped and overdue
+		/// ones expire.
+		///
+		/// The one-by-one fallback is charged up front and refunded when every batch passes.
+		#[pallet::call_index(6)]
+		#[pallet::weight(
+			T::WeightInfo::verify_tasks_batch(task_ids.len() as u32)
+				.saturating_add(Pallet::<T>::max_batch_verification_weight(task_ids.len() as u32))
+		)]
+		#[transactional]
+		pub fn verify_tasks_batch(
+			origin: OriginFor<T>,
+			mut task_ids: Vec<T::TaskId>,
+		) -> DispatchResultWithPostInfo {
+			let verifier = ensure_signed(origin)?;
+			ensure!(!task_ids.is_empty(), Error::<T>::EmptyBatch);
+			ensure!(
+				task_ids.len() as u32 <= T::MaxVerificationBatch::get(),
+				Error::<T>::BatchTooLarge
+			);
+			let batch_len = task_ids.len() as u32;
+			task_ids.sort();
+			task_ids.dedup();
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut verdicts = Vec::with_capacity(task_ids.len());
+			// Pending tasks grouped by the key version they were submitted against.
+			let mut groups: BTreeMap<(CircuitId, KeyVersion), Vec<(T::TaskId, ZkTaskOf<T>)>> =
+				BTreeMap::new();
+			for task_id in task_ids {
+				let mut task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+				if task.status != ZkVerificationStatus::Pending {
+					continue
+				}
+				if Self::is_overdue(&task, now) {
+					Self::expire_task(task_id, &mut task);
+					continue
+				}
+				groups.entry((task.circuit_id, task.key_version)).or_default().push((task_id, task));
+			}
+
+			let mut verification_weight = Weight::zero();
+			for ((circuit_id, key_version), tasks) in groups {
+				let (group_verdicts, weight) = Self::verify_group(circuit_id, key_version, &tasks);
+				verification_weight.saturating_accrue(weight);
+				verdicts.extend(
+					tasks.into_iter().zip(group_verdicts).map(|((id, task), ok)| (id, task, ok)),
 				);
 			}
 
-			Self::deposit_event(Event::ProofVerifiedByOcw {
-				task_id,
-				verified,
+			let (mut verified_count, mut failed_count) = (0u32, 0u32);
+			for (task_id, mut task, verified) in verdicts {
+				Self::settle_verdict(task_id, &mut task, verified)?;
+				if verified {
+					verified_count += 1;
+				} else {
+					failed_count += 1;
+				}
+				Self::deposit_event(Event::ProofVerified {
+					task_id,
+					verifier: verifier.clone(),
+					status: task.status,
+				});
+			}
+
+			Self::deposit_event(Event::TasksBatchVerified {
+				verifier,
+				verified: verified_count,
+				failed: failed_count,
 			});
 
-			Ok(())
+			let actual_weight =
+				T::WeightInfo::verify_tasks_batch(batch_len).saturating_add(verification_weight);
+			Ok(Some(actual_weight).into())
 		}
 	}
 
@@ -445,9 +817,19 @@ pub mod pallet {
 
 		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
 			match call {
-				Call::submit_verification_unsigned { task_id, .. } => {
+				Call::submit_verification_unsigned { payload, signature } => {
+					if !SignedPayload::<T>::verify::<T::AuthorityCrypto>(payload, signature.clone()) {
+						return InvalidTransaction::BadProof.into()
+					}
+					if !Self::is_authority(&payload.public) {
+						return InvalidTransaction::BadSigner.into()
+					}
+					if payload.block_number > <frame_system::Pallet<T>>::block_number() {
+						return InvalidTransaction::Future.into()
+					}
+
 					// Verify task exists and is in Pending status
-					let task = match Tasks::<T>::get(task_id) {
+					let task = match Tasks::<T>::get(payload.task_id) {
 						Some(t) => t,
 						None => return InvalidTransaction::BadProof.into(),
 					};
@@ -457,9 +839,10 @@ pub mod pallet {
 
 					ValidTransaction::with_tag_prefix("zk-verify")
 						.priority(UNSIGNED_TXS_PRIORITY)
-						// Deduplicate: only one verification per task
-						.and_provides(("zk-verify", *task_id))
-						.longevity(5)
+						// Deduplicate: racing authorities provide the same tag, so only one
+						// verdict per task enters the pool
+						.and_provides(("zk-verify", payload.task_id))
+						.longevity(OCW_RESUBMIT_INTERVAL as u64)
 						.propagate(true)
 						.build()
 				},
@@ -469,24 +852,309 @@ pub mod pallet {
 	}
 
 	impl<T: Config> Pallet<T> {
-		fn ocw_verify_pending_tasks() -> Result<(), Error<T>> {
-			let pending = PendingTasks::<T>::get();
-			for task_id in pending.iter() {
+		fn ocw_verify_pending_tasks(block_number: T::BlockNumber) -> Result<(), &'static str> {
+			let authorities = Self::authority_signers();
+			let signer = Signer::<T, T::AuthorityCrypto>::any_account().with_filter(authorities);
+			if !signer.can_sign() {
+				return Err("no local zk-compute authority key")
+			}
+
+			for task_id in PendingTasks::<T>::iter_keys() {
 				if let Some(task) = Tasks::<T>::get(task_id) {
 					if task.status != ZkVerificationStatus::Pending {
 						continue;
 					}
-					let verified = T::ZkVerifier::verify(task.proof.as_ref(), task.dimensions);
-					let call = Call::submit_verification_unsigned {
-						task_id: *task_id,
-						verified,
-					};
-					let _ = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into());
+					// Left for `on_idle` to expire.
+					if Self::is_overdue(&task, block_number) {
+						continue;
+					}
+					if !Self::ocw_should_submit(task_id, block_number) {
+						continue;
+					}
+					let verified = Self::verify_proof(&task);
+					let _ = signer.send_unsigned_transaction(
+						|account| VerificationPayload {
+							task_id,
+							verified,
+							block_number,
+							public: account.public.clone(),
+						},
+						|payload, signature| Call::submit_verification_unsigned { payload, signature },
+					);
+				}
+			}
+			Ok(())
+		}
+
+		/// Records that this node reported `task_id` at `block_number`, unless it already
+		/// did so within `OCW_RESUBMIT_INTERVAL` blocks.
+		fn ocw_should_submit(task_id: T::TaskId, block_number: T::BlockNumber) -> bool {
+			let key = (b"zk-compute::sent", task_id).encode();
+			let storage = StorageValueRef::persistent(&key);
+			let res = storage.mutate(
+				|last: Result<Option<T::BlockNumber>, StorageRetrievalError>| match last {
+					Ok(Some(sent_at))
+						if block_number < sent_at.saturating_add(OCW_RESUBMIT_INTERVAL.into()) =>
+						Err(()),
+					_ => Ok(block_number),
+				},
+			);
+			res.is_ok()
+		}
+
+		/// Current authorities as the signing public keys `Signer` filters by.
+		fn authority_signers() -> Vec<T::Public> {
+			Authorities::<T>::get()
+				.into_iter()
+				.map(|key| {
+					let generic: GenericPublicOf<T> = key.into();
+					generic.into()
+				})
+				.collect()
+		}
+
+		fn is_authority(public: &T::Public) -> bool {
+			let generic = match GenericPublicOf::<T>::try_from(public.clone()) {
+				Ok(generic) => generic,
+				Err(_) => return false,
+			};
+			let key: T::AuthorityId = generic.into();
+			Authorities::<T>::get().contains(&key)
+		}
+
+		pub(crate) fn set_authorities<I: Iterator<Item = T::AuthorityId>>(keys: I) {
+			let keys = WeakBoundedVec::<_, T::MaxAuthorities>::force_from(
+				keys.collect(),
+				Some("Warning: the session has more validators than zk-compute MaxAuthorities"),
+			);
+			Authorities::<T>::put(keys);
+		}
+
+		/// Key version new proofs for `circuit_id` are checked against at block `now`.
+		fn active_key_version(
+			circuit_id: CircuitId,
+			now: T::BlockNumber,
+		) -> Result<KeyVersion, Error<T>> {
+			let latest = LatestKeyVersion::<T>::get(circuit_id)
+				.ok_or(Error::<T>::VerificationKeyNotFound)?;
+			// Only the newest version can still be pending, so the active one is either it
+			// or its predecessor.
+			let version = match VerificationKeys::<T>::get(circuit_id, latest) {
+				Some(key) if key.activation_block > now => {
+					latest.checked_sub(1).ok_or(Error::<T>::NoActiveVerificationKey)?
+				},
+				Some(_) => latest,
+				None => return Err(Error::<T>::VerificationKeyNotFound),
+			};
+			let key = VerificationKeys::<T>::get(circuit_id, version)
+				.ok_or(Error::<T>::VerificationKeyNotFound)?;
+			ensure!(key.is_active_at(&now), Error::<T>::NoActiveVerificationKey);
+			Ok(version)
+		}
+
+		/// Check a task's proof with the backend of the key version it was submitted against.
+		pub fn verify_proof(task: &ZkTaskOf<T>) -> bool {
+			let key = match VerificationKeys::<T>::get(task.circuit_id, task.key_version) {
+				Some(key) => key,
+				None => return false,
+			};
+			match key.proving_system {
+				ProvingSystem::HashCommitment => T::HashCommitmentVerifier::verify(
+					&key.key,
+					&task.proof,
+					&task.public_inputs,
+					task.dimensions,
+				),
+				ProvingSystem::Groth16Bn254 => groth16::Groth16Verifier::<T>::verify(
+					&key.key,
+					&task.proof,
+					&task.public_inputs,
+					task.dimensions,
+				),
+			}
+		}
+
+		/// Worst-case verification weight over all proving systems.
+		pub fn max_verification_weight() -> Weight {
+			T::HashCommitmentVerifier::verification_weight()
+				.max(groth16::Groth16Verifier::<T>::verification_weight())
+		}
+
+		/// Worst-case verification weight of a `verify_tasks_batch` call over `tasks` tasks,
+		/// including the one-by-one fallback after a failed batch.
+		pub fn max_batch_verification_weight(tasks: u32) -> Weight {
+			Self::batch_weight_with_fallback::<T::HashCommitmentVerifier>(tasks)
+				.max(Self::batch_weight_with_fallback::<groth16::Groth16Verifier<T>>(tasks))
+		}
+
+		fn batch_weight_with_fallback<V: VerifyZkProof>(tasks: u32) -> Weight {
+			V::batch_verification_weight(tasks)
+				.saturating_add(V::verification_weight().saturating_mul(tasks as u64))
+		}
+
+		/// Check tasks submitted against the same key version, returning each task's verdict
+		/// and the verification weight used.
+		fn verify_group(
+			circuit_id: CircuitId,
+			key_version: KeyVersion,
+			tasks: &[(T::TaskId, ZkTaskOf<T>)],
+		) -> (Vec<bool>, Weight) {
+			let key = match VerificationKeys::<T>::get(circuit_id, key_version) {
+				Some(key) => key,
+				None => return (vec![false; tasks.len()], Weight::zero()),
+			};
+			let proofs = tasks
+				.iter()
+				.map(|(_, task)| BatchProof {
+					proof: &task.proof,
+					public_inputs: &task.public_inputs,
+					dimensions: task.dimensions,
+				})
+				.collect::<Vec<_>>();
+			match key.proving_system {
+				ProvingSystem::HashCommitment =>
+					Self::verify_batch_with::<T::HashCommitmentVerifier>(&key.key, &proofs),
+				ProvingSystem::Groth16Bn254 =>
+					Self::verify_batch_with::<groth16::Groth16Verifier<T>>(&key.key, &proofs),
+			}
+		}
+
+		fn verify_batch_with<V: VerifyZkProof>(
+			key: &[u8],
+			proofs: &[BatchProof],
+		) -> (Vec<bool>, Weight) {
+			let verify_each = || {
+				proofs
+					.iter()
+					.map(|p| V::verify(key, p.proof, p.public_inputs, p.dimensions))
+					.collect::<Vec<_>>()
+			};
+			let single_weight = V::verification_weight().saturating_mul(proofs.len() as u64);
+			if proofs.len() == 1 {
+				return (verify_each(), single_weight)
+			}
+
+			let batch_weight = V::batch_verification_weight(proofs.len() as u32);
+			if V::verify_batch(key, proofs) {
+				(vec![true; proofs.len()], batch_weight)
+			} else {
+				(verify_each(), batch_weight.saturating_add(single_weight))
+			}
+		}
+
+		/// Record the verdict on a pending task: release the miner's pending slot, then
+		/// raise its score or slash its submission deposit.
+		fn settle_verdict(
+			task_id: T::TaskId,
+			task: &mut ZkTaskOf<T>,
+			verified: bool,
+		) -> DispatchResult {
+			task.status = if verified {
+				ZkVerificationStatus::Verified
+			} else {
+				ZkVerificationStatus::Failed
+			};
+			Tasks::<T>::insert(task_id, &*task);
+
+			PendingTasks::<T>::remove(task_id);
+			let miner_pending = MinerPendingCount::<T>::get(&task.miner);
+			MinerPendingCount::<T>::insert(&task.miner, miner_pending.saturating_sub(1));
+
+			if verified {
+				Self::record_verified(task_id);
+				Self::increase_score(&task.miner);
+			} else {
+				Self::schedule_prune(task_id);
+				Self::decrease_score(&task.miner);
+				let moved = T::Currency::repatriate_reserved(
+					&task.miner,
+					&Self::account_id(),
+					task.submission_deposit,
+					BalanceStatus::Free,
+				)?;
+				let slashed = task.submission_deposit.saturating_sub(moved);
+				if !slashed.is_zero() {
+					Self::deposit_event(Event::DepositSlashed {
+						task_id,
+						miner: task.miner.clone(),
+						amount: slashed,
+					});
 				}
 			}
 			Ok(())
 		}
 
+		fn is_overdue(task: &ZkTaskOf<T>, now: T::BlockNumber) -> bool {
+			now > task.submitted_at.saturating_add(T::VerificationTimeout::get())
+		}
+
+		/// Expire a pending task that was not verified in time, refunding the miner's deposit.
+		fn expire_task(task_id: T::TaskId, task: &mut ZkTaskOf<T>) {
+			task.status = ZkVerificationStatus::Expired;
+			Tasks::<T>::insert(task_id, &*task);
+
+			PendingTasks::<T>::remove(task_id);
+			let miner_pending = MinerPendingCount::<T>::get(&task.miner);
+			MinerPendingCount::<T>::insert(&task.miner, miner_pending.saturating_sub(1));
+			Self::schedule_prune(task_id);
+
+			let missing = T::Currency::unreserve(&task.miner, task.submission_deposit);
+			Self::deposit_event(Event::TaskExpired {
+				task_id,
+				miner: task.miner.clone(),
+				refunded: task.submission_deposit.saturating_sub(missing),
+			});
+		}
+
+		/// Expire pending tasks past their deadline, oldest first, using at most `limit` weight.
+		pub(crate) fn expire_overdue_tasks(now: T::BlockNumber, limit: Weight) -> Weight {
+			let per_task = T::WeightInfo::expire_overdue_task();
+			// Reading the cursor and `NextTaskId`, and writing the cursor back.
+			let mut used = T::DbWeight::get().reads_writes(2, 1);
+			if used.any_gt(limit) {
+				return Weight::zero()
+			}
+
+			let first = ExpiryCursor::<T>::get();
+			let next_task_id = NextTaskId::<T>::get();
+			let mut cursor = first;
+			while cursor < next_task_id && used.saturating_add(per_task).all_lte(limit) {
+				used.saturating_accrue(per_task);
+				if PendingTasks::<T>::contains_key(cursor) {
+					if let Some(mut task) = Tasks::<T>::get(cursor) {
+						// Later tasks were submitted no earlier, so none of them is overdue.
+						if !Self::is_overdue(&task, now) {
+							break
+						}
+						Self::expire_task(cursor, &mut task);
+					}
+				}
+				cursor = cursor.saturating_add(One::one());
+			}
+
+			if cursor != first {
+				ExpiryCursor::<T>::put(cursor);
+			}
+			used
+		}
+
+		pub(crate) fn register_hash_commitment_circuit() {
+			if LatestKeyVersion::<T>::contains_key(HASH_COMMITMENT_CIRCUIT) {
+				return
+			}
+			VerificationKeys::<T>::insert(
+				HASH_COMMITMENT_CIRCUIT,
+				0,
+				VerificationKeyInfo {
+					proving_system: ProvingSystem::HashCommitment,
+					key: BoundedVec::default(),
+					activation_block: Zero::zero(),
+					deprecation_block: None,
+				},
+			);
+			LatestKeyVersion::<T>::insert(HASH_COMMITMENT_CIRCUIT, 0);
+		}
+
 		pub fn account_id() -> T::AccountId {
 			T::PalletId::get().into_account_truncating()
 		}
@@ -520,14 +1188,100 @@ pub mod pallet {
 			Ok(reward_u128.saturated_into::<BalanceOf<T>>())
 		}
 
-		fn remove_from_pending(task_id: T::TaskId) {
-			PendingTasks::<T>::mutate(|queue| {
-				if let Some(index) = queue.iter().position(|id| *id == task_id) {
-					queue.swap_remove(index);
-				}
+		/// Append `task_id` to the `VerifiedTasks` ring, overwriting its oldest entry when full.
+		pub(crate) fn record_verified(task_id: T::TaskId) {
+			let count = VerifiedTasksCount::<T>::get();
+			let slot = count % T::MaxVerifiedTasks::get().max(1) as u64;
+			VerifiedTasks::<T>::insert(slot as u32, task_id);
+			VerifiedTasksCount::<T>::put(count.saturating_add(1));
+		}
+
+		/// Tasks currently held by the `VerifiedTasks` ring, oldest first.
+		pub fn recent_verified_tasks() -> Vec<T::TaskId> {
+			let capacity = T::MaxVerifiedTasks::get().max(1) as u64;
+			let count = VerifiedTasksCount::<T>::get();
+			(count.saturating_sub(capacity)..count)
+				.filter_map(|index| VerifiedTasks::<T>::get((index % capacity) as u32))
+				.collect()
+		}
+
+		/// Queue a settled task's record for removal once `TaskRetentionPeriod` has passed.
+		pub(crate) fn schedule_prune(task_id: T::TaskId) {
+			let prune_at = <frame_system::Pallet<T>>::block_number()
+				.saturating_add(T::TaskRetentionPeriod::get());
+			PruneQueueBounds::<T>::mutate(|(_, tail)| {
+				PruneQueue::<T>::insert(*tail, (task_id, prune_at));
+				*tail = tail.saturating_add(1);
 			});
 		}
 
+		/// Queue for pruning the tasks from before the v4 migration that were already failed or
+		/// claimed, using at most `limit` weight.
+		pub(crate) fn scan_legacy_tasks(limit: Weight) -> Weight {
+			// Reading a task, and queueing it through `PruneQueueBounds` and `PruneQueue`.
+			let per_task = T::DbWeight::get().reads_writes(2, 2);
+			// Reading and writing the scan range.
+			let mut used = T::DbWeight::get().reads_writes(1, 1);
+			if used.any_gt(limit) {
+				return Weight::zero()
+			}
+			let (mut next, end) = match LegacyPruneScan::<T>::get() {
+				Some(scan) => scan,
+				None => return T::DbWeight::get().reads(1),
+			};
+
+			while next < end && used.saturating_add(per_task).all_lte(limit) {
+				used.saturating_accrue(per_task);
+				if let Some(task) = Tasks::<T>::get(next) {
+					if task.status == ZkVerificationStatus::Failed || task.reward_claimed {
+						Self::schedule_prune(next);
+					}
+				}
+				next = next.saturating_add(One::one());
+			}
+
+			if next < end {
+				LegacyPruneScan::<T>::put((next, end));
+			} else {
+				LegacyPruneScan::<T>::kill();
+			}
+			used
+		}
+
+		/// Remove settled tasks whose retention period is over, using at most `limit` weight.
+		pub(crate) fn prune_settled_tasks(now: T::BlockNumber, limit: Weight) -> Weight {
+			let per_task = T::WeightInfo::prune_settled_task();
+			// Reading the bounds and the first entry not yet due, and writing the bounds back.
+			let mut used = T::DbWeight::get().reads_writes(2, 1);
+			if used.any_gt(limit) {
+				return Weight::zero()
+			}
+
+			let (first, tail) = PruneQueueBounds::<T>::get();
+			let mut head = first;
+			let mut pruned = 0u32;
+			while head < tail && used.saturating_add(per_task).all_lte(limit) {
+				if let Some((task_id, prune_at)) = PruneQueue::<T>::get(head) {
+					if prune_at > now {
+						break
+					}
+					Tasks::<T>::remove(task_id);
+					PruneQueue::<T>::remove(head);
+					pruned += 1;
+				}
+				head += 1;
+				used.saturating_accrue(per_task);
+			}
+
+			if head != first {
+				PruneQueueBounds::<T>::put((head, tail));
+			}
+			if pruned > 0 {
+				Self::deposit_event(Event::TasksPruned { count: pruned });
+			}
+			used
+		}
+
 		fn current_miner_score(miner: &T::AccountId) -> u32 {
 			MinerScores::<T>::get(miner).unwrap_or_else(T::InitialMinerScore::get)
 		}
@@ -548,4 +1302,26 @@ pub mod pallet {
 	}
 }
 
+impl<T: Config> sp_runtime::BoundToRuntimeAppPublic for Pallet<T> {
+	type Public = T::AuthorityId;
+}
+
+impl<T: Config> OneSessionHandler<T::AccountId> for Pallet<T> {
+	type Key = T::AuthorityId;
 
+	fn on_genesis_session<'a, I: 'a>(validators: I)
+	where
+		I: Iterator<Item = (&'a T::AccountId, T::AuthorityId)>,
+	{
+		Self::set_authorities(validators.map(|(_, key)| key));
+	}
+
+	fn on_new_session<'a, I: 'a>(_changed: bool, validators: I, _queued_validators: I)
+	where
+		I: Iterator<Item = (&'a T::AccountId, T::AuthorityId)>,
+	{
+		Self::set_authorities(validators.map(|(_, key)| key));
+	}
+
+	fn on_disabled(_validator_index: u32) {}
+}
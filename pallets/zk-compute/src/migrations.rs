@@ -0,0 +1,236 @@
+use crate::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+const TARGET: &str = "runtime::zk-compute";
+
+pub mod v2 {
+	use super::*;
+
+	/// Marks the switch to off-chain worker verification signed by session authorities. No
+	/// stored value changes: `Authorities` fills at the next session, once validators have
+	/// registered `zkvf` session keys.
+	pub struct Migration<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				log::info!(target: TARGET, "v2 migration skipped, remove it from the runtime");
+				return T::DbWeight::get().reads(1)
+			}
+			StorageVersion::new(2).put::<Pallet<T>>();
+			log::info!(target: TARGET, "v2 migration applied");
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "this migration can be deleted");
+			Ok(Vec::new())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration needs to be removed");
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldZkTask<TaskId, AccountId, BlockNumber, Balance, Proof> {
+		pub task_id: TaskId,
+		pub miner: AccountId,
+		pub proof: Proof,
+		pub dimensions: (u32, u32, u32),
+		pub status: ZkVerificationStatus,
+		pub base_reward: Balance,
+		pub multiplier_q100: u32,
+		pub submitted_at: BlockNumber,
+		pub submission_deposit: Balance,
+		pub reward_claimed: bool,
+	}
+
+	type OldZkTaskOf<T> = OldZkTask<
+		TaskIdOf<T>,
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::BlockNumber,
+		BalanceOf<T>,
+		BoundedProofOf<T>,
+	>;
+
+	/// Registers the built-in hash-commitment circuit and moves existing tasks onto it. All
+	/// tasks are translated in the upgrade block. `pre_upgrade` checks that this fits in a block.
+	pub struct Migration<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				log::info!(target: TARGET, "v3 migration skipped, remove it from the runtime");
+				return T::DbWeight::get().reads(1)
+			}
+			migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 2, "this migration can be deleted");
+			let tasks = Tasks::<T>::iter_keys().count() as u32;
+			ensure!(
+				weight::<T>(tasks.into())
+					.all_lte(<T as frame_system::Config>::BlockWeights::get().max_block),
+				"too many tasks to migrate in one block"
+			);
+			Ok(tasks.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let tasks_before = u32::decode(&mut &state[..]).map_err(|_| "invalid pre_upgrade state")?;
+			ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration needs to be removed");
+			ensure!(
+				Tasks::<T>::iter_values().count() as u32 == tasks_before,
+				"tasks failed to decode after migration"
+			);
+			ensure!(
+				LatestKeyVersion::<T>::contains_key(HASH_COMMITMENT_CIRCUIT),
+				"hash-commitment circuit not registered"
+			);
+			Ok(())
+		}
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		Pallet::<T>::register_hash_commitment_circuit();
+
+		let mut translated = 0u64;
+		Tasks::<T>::translate(|_task_id, old: OldZkTaskOf<T>| {
+			translated += 1;
+			Some(ZkTask {
+				task_id: old.task_id,
+				miner: old.miner,
+				circuit_id: HASH_COMMITMENT_CIRCUIT,
+				key_version: 0,
+				proof: old.proof,
+				public_inputs: Default::default(),
+				dimensions: old.dimensions,
+				status: old.status,
+				base_reward: old.base_reward,
+				multiplier_q100: old.multiplier_q100,
+				submitted_at: old.submitted_at,
+				submission_deposit: old.submission_deposit,
+				reward_claimed: old.reward_claimed,
+			})
+		});
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		log::info!(target: TARGET, "v3 migration moved {} tasks onto the hash-commitment circuit", translated);
+		weight::<T>(translated)
+	}
+
+	/// Weight of registering the circuit and migrating `tasks` tasks
+	fn weight<T: Config>(tasks: u64) -> Weight {
+		T::DbWeight::get().reads_writes(tasks.saturating_add(2), tasks.saturating_add(3))
+	}
+}
+
+pub mod v4 {
+	use super::*;
+	use sp_runtime::traits::Zero;
+
+	pub(crate) mod old {
+		use super::*;
+
+		#[frame_support::storage_alias]
+		pub type PendingTasks<T: Config> = StorageValue<
+			Pallet<T>,
+			BoundedVec<<T as Config>::TaskId, <T as Config>::MaxPendingTasks>,
+			ValueQuery,
+		>;
+
+		#[frame_support::storage_alias]
+		pub type VerifiedTasks<T: Config> = StorageValue<
+			Pallet<T>,
+			BoundedVec<<T as Config>::TaskId, <T as Config>::MaxVerifiedTasks>,
+			ValueQuery,
+		>;
+	}
+
+	/// Moves the `PendingTasks`/`VerifiedTasks` vectors into map storage and starts the scan
+	/// that queues tasks already failed or claimed for pruning, see `LegacyPruneScan`.
+	pub struct Migration<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 3 {
+				log::info!(target: TARGET, "v4 migration skipped, remove it from the runtime");
+				return T::DbWeight::get().reads(1)
+			}
+			migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 3, "this migration can be deleted");
+			let pending = old::PendingTasks::<T>::get().len() as u32;
+			let verified = old::VerifiedTasks::<T>::get().len() as u32;
+			Ok((pending, verified).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let (pending, verified) =
+				<(u32, u32)>::decode(&mut &state[..]).map_err(|_| "invalid pre_upgrade state")?;
+			ensure!(Pallet::<T>::on_chain_storage_version() == 4, "this migration needs to be removed");
+			ensure!(PendingTasks::<T>::count() == pending, "pending tasks lost in migration");
+			ensure!(
+				Pallet::<T>::recent_verified_tasks().len() as u32 == verified,
+				"verified tasks lost in migration"
+			);
+			ensure!(
+				NextTaskId::<T>::get().is_zero() || LegacyPruneScan::<T>::exists(),
+				"settled tasks are never queued for pruning"
+			);
+			Ok(())
+		}
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let pending = old::PendingTasks::<T>::take();
+		let verified = old::VerifiedTasks::<T>::take();
+		let mut weight = T::DbWeight::get().reads_writes(2, 2);
+
+		for task_id in pending.iter() {
+			PendingTasks::<T>::insert(task_id, ());
+		}
+		for task_id in verified.iter() {
+			Pallet::<T>::record_verified(*task_id);
+		}
+		weight.saturating_accrue(T::DbWeight::get().reads_writes(
+			(pending.len() + verified.len()) as u64,
+			2 * (pending.len() + verified.len()) as u64,
+		));
+
+		// Settled tasks get a full retention period from the upgrade on. `Tasks` is unbounded,
+		// so `on_idle` queues them over the following blocks instead of this block.
+		let end = NextTaskId::<T>::get();
+		if end > Zero::zero() {
+			LegacyPruneScan::<T>::put((T::TaskId::zero(), end));
+		}
+		weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+
+		log::info!(
+			target: TARGET,
+			"v4 migration moved {} pending and {} verified tasks, scanning {:?} tasks for pruning",
+			pending.len(),
+			verified.len(),
+			end
+		);
+		weight
+	}
+}
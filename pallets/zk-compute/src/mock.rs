@@ -3,12 +3,15 @@ use crate::VerifyZkProof;
 use frame_support::{
 	construct_runtime,
 	parameter_types,
-	traits::{ConstU16, ConstU32},
+	traits::{ConstU16, ConstU32, GenesisBuild},
 	PalletId,
 };
 use frame_system as system;
 use sp_core::H256;
-use sp_runtime::traits::IdentityLookup;
+use sp_runtime::{
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::IdentityLookup,
+};
 
 pub type AccountId = u64;
 pub type Balance = u64;
@@ -40,14 +43,17 @@ parameter_types! {
 	pub const MaxVerificationKeySize: u32 = 2048;
 	pub const MaxPublicInputsSize: u32 = 512;
 	pub const MaxPendingTasks: u32 = 32;
-	pub const MaxVerifiedTasks: u32 = 64;
+	pub const MaxVerifiedTasks: u32 = 4;
 	pub const MaxPendingPerMiner: u32 = 2;
+	pub const MaxVerificationBatch: u32 = 8;
+	pub const TaskRetentionPeriod: BlockNumber = 10;
 	pub const VerificationTimeout: BlockNumber = 5;
 	pub const InitialMinerScore: u32 = 50;
 	pub const MinMinerScoreToSubmit: u32 = 10;
 	pub const MaxMinerScore: u32 = 100;
 	pub const ScoreOnSuccess: u32 = 10;
 	pub const ScorePenaltyOnFailure: u32 = 20;
+	pub const MaxAuthorities: u32 = 10;
 }
 
 impl system::Config for Test {	type BaseCallFilter = frame_support::traits::Everything;	type BlockWeights = ();	type BlockLength = ();	type DbWeight = ();	type RuntimeOrigin = RuntimeOrigin;	type RuntimeCall = RuntimeCall;	type Index = u64;	type BlockNumber = BlockNumber;	type Hash = H256;	type Hashing = sp_runtime::traits::BlakeTwo256;	type AccountId = AccountId;	type Lookup = IdentityLookup<Self::AccountId>;	type Header = sp_runtime::generic::Header<BlockNumber, sp_runtime::traits::BlakeTwo256>;	type RuntimeEvent = RuntimeEvent;	type BlockHashCount = BlockHashCount;	type Version = ();	type PalletInfo = PalletInfo;	type AccountData = pallet_balances::AccountData<Balance>;	type OnNewAccount = ();	type OnKilledAccount = ();	type SystemWeightInfo = ();	type SS58Prefix = ConstU16<42>;	type OnSetCode = ();	type MaxConsumers = ConstU32<16>;}
@@ -71,7 +77,12 @@ impl pallet_balances::Config for Test {
 pub struct MockZkVerifier;
 
 impl VerifyZkProof for MockZkVerifier {
-	fn verify(proof: &[u8], dimensions: (u32, u32, u32)) -> bool {
+	fn verify(
+		_verification_key: &[u8],
+		proof: &[u8],
+		_public_inputs: &[u8],
+		dimensions: (u32, u32, u32),
+	) -> bool {
 		proof.first().copied() == Some(1)
 			&& dimensions.0 > 0
 			&& dimensions.1 > 0
@@ -79,22 +90,54 @@ impl VerifyZkProof for MockZkVerifier {
 	}
 }
 
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
 impl frame_system::offchain::SendTransactionTypes<crate::Call<Test>> for Test {
-    type Extrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+    type Extrinsic = Extrinsic;
     type OverarchingCall = RuntimeCall;
 }
 
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl frame_system::offchain::CreateSignedTransaction<crate::Call<Test>> for Test {
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(RuntimeCall, <Extrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+pub struct TestAuthorityCrypto;
+
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthorityCrypto {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
 impl pallet_zk_compute::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type TaskId = u64;
-	type ZkVerifier = MockZkVerifier;
+	type HashCommitmentVerifier = MockZkVerifier;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type AuthorityId = UintAuthorityId;
+	type AuthorityCrypto = TestAuthorityCrypto;
+	type MaxAuthorities = MaxAuthorities;
 	type MaxProofSize = MaxProofSize;
 	type MaxVerificationKeySize = MaxVerificationKeySize;
 	type MaxPublicInputsSize = MaxPublicInputsSize;
 	type MaxPendingTasks = MaxPendingTasks;
 	type MaxVerifiedTasks = MaxVerifiedTasks;
 	type MaxPendingPerMiner = MaxPendingPerMiner;
+	type MaxVerificationBatch = MaxVerificationBatch;
+	type TaskRetentionPeriod = TaskRetentionPeriod;
 	type BaseReward = BaseReward;
 	type SubmissionDeposit = SubmissionDeposit;
 	type VerificationTimeout = VerificationTimeout;
@@ -125,8 +168,39 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	}
 	.assimilate_storage(&mut storage)
 	.expect("balances storage assimilates");
+	<pallet_zk_compute::GenesisConfig<Test> as GenesisBuild<Test>>::assimilate_storage(
+		&Default::default(),
+		&mut storage,
+	)
+	.expect("zk-compute storage assimilates");
 
 	let mut ext = sp_io::TestExternalities::new(storage);
-	ext.execute_with(|| System::set_block_number(1));
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		ZkCompute::set_authorities(AUTHORITIES.iter().map(|id| UintAuthorityId(*id)));
+	});
 	ext
 }
+
+/// Session authorities registered by `new_test_ext`.
+pub const AUTHORITIES: [u64; 3] = [11, 12, 13];
+
+/// Verification payload from `signer`, signed with its test key.
+pub fn signed_verification(
+	signer: u64,
+	task_id: u64,
+	verified: bool,
+) -> (crate::VerificationPayloadOf<Test>, TestSignature) {
+	use sp_runtime::RuntimeAppPublic;
+
+	let payload = crate::VerificationPayload {
+		task_id,
+		verified,
+		block_number: System::block_number(),
+		public: UintAuthorityId(signer),
+	};
+	let signature = UintAuthorityId(signer)
+		.sign(&codec::Encode::encode(&payload))
+		.expect("test keys always sign");
+	(payload, signature)
+}
@@ -1,16 +1,38 @@
 use crate::mock::{
-	new_test_ext, Balances, RuntimeOrigin, SubmissionDeposit, System, Test, VerificationTimeout,
-	ZkCompute,
+	new_test_ext, signed_verification, Balances, Extrinsic, MaxVerificationBatch, RuntimeCall,
+	RuntimeOrigin, SubmissionDeposit, System, TaskRetentionPeriod, Test, VerificationTimeout,
+	ZkCompute, AUTHORITIES,
+};
+use crate::{
+	weights::WeightInfo, Call, Error, Event, ExpiryCursor, MinerScores, PendingTasks,
+	ProvingSystem, PruneQueueBounds, Tasks, ZkVerificationStatus, HASH_COMMITMENT_CIRCUIT,
+};
+use codec::Decode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Hooks, OneSessionHandler, ReservableCurrency},
+	unsigned::ValidateUnsigned,
+	weights::Weight,
+	BoundedVec,
+};
+use sp_core::offchain::{
+	testing::{TestOffchainExt, TestTransactionPoolExt},
+	OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+};
+use sp_runtime::{
+	testing::UintAuthorityId,
+	traits::BadOrigin,
+	transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError},
 };
-use crate::{Error, Event, MinerScores, ZkVerificationStatus};
-use frame_support::{assert_noop, assert_ok, traits::ReservableCurrency};
 
 #[test]
 fn submit_proof_should_store_task_and_reserve_deposit() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1, 2, 3],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
@@ -22,7 +44,7 @@ fn submit_proof_should_store_task_and_reserve_deposit() {
 		assert_eq!(task.base_reward, 100);
 		assert_eq!(task.multiplier_q100, 120);
 		assert_eq!(Balances::reserved_balance(1), SubmissionDeposit::get());
-		assert_eq!(ZkCompute::pending_tasks().len(), 1);
+		assert_eq!(PendingTasks::<Test>::count(), 1);
 
 		System::assert_last_event(
 			Event::<Test>::ProofSubmitted {
@@ -41,14 +63,16 @@ fn submit_proof_should_reject_replay_nonce() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1],
+			vec![],
 			(100, 100, 100),
 			120,
 			9,
 		));
 
 		assert_noop!(
-			ZkCompute::submit_proof(RuntimeOrigin::signed(1), vec![1], (100, 100, 100), 120, 9,),
+			ZkCompute::submit_proof(RuntimeOrigin::signed(1), 0, vec![1], vec![], (100, 100, 100), 120, 9,),
 			Error::<Test>::NonceAlreadyUsed
 		);
 	});
@@ -59,21 +83,25 @@ fn submit_proof_should_limit_pending_per_miner() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
 		));
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1],
+			vec![],
 			(100, 100, 100),
 			120,
 			2,
 		));
 
 		assert_noop!(
-			ZkCompute::submit_proof(RuntimeOrigin::signed(1), vec![1], (100, 100, 100), 120, 3,),
+			ZkCompute::submit_proof(RuntimeOrigin::signed(1), 0, vec![1], vec![], (100, 100, 100), 120, 3,),
 			Error::<Test>::TooManyPendingTasksForMiner
 		);
 	});
@@ -84,7 +112,7 @@ fn submit_proof_should_fail_if_score_too_low() {
 	new_test_ext().execute_with(|| {
 		MinerScores::<Test>::insert(1, 5);
 		assert_noop!(
-			ZkCompute::submit_proof(RuntimeOrigin::signed(1), vec![1], (100, 100, 100), 120, 1,),
+			ZkCompute::submit_proof(RuntimeOrigin::signed(1), 0, vec![1], vec![], (100, 100, 100), 120, 1,),
 			Error::<Test>::InsufficientMinerScore
 		);
 	});
@@ -95,7 +123,9 @@ fn verify_task_should_mark_verified_and_increase_score() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1, 0, 0],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
@@ -104,8 +134,8 @@ fn verify_task_should_mark_verified_and_increase_score() {
 
 		let task = ZkCompute::tasks(0u64).expect("task exists");
 		assert_eq!(task.status, ZkVerificationStatus::Verified);
-		assert_eq!(ZkCompute::pending_tasks().len(), 0);
-		assert_eq!(ZkCompute::verified_tasks().len(), 1);
+		assert_eq!(PendingTasks::<Test>::count(), 0);
+		assert_eq!(ZkCompute::recent_verified_tasks().len(), 1);
 		assert_eq!(ZkCompute::miner_score(1), Some(60));
 	});
 }
@@ -116,7 +146,9 @@ fn verify_task_should_mark_failed_and_slash_deposit() {
 		let pallet_before = Balances::free_balance(ZkCompute::account_id());
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![0, 9, 9],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
@@ -132,11 +164,14 @@ fn verify_task_should_mark_failed_and_slash_deposit() {
 }
 
 #[test]
-fn verify_task_should_fail_after_timeout() {
+fn verify_task_should_expire_after_timeout() {
 	new_test_ext().execute_with(|| {
+		let free_before = Balances::free_balance(1);
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1, 0, 0],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
@@ -145,7 +180,66 @@ fn verify_task_should_fail_after_timeout() {
 		assert_ok!(ZkCompute::verify_task(RuntimeOrigin::signed(2), 0u64));
 
 		let task = ZkCompute::tasks(0u64).expect("task exists");
-		assert_eq!(task.status, ZkVerificationStatus::Failed);
+		assert_eq!(task.status, ZkVerificationStatus::Expired);
+		assert_eq!(Balances::free_balance(1), free_before);
+		assert_eq!(ZkCompute::miner_pending_count(1), 0);
+		assert_eq!(ZkCompute::miner_score(1), None);
+		System::assert_last_event(
+			Event::<Test>::TaskExpired {
+				task_id: 0,
+				miner: 1,
+				refunded: SubmissionDeposit::get(),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn on_idle_should_expire_overdue_tasks_in_submission_order() {
+	new_test_ext().execute_with(|| {
+		let first = submit_hash_proof(1, true, 1);
+		System::set_block_number(3);
+		let second = submit_hash_proof(2, true, 1);
+
+		// Only the first task is past its deadline.
+		let now = 2 + VerificationTimeout::get();
+		ZkCompute::on_idle(now, Weight::MAX);
+		assert_eq!(ZkCompute::tasks(first).unwrap().status, ZkVerificationStatus::Expired);
+		assert_eq!(ZkCompute::tasks(second).unwrap().status, ZkVerificationStatus::Pending);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(ZkCompute::miner_pending_count(1), 0);
+		assert_eq!(PendingTasks::<Test>::count(), 1);
+		assert_eq!(ExpiryCursor::<Test>::get(), second);
+		System::assert_has_event(
+			Event::<Test>::TaskExpired {
+				task_id: first,
+				miner: 1,
+				refunded: SubmissionDeposit::get(),
+			}
+			.into(),
+		);
+
+		ZkCompute::on_idle(now + 2, Weight::MAX);
+		assert_eq!(ZkCompute::tasks(second).unwrap().status, ZkVerificationStatus::Expired);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(PendingTasks::<Test>::count(), 0);
+		assert_eq!(ExpiryCursor::<Test>::get(), second + 1);
+	});
+}
+
+#[test]
+fn on_idle_should_skip_settled_tasks_when_expiring() {
+	new_test_ext().execute_with(|| {
+		let verified = submit_hash_proof(1, true, 1);
+		let pending = submit_hash_proof(2, true, 1);
+		assert_ok!(ZkCompute::verify_task(RuntimeOrigin::signed(3), verified));
+
+		ZkCompute::on_idle(2 + VerificationTimeout::get(), Weight::MAX);
+		assert_eq!(ZkCompute::tasks(verified).unwrap().status, ZkVerificationStatus::Verified);
+		assert_eq!(ZkCompute::tasks(pending).unwrap().status, ZkVerificationStatus::Expired);
+		// The verified miner's deposit stays reserved until the reward is claimed.
+		assert_eq!(Balances::reserved_balance(1), SubmissionDeposit::get());
 	});
 }
 
@@ -154,7 +248,9 @@ fn claim_reward_should_pay_and_unreserve_deposit() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1, 7, 7],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
@@ -181,7 +277,9 @@ fn claim_reward_should_fail_for_unverified_task() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(ZkCompute::submit_proof(
 			RuntimeOrigin::signed(1),
+			0,
 			vec![1, 2, 3],
+			vec![],
 			(100, 100, 100),
 			120,
 			1,
@@ -192,3 +290,739 @@ fn claim_reward_should_fail_for_unverified_task() {
 		);
 	});
 }
+
+fn validate_verification(signer: u64, task_id: u64, verified: bool) -> Result<(), InvalidTransaction> {
+	let (payload, signature) = signed_verification(signer, task_id, verified);
+	ZkCompute::validate_unsigned(
+		TransactionSource::External,
+		&Call::submit_verification_unsigned { payload, signature },
+	)
+	.map(|_| ())
+	.map_err(|e| match e {
+		TransactionValidityError::Invalid(e) => e,
+		_ => InvalidTransaction::Call,
+	})
+}
+
+#[test]
+fn authority_verification_should_mark_task_verified() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			0,
+			vec![1, 2, 3],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+
+		assert_ok!(validate_verification(AUTHORITIES[0], 0, true));
+		let (payload, signature) = signed_verification(AUTHORITIES[0], 0, true);
+		assert_ok!(ZkCompute::submit_verification_unsigned(RuntimeOrigin::none(), payload, signature));
+
+		let task = ZkCompute::tasks(0u64).expect("task exists");
+		assert_eq!(task.status, ZkVerificationStatus::Verified);
+		assert_eq!(ZkCompute::miner_pending_count(1), 0);
+		System::assert_last_event(
+			Event::<Test>::ProofVerifiedByOcw { task_id: 0u64, verified: true }.into(),
+		);
+	});
+}
+
+#[test]
+fn verification_from_non_authority_should_be_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			0,
+			vec![1, 2, 3],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+
+		assert_eq!(validate_verification(99, 0, true), Err(InvalidTransaction::BadSigner));
+
+		// A signature that does not match the payload is rejected before the authority check.
+		let (mut payload, signature) = signed_verification(AUTHORITIES[0], 0, false);
+		payload.verified = true;
+		assert_eq!(
+			ZkCompute::validate_unsigned(
+				TransactionSource::External,
+				&Call::submit_verification_unsigned { payload, signature },
+			),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadProof))
+		);
+	});
+}
+
+#[test]
+fn racing_authority_verifications_should_settle_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			0,
+			vec![0, 2, 3],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+
+		// Every authority's verdict for the task provides the same pool tag.
+		let (first, first_sig) = signed_verification(AUTHORITIES[0], 0, false);
+		let (second, second_sig) = signed_verification(AUTHORITIES[1], 0, false);
+		let first_call = Call::submit_verification_unsigned { payload: first.clone(), signature: first_sig.clone() };
+		let second_call = Call::submit_verification_unsigned { payload: second.clone(), signature: second_sig.clone() };
+		assert_eq!(
+			ZkCompute::validate_unsigned(TransactionSource::External, &first_call).unwrap().provides,
+			ZkCompute::validate_unsigned(TransactionSource::External, &second_call).unwrap().provides,
+		);
+
+		assert_ok!(ZkCompute::submit_verification_unsigned(RuntimeOrigin::none(), first, first_sig));
+		let reserved_after_first = Balances::reserved_balance(1);
+
+		// The late verdict is stale in the pool and a no-op if it still gets dispatched.
+		assert_eq!(validate_verification(AUTHORITIES[1], 0, false), Err(InvalidTransaction::Stale));
+		assert_ok!(ZkCompute::submit_verification_unsigned(RuntimeOrigin::none(), second, second_sig));
+		assert_eq!(Balances::reserved_balance(1), reserved_after_first);
+		assert_eq!(ZkCompute::tasks(0u64).unwrap().status, ZkVerificationStatus::Failed);
+		assert_eq!(MinerScores::<Test>::get(1), Some(30));
+	});
+}
+
+#[test]
+fn new_session_should_replace_authorities() {
+	new_test_ext().execute_with(|| {
+		let validators = [(1u64, UintAuthorityId(21)), (2u64, UintAuthorityId(22))];
+		<ZkCompute as OneSessionHandler<u64>>::on_new_session(
+			true,
+			validators.iter().map(|(who, key)| (who, key.clone())),
+			validators.iter().map(|(who, key)| (who, key.clone())),
+		);
+
+		assert_eq!(ZkCompute::authorities().to_vec(), vec![UintAuthorityId(21), UintAuthorityId(22)]);
+
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			0,
+			vec![1, 2, 3],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+		assert_eq!(validate_verification(AUTHORITIES[0], 0, true), Err(InvalidTransaction::BadSigner));
+		assert_ok!(validate_verification(21, 0, true));
+	});
+}
+
+#[test]
+fn offchain_worker_should_submit_signed_verdict_once() {
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![AUTHORITIES[1], 77]);
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			0,
+			vec![1, 2, 3],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+
+		ZkCompute::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().expect("verdict submitted");
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		match tx.call {
+			RuntimeCall::ZkCompute(Call::submit_verification_unsigned { payload, .. }) => {
+				assert_eq!(payload.task_id, 0);
+				assert!(payload.verified);
+				assert_eq!(payload.public, UintAuthorityId(AUTHORITIES[1]));
+			},
+			call => panic!("unexpected call: {:?}", call),
+		}
+
+		// The same node does not resend the verdict while it can still be included.
+		ZkCompute::offchain_worker(2);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn register_verification_key_should_require_admin_and_valid_key() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ZkCompute::register_verification_key(
+				RuntimeOrigin::signed(1),
+				7,
+				ProvingSystem::HashCommitment,
+				vec![1],
+				0,
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			ZkCompute::register_verification_key(
+				RuntimeOrigin::root(),
+				7,
+				ProvingSystem::Groth16Bn254,
+				vec![0; 64],
+				0,
+			),
+			Error::<Test>::InvalidVerificationKey
+		);
+
+		assert_ok!(ZkCompute::register_verification_key(
+			RuntimeOrigin::root(),
+			7,
+			ProvingSystem::HashCommitment,
+			vec![1],
+			0,
+		));
+		assert_eq!(ZkCompute::latest_key_version(7), Some(0));
+		let key = ZkCompute::verification_key(7, 0).expect("key registered");
+		assert_eq!(key.activation_block, 1);
+		System::assert_last_event(
+			Event::<Test>::VerificationKeyRegistered {
+				circuit_id: 7,
+				version: 0,
+				proving_system: ProvingSystem::HashCommitment,
+				activation_block: 1,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn new_key_version_should_apply_from_activation_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ZkCompute::register_verification_key(
+			RuntimeOrigin::root(),
+			HASH_COMMITMENT_CIRCUIT,
+			ProvingSystem::HashCommitment,
+			vec![2],
+			10,
+		));
+		assert_eq!(ZkCompute::latest_key_version(HASH_COMMITMENT_CIRCUIT), Some(1));
+
+		// Only one version may wait for activation at a time.
+		assert_noop!(
+			ZkCompute::register_verification_key(
+				RuntimeOrigin::root(),
+				HASH_COMMITMENT_CIRCUIT,
+				ProvingSystem::HashCommitment,
+				vec![3],
+				20,
+			),
+			Error::<Test>::KeyVersionPending
+		);
+
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			HASH_COMMITMENT_CIRCUIT,
+			vec![1],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+		assert_eq!(ZkCompute::tasks(0u64).unwrap().key_version, 0);
+
+		System::set_block_number(10);
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			HASH_COMMITMENT_CIRCUIT,
+			vec![1],
+			vec![],
+			(100, 100, 100),
+			120,
+			2,
+		));
+		assert_eq!(ZkCompute::tasks(1u64).unwrap().key_version, 1);
+	});
+}
+
+#[test]
+fn deprecated_key_should_reject_new_proofs_but_verify_existing_tasks() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ZkCompute::submit_proof(
+			RuntimeOrigin::signed(1),
+			HASH_COMMITMENT_CIRCUIT,
+			vec![1],
+			vec![],
+			(100, 100, 100),
+			120,
+			1,
+		));
+		assert_ok!(ZkCompute::deprecate_verification_key(
+			RuntimeOrigin::root(),
+			HASH_COMMITMENT_CIRCUIT,
+			0,
+			2,
+		));
+		System::assert_last_event(
+			Event::<Test>::VerificationKeyDeprecated {
+				circuit_id: HASH_COMMITMENT_CIRCUIT,
+				version: 0,
+				deprecation_block: 2,
+			}
+			.into(),
+		);
+
+		System::set_block_number(2);
+		assert_noop!(
+			ZkCompute::submit_proof(
+				RuntimeOrigin::signed(1),
+				HASH_COMMITMENT_CIRCUIT,
+				vec![1],
+				vec![],
+				(100, 100, 100),
+				120,
+				2,
+			),
+			Error::<Test>::NoActiveVerificationKey
+		);
+		assert_noop!(
+			ZkCompute::submit_proof(RuntimeOrigin::signed(1), 9, vec![1], vec![], (100, 100, 100), 120, 2,),
+			Error::<Test>::VerificationKeyNotFound
+		);
+
+		assert_ok!(ZkCompute::verify_task(RuntimeOrigin::signed(2), 0u64));
+		assert_eq!(ZkCompute::tasks(0u64).unwrap().status, ZkVerificationStatus::Verified);
+	});
+}
+
+#[test]
+fn verify_tasks_batch_should_slash_only_failing_miners() {
+	new_test_ext().execute_with(|| {
+		for (miner, proof) in [(1, vec![1, 2]), (2, vec![0, 2]), (3, vec![1, 3])] {
+			assert_ok!(ZkCompute::submit_proof(
+				RuntimeOrigin::signed(miner),
+				HASH_COMMITMENT_CIRCUIT,
+				proof,
+				vec![],
+				(100, 100, 100),
+				120,
+				1,
+			));
+		}
+
+		// Duplicate ids are verified once.
+		assert_ok!(ZkCompute::verify_tasks_batch(RuntimeOrigin::signed(4), vec![2, 0, 1, 0]));
+
+		assert_eq!(ZkCompute::tasks(0u64).unwrap().status, ZkVerificationStatus::Verified);
+		assert_eq!(ZkCompute::tasks(1u64).unwrap().status, ZkVerificationStatus::Failed);
+		assert_eq!(ZkCompute::tasks(2u64).unwrap().status, ZkVerificationStatus::Verified);
+		assert_eq!(Balances::reserved_balance(1), SubmissionDeposit::get());
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::reserved_balance(3), SubmissionDeposit::get());
+		assert_eq!(ZkCompute::miner_score(2), Some(30));
+		assert_eq!(PendingTasks::<Test>::count(), 0);
+		assert_eq!(ZkCompute::recent_verified_tasks().len(), 2);
+		System::assert_last_event(
+			Event::<Test>::TasksBatchVerified { verifier: 4, verified: 2, failed: 1 }.into(),
+		);
+
+		// Tasks that were already settled are skipped.
+		assert_ok!(ZkCompute::verify_tasks_batch(RuntimeOrigin::signed(4), vec![0, 1]));
+		System::assert_last_event(
+			Event::<Test>::TasksBatchVerified { verifier: 4, verified: 0, failed: 0 }.into(),
+		);
+		assert_eq!(ZkCompute::miner_score(1), Some(60));
+	});
+}
+
+#[test]
+fn verify_tasks_batch_should_enforce_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ZkCompute::verify_tasks_batch(RuntimeOrigin::signed(4), vec![]),
+			Error::<Test>::EmptyBatch
+		);
+		assert_noop!(
+			ZkCompute::verify_tasks_batch(
+				RuntimeOrigin::signed(4),
+				(0..=MaxVerificationBatch::get() as u64).collect()
+			),
+			Error::<Test>::BatchTooLarge
+		);
+		assert_noop!(
+			ZkCompute::verify_tasks_batch(RuntimeOrigin::signed(4), vec![5]),
+			Error::<Test>::TaskNotFound
+		);
+	});
+}
+
+fn submit_hash_proof(miner: u64, valid: bool, nonce: u64) -> u64 {
+	let task_id = ZkCompute::next_task_id();
+	assert_ok!(ZkCompute::submit_proof(
+		RuntimeOrigin::signed(miner),
+		HASH_COMMITMENT_CIRCUIT,
+		vec![valid as u8],
+		vec![],
+		(100, 100, 100),
+		120,
+		nonce,
+	));
+	task_id
+}
+
+#[test]
+fn verified_tasks_should_keep_the_latest_in_a_ring() {
+	new_test_ext().execute_with(|| {
+		for nonce in 0..6 {
+			let task_id = submit_hash_proof(1, true, nonce);
+			assert_ok!(ZkCompute::verify_task(RuntimeOrigin::signed(2), task_id));
+		}
+
+		// `MaxVerifiedTasks` is 4: the oldest entries were overwritten instead of failing.
+		assert_eq!(ZkCompute::recent_verified_tasks(), vec![2, 3, 4, 5]);
+		assert_eq!(PendingTasks::<Test>::count(), 0);
+	});
+}
+
+#[test]
+fn on_idle_should_prune_settled_tasks_after_retention() {
+	new_test_ext().execute_with(|| {
+		let failed = submit_hash_proof(1, false, 1);
+		let claimed = submit_hash_proof(2, true, 1);
+		let unclaimed = submit_hash_proof(3, true, 1);
+		assert_ok!(ZkCompute::verify_tasks_batch(
+			RuntimeOrigin::signed(4),
+			vec![failed, claimed, unclaimed]
+		));
+		assert_ok!(ZkCompute::claim_reward(RuntimeOrigin::signed(2), claimed));
+		assert_eq!(PruneQueueBounds::<Test>::get(), (0, 2));
+
+		ZkCompute::on_idle(TaskRetentionPeriod::get(), Weight::MAX);
+		assert!(Tasks::<Test>::contains_key(failed));
+
+		ZkCompute::on_idle(1 + TaskRetentionPeriod::get(), Weight::MAX);
+		assert!(!Tasks::<Test>::contains_key(failed));
+		assert!(!Tasks::<Test>::contains_key(claimed));
+		// Verified rewards stay claimable.
+		assert!(Tasks::<Test>::contains_key(unclaimed));
+		assert_eq!(PruneQueueBounds::<Test>::get(), (2, 2));
+		System::assert_last_event(Event::<Test>::TasksPruned { count: 2 }.into());
+	});
+}
+
+#[test]
+fn on_idle_should_stop_at_remaining_weight() {
+	new_test_ext().execute_with(|| {
+		let first = submit_hash_proof(1, false, 1);
+		let second = submit_hash_proof(2, false, 1);
+		assert_ok!(ZkCompute::verify_tasks_batch(RuntimeOrigin::signed(4), vec![first, second]));
+
+		let now = 1 + TaskRetentionPeriod::get();
+		let per_task = <() as WeightInfo>::prune_settled_task();
+		assert_eq!(ZkCompute::on_idle(now, per_task), per_task);
+		assert!(!Tasks::<Test>::contains_key(first));
+		assert!(Tasks::<Test>::contains_key(second));
+
+		assert_eq!(ZkCompute::on_idle(now, Weight::zero()), Weight::zero());
+		assert!(Tasks::<Test>::contains_key(second));
+
+		ZkCompute::on_idle(now, per_task);
+		assert!(!Tasks::<Test>::contains_key(second));
+	});
+}
+
+#[test]
+fn migration_v4_should_move_task_vectors_into_maps() {
+	use crate::migrations::v4;
+	use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		let failed = submit_hash_proof(1, false, 1);
+		let verified = submit_hash_proof(2, true, 1);
+		let pending = submit_hash_proof(3, true, 1);
+		Tasks::<Test>::mutate(failed, |task| {
+			task.as_mut().unwrap().status = ZkVerificationStatus::Failed
+		});
+		Tasks::<Test>::mutate(verified, |task| {
+			task.as_mut().unwrap().status = ZkVerificationStatus::Verified
+		});
+
+		// Rebuild the v3 layout.
+		let _ = PendingTasks::<Test>::clear(u32::MAX, None);
+		v4::old::PendingTasks::<Test>::put(BoundedVec::truncate_from(vec![pending]));
+		v4::old::VerifiedTasks::<Test>::put(BoundedVec::truncate_from(vec![verified]));
+		StorageVersion::new(3).put::<ZkCompute>();
+
+		v4::migrate::<Test>();
+
+		assert_eq!(ZkCompute::on_chain_storage_version(), 4);
+		assert_eq!(PendingTasks::<Test>::count(), 1);
+		assert!(PendingTasks::<Test>::contains_key(pending));
+		assert_eq!(ZkCompute::recent_verified_tasks(), vec![verified]);
+		// Settled tasks are queued for pruning over the next blocks, not by the migration
+		assert_eq!(PruneQueueBounds::<Test>::get(), (0, 0));
+		assert_eq!(crate::LegacyPruneScan::<Test>::get(), Some((0, 3)));
+
+
+		ZkCompute::on_idle(1, Weight::MAX);
+		assert_eq!(crate::LegacyPruneScan::<Test>::get(), None);
+		assert_eq!(PruneQueueBounds::<Test>::get(), (0, 1));
+		assert_eq!(crate::PruneQueue::<Test>::get(0).map(|(id, _)| id), Some(failed));
+	});
+}
+
+mod groth16 {
+	use super::*;
+	use crate::{
+		groth16::{self, encode_u32_scalar, Groth16Verifier},
+		VerifyZkProof,
+	};
+	use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+	use ark_ff::{BigInteger, PrimeField};
+	use ark_groth16::{Groth16, Proof, VerifyingKey};
+	use ark_relations::{
+		lc,
+		r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+	};
+	use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+	use frame_support::traits::Get;
+
+	/// Proves `m * n * k = volume` with public inputs `[m, n, k, volume]`.
+	#[derive(Clone)]
+	struct VolumeCircuit {
+		m: Fr,
+		n: Fr,
+		k: Fr,
+	}
+
+	impl ConstraintSynthesizer<Fr> for VolumeCircuit {
+		fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+			let m = cs.new_input_variable(|| Ok(self.m))?;
+			let n = cs.new_input_variable(|| Ok(self.n))?;
+			let k = cs.new_input_variable(|| Ok(self.k))?;
+			let volume = cs.new_input_variable(|| Ok(self.m * self.n * self.k))?;
+			let mn = cs.new_witness_variable(|| Ok(self.m * self.n))?;
+			cs.enforce_constraint(lc!() + m, lc!() + n, lc!() + mn)?;
+			cs.enforce_constraint(lc!() + mn, lc!() + k, lc!() + volume)?;
+			Ok(())
+		}
+	}
+
+	fn fq(value: Fq) -> Vec<u8> {
+		value.into_bigint().to_bytes_be()
+	}
+
+	fn g1(point: &G1Affine) -> Vec<u8> {
+		[fq(point.x), fq(point.y)].concat()
+	}
+
+	fn g2(point: &G2Affine) -> Vec<u8> {
+		[fq(point.x.c1), fq(point.x.c0), fq(point.y.c1), fq(point.y.c0)].concat()
+	}
+
+	fn encode_vk(vk: &VerifyingKey<Bn254>) -> Vec<u8> {
+		let mut bytes = [g1(&vk.alpha_g1), g2(&vk.beta_g2), g2(&vk.gamma_g2), g2(&vk.delta_g2)].concat();
+		vk.gamma_abc_g1.iter().for_each(|point| bytes.extend(g1(point)));
+		bytes
+	}
+
+	fn encode_proof(proof: &Proof<Bn254>) -> Vec<u8> {
+		[g1(&proof.a), g2(&proof.b), g1(&proof.c)].concat()
+	}
+
+	/// Verifying key, proof and public inputs for a `(m, n, k)` volume proof.
+	fn volume_fixture(dimensions: (u32, u32, u32)) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+		let (vk, mut proofs) = volume_fixtures(&[dimensions]);
+		let (proof, inputs) = proofs.remove(0);
+		(vk, proof, inputs)
+	}
+
+	/// One verifying key and a `(proof, public_inputs)` pair per entry of `dimensions`.
+	fn volume_fixtures(dimensions: &[(u32, u32, u32)]) -> (Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>) {
+		let mut rng = ark_std::test_rng();
+		let shape = VolumeCircuit { m: 1u32.into(), n: 1u32.into(), k: 1u32.into() };
+		let (pk, vk) = Groth16::<Bn254>::setup(shape, &mut rng).unwrap();
+
+		let proofs = dimensions
+			.iter()
+			.map(|&(m, n, k)| {
+				let circuit = VolumeCircuit { m: m.into(), n: n.into(), k: k.into() };
+				let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+
+				let volume = Fr::from(m) * Fr::from(n) * Fr::from(k);
+				let mut inputs = Vec::new();
+				for dim in [m, n, k] {
+					inputs.extend(encode_u32_scalar(dim));
+				}
+				inputs.extend(volume.into_bigint().to_bytes_be());
+				(encode_proof(&proof), inputs)
+			})
+			.collect();
+		(encode_vk(&vk), proofs)
+	}
+
+	#[test]
+	fn valid_proof_should_verify() {
+		let (vk, proof, inputs) = volume_fixture((8, 8, 8));
+		assert!(groth16::verify(&vk, &proof, &inputs));
+	}
+
+	#[test]
+	fn tampered_public_input_should_fail() {
+		let (vk, proof, mut inputs) = volume_fixture((8, 8, 8));
+		// Claim a different volume for the same proof.
+		let last = inputs.len() - 1;
+		inputs[last] ^= 1;
+		assert!(!groth16::verify(&vk, &proof, &inputs));
+	}
+
+	#[test]
+	fn malformed_points_should_be_rejected() {
+		let (mut vk, proof, inputs) = volume_fixture((8, 8, 8));
+
+		// Point not on the curve in the proof.
+		let mut bad_proof = proof.clone();
+		bad_proof[63] ^= 1;
+		assert!(!groth16::verify(&vk, &bad_proof, &inputs));
+
+		// Non-canonical field element in the verifying key.
+		vk[..32].copy_from_slice(&[0xff; 32]);
+		assert!(groth16::decode_verifying_key(&vk).is_none());
+		assert!(!groth16::verify(&vk, &proof, &inputs));
+
+		// Verifying key with a truncated `ic` point.
+		assert!(groth16::decode_verifying_key(&vk[..vk.len() - 1]).is_none());
+	}
+
+	#[test]
+	fn verifier_should_bind_dimensions_and_respect_limits() {
+		let (vk, proof, inputs) = volume_fixture((4, 5, 6));
+
+		assert!(Groth16Verifier::<Test>::verify(&vk, &proof, &inputs, (4, 5, 6)));
+		// The proof is for different dimensions than the task claims.
+		assert!(!Groth16Verifier::<Test>::verify(&vk, &proof, &inputs, (6, 5, 4)));
+		assert!(!Groth16Verifier::<Test>::verify(&vk, &proof, &[], (4, 5, 6)));
+
+		// Public inputs above `MaxPublicInputsSize` are rejected before decoding.
+		let mut oversized = inputs.clone();
+		oversized.resize(crate::mock::MaxPublicInputsSize::get() as usize + 32, 0);
+		assert!(!Groth16Verifier::<Test>::verify(&vk, &proof, &oversized, (4, 5, 6)));
+
+		assert!(!Groth16Verifier::<Test>::verify(&[], &proof, &inputs, (4, 5, 6)));
+	}
+
+	#[test]
+	fn registered_groth16_circuit_should_verify_tasks() {
+		new_test_ext().execute_with(|| {
+			let (vk, proof, inputs) = volume_fixture((4, 5, 6));
+			assert_ok!(ZkCompute::register_verification_key(
+				RuntimeOrigin::root(),
+				1,
+				ProvingSystem::Groth16Bn254,
+				vk,
+				0,
+			));
+
+			assert_ok!(ZkCompute::submit_proof(
+				RuntimeOrigin::signed(1),
+				1,
+				proof.clone(),
+				inputs.clone(),
+				(4, 5, 6),
+				120,
+				1,
+			));
+			// The same proof claimed for other dimensions fails and is slashed.
+			assert_ok!(ZkCompute::submit_proof(
+				RuntimeOrigin::signed(2),
+				1,
+				proof,
+				inputs,
+				(6, 5, 4),
+				120,
+				1,
+			));
+
+			assert_ok!(ZkCompute::verify_task(RuntimeOrigin::signed(3), 0u64));
+			assert_ok!(ZkCompute::verify_task(RuntimeOrigin::signed(3), 1u64));
+			assert_eq!(ZkCompute::tasks(0u64).unwrap().status, ZkVerificationStatus::Verified);
+			assert_eq!(ZkCompute::tasks(1u64).unwrap().status, ZkVerificationStatus::Failed);
+			assert_eq!(Balances::reserved_balance(2), 0);
+		});
+	}
+
+	#[test]
+	fn batch_should_verify_many_proofs_and_reject_any_invalid_one() {
+		let (vk, proofs) = volume_fixtures(&[(2, 3, 4), (4, 5, 6), (8, 8, 8)]);
+		let pairs = proofs.iter().map(|(p, i)| (p.as_slice(), i.as_slice())).collect::<Vec<_>>();
+		assert!(groth16::verify_batch(&vk, &pairs));
+
+		// One proof paired with another proof's inputs fails the whole batch.
+		let mut swapped = pairs.clone();
+		swapped[1].1 = pairs[2].1;
+		assert!(!groth16::verify_batch(&vk, &swapped));
+
+		// A single tampered input fails the whole batch.
+		let mut tampered = proofs.clone();
+		tampered[0].1[0] ^= 1;
+		let tampered =
+			tampered.iter().map(|(p, i)| (p.as_slice(), i.as_slice())).collect::<Vec<_>>();
+		assert!(!groth16::verify_batch(&vk, &tampered));
+	}
+
+	#[test]
+	fn batched_groth16_tasks_should_slash_only_failing_miners() {
+		new_test_ext().execute_with(|| {
+			let (vk, proofs) = volume_fixtures(&[(2, 3, 4), (4, 5, 6), (8, 8, 8)]);
+			assert_ok!(ZkCompute::register_verification_key(
+				RuntimeOrigin::root(),
+				1,
+				ProvingSystem::Groth16Bn254,
+				vk,
+				0,
+			));
+
+			// Miner 2 claims other dimensions than its proof covers.
+			let claims = [(1, (2, 3, 4)), (2, (5, 4, 6)), (3, (8, 8, 8))];
+			for ((miner, dimensions), (proof, inputs)) in claims.into_iter().zip(proofs) {
+				assert_ok!(ZkCompute::submit_proof(
+					RuntimeOrigin::signed(miner),
+					1,
+					proof,
+					inputs,
+					dimensions,
+					120,
+					1,
+				));
+			}
+
+			assert_ok!(ZkCompute::verify_tasks_batch(RuntimeOrigin::signed(4), vec![0, 1, 2]));
+			assert_eq!(ZkCompute::tasks(0u64).unwrap().status, ZkVerificationStatus::Verified);
+			assert_eq!(ZkCompute::tasks(1u64).unwrap().status, ZkVerificationStatus::Failed);
+			assert_eq!(ZkCompute::tasks(2u64).unwrap().status, ZkVerificationStatus::Verified);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::reserved_balance(3), SubmissionDeposit::get());
+		});
+	}
+
+	#[test]
+	fn batch_weight_per_proof_should_be_below_single_verification() {
+		let n = MaxVerificationBatch::get();
+		let single = <() as WeightInfo>::verify_task()
+			.saturating_add(Groth16Verifier::<Test>::verification_weight());
+		let batch = <() as WeightInfo>::verify_tasks_batch(n)
+			.saturating_add(Groth16Verifier::<Test>::batch_verification_weight(n));
+		assert!(batch.ref_time() / n as u64 < single.ref_time());
+	}
+}
@@ -39,7 +39,14 @@ pub trait WeightInfo {
 	fn submit_proof() -> Weight;
 	fn verify_task() -> Weight;
 	fn claim_reward() -> Weight;
-	fn submit_verification_unsigned() -> Weight;
+	fn submit_verification_unsigned(k: u32, ) -> Weight;
+	fn register_verification_key() -> Weight;
+	fn deprecate_verification_key() -> Weight;
+	fn verify_tasks_batch(n: u32, ) -> Weight;
+	fn verify_groth16_proof(i: u32, ) -> Weight;
+	fn verify_groth16_batch(n: u32, i: u32, ) -> Weight;
+	fn prune_settled_task() -> Weight;
+	fn expire_overdue_task() -> Weight;
 }
 
 /// Weights for `pallet_zk_compute` using the Substrate node and recommended hardware.
@@ -51,71 +58,211 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
 	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute NextTaskId (r:1 w:1)
 	/// Proof: ZkCompute NextTaskId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: ZkCompute PendingTasks (r:1 w:1)
-	/// Proof: ZkCompute PendingTasks (max_values: Some(1), max_size: Some(8002), added: 8497, mode: MaxEncodedLen)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute Tasks (r:0 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	/// Storage: ZkCompute LatestKeyVersion (r:1 w:0)
+	/// Proof: ZkCompute LatestKeyVersion (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:2 w:0)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
 	fn submit_proof() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `180`
-		//  Estimated: `9487`
-		// Minimum execution time: 53_961_000 picoseconds.
-		Weight::from_parts(54_409_000, 9487)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+		//  Measured:  `4735`
+		//  Estimated: `14206`
+		// Minimum execution time: 62_453_000 picoseconds.
+		Weight::from_parts(64_385_000, 14206)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
 	}
 	/// Storage: ZkCompute Tasks (r:1 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
 	/// Storage: ZkCompute PendingTasks (r:1 w:1)
-	/// Proof: ZkCompute PendingTasks (max_values: Some(1), max_size: Some(8002), added: 8497, mode: MaxEncodedLen)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
 	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerScores (r:1 w:1)
 	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:1 w:0)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
 	fn verify_task() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `603`
+		//  Measured:  `3162`
 		//  Estimated: `9487`
-		// Minimum execution time: 84_279_000 picoseconds.
-		Weight::from_parts(87_185_000, 9487)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+		// Minimum execution time: 88_471_000 picoseconds.
+		Weight::from_parts(91_208_000, 9487)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
 	}
 	/// Storage: ZkCompute Tasks (r:1 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
 	fn claim_reward() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `530`
+		//  Measured:  `2560`
 		//  Estimated: `7681`
-		// Minimum execution time: 85_086_000 picoseconds.
-		Weight::from_parts(86_778_000, 7681)
-			.saturating_add(T::DbWeight::get().reads(2_u64))
-			.saturating_add(T::DbWeight::get().writes(2_u64))
+		// Minimum execution time: 85_665_000 picoseconds.
+		Weight::from_parts(88_315_000, 7681)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
 	/// Storage: ZkCompute Tasks (r:1 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
 	/// Storage: ZkCompute PendingTasks (r:1 w:1)
-	/// Proof: ZkCompute PendingTasks (max_values: Some(1), max_size: Some(8002), added: 8497, mode: MaxEncodedLen)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
 	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
-	/// Storage: ZkCompute VerifiedTasks (r:1 w:1)
-	/// Proof: ZkCompute VerifiedTasks (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerifiedTasksCount (r:1 w:1)
+	/// Proof: ZkCompute VerifiedTasksCount (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerifiedTasks (r:0 w:1)
+	/// Proof: ZkCompute VerifiedTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerScores (r:1 w:1)
 	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
-	fn submit_verification_unsigned() -> Weight {
+	/// Storage: ZkCompute Authorities (r:1 w:0)
+	/// Proof: ZkCompute Authorities (max_values: Some(1), max_size: Some(3202), added: 3697, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 100]`.
+	fn submit_verification_unsigned(k: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `535 + k * (32 ±0)`
+		//  Estimated: `11384`
+		// Minimum execution time: 97_834_000 picoseconds.
+		Weight::from_parts(100_126_000, 11384)
+			// Standard Error: 3_906
+			.saturating_add(Weight::from_parts(41_237, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: ZkCompute LatestKeyVersion (r:1 w:1)
+	/// Proof: ZkCompute LatestKeyVersion (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:1 w:1)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	fn register_verification_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4065`
+		//  Estimated: `12196`
+		// Minimum execution time: 29_642_000 picoseconds.
+		Weight::from_parts(31_874_000, 12196)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: ZkCompute VerificationKeys (r:1 w:1)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	fn deprecate_verification_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2532`
+		//  Estimated: `7598`
+		// Minimum execution time: 17_578_000 picoseconds.
+		Weight::from_parts(18_902_000, 7598)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerifiedTasksCount (r:1 w:1)
+	/// Proof: ZkCompute VerifiedTasksCount (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:1 w:0)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	/// Storage: ZkCompute Tasks (r:100 w:100)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PendingTasks (r:100 w:100)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute MinerPendingCount (r:100 w:100)
+	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: ZkCompute MinerScores (r:100 w:100)
+	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:100 w:100)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:100)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 100]`.
+	fn verify_tasks_batch(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4964 + n * (5166 ±0)`
+		//  Estimated: `14892 + n * (10333 ±0)`
+		// Minimum execution time: 49_735_000 picoseconds.
+		Weight::from_parts(51_274_000, 14892)
+			// Standard Error: 15_161
+			.saturating_add(Weight::from_parts(33_917_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10333).saturating_mul(n.into()))
+	}
+	/// The range of component `i` is `[3, 32]`.
+	fn verify_groth16_proof(i: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `502`
-		//  Estimated: `81487`
-		// Minimum execution time: 48_681_000 picoseconds.
-		Weight::from_parts(49_736_000, 81487)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+		//  Measured:  `0`
+		//  Estimated: `0`
+		Weight::from_parts(48_512_000_000, 0)
+			.saturating_add(Weight::from_parts(1_214_000_000, 0).saturating_mul(i.into()))
+	}
+	/// The range of component `n` is `[1, 100]`.
+	/// The range of component `i` is `[3, 32]`.
+	fn verify_groth16_batch(n: u32, i: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		Weight::from_parts(26_760_000_000, 0)
+			.saturating_add(Weight::from_parts(11_479_840_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(1_214_000_000, 0).saturating_mul(i.into()))
+	}
+	/// Storage: ZkCompute PruneQueue (r:1 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
+	/// Storage: ZkCompute Tasks (r:0 w:1)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	fn prune_settled_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `834`
+		//  Estimated: `2503`
+		// Minimum execution time: 14_390_000 picoseconds.
+		Weight::from_parts(14_836_000, 2503)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: ZkCompute PendingTasks (r:1 w:1)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute Tasks (r:1 w:1)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
+	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
+	fn expire_overdue_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2906`
+		//  Estimated: `8720`
+		// Minimum execution time: 40_286_000 picoseconds.
+		Weight::from_parts(41_532_000, 8720)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
 	}
 }
 
@@ -127,70 +274,210 @@ impl WeightInfo for () {
 	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
 	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute NextTaskId (r:1 w:1)
 	/// Proof: ZkCompute NextTaskId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: ZkCompute PendingTasks (r:1 w:1)
-	/// Proof: ZkCompute PendingTasks (max_values: Some(1), max_size: Some(8002), added: 8497, mode: MaxEncodedLen)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute Tasks (r:0 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	/// Storage: ZkCompute LatestKeyVersion (r:1 w:0)
+	/// Proof: ZkCompute LatestKeyVersion (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:2 w:0)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
 	fn submit_proof() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `180`
-		//  Estimated: `9487`
-		// Minimum execution time: 53_961_000 picoseconds.
-		Weight::from_parts(54_409_000, 9487)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+		//  Measured:  `4735`
+		//  Estimated: `14206`
+		// Minimum execution time: 62_453_000 picoseconds.
+		Weight::from_parts(64_385_000, 14206)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
 	}
 	/// Storage: ZkCompute Tasks (r:1 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
 	/// Storage: ZkCompute PendingTasks (r:1 w:1)
-	/// Proof: ZkCompute PendingTasks (max_values: Some(1), max_size: Some(8002), added: 8497, mode: MaxEncodedLen)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
 	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerScores (r:1 w:1)
 	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:1 w:0)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
 	fn verify_task() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `603`
+		//  Measured:  `3162`
 		//  Estimated: `9487`
-		// Minimum execution time: 84_279_000 picoseconds.
-		Weight::from_parts(87_185_000, 9487)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+		// Minimum execution time: 88_471_000 picoseconds.
+		Weight::from_parts(91_208_000, 9487)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
 	}
 	/// Storage: ZkCompute Tasks (r:1 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
 	fn claim_reward() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `530`
+		//  Measured:  `2560`
 		//  Estimated: `7681`
-		// Minimum execution time: 85_086_000 picoseconds.
-		Weight::from_parts(86_778_000, 7681)
-			.saturating_add(RocksDbWeight::get().reads(2_u64))
-			.saturating_add(RocksDbWeight::get().writes(2_u64))
+		// Minimum execution time: 85_665_000 picoseconds.
+		Weight::from_parts(88_315_000, 7681)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
 	/// Storage: ZkCompute Tasks (r:1 w:1)
-	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(4216), added: 6691, mode: MaxEncodedLen)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
 	/// Storage: ZkCompute PendingTasks (r:1 w:1)
-	/// Proof: ZkCompute PendingTasks (max_values: Some(1), max_size: Some(8002), added: 8497, mode: MaxEncodedLen)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
 	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
-	/// Storage: ZkCompute VerifiedTasks (r:1 w:1)
-	/// Proof: ZkCompute VerifiedTasks (max_values: Some(1), max_size: Some(80002), added: 80497, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerifiedTasksCount (r:1 w:1)
+	/// Proof: ZkCompute VerifiedTasksCount (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerifiedTasks (r:0 w:1)
+	/// Proof: ZkCompute VerifiedTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
 	/// Storage: ZkCompute MinerScores (r:1 w:1)
 	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
-	fn submit_verification_unsigned() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `502`
-		//  Estimated: `81487`
-		// Minimum execution time: 48_681_000 picoseconds.
-		Weight::from_parts(49_736_000, 81487)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	/// Storage: ZkCompute Authorities (r:1 w:0)
+	/// Proof: ZkCompute Authorities (max_values: Some(1), max_size: Some(3202), added: 3697, mode: MaxEncodedLen)
+	/// The range of component `k` is `[1, 100]`.
+	fn submit_verification_unsigned(k: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `535 + k * (32 ±0)`
+		//  Estimated: `11384`
+		// Minimum execution time: 97_834_000 picoseconds.
+		Weight::from_parts(100_126_000, 11384)
+			// Standard Error: 3_906
+			.saturating_add(Weight::from_parts(41_237, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: ZkCompute LatestKeyVersion (r:1 w:1)
+	/// Proof: ZkCompute LatestKeyVersion (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:1 w:1)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	fn register_verification_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4065`
+		//  Estimated: `12196`
+		// Minimum execution time: 29_642_000 picoseconds.
+		Weight::from_parts(31_874_000, 12196)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: ZkCompute VerificationKeys (r:1 w:1)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	fn deprecate_verification_key() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2532`
+		//  Estimated: `7598`
+		// Minimum execution time: 17_578_000 picoseconds.
+		Weight::from_parts(18_902_000, 7598)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerifiedTasksCount (r:1 w:1)
+	/// Proof: ZkCompute VerifiedTasksCount (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute VerificationKeys (r:1 w:0)
+	/// Proof: ZkCompute VerificationKeys (max_values: None, max_size: Some(4133), added: 6608, mode: MaxEncodedLen)
+	/// Storage: ZkCompute Tasks (r:100 w:100)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PendingTasks (r:100 w:100)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute MinerPendingCount (r:100 w:100)
+	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: ZkCompute MinerScores (r:100 w:100)
+	/// Proof: ZkCompute MinerScores (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:100 w:100)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:100)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
+	/// The range of component `n` is `[1, 100]`.
+	fn verify_tasks_batch(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4964 + n * (5166 ±0)`
+		//  Estimated: `14892 + n * (10333 ±0)`
+		// Minimum execution time: 49_735_000 picoseconds.
+		Weight::from_parts(51_274_000, 14892)
+			// Standard Error: 15_161
+			.saturating_add(Weight::from_parts(33_917_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10333).saturating_mul(n.into()))
+	}
+	/// The range of component `i` is `[3, 32]`.
+	fn verify_groth16_proof(i: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		Weight::from_parts(48_512_000_000, 0)
+			.saturating_add(Weight::from_parts(1_214_000_000, 0).saturating_mul(i.into()))
+	}
+	/// The range of component `n` is `[1, 100]`.
+	/// The range of component `i` is `[3, 32]`.
+	fn verify_groth16_batch(n: u32, i: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		Weight::from_parts(26_760_000_000, 0)
+			.saturating_add(Weight::from_parts(11_479_840_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(1_214_000_000, 0).saturating_mul(i.into()))
+	}
+	/// Storage: ZkCompute PruneQueue (r:1 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
+	/// Storage: ZkCompute Tasks (r:0 w:1)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	fn prune_settled_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `834`
+		//  Estimated: `2503`
+		// Minimum execution time: 14_390_000 picoseconds.
+		Weight::from_parts(14_836_000, 2503)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: ZkCompute PendingTasks (r:1 w:1)
+	/// Proof: ZkCompute PendingTasks (max_values: None, max_size: Some(24), added: 2499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute Tasks (r:1 w:1)
+	/// Proof: ZkCompute Tasks (max_values: None, max_size: Some(5255), added: 7730, mode: MaxEncodedLen)
+	/// Storage: ZkCompute CounterForPendingTasks (r:1 w:1)
+	/// Proof: ZkCompute CounterForPendingTasks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: ZkCompute MinerPendingCount (r:1 w:1)
+	/// Proof: ZkCompute MinerPendingCount (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueueBounds (r:1 w:1)
+	/// Proof: ZkCompute PruneQueueBounds (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: ZkCompute PruneQueue (r:0 w:1)
+	/// Proof: ZkCompute PruneQueue (max_values: None, max_size: Some(28), added: 2503, mode: MaxEncodedLen)
+	fn expire_overdue_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2906`
+		//  Estimated: `8720`
+		// Minimum execution time: 40_286_000 picoseconds.
+		Weight::from_parts(41_532_000, 8720)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
 	}
 }
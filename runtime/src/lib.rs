@@ -155,10 +155,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // and set impl_version to 0. If only runtime
     // implementation changes and behavior does not, then leave spec_version as
     // is and increment impl_version.
-    spec_version: 407,
+    spec_version: 408,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
     state_version: 1,
 };
 
@@ -586,6 +586,7 @@ pub mod opaque {
             pub babe: Babe,
             pub im_online: ImOnline,
             pub authority_discovery: AuthorityDiscovery,
+            pub zk_compute: ZkCompute,
         }
     }
 }
@@ -1284,7 +1285,7 @@ impl pallet_assets::Config for Runtime {
     type MetadataDepositPerByte = MetadataDepositPerByte;
     type ApprovalDeposit = ApprovalDeposit;
     type StringLimit = StringLimit;
-    type Freezer = ();
+    type Freezer = X402Settlement;
     type Extra = ();
     type CallbackHandle = ();
     type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
@@ -1412,12 +1413,32 @@ parameter_types! {
     pub const StakeSlashPercent: u32 = 10;
     pub const MaxTasksPerPool: u32 = 1000;
     pub const InitialReputation: u32 = 100;
+    pub const PoolUnbondingPeriod: BlockNumber = 7 * DAYS;
+    pub const MaxPoolCommission: Perbill = Perbill::from_percent(50);
+    pub const SchedulerPalletId: PalletId = PalletId(*b"dbc/pool");
+    pub const MaxQueuedTasks: u32 = 100;
+    pub const TaskPriorityFee: Perbill = Perbill::from_percent(10);
+    pub const MaxRequiredGpuModels: u32 = 16;
+    pub const MaxPoolProfiles: u32 = 256;
+    pub const MaxShardsPerTask: u32 = 16;
 
     // Agent Attestation
     pub const AttestationDeposit: Balance = 100 * DBCS;
     pub const ChallengeWindow: BlockNumber = 7200; // ~12 hours
     pub const SlashPercent: u32 = 50;
+    pub const FinalizationTip: Balance = DBCS;
+    pub const MaxFinalizationsPerBlock: u32 = 100;
+    pub const ChallengeBond: Balance = 100 * DBCS;
+    pub const ChallengerSlashShare: Perbill = Perbill::from_percent(50);
+    pub const AttestationJurySize: u32 = 5;
+    pub const JuryCommitDuration: BlockNumber = 2 * HOURS;
+    pub const JuryRevealDuration: BlockNumber = 2 * HOURS;
     pub const AttestationHeartbeatInterval: BlockNumber = 100;
+    pub const MaxMissedHeartbeats: u32 = 3;
+    pub const MaxLivenessChecksPerBlock: u32 = 50;
+    pub const NodeRegistrationBond: Balance = 1_000 * DBCS;
+    pub const InactivitySlash: Perbill = Perbill::from_percent(1);
+    pub const NodeUnbondingPeriod: BlockNumber = 7 * DAYS;
     pub const MaxGpuUuidLen: u32 = 128;
     pub const MaxModelsPerAgent: u32 = 10;
 
@@ -1425,6 +1446,8 @@ parameter_types! {
     pub const MaxPendingTasks: u32 = 1000;
     pub const MaxVerifiedTasks: u32 = 10000;
     pub const MaxPendingPerMiner: u32 = 10;
+    pub const TaskRetentionPeriod: BlockNumber = 7 * DAYS;
+    pub const MaxVerificationBatch: u32 = 100;
     pub const BaseReward: Balance = 100 * DBCS;
     pub const SubmissionDeposit: Balance = 10 * DBCS;
     pub const ZkVerificationTimeout: BlockNumber = 1200; // ~2 hours
@@ -1436,11 +1459,21 @@ parameter_types! {
     pub const ZkPalletId: frame_support::PalletId = frame_support::PalletId(*b"dbc/zkcp");
 
     // X402 Settlement
+    // The facilitator configured before the registry, registered by the x402 v2 migration
     pub const FacilitatorAccount: AccountId = AccountId::new(hex_literal::hex!("189dac29296d31814dc8c56cf3d36a0543372bba7538fa322a4aebfebc39e056")); // sr25519 from seed [1u8; 32]
-    pub const MaxSignatureLen: u32 = 128;
     pub const FacilitatorPublicKey: [u8; 32] = hex_literal::hex!("189dac29296d31814dc8c56cf3d36a0543372bba7538fa322a4aebfebc39e056");
+    pub const MaxSignatureLen: u32 = 128;
+    pub const MinFacilitatorStake: Balance = 10_000 * DBCS;
+    pub const FacilitatorSlash: Balance = 1_000 * DBCS; // per verified intent that fails or expires
     pub const SettlementDelay: BlockNumber = 5; // 5 blocks (~30s) for dev, increase for production
     pub const PaymentIntentTTL: BlockNumber = 14400; // ~24 hours at 6s blocks
+    pub const ChannelDisputeWindow: BlockNumber = 600; // ~1 hour for a higher voucher to show up
+    pub const MaxExpiriesPerBlock: u32 = 256; // pending intents expired per block, the rest carry over
+    // EIP-712 payment intents are signed for the X402Settlement precompile
+    pub X402VerifyingContract: H160 = H160::from_low_u64_be(2100);
+    // Swaps stablecoin payments into DBC for miners who asked to be paid in DBC. Topped up with DBC by
+    // treasury spends, conversion is refused while it lacks the DBC (see docs/CHANGELOG.md)
+    pub X402ConversionAccount: AccountId = sp_runtime::traits::AccountIdConversion::<AccountId>::into_account_truncating(&PalletId(*b"dbc/x4cv"));
 }
 
 /// Hash-commitment ZK verifier for matrix multiplication proofs.
@@ -1449,7 +1482,12 @@ parameter_types! {
 pub struct HashCommitmentZkVerifier;
 
 impl pallet_zk_compute::VerifyZkProof for HashCommitmentZkVerifier {
-    fn verify(proof: &[u8], dimensions: (u32, u32, u32)) -> bool {
+    fn verify(
+        _verification_key: &[u8],
+        proof: &[u8],
+        _public_inputs: &[u8],
+        dimensions: (u32, u32, u32),
+    ) -> bool {
         // Minimum proof length: 1 + 32 + 32 + 32 = 97
         if proof.len() < 97 {
             return false;
@@ -1508,6 +1546,8 @@ impl pallet_zk_compute::Config for Runtime {
     type MaxPendingTasks = MaxPendingTasks;
     type MaxVerifiedTasks = MaxVerifiedTasks;
     type MaxPendingPerMiner = MaxPendingPerMiner;
+    type TaskRetentionPeriod = TaskRetentionPeriod;
+    type MaxVerificationBatch = MaxVerificationBatch;
     type BaseReward = BaseReward;
     type SubmissionDeposit = SubmissionDeposit;
     type VerificationTimeout = ZkVerificationTimeout;
@@ -1518,7 +1558,11 @@ impl pallet_zk_compute::Config for Runtime {
     type ScorePenaltyOnFailure = ScorePenaltyOnFailure;
     type PalletId = ZkPalletId;
     type WeightInfo = pallet_zk_compute::weights::SubstrateWeight<Runtime>;
-    type ZkVerifier = HashCommitmentZkVerifier;
+    type HashCommitmentVerifier = HashCommitmentZkVerifier;
+    type AdminOrigin = EnsureRootOrHalfCouncil;
+    type AuthorityId = pallet_zk_compute::sr25519::AuthorityId;
+    type AuthorityCrypto = pallet_zk_compute::sr25519::AuthorityCrypto;
+    type MaxAuthorities = MaxAuthorities;
 }
 
 impl pallet_compute_pool_scheduler::Config for Runtime {
@@ -1534,6 +1578,14 @@ impl pallet_compute_pool_scheduler::Config for Runtime {
     type MaxTasksPerPool = MaxTasksPerPool;
     type InitialReputation = InitialReputation;
     type VerificationTimeout = VerificationTimeout;
+    type UnbondingPeriod = PoolUnbondingPeriod;
+    type MaxCommission = MaxPoolCommission;
+    type PalletId = SchedulerPalletId;
+    type MaxQueuedTasks = MaxQueuedTasks;
+    type PriorityFee = TaskPriorityFee;
+    type MaxRequiredGpuModels = MaxRequiredGpuModels;
+    type MaxPoolProfiles = MaxPoolProfiles;
+    type MaxShardsPerTask = MaxShardsPerTask;
     type WeightInfo = pallet_compute_pool_scheduler::weights::SubstrateWeight<Runtime>;
     type OnTaskCompleted = AgentAttestation;
 }
@@ -1541,28 +1593,127 @@ impl pallet_compute_pool_scheduler::Config for Runtime {
 impl pallet_agent_attestation::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Currency = Balances;
-    type AdminOrigin = frame_system::EnsureSigned<AccountId>;
     type AttestationDeposit = AttestationDeposit;
     type ChallengeWindow = ChallengeWindow;
     type SlashPercent = SlashPercent;
     type HeartbeatInterval = AttestationHeartbeatInterval;
+    type MaxMissedHeartbeats = MaxMissedHeartbeats;
+    type MaxLivenessChecksPerBlock = MaxLivenessChecksPerBlock;
+    type RegistrationBond = NodeRegistrationBond;
+    type InactivitySlash = InactivitySlash;
+    type UnbondingPeriod = NodeUnbondingPeriod;
     type MaxModelIdLen = MaxModelIdLen;
     type MaxGpuUuidLen = MaxGpuUuidLen;
     type MaxModelsPerAgent = MaxModelsPerAgent;
+    type FinalizationTip = FinalizationTip;
+    type MaxFinalizationsPerBlock = MaxFinalizationsPerBlock;
     type WeightInfo = pallet_agent_attestation::weights::SubstrateWeight<Runtime>;
     type OnAttestationConfirmed = X402Settlement;
+    type ManageCommittee = Committee;
+    type Randomness = RandomnessCollectiveFlip;
+    type Slash = Treasury;
+    type ChallengeBond = ChallengeBond;
+    type ChallengerSlashShare = ChallengerSlashShare;
+    type JurySize = AttestationJurySize;
+    type CommitDuration = JuryCommitDuration;
+    type RevealDuration = JuryRevealDuration;
+    type AdminOrigin = EnsureRootOrHalfCouncil;
+    type UnixTime = Timestamp;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = AttestationBenchmarkHelper;
+}
+
+/// Turns benchmark accounts into staked, available committee members.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct AttestationBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_agent_attestation::BenchmarkHelper<AccountId> for AttestationBenchmarkHelper {
+    fn add_committee_member(who: &AccountId) {
+        if Committee::committee_stake_params().is_none() {
+            let _ = Committee::set_committee_stake_params(
+                RuntimeOrigin::root(),
+                committee::CommitteeStakeParamsInfo {
+                    stake_baseline: 20_000 * DBCS,
+                    stake_per_order: 1_000 * DBCS,
+                    min_free_stake_percent: Perbill::from_percent(40),
+                },
+            );
+        }
+        let _ = <Balances as frame_support::traits::Currency<AccountId>>::deposit_creating(
+            who,
+            100_000 * DBCS,
+        );
+        let _ = Committee::add_committee(RuntimeOrigin::root(), who.clone());
+        let _ = Committee::committee_set_box_pubkey(RuntimeOrigin::signed(who.clone()), [0u8; 32]);
+    }
 }
 
 impl pallet_x402_settlement::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Currency = Balances;
-    type AdminOrigin = frame_system::EnsureSigned<AccountId>;
-    type FacilitatorAccount = FacilitatorAccount;
+    type AdminOrigin = EnsureRootOrHalfCouncil;
     type MaxSignatureLen = MaxSignatureLen;
-    type FacilitatorPublicKey = FacilitatorPublicKey;
+    type MinFacilitatorStake = MinFacilitatorStake;
+    type FacilitatorSlash = FacilitatorSlash;
     type SettlementDelay = SettlementDelay;
     type PaymentIntentTTL = PaymentIntentTTL;
+    type ChannelDisputeWindow = ChannelDisputeWindow;
+    type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+    type AddressMapping = <Runtime as pallet_evm::Config>::AddressMapping;
+    type ChainId = EVMChainId;
+    type Eip712VerifyingContract = X402VerifyingContract;
+    type AssetId = u32;
+    type Assets = Assets;
+    type DbcPrice = DBCPriceOCW;
+    type ConversionAccount = X402ConversionAccount;
     type WeightInfo = pallet_x402_settlement::weights::SubstrateWeight<Runtime>;
+    type OnOrderSettled = TaskMode;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = X402BenchmarkHelper;
+}
+
+/// Funds benchmark accounts with a 6-decimal stablecoin priced by `DBCPriceOCW` and gives
+/// payment channels a task definition to bill against.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct X402BenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_x402_settlement::BenchmarkHelper<u32, AccountId, Balance> for X402BenchmarkHelper {
+    fn fund_asset(who: &AccountId, amount: Balance) -> u32 {
+        use frame_support::traits::tokens::fungibles::{metadata::Mutate as _, Create, Mutate};
+        const ASSET: u32 = 402;
+        let owner: AccountId = frame_benchmarking::account("x402-asset-owner", 0, 0);
+        if !<Assets as frame_support::traits::tokens::fungibles::Inspect<AccountId>>::asset_exists(ASSET) {
+            let _ = <Balances as frame_support::traits::Currency<AccountId>>::deposit_creating(
+                &owner,
+                100 * DBCS,
+            );
+            let _ = <Assets as Create<AccountId>>::create(ASSET, owner.clone(), true, 1);
+            let _ = Assets::set(ASSET, &owner, b"USD Coin".to_vec(), b"USDC".to_vec(), 6);
+            for _ in 0..dbc_price_ocw::MAX_LEN {
+                DBCPriceOCW::add_price(10_000);
+            }
+            DBCPriceOCW::add_avg_price();
+        }
+        let _ = <Assets as Mutate<AccountId>>::mint_into(ASSET, who, amount);
+        ASSET
+    }
+
+    fn billable_task() -> u64 {
+        if let Some(task_id) = TaskMode::next_task_id().checked_sub(1) {
+            return task_id
+        }
+        let admin: AccountId = frame_benchmarking::account("x402-task-admin", 0, 0);
+        let _ = TaskMode::create_task_definition(
+            RuntimeOrigin::signed(admin),
+            b"llama3-70b".to_vec(),
+            b"v1".to_vec(),
+            2_000_000,
+            4_000_000,
+            10_000,
+            b"ipfs://policy".to_vec(),
+        );
+        0
+    }
 }
 
 
@@ -1899,7 +2050,26 @@ pub type Executive = frame_executive::Executive<
 
 // All migrations executed on runtime upgrade as a nested tuple of types implementing
 // `OnRuntimeUpgrade`.
-type Migrations = ();
+type Migrations = (
+    migrations::v3::ZkComputeSessionKeys<Runtime>,
+    pallet_zk_compute::migrations::v2::Migration<Runtime>,
+    pallet_zk_compute::migrations::v3::Migration<Runtime>,
+    pallet_zk_compute::migrations::v4::Migration<Runtime>,
+    pallet_agent_attestation::migrations::v2::Migration<Runtime>,
+    pallet_agent_attestation::migrations::v3::Migration<Runtime>,
+    pallet_agent_attestation::migrations::v4::Migration<Runtime>,
+    pallet_agent_attestation::migrations::v5::Migration<Runtime>,
+    pallet_agent_attestation::migrations::v6::Migration<Runtime>,
+    pallet_agent_attestation::migrations::v7::Migration<Runtime>,
+    pallet_x402_settlement::migrations::v2::Migration<Runtime, FacilitatorAccount, FacilitatorPublicKey>,
+    pallet_x402_settlement::migrations::v3::Migration<Runtime>,
+    pallet_x402_settlement::migrations::v4::Migration<Runtime>,
+    pallet_x402_settlement::migrations::v5::Migration<Runtime>,
+    pallet_x402_settlement::migrations::v6::Migration<Runtime>,
+    pallet_compute_pool_scheduler::migrations::v2::Migration<Runtime>,
+    pallet_compute_pool_scheduler::migrations::v3::Migration<Runtime>,
+    pallet_compute_pool_scheduler::migrations::v4::Migration<Runtime>,
+);
 
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
@@ -2920,6 +3090,19 @@ impl_runtime_apis! {
                 .count() as u64
         }
 
+        fn get_model_providers(
+            model_id: Vec<u8>,
+            region: Option<Vec<u8>>,
+            max_price: Option<Balance>,
+            min_free_concurrency: u32,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<u8> {
+            use parity_scale_codec::Encode;
+            let filter = pallet_agent_attestation::ProviderFilter { region, max_price, min_free_concurrency };
+            AgentAttestation::discover_providers(&model_id, &filter, offset, limit).encode()
+        }
+
         fn get_payment_intent(intent_id: u64) -> Option<Vec<u8>> {
             use parity_scale_codec::Encode;
             pallet_x402_settlement::PaymentIntents::<Runtime>::get(intent_id).map(|v| v.encode())
@@ -2998,6 +3181,16 @@ impl_runtime_apis! {
                 .filter(|(_, intent)| matches!(intent.status, pallet_x402_settlement::pallet::PaymentIntentStatus::Pending))
                 .count() as u64
         }
+
+        fn get_facilitator(account: AccountId) -> Option<Vec<u8>> {
+            use parity_scale_codec::Encode;
+            pallet_x402_settlement::Facilitators::<Runtime>::get(&account).map(|v| v.encode())
+        }
+
+        fn get_facilitator_stats(account: AccountId) -> Vec<u8> {
+            use parity_scale_codec::Encode;
+            pallet_x402_settlement::FacilitatorStatsOf::<Runtime>::get(&account).encode()
+        }
     }
 
 }
@@ -192,3 +192,64 @@ pub mod v2 {
         }
     }
 }
+
+pub mod v3 {
+    use super::*;
+    use crate::{
+        opaque::SessionKeys, AccountId, AuthorityDiscovery, Babe, Grandpa, ImOnline,
+        RuntimeBlockWeights, Session,
+    };
+
+    use parity_scale_codec::{DecodeAll, Encode};
+    use sp_runtime::{impl_opaque_keys, Perbill};
+
+    impl_opaque_keys! {
+        pub struct OldSessionKeys {
+            pub grandpa: Grandpa,
+            pub babe: Babe,
+            pub im_online: ImOnline,
+            pub authority_discovery: AuthorityDiscovery,
+        }
+    }
+
+    // No keystore holds the secret of this placeholder, so a validator's off-chain worker
+    // signs no zk-compute verification until the validator inserts a `zkvf` key (or rotates
+    // keys with `author_rotateKeys`) and registers it with `session.setKeys`.
+    fn transform_session_keys(validator: AccountId, old: OldSessionKeys) -> SessionKeys {
+        let placeholder = sp_io::hashing::blake2_256(&(b"zkvf", &validator).encode());
+        SessionKeys {
+            grandpa: old.grandpa,
+            babe: old.babe,
+            im_online: old.im_online,
+            authority_discovery: old.authority_discovery,
+            zk_compute: sp_core::sr25519::Public::from_raw(placeholder).into(),
+        }
+    }
+
+    /// Whether the queued session keys still have the layout without a zk-compute key.
+    fn has_old_session_keys() -> bool {
+        pallet_session::NextKeys::<Runtime>::iter_keys().next().map_or(false, |validator| {
+            let key = pallet_session::NextKeys::<Runtime>::hashed_key_for(&validator);
+            frame_support::storage::unhashed::get_raw(&key)
+                .map_or(false, |raw| OldSessionKeys::decode_all(&mut &raw[..]).is_ok())
+        })
+    }
+
+    /// Adds a zk-compute verification key slot to every validator's session keys. Validators
+    /// must insert or rotate a `zkvf` key afterwards, see `transform_session_keys`.
+    pub struct ZkComputeSessionKeys<T>(sp_std::marker::PhantomData<T>);
+    impl<T: frame_system::Config> OnRuntimeUpgrade for ZkComputeSessionKeys<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if !has_old_session_keys() {
+                log::info!(target: LOG_TARGET, "ZkComputeSessionKeys already applied, skipping");
+                return T::DbWeight::get().reads(2)
+            }
+
+            Session::upgrade_keys::<OldSessionKeys, _>(transform_session_keys);
+
+            log::info!(target: LOG_TARGET, "ZkComputeSessionKeys upgraded session keys");
+
+            Perbill::from_percent(50) * RuntimeBlockWeights::get().max_block
+        }
+    }
+}
@@ -65,6 +65,7 @@ where
     BalanceOf<T>: TryFrom<U256> + Into<U256>,
 {
     /// createTaskOrder(uint64 task_id, address miner, uint64 input_tokens, uint64 output_tokens)
+    /// A zero miner address lets the compute scheduler pick the miner.
     /// Returns: uint64 order_id
     fn create_task_order(handle: &mut impl PrecompileHandle) -> PrecompileResult {
         let input = handle.input();
@@ -94,7 +95,11 @@ where
                 exit_status: ExitRevert::Reverted,
                 output: "decode miner address failed".into(),
             })?;
-        let miner_account: T::AccountId = T::AddressMapping::into_account_id(miner_address);
+        let miner_account: Option<T::AccountId> = if miner_address.is_zero() {
+            None
+        } else {
+            Some(T::AddressMapping::into_account_id(miner_address))
+        };
 
         let input_tokens_uint =
             param[2].clone().into_uint().ok_or_else(|| PrecompileFailure::Revert {
@@ -5,11 +5,13 @@ use fp_evm::{
 use sp_core::U256;
 use sp_runtime::RuntimeDebug;
 extern crate alloc;
-use alloc::format;
+use alloc::{boxed::Box, format, vec::Vec};
 use core::marker::PhantomData;
-use frame_support::{ensure, pallet_prelude::Weight};
+use dbc_support::traits::{GpuModelName, TaskRequirements};
+use frame_support::{ensure, pallet_prelude::Weight, traits::Currency};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use pallet_evm::GasWeightMapping;
+use pallet_compute_pool_scheduler::{weights::WeightInfo, TaskDimensions, TaskPriority};
+use pallet_evm::{AddressMapping, GasWeightMapping};
 
 pub struct ComputePoolPrecompile<T>(PhantomData<T>);
 
@@ -19,11 +21,17 @@ pub struct ComputePoolPrecompile<T>(PhantomData<T>);
 pub enum Selector {
     QueryPool = "queryPool(uint64)",
     QueryTask = "queryTask(uint64)",
+    SubmitTask = "submitTask(uint32,uint32,uint32,uint8,uint32,bytes[],uint32,bool,bytes2,uint256,uint32)",
 }
 
+type BalanceOf<T> = <<T as pallet_compute_pool_scheduler::Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+
 impl<T> Precompile for ComputePoolPrecompile<T>
 where
     T: pallet_evm::Config + pallet_compute_pool_scheduler::Config,
+    BalanceOf<T>: TryFrom<U256>,
 {
     fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
         let input = handle.input();
@@ -42,6 +50,7 @@ where
         match selector {
             Selector::QueryPool => Self::query_pool(handle),
             Selector::QueryTask => Self::query_task(handle),
+            Selector::SubmitTask => Self::submit_task(handle),
         }
     }
 }
@@ -49,6 +58,7 @@ where
 impl<T> ComputePoolPrecompile<T>
 where
     T: pallet_evm::Config + pallet_compute_pool_scheduler::Config,
+    BalanceOf<T>: TryFrom<U256>,
 {
     fn query_pool(handle: &mut impl PrecompileHandle) -> PrecompileResult {
         handle.record_cost(T::GasWeightMapping::weight_to_gas(
@@ -68,7 +78,7 @@ where
         })?.as_u64();
         let pool = pallet_compute_pool_scheduler::Pools::<T>::get(pool_id);
         let (active, gpu_count, max_tasks) = match pool {
-            Some(p) => (true, p.gpu_memory, p.total_tasks),
+            Some(p) => (true, p.gpu_count, p.total_tasks),
             None => (false, 0u32, 0u32),
         };
         let encoded = ethabi::encode(&[
@@ -124,4 +134,135 @@ where
             output: encoded,
         })
     }
+
+    /// submitTask(uint32 m, uint32 n, uint32 k, uint8 priority, uint32 min_vram,
+    ///     bytes[] gpu_models, uint32 gpu_count, bool nvlink_required, bytes2 region,
+    ///     uint256 max_price, uint32 deadline)
+    /// A zero region, max price or deadline leaves that requirement unset.
+    /// Returns: uint64 task_id
+    fn submit_task(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(T::GasWeightMapping::weight_to_gas(
+            <T as pallet_compute_pool_scheduler::Config>::WeightInfo::submit_task(),
+        ))?;
+        let input = handle.input();
+        let param = ethabi::decode(
+            &[
+                ethabi::ParamType::Uint(32), // m
+                ethabi::ParamType::Uint(32), // n
+                ethabi::ParamType::Uint(32), // k
+                ethabi::ParamType::Uint(8), // priority
+                ethabi::ParamType::Uint(32), // min_vram
+                ethabi::ParamType::Array(Box::new(ethabi::ParamType::Bytes)), // gpu_models
+                ethabi::ParamType::Uint(32), // gpu_count
+                ethabi::ParamType::Bool, // nvlink_required
+                ethabi::ParamType::FixedBytes(2), // region
+                ethabi::ParamType::Uint(256), // max_price
+                ethabi::ParamType::Uint(32), // deadline
+            ],
+            &input.get(4..).unwrap_or_default(),
+        ).map_err(|e| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: format!("decode failed: {:?}", e).into(),
+        })?;
+
+        let dimensions = TaskDimensions {
+            m: Self::decode_u32(&param[0], "m")?,
+            n: Self::decode_u32(&param[1], "n")?,
+            k: Self::decode_u32(&param[2], "k")?,
+        };
+        let priority = match Self::decode_u32(&param[3], "priority")? {
+            0 => TaskPriority::Low,
+            1 => TaskPriority::Normal,
+            2 => TaskPriority::High,
+            3 => TaskPriority::Critical,
+            _ => return Err(PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: "invalid priority".into(),
+            }),
+        };
+        let gpu_models: Vec<GpuModelName> = param[5]
+            .clone()
+            .into_array()
+            .ok_or_else(|| PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: "decode gpu_models failed".into(),
+            })?
+            .into_iter()
+            .map(|model| model.into_bytes().and_then(|bytes| bytes.try_into().ok()))
+            .collect::<Option<_>>()
+            .ok_or_else(|| PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: "decode gpu_models failed".into(),
+            })?;
+        let gpu_models = gpu_models.try_into().map_err(|_| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "too many gpu_models".into(),
+        })?;
+        let nvlink_required = param[7].clone().into_bool().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "decode nvlink_required failed".into(),
+        })?;
+        let region = param[8]
+            .clone()
+            .into_fixed_bytes()
+            .and_then(|bytes| <[u8; 2]>::try_from(bytes.as_slice()).ok())
+            .ok_or_else(|| PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: "decode region failed".into(),
+            })?;
+        let max_price = param[9].clone().into_uint().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "decode max_price failed".into(),
+        })?;
+        let max_price: Option<BalanceOf<T>> = if max_price.is_zero() {
+            None
+        } else {
+            Some(max_price.try_into().map_err(|_| PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: "max_price overflow".into(),
+            })?)
+        };
+        let deadline = Self::decode_u32(&param[10], "deadline")?;
+
+        let requirements = TaskRequirements {
+            min_vram: Self::decode_u32(&param[4], "min_vram")?,
+            gpu_models,
+            gpu_count: Self::decode_u32(&param[6], "gpu_count")?,
+            nvlink_required,
+            region: if region == [0u8; 2] { None } else { Some(region) },
+            max_price,
+            deadline: if deadline == 0 { None } else { Some(deadline.into()) },
+        };
+
+        let caller: T::AccountId = T::AddressMapping::into_account_id(handle.context().caller);
+        let task_id = pallet_compute_pool_scheduler::NextTaskId::<T>::get();
+        pallet_compute_pool_scheduler::Pallet::<T>::submit_task(
+            frame_system::RawOrigin::Signed(caller).into(),
+            dimensions,
+            requirements,
+            priority,
+            None,
+        )
+        .map_err(|e| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: format!("submit_task failed: {:?}", e).into(),
+        })?;
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: ethabi::encode(&[ethabi::Token::Uint(U256::from(task_id))]),
+        })
+    }
+
+    fn decode_u32(token: &ethabi::Token, name: &str) -> Result<u32, PrecompileFailure> {
+        token
+            .clone()
+            .into_uint()
+            .filter(|value| *value <= U256::from(u32::MAX))
+            .map(|value| value.as_u32())
+            .ok_or_else(|| PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: format!("decode {} failed", name).into(),
+            })
+    }
 }
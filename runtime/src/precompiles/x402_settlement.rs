@@ -2,13 +2,17 @@ use fp_evm::{
     ExitRevert, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
     PrecompileResult,
 };
+use parity_scale_codec::Decode;
+use sp_core::U256;
 use sp_runtime::RuntimeDebug;
 extern crate alloc;
+use crate::precompiles::LOG_TARGET;
 use alloc::format;
 use core::marker::PhantomData;
 use frame_support::{ensure, pallet_prelude::Weight};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use pallet_evm::GasWeightMapping;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use pallet_x402_settlement::weights::WeightInfo;
 
 pub struct X402SettlementPrecompile<T>(PhantomData<T>);
 
@@ -18,6 +22,7 @@ pub struct X402SettlementPrecompile<T>(PhantomData<T>);
 pub enum Selector {
     QueryPaymentIntent = "queryPaymentIntent(uint64)",
     QuerySettlementReceipt = "querySettlementReceipt(uint64)",
+    SubmitPaymentIntent = "submitPaymentIntent(bytes32,uint256,uint64,bytes32,bytes32,bytes)",
 }
 
 impl<T> Precompile for X402SettlementPrecompile<T>
@@ -41,6 +46,7 @@ where
         match selector {
             Selector::QueryPaymentIntent => Self::query_payment_intent(handle),
             Selector::QuerySettlementReceipt => Self::query_settlement_receipt(handle),
+            Selector::SubmitPaymentIntent => Self::submit_payment_intent(handle),
         }
     }
 }
@@ -109,4 +115,96 @@ where
             output: encoded,
         })
     }
+
+    /// submitPaymentIntent(bytes32 miner, uint256 amount, uint64 nonce, bytes32 replayFingerprint,
+    ///     bytes32 facilitator, bytes facilitatorSignature)
+    /// Opens a payment intent paid by the caller's mapped account. `miner` and `facilitator` are
+    /// the raw 32-byte account ids.
+    /// Returns: uint64 intent_id
+    fn submit_payment_intent(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(T::GasWeightMapping::weight_to_gas(
+            <T as pallet_x402_settlement::Config>::WeightInfo::submit_payment_intent(),
+        ))?;
+        let input = handle.input();
+        let param = ethabi::decode(
+            &[
+                ethabi::ParamType::FixedBytes(32), // miner
+                ethabi::ParamType::Uint(256),      // amount
+                ethabi::ParamType::Uint(64),       // nonce
+                ethabi::ParamType::FixedBytes(32), // replay_fingerprint
+                ethabi::ParamType::FixedBytes(32), // facilitator
+                ethabi::ParamType::Bytes,          // facilitator_signature
+            ],
+            &input.get(4..).unwrap_or_default(),
+        ).map_err(|e| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: format!("decode failed: {:?}", e).into(),
+        })?;
+
+        let miner = Self::decode_account(param[0].clone(), "miner")?;
+        let amount: U256 = param[1].clone().into_uint().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "decode amount failed".into(),
+        })?;
+        let amount: u128 = amount.try_into().map_err(|_| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "amount overflow".into(),
+        })?;
+        let nonce = param[2].clone().into_uint().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "decode nonce failed".into(),
+        })?.as_u64();
+        let replay_fingerprint = param[3].clone().into_fixed_bytes().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "decode replay_fingerprint failed".into(),
+        })?;
+        let replay_fingerprint = sp_core::H256::from_slice(&replay_fingerprint);
+        let facilitator = Self::decode_account(param[4].clone(), "facilitator")?;
+        let facilitator_signature = param[5].clone().into_bytes().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: "decode facilitator_signature failed".into(),
+        })?;
+
+        let caller_evm = handle.context().caller;
+        let merchant: T::AccountId = T::AddressMapping::into_account_id(caller_evm);
+        log::debug!(
+            target: LOG_TARGET,
+            "submit_payment_intent: caller: {:?}, amount: {}, nonce: {}",
+            caller_evm,
+            amount,
+            nonce
+        );
+
+        let intent_id = pallet_x402_settlement::NextIntentId::<T>::get();
+        let origin = frame_system::RawOrigin::Signed(merchant).into();
+        pallet_x402_settlement::Pallet::<T>::submit_payment_intent(
+            origin,
+            miner,
+            amount,
+            nonce,
+            replay_fingerprint,
+            facilitator,
+            facilitator_signature,
+        )
+        .map_err(|e| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: format!("submit_payment_intent failed: {:?}", e).into(),
+        })?;
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: ethabi::encode(&[ethabi::Token::Uint(U256::from(intent_id))]),
+        })
+    }
+
+    fn decode_account(token: ethabi::Token, name: &str) -> Result<T::AccountId, PrecompileFailure> {
+        let bytes = token.into_fixed_bytes().ok_or_else(|| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: format!("decode {} failed", name).into(),
+        })?;
+        T::AccountId::decode(&mut &bytes[..]).map_err(|_| PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: format!("invalid {} account", name).into(),
+        })
+    }
 }
@@ -78,6 +78,7 @@ where
                     pallet_zk_compute::ZkVerificationStatus::Pending => 0,
                     pallet_zk_compute::ZkVerificationStatus::Verified => 1,
                     pallet_zk_compute::ZkVerificationStatus::Failed => 2,
+                    pallet_zk_compute::ZkVerificationStatus::Expired => 3,
                 };
                 (s, t.dimensions.0, t.dimensions.1, t.dimensions.2)
             },
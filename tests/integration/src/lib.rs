@@ -7,13 +7,14 @@
 mod tests {
     use frame_support::{
         construct_runtime, parameter_types,
-        traits::{ConstU16, ConstU32, Everything},
+        traits::{ConstU128, ConstU16, ConstU32, ConstU64, Everything},
     };
     use sp_core::H256;
     use sp_runtime::{
         generic::Header,
+        testing::{TestSignature, UintAuthorityId},
         traits::{BlakeTwo256, IdentityLookup},
-        Percent,
+        Perbill, Percent,
     };
     use std::cell::RefCell;
     use codec;
@@ -25,6 +26,8 @@ mod tests {
     pub type BlockNumber = u64;
     pub type Balance = u128;
 
+    const FACILITATOR_SEED: [u8; 32] = [1u8; 32];
+
     // ================================================================
     // construct_runtime! with all 4 DBC 3.0 pallets wired together
     // ================================================================
@@ -36,6 +39,7 @@ mod tests {
         {
             System: frame_system,
             Balances: pallet_balances,
+            Assets: pallet_assets,
             TaskMode: pallet_task_mode,
             ComputePoolScheduler: pallet_compute_pool_scheduler,
             AgentAttestation: pallet_agent_attestation,
@@ -63,6 +67,7 @@ mod tests {
         pub const TreasuryAccount: AccountId = 99;
         pub const MaxModelIdLen: u32 = 256;
         pub const MaxPolicyCidLen: u32 = 1024;
+        pub const OrderTimeout: BlockNumber = 200;
 
         // ComputePoolScheduler parameters
         pub const PoolDeposit: Balance = 1_000;
@@ -72,6 +77,11 @@ mod tests {
         pub const MaxGpuModelLen: u32 = 64;
         pub const MaxTasksPerPool: u32 = 16;
         pub const InitialReputation: u32 = 80;
+        pub const ProofVerificationTimeout: BlockNumber = 20;
+        pub const PoolUnbondingPeriod: BlockNumber = 20;
+        pub const MaxPoolCommission: Perbill = Perbill::from_percent(50);
+        pub const TaskPriorityFee: Perbill = Perbill::from_percent(10);
+        pub const SchedulerPalletId: frame_support::PalletId = frame_support::PalletId(*b"dbc/pool");
 
         // AgentAttestation parameters
         pub const AttestationDeposit: Balance = 1_000;
@@ -79,12 +89,31 @@ mod tests {
         pub const SlashPercent: u32 = 50;
         pub const HeartbeatInterval: BlockNumber = 100;
         pub const MaxGpuUuidLen: u32 = 128;
+        pub const ChallengeBond: Balance = 500;
+        pub const FinalizationTip: Balance = 10;
+        pub const ChallengerSlashShare: Perbill = Perbill::from_percent(50);
+        pub const InactivitySlash: Perbill = Perbill::from_percent(10);
+        pub const NodeUnbondingPeriod: BlockNumber = 20;
+        pub const JuryCommitDuration: BlockNumber = 10;
+        pub const JuryRevealDuration: BlockNumber = 10;
 
         // X402Settlement parameters
         pub const FacilitatorAccount: AccountId = 100;
         pub const MaxSignatureLen: u32 = 256;
+        pub const MinFacilitatorStake: Balance = 1_000;
+        pub const FacilitatorSlash: Balance = 100;
         pub const SettlementDelay: BlockNumber = 10;
         pub const PaymentIntentTTL: BlockNumber = 100;
+        pub const ChannelDisputeWindow: BlockNumber = 20;
+        pub FacilitatorPublicKey: [u8; 32] = {
+            use sp_core::Pair;
+            sp_core::sr25519::Pair::from_seed(&FACILITATOR_SEED).public().0
+        };
+        pub const EvmChainId: u64 = 19880818;
+        pub X402VerifyingContract: sp_core::H160 = sp_core::H160::from_low_u64_be(2100);
+        pub const ConversionAccount: AccountId = 101;
+        pub const MinLockAmount: Balance = 1;
+        pub const MaxLockDuration: BlockNumber = 1_000;
 
         // ZkCompute parameters
         pub const MaxProofSize: u32 = 4096;
@@ -149,12 +178,55 @@ mod tests {
         }
     }
 
+    // ================================================================
+    // Fixed committee the attestation juries are drawn from
+    // ================================================================
+    pub const JURORS: [AccountId; 3] = [20, 21, 22];
+
+    pub struct MockCommittee;
+    impl dbc_support::traits::ManageCommittee for MockCommittee {
+        type AccountId = AccountId;
+        type Balance = Balance;
+        type ReportId = u64;
+
+        fn is_valid_committee(who: &AccountId) -> bool {
+            JURORS.contains(who)
+        }
+        fn available_committee() -> Option<Vec<AccountId>> {
+            Some(JURORS.to_vec())
+        }
+        fn change_used_stake(_: AccountId, _: Balance, _: bool) -> Result<(), ()> {
+            Ok(())
+        }
+        fn change_total_stake(_: AccountId, _: Balance, _: bool, _: bool) -> Result<(), ()> {
+            Ok(())
+        }
+        fn stake_per_order() -> Option<Balance> {
+            None
+        }
+        fn add_reward(_: AccountId, _: Balance) {}
+    }
+
+    pub struct TestRandomness;
+    impl frame_support::traits::Randomness<H256, BlockNumber> for TestRandomness {
+        fn random(subject: &[u8]) -> (H256, BlockNumber) {
+            (H256(sp_io::hashing::blake2_256(subject)), 0)
+        }
+    }
+
     // ================================================================
     // Mock ZK Verifier (always returns true for testing)
     // ================================================================
+    pub struct MockUnixTime;
+    impl frame_support::traits::UnixTime for MockUnixTime {
+        fn now() -> core::time::Duration {
+            core::time::Duration::from_secs(1_700_000_000)
+        }
+    }
+
     pub struct MockZkVerifier;
     impl pallet_zk_compute::VerifyZkProof for MockZkVerifier {
-        fn verify(_proof: &[u8], _dims: (u32, u32, u32)) -> bool {
+        fn verify(_vk: &[u8], _proof: &[u8], _inputs: &[u8], _dims: (u32, u32, u32)) -> bool {
             true
         }
     }
@@ -165,6 +237,32 @@ mod tests {
         type OverarchingCall = RuntimeCall;
     }
 
+    impl frame_system::offchain::SigningTypes for Test {
+        type Public = UintAuthorityId;
+        type Signature = TestSignature;
+    }
+
+    impl frame_system::offchain::CreateSignedTransaction<pallet_zk_compute::Call<Test>> for Test {
+        fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+            call: RuntimeCall,
+            _public: UintAuthorityId,
+            account: AccountId,
+            _nonce: u64,
+        ) -> Option<(RuntimeCall, <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+            Some((call, (account, (), ())))
+        }
+    }
+
+    pub struct ZkAuthorityCrypto;
+    impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for ZkAuthorityCrypto {
+        type RuntimeAppPublic = UintAuthorityId;
+        type GenericPublic = UintAuthorityId;
+        type GenericSignature = TestSignature;
+    }
+
+    /// Session authority allowed to report zk verification results.
+    const ZK_AUTHORITY: u64 = 5;
+
     // ================================================================
     // Pallet Configs
     // ================================================================
@@ -212,6 +310,29 @@ mod tests {
         type HoldIdentifier = ();
     }
 
+    impl pallet_assets::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type Balance = Balance;
+        type AssetId = u32;
+        type AssetIdParameter = u32;
+        type Currency = Balances;
+        type CreateOrigin = frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+        type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+        type AssetDeposit = ConstU128<1>;
+        type AssetAccountDeposit = ConstU128<1>;
+        type MetadataDepositBase = ConstU128<1>;
+        type MetadataDepositPerByte = ConstU128<1>;
+        type ApprovalDeposit = ConstU128<1>;
+        type StringLimit = ConstU32<50>;
+        type Freezer = X402Settlement;
+        type Extra = ();
+        type CallbackHandle = ();
+        type WeightInfo = ();
+        type RemoveItemsLimit = ConstU32<5>;
+        type MinLockAmount = MinLockAmount;
+        type MaxLockDuration = MaxLockDuration;
+    }
+
     // REAL WIRING: TaskMode uses ComputePoolScheduler for compute scheduling
     impl pallet_task_mode::Config for Test {
         type RuntimeEvent = RuntimeEvent;
@@ -224,6 +345,7 @@ mod tests {
         type EraDuration = EraDuration;
         type MaxModelIdLen = MaxModelIdLen;
         type MaxPolicyCidLen = MaxPolicyCidLen;
+        type OrderTimeout = OrderTimeout;
         type WeightInfo = ();
         // REAL: TaskMode -> ComputePoolScheduler
         type ComputeScheduler = ComputePoolScheduler;
@@ -240,9 +362,18 @@ mod tests {
         type MaxGpuModelLen = MaxGpuModelLen;
         type MaxTasksPerPool = MaxTasksPerPool;
         type InitialReputation = InitialReputation;
+        type VerificationTimeout = ProofVerificationTimeout;
         type WeightInfo = ();
         type MinPoolStake = MinPoolStake;
         type StakeSlashPercent = StakeSlashPercent;
+        type UnbondingPeriod = PoolUnbondingPeriod;
+        type MaxCommission = MaxPoolCommission;
+        type PalletId = SchedulerPalletId;
+        type MaxQueuedTasks = ConstU32<16>;
+        type PriorityFee = TaskPriorityFee;
+        type MaxRequiredGpuModels = ConstU32<8>;
+        type MaxPoolProfiles = ConstU32<256>;
+        type MaxShardsPerTask = ConstU32<16>;
         // REAL: ComputePoolScheduler -> AgentAttestation
         type OnTaskCompleted = AgentAttestation;
     }
@@ -255,26 +386,48 @@ mod tests {
         type ChallengeWindow = ChallengeWindow;
         type SlashPercent = SlashPercent;
         type HeartbeatInterval = HeartbeatInterval;
+        type MaxMissedHeartbeats = ConstU32<3>;
+        type MaxLivenessChecksPerBlock = ConstU32<16>;
+        type RegistrationBond = ConstU128<0>;
+        type InactivitySlash = InactivitySlash;
+        type UnbondingPeriod = NodeUnbondingPeriod;
         type MaxModelIdLen = MaxModelIdLen;
         type MaxGpuUuidLen = MaxGpuUuidLen;
         type WeightInfo = ();
         type MaxModelsPerAgent = MaxModelsPerAgent;
-        type AdminOrigin = frame_system::EnsureSigned<AccountId>;
+        type FinalizationTip = FinalizationTip;
+        type MaxFinalizationsPerBlock = ConstU32<16>;
         // REAL: AgentAttestation -> X402Settlement
         type OnAttestationConfirmed = X402Settlement;
+        type ManageCommittee = MockCommittee;
+        type Randomness = TestRandomness;
+        type Slash = ();
+        type ChallengeBond = ChallengeBond;
+        type ChallengerSlashShare = ChallengerSlashShare;
+        type JurySize = ConstU32<3>;
+        type CommitDuration = JuryCommitDuration;
+        type RevealDuration = JuryRevealDuration;
+        type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+        type UnixTime = MockUnixTime;
     }
 
     impl pallet_zk_compute::Config for Test {
         type RuntimeEvent = RuntimeEvent;
         type Currency = Balances;
         type TaskId = u64;
-        type ZkVerifier = MockZkVerifier;
+        type HashCommitmentVerifier = MockZkVerifier;
+        type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+        type AuthorityId = UintAuthorityId;
+        type AuthorityCrypto = ZkAuthorityCrypto;
+        type MaxAuthorities = ConstU32<10>;
         type MaxProofSize = MaxProofSize;
         type MaxVerificationKeySize = MaxVerificationKeySize;
         type MaxPublicInputsSize = MaxPublicInputsSize;
         type MaxPendingTasks = MaxPendingTasks;
         type MaxVerifiedTasks = MaxVerifiedTasks;
         type MaxPendingPerMiner = MaxPendingPerMiner;
+        type TaskRetentionPeriod = ConstU64<100>;
+        type MaxVerificationBatch = ConstU32<16>;
         type BaseReward = BaseReward;
         type SubmissionDeposit = SubmissionDeposit;
         type VerificationTimeout = VerificationTimeout;
@@ -290,12 +443,34 @@ mod tests {
     impl pallet_x402_settlement::Config for Test {
         type RuntimeEvent = RuntimeEvent;
         type Currency = Balances;
-        type FacilitatorAccount = FacilitatorAccount;
         type MaxSignatureLen = MaxSignatureLen;
+        type MinFacilitatorStake = MinFacilitatorStake;
+        type FacilitatorSlash = FacilitatorSlash;
         type SettlementDelay = SettlementDelay;
         type PaymentIntentTTL = PaymentIntentTTL;
-        type AdminOrigin = frame_system::EnsureSigned<AccountId>;
+        type ChannelDisputeWindow = ChannelDisputeWindow;
+        type MaxExpiriesPerBlock = ConstU32<16>;
+        type AddressMapping = EvmAddressMapping;
+        type ChainId = EvmChainId;
+        type Eip712VerifyingContract = X402VerifyingContract;
+        type AssetId = u32;
+        type Assets = Assets;
+        type DbcPrice = MockDbcPriceProvider;
+        type ConversionAccount = ConversionAccount;
+        type AdminOrigin = frame_system::EnsureRoot<AccountId>;
         type WeightInfo = ();
+        // REAL: X402Settlement -> TaskMode once an order has been paid
+        type OnOrderSettled = TaskMode;
+    }
+
+    /// Maps an EVM address to the account in its low 8 bytes
+    pub struct EvmAddressMapping;
+    impl pallet_evm::AddressMapping<AccountId> for EvmAddressMapping {
+        fn into_account_id(address: sp_core::H160) -> AccountId {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&address.as_bytes()[12..]);
+            AccountId::from_be_bytes(bytes)
+        }
     }
 
     // ================================================================
@@ -320,6 +495,12 @@ mod tests {
         .assimilate_storage(&mut t)
         .expect("balances storage assimilates");
 
+        <pallet_zk_compute::GenesisConfig<Test> as frame_support::traits::GenesisBuild<Test>>::assimilate_storage(
+            &Default::default(),
+            &mut t,
+        )
+        .expect("zk-compute storage assimilates");
+
         let mut ext = sp_io::TestExternalities::new(t);
         ext.execute_with(|| {
             System::set_block_number(1);
@@ -329,10 +510,61 @@ mod tests {
             use sp_runtime::traits::AccountIdConversion;
             let zk_account: AccountId = ZkPalletId::get().into_account_truncating();
             let _ = <Balances as frame_support::traits::Currency<AccountId>>::deposit_creating(&zk_account, 1_000_000);
+            // Register the zk verification session authority
+            let validators = [(ZK_AUTHORITY, UintAuthorityId(ZK_AUTHORITY))];
+            <ZkCompute as frame_support::traits::OneSessionHandler<AccountId>>::on_genesis_session(
+                validators.iter().map(|(who, key)| (who, key.clone())),
+            );
+            // Register the x402 facilitator
+            pallet_x402_settlement::Pallet::<Test>::register_facilitator(
+                RuntimeOrigin::root(),
+                FacilitatorAccount::get(),
+                pallet_x402_settlement::FacilitatorKey::Sr25519(FacilitatorPublicKey::get()),
+                MinFacilitatorStake::get(),
+                Perbill::zero(),
+                0,
+                None,
+            )
+            .expect("facilitator registers");
         });
         ext
     }
 
+    /// Validate and dispatch a zk verification signed by `signer`, as the OCW would.
+    fn submit_zk_verification(signer: u64, task_id: u64, verified: bool) -> Result<(), &'static str> {
+        use frame_support::{traits::UnfilteredDispatchable, unsigned::ValidateUnsigned};
+        use sp_runtime::RuntimeAppPublic;
+
+        let payload = pallet_zk_compute::VerificationPayload {
+            task_id,
+            verified,
+            block_number: System::block_number(),
+            public: UintAuthorityId(signer),
+        };
+        let signature = UintAuthorityId(signer)
+            .sign(&codec::Encode::encode(&payload))
+            .ok_or("signing failed")?;
+        let call = pallet_zk_compute::Call::<Test>::submit_verification_unsigned { payload, signature };
+        ZkCompute::validate_unsigned(sp_runtime::transaction_validity::TransactionSource::Local, &call)
+            .map_err(<&'static str>::from)?;
+        call.dispatch_bypass_filter(RuntimeOrigin::none())
+            .map(|_| ())
+            .map_err(|e| e.error.into())
+    }
+
+    /// Sign payment parameters with the facilitator key configured above.
+    fn facilitator_sig(merchant: AccountId, miner: AccountId, amount: Balance, nonce: u64, fingerprint: H256) -> Vec<u8> {
+        use sp_core::Pair;
+        let pair = sp_core::sr25519::Pair::from_seed(&FACILITATOR_SEED);
+        let mut message = Vec::new();
+        codec::Encode::encode_to(&merchant, &mut message);
+        codec::Encode::encode_to(&miner, &mut message);
+        codec::Encode::encode_to(&amount, &mut message);
+        codec::Encode::encode_to(&nonce, &mut message);
+        codec::Encode::encode_to(&fingerprint, &mut message);
+        pair.sign(&message).0.to_vec()
+    }
+
     fn run_to_block(n: BlockNumber) {
         while System::block_number() < n {
             let next = System::block_number() + 1;
@@ -357,9 +589,11 @@ mod tests {
                 RuntimeOrigin::signed(miner),
                 gpu_model,
                 80, // gpu_memory
+                1,  // gpu_count
                 false,
-                100, // nvlink_efficiency (100 = no nvlink)
-                10,  // price_per_task
+                100,    // nvlink_efficiency (100 = no nvlink)
+                *b"US", // region
+                10,     // price_per_task
             ).is_ok());
 
             // Verify pool exists
@@ -390,10 +624,11 @@ mod tests {
             assert!(task_def.is_active);
 
             // ----- Step 4: Create a task order (customer) -----
+            // No miner named: TaskMode schedules the compute task onto the miner's pool itself
             assert!(pallet_task_mode::Pallet::<Test>::create_task_order(
                 RuntimeOrigin::signed(customer),
                 0,     // task_id (the definition we just created)
-                miner, // miner
+                None,  // miner
                 500,   // input_tokens
                 1000,  // output_tokens
             ).is_ok());
@@ -406,59 +641,70 @@ mod tests {
                 order.status,
                 pallet_task_mode::pallet::TaskOrderStatus::InProgress
             ));
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::reserved_balance(customer),
+                order.total_dbc_charged
+            );
 
-            // ----- Step 5: Submit a compute task directly via scheduler -----
-            // (In a real runtime, TaskMode would call schedule_compute internally)
-            let dimensions = pallet_compute_pool_scheduler::pallet::TaskDimensions {
-                m: 100,
-                n: 100,
-                k: 10, // must be <= gpu_memory (80)
-            };
-            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::submit_task(
-                RuntimeOrigin::signed(customer),
-                dimensions,
-                pallet_compute_pool_scheduler::pallet::TaskPriority::Normal,
-                None,
-            ).is_ok());
-
-            // Verify task was assigned to the pool
-            let compute_task = pallet_compute_pool_scheduler::Tasks::<Test>::get(0).unwrap();
+            // ----- Step 5: Verify the compute task was created for the order -----
+            let scheduler_task_id = pallet_task_mode::OrderComputeTasks::<Test>::get(0)
+                .expect("order is linked to a scheduler task");
+            let compute_task = pallet_compute_pool_scheduler::Tasks::<Test>::get(scheduler_task_id).unwrap();
             assert_eq!(compute_task.pool_id, 0);
+            assert_eq!(compute_task.user, customer);
             assert!(matches!(
                 compute_task.status,
                 pallet_compute_pool_scheduler::pallet::TaskStatus::Computing
             ));
-
-            // ----- Step 6: Submit proof (miner) -----
-            // This triggers OnTaskCompleted -> AgentAttestation::on_task_completed
+            let context = pallet_compute_pool_scheduler::TaskOrderContexts::<Test>::get(scheduler_task_id)
+                .expect("order context is stored with the task");
+            assert_eq!(context.order_id, 0);
+            assert_eq!(context.customer, customer);
+            assert_eq!(context.agreed_price, order.miner_payout);
+
+            // ----- Step 6: Submit proof (miner) and verify it (independent verifier) -----
+            // Verification triggers OnTaskCompleted -> AgentAttestation::on_task_completed
             let proof_hash = [1u8; 32];
             assert!(pallet_compute_pool_scheduler::Pallet::<Test>::submit_proof(
                 RuntimeOrigin::signed(miner),
-                0,          // task_id
-                proof_hash, // proof_hash
-                true,       // verification_result
+                scheduler_task_id,
+                proof_hash,
+            ).is_ok());
+            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::verify_proof(
+                RuntimeOrigin::signed(admin),
+                scheduler_task_id,
+                true,
             ).is_ok());
 
             // Verify task completed
-            let completed_task = pallet_compute_pool_scheduler::Tasks::<Test>::get(0).unwrap();
+            let completed_task = pallet_compute_pool_scheduler::Tasks::<Test>::get(scheduler_task_id).unwrap();
             assert!(matches!(
                 completed_task.status,
                 pallet_compute_pool_scheduler::pallet::TaskStatus::Completed
             ));
             assert_eq!(completed_task.verification_result, Some(true));
 
-            // Verify attestation was created automatically by cross-pallet call
+            // Verify attestation carries the real order usage and provenance
             let attestation = pallet_agent_attestation::Attestations::<Test>::get(0).unwrap();
             assert_eq!(attestation.attester, miner);
-            assert_eq!(attestation.task_id, 0);
+            assert_eq!(attestation.task_id, scheduler_task_id);
+            assert_eq!(attestation.model_id.to_vec(), b"llama-70b".to_vec());
+            assert_eq!(attestation.input_tokens, 500);
+            assert_eq!(attestation.output_tokens, 1000);
             assert!(matches!(
                 attestation.status,
                 pallet_agent_attestation::pallet::AttestationStatus::Pending
             ));
+            let provenance = pallet_agent_attestation::AttestationOrders::<Test>::get(0).unwrap();
+            assert_eq!(provenance.order_id, 0);
+            assert_eq!(provenance.customer, customer);
+            assert_eq!(provenance.agreed_price, order.miner_payout);
 
             // ----- Step 7: Advance past challenge window and confirm attestation -----
             run_to_block(1 + ChallengeWindow::get() + 1);
 
+            let miner_free_before = pallet_balances::Pallet::<Test>::free_balance(miner);
+            let treasury_before = pallet_balances::Pallet::<Test>::free_balance(TreasuryAccount::get());
             assert!(pallet_agent_attestation::Pallet::<Test>::confirm_attestation(
                 RuntimeOrigin::signed(admin), // anyone can confirm after window
                 0, // attestation_id
@@ -471,39 +717,116 @@ mod tests {
                 pallet_agent_attestation::pallet::AttestationStatus::Confirmed
             ));
 
-            // ----- Step 8: Verify settlement receipt was created by cross-pallet call -----
+            // ----- Step 8: Verify the customer paid the miner the agreed price -----
             // X402Settlement::settle_for_attestation was called by AgentAttestation::confirm_attestation
-            let receipt = pallet_x402_settlement::pallet::SettlementReceipts::<Test>::get(0);
-            assert!(receipt.is_some(), "Settlement receipt should exist from cross-pallet call");
-            let receipt = receipt.unwrap();
+            let receipt_id = pallet_x402_settlement::pallet::OrderReceipts::<Test>::get(0)
+                .expect("Settlement receipt should exist from cross-pallet call");
+            let receipt = pallet_x402_settlement::pallet::SettlementReceipts::<Test>::get(receipt_id).unwrap();
+            assert_eq!(receipt.merchant, customer);
             assert_eq!(receipt.miner, miner);
+            assert_eq!(receipt.amount, order.miner_payout);
 
-            // ----- Step 9: Claim compute reward -----
-            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::claim_reward(
-                RuntimeOrigin::signed(miner),
-                0, // task_id
-            ).is_ok());
+            // Miner gets the attestation deposit back, less the caller's tip, plus the order payout
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::free_balance(miner),
+                miner_free_before + AttestationDeposit::get() - FinalizationTip::get() + order.miner_payout
+            );
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::free_balance(TreasuryAccount::get()),
+                treasury_before + order.dbc_burned
+            );
+            assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(customer), 0);
 
-            // ----- Step 10: Settle the TaskMode order -----
-            assert!(pallet_task_mode::Pallet::<Test>::mark_order_completed(
-                RuntimeOrigin::signed(miner),
-                0, // order_id
-                [2u8; 32], // attestation_hash
-            ).is_ok());
+            // ----- Step 9: The TaskMode order was settled by the same call -----
+            let settled_order = pallet_task_mode::TaskOrders::<Test>::get(0).unwrap();
+            assert!(matches!(
+                settled_order.status,
+                pallet_task_mode::pallet::TaskOrderStatus::Settled
+            ));
+            let era = pallet_task_mode::EraStats::<Test>::get(0);
+            assert_eq!(era.completed_orders, 1);
+            assert_eq!(era.total_miner_payout, order.miner_payout);
 
+            // ----- Step 10: The order cannot be paid out a second time -----
             assert!(pallet_task_mode::Pallet::<Test>::settle_task_order(
                 RuntimeOrigin::signed(customer),
                 0,    // order_id
                 None, // attestation_hash
+            ).is_err());
+
+            println!("PASS: full_pipeline_task_to_settlement - all cross-pallet calls verified");
+        });
+    }
+
+    // ================================================================
+    // Test 1b: Order without a miner is scheduled onto the best pool
+    // ================================================================
+    #[test]
+    fn order_without_miner_is_scheduled_and_tracks_compute_task() {
+        new_test_ext().execute_with(|| {
+            let admin: AccountId = 3;
+            let customer: AccountId = 1;
+            let pool_owner: AccountId = 2;
+
+            let gpu_model: frame_support::BoundedVec<u8, MaxGpuModelLen> =
+                b"NVIDIA-H100".to_vec().try_into().unwrap();
+            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::register_pool(
+                RuntimeOrigin::signed(pool_owner),
+                gpu_model,
+                80, 1, false, 100, *b"US", 10,
+            ).is_ok());
+            assert!(pallet_agent_attestation::Pallet::<Test>::register_node(
+                RuntimeOrigin::signed(pool_owner),
+                b"GPU-UUID-002".to_vec(),
+                400,
+            ).is_ok());
+            assert!(pallet_task_mode::Pallet::<Test>::create_task_definition(
+                RuntimeOrigin::signed(admin),
+                b"llama-70b".to_vec(),
+                b"v1.0".to_vec(),
+                5, 15, 4096,
+                b"QmPolicyCid123".to_vec(),
             ).is_ok());
 
-            let settled_order = pallet_task_mode::TaskOrders::<Test>::get(0).unwrap();
+            // No miner given: the scheduler picks the pool and its owner becomes the miner
+            assert!(pallet_task_mode::Pallet::<Test>::create_task_order(
+                RuntimeOrigin::signed(customer),
+                0, None, 500, 1000,
+            ).is_ok());
+            let order = pallet_task_mode::TaskOrders::<Test>::get(0).unwrap();
+            assert_eq!(order.miner, pool_owner);
+            let scheduler_task_id = pallet_task_mode::OrderComputeTasks::<Test>::get(0).unwrap();
+
+            // Order cannot complete before its compute task does
+            assert!(pallet_task_mode::Pallet::<Test>::sync_order_status(
+                RuntimeOrigin::signed(admin), 0,
+            ).is_err());
+
+            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::submit_proof(
+                RuntimeOrigin::signed(pool_owner), scheduler_task_id, [7u8; 32],
+            ).is_ok());
+            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::verify_proof(
+                RuntimeOrigin::signed(admin), scheduler_task_id, true,
+            ).is_ok());
+
+            assert!(pallet_task_mode::Pallet::<Test>::sync_order_status(
+                RuntimeOrigin::signed(admin), 0,
+            ).is_ok());
+            let order = pallet_task_mode::TaskOrders::<Test>::get(0).unwrap();
             assert!(matches!(
-                settled_order.status,
-                pallet_task_mode::pallet::TaskOrderStatus::Settled
+                order.status,
+                pallet_task_mode::pallet::TaskOrderStatus::Completed
             ));
 
-            println!("PASS: full_pipeline_task_to_settlement - all cross-pallet calls verified");
+            // A named miner without a compute pool still takes orders, outside the scheduler
+            let scheduler_tasks = pallet_compute_pool_scheduler::NextTaskId::<Test>::get();
+            assert!(pallet_task_mode::Pallet::<Test>::create_task_order(
+                RuntimeOrigin::signed(customer),
+                0, Some(4), 500, 1000,
+            ).is_ok());
+            assert_eq!(pallet_task_mode::TaskOrders::<Test>::get(1).unwrap().miner, 4);
+            assert_eq!(pallet_task_mode::OrderComputeTasks::<Test>::get(1), None);
+            assert_eq!(pallet_compute_pool_scheduler::NextTaskId::<Test>::get(), scheduler_tasks);
         });
     }
 
@@ -522,10 +845,12 @@ mod tests {
             assert!(pallet_compute_pool_scheduler::Pallet::<Test>::register_pool(
                 RuntimeOrigin::signed(pool_owner),
                 gpu_model,
-                24,    // gpu_memory
-                false, // no nvlink
-                100,   // nvlink_efficiency
-                5,     // price_per_task
+                24,     // gpu_memory
+                1,      // gpu_count
+                false,  // no nvlink
+                100,    // nvlink_efficiency
+                *b"US", // region
+                5,      // price_per_task
             ).is_ok());
 
             // Register pool owner as attestation node (needed for on_task_completed)
@@ -551,6 +876,7 @@ mod tests {
             assert!(pallet_compute_pool_scheduler::Pallet::<Test>::submit_task(
                 RuntimeOrigin::signed(task_user),
                 dims,
+                dbc_support::traits::TaskRequirements { min_vram: 24, ..Default::default() },
                 pallet_compute_pool_scheduler::pallet::TaskPriority::High,
                 None,
             ).is_ok());
@@ -573,6 +899,10 @@ mod tests {
                 RuntimeOrigin::signed(pool_owner),
                 0,
                 proof_hash,
+            ).is_ok());
+            assert!(pallet_compute_pool_scheduler::Pallet::<Test>::verify_proof(
+                RuntimeOrigin::signed(3),
+                0,
                 true,
             ).is_ok());
 
@@ -614,6 +944,33 @@ mod tests {
         });
     }
 
+    /// Every juror of `attestation_id` commits and reveals the same verdict
+    fn jury_votes(attestation_id: u64, attester_is_guilty: bool) {
+        let jury = pallet_agent_attestation::Disputes::<Test>::get(attestation_id)
+            .expect("dispute opened")
+            .jury;
+        for juror in jury.iter() {
+            let hash = pallet_agent_attestation::Pallet::<Test>::vote_hash(
+                attestation_id,
+                b"salt".to_vec(),
+                attester_is_guilty,
+            );
+            assert!(pallet_agent_attestation::Pallet::<Test>::commit_vote(
+                RuntimeOrigin::signed(*juror),
+                attestation_id,
+                hash,
+            ).is_ok());
+        }
+        for juror in jury.iter() {
+            assert!(pallet_agent_attestation::Pallet::<Test>::reveal_vote(
+                RuntimeOrigin::signed(*juror),
+                attestation_id,
+                b"salt".to_vec(),
+                attester_is_guilty,
+            ).is_ok());
+        }
+    }
+
     // ================================================================
     // Test 3: Attestation challenge and resolution flow
     // ================================================================
@@ -665,14 +1022,19 @@ mod tests {
             let challenged_att = pallet_agent_attestation::Attestations::<Test>::get(0).unwrap();
             assert_eq!(challenged_att.challenger, Some(challenger));
 
-            // ----- Resolve challenge: attester is guilty (slash) -----
-            let _attester_balance_before = pallet_balances::Pallet::<Test>::free_balance(attester);
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::reserved_balance(challenger),
+                ChallengeBond::get()
+            );
+
+            // ----- Resolve challenge: the jury finds the attester guilty (slash) -----
             let attester_reserved_before = pallet_balances::Pallet::<Test>::reserved_balance(attester);
+            let challenger_free_before = pallet_balances::Pallet::<Test>::free_balance(challenger);
 
+            jury_votes(0, true);
             assert!(pallet_agent_attestation::Pallet::<Test>::resolve_challenge(
-                RuntimeOrigin::root(), // root only
-                0,    // attestation_id
-                true, // attester_is_guilty
+                RuntimeOrigin::signed(4),
+                0, // attestation_id
             ).is_ok());
 
             let slashed_att = pallet_agent_attestation::Attestations::<Test>::get(0).unwrap();
@@ -687,6 +1049,11 @@ mod tests {
                 attester_reserved_after < attester_reserved_before,
                 "Attester should have been slashed"
             );
+            // Bond returned plus half of the 500 slashed from the deposit
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::free_balance(challenger),
+                challenger_free_before + ChallengeBond::get() + 250
+            );
 
             // ----- Test defend flow: submit new attestation and defend -----
             assert!(pallet_agent_attestation::Pallet::<Test>::submit_attestation(
@@ -707,10 +1074,10 @@ mod tests {
             // Resolve: attester wins (defended)
             let reserved_before_defend = pallet_balances::Pallet::<Test>::reserved_balance(attester);
 
+            jury_votes(1, false);
             assert!(pallet_agent_attestation::Pallet::<Test>::resolve_challenge(
-                RuntimeOrigin::root(),
-                1,     // attestation_id
-                false, // attester is NOT guilty
+                RuntimeOrigin::signed(4),
+                1, // attestation_id
             ).is_ok());
 
             let defended_att = pallet_agent_attestation::Attestations::<Test>::get(1).unwrap();
@@ -725,11 +1092,134 @@ mod tests {
                 reserved_after_defend < reserved_before_defend,
                 "Deposit should have been unreserved after defense"
             );
+            // The losing challenger forfeited the bond
+            assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(challenger), 0);
 
             println!("PASS: attestation_challenge_flow - slash and defend paths verified");
         });
     
     }
+
+    /// Run an order from customer 1 through scheduling and verification on the pool of miner 2,
+    /// then have account 3 challenge the resulting attestation
+    fn challenged_order_attestation() -> pallet_task_mode::pallet::TaskOrder<AccountId, BlockNumber, Balance> {
+        let gpu_model: frame_support::BoundedVec<u8, MaxGpuModelLen> =
+            b"NVIDIA-A100".to_vec().try_into().unwrap();
+        assert!(pallet_compute_pool_scheduler::Pallet::<Test>::register_pool(
+            RuntimeOrigin::signed(2),
+            gpu_model,
+            80, 1, false, 100, *b"US", 10,
+        ).is_ok());
+        assert!(pallet_agent_attestation::Pallet::<Test>::register_node(
+            RuntimeOrigin::signed(2),
+            b"GPU-UUID-001".to_vec(),
+            312,
+        ).is_ok());
+        assert!(pallet_task_mode::Pallet::<Test>::create_task_definition(
+            RuntimeOrigin::signed(3),
+            b"llama-70b".to_vec(),
+            b"v1.0".to_vec(),
+            5, 15, 4096,
+            b"QmPolicyCid123".to_vec(),
+        ).is_ok());
+        assert!(pallet_task_mode::Pallet::<Test>::create_task_order(
+            RuntimeOrigin::signed(1),
+            0, None, 500, 1000,
+        ).is_ok());
+
+        let scheduler_task_id = pallet_task_mode::OrderComputeTasks::<Test>::get(0).unwrap();
+        assert!(pallet_compute_pool_scheduler::Pallet::<Test>::submit_proof(
+            RuntimeOrigin::signed(2),
+            scheduler_task_id,
+            [1u8; 32],
+        ).is_ok());
+        assert!(pallet_compute_pool_scheduler::Pallet::<Test>::verify_proof(
+            RuntimeOrigin::signed(3),
+            scheduler_task_id,
+            true,
+        ).is_ok());
+        assert!(pallet_agent_attestation::AttestationOrders::<Test>::contains_key(0));
+
+        assert!(pallet_agent_attestation::Pallet::<Test>::challenge_attestation(
+            RuntimeOrigin::signed(3),
+            0,
+        ).is_ok());
+        pallet_task_mode::TaskOrders::<Test>::get(0).unwrap()
+    }
+
+    // ================================================================
+    // Test 3b: A defended order-backed attestation pays the miner
+    // ================================================================
+    #[test]
+    fn defended_order_attestation_settles_the_order() {
+        new_test_ext().execute_with(|| {
+            let (customer, miner) = (1, 2);
+            let order = challenged_order_attestation();
+            let miner_free_before = pallet_balances::Pallet::<Test>::free_balance(miner);
+
+            jury_votes(0, false);
+            assert!(pallet_agent_attestation::Pallet::<Test>::resolve_challenge(
+                RuntimeOrigin::signed(4),
+                0,
+            ).is_ok());
+
+            let receipt_id = pallet_x402_settlement::pallet::OrderReceipts::<Test>::get(0)
+                .expect("the defended attestation settles its order");
+            let receipt = pallet_x402_settlement::pallet::SettlementReceipts::<Test>::get(receipt_id).unwrap();
+            assert_eq!(receipt.miner, miner);
+            assert_eq!(receipt.amount, order.miner_payout);
+            // Deposit returned in full (no finalization tip on a dispute) plus the order payout
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::free_balance(miner),
+                miner_free_before + AttestationDeposit::get() + order.miner_payout
+            );
+            assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(customer), 0);
+            assert!(matches!(
+                pallet_task_mode::TaskOrders::<Test>::get(0).unwrap().status,
+                pallet_task_mode::pallet::TaskOrderStatus::Settled
+            ));
+        });
+    }
+
+    // ================================================================
+    // Test 3c: A slashed order-backed attestation refunds the customer
+    // ================================================================
+    #[test]
+    fn slashed_order_attestation_refunds_the_customer() {
+        new_test_ext().execute_with(|| {
+            let customer = 1;
+            let order = challenged_order_attestation();
+            let customer_free_before = pallet_balances::Pallet::<Test>::free_balance(customer);
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::reserved_balance(customer),
+                order.total_dbc_charged
+            );
+
+            jury_votes(0, true);
+            assert!(pallet_agent_attestation::Pallet::<Test>::resolve_challenge(
+                RuntimeOrigin::signed(4),
+                0,
+            ).is_ok());
+
+            assert!(pallet_x402_settlement::pallet::OrderReceipts::<Test>::get(0).is_none());
+            assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(customer), 0);
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::free_balance(customer),
+                customer_free_before + order.total_dbc_charged
+            );
+            assert!(matches!(
+                pallet_task_mode::TaskOrders::<Test>::get(0).unwrap().status,
+                pallet_task_mode::pallet::TaskOrderStatus::Settled
+            ));
+            System::assert_has_event(RuntimeEvent::TaskMode(
+                pallet_task_mode::pallet::Event::TaskOrderRefunded {
+                    order_id: 0,
+                    customer,
+                    refunded: order.total_dbc_charged,
+                },
+            ));
+        });
+    }
     // ================================================================
     // Test 4: Pool staking integration (P1-4)
     // ================================================================
@@ -746,7 +1236,7 @@ mod tests {
             assert!(pallet_compute_pool_scheduler::Pallet::<Test>::register_pool(
                 RuntimeOrigin::signed(pool_owner),
                 gpu_model,
-                80, false, 100, 10,
+                80, 1, false, 100, *b"US", 10,
             ).is_ok());
 
             // Stake to pool
@@ -758,7 +1248,7 @@ mod tests {
 
             // Verify stake recorded
             assert_eq!(
-                pallet_compute_pool_scheduler::PoolStakes::<Test>::get(0, staker),
+                pallet_compute_pool_scheduler::Pallet::<Test>::delegated_stake(0, &staker),
                 stake_amount
             );
             assert_eq!(
@@ -776,15 +1266,19 @@ mod tests {
                 8_000
             );
 
-            // Unstake partial
+            // Unstake partial; it unbonds before it can be withdrawn
             assert!(pallet_compute_pool_scheduler::Pallet::<Test>::unstake_from_pool(
                 RuntimeOrigin::signed(staker),
                 0, 2_000,
             ).is_ok());
             assert_eq!(
-                pallet_compute_pool_scheduler::PoolStakes::<Test>::get(0, staker),
+                pallet_compute_pool_scheduler::Pallet::<Test>::delegated_stake(0, &staker),
                 3_000
             );
+            assert_eq!(
+                pallet_compute_pool_scheduler::Pallet::<Test>::unbonding_stake(0, &staker),
+                2_000
+            );
             assert_eq!(
                 pallet_compute_pool_scheduler::TotalPoolStake::<Test>::get(0),
                 6_000
@@ -874,28 +1368,19 @@ mod tests {
         new_test_ext().execute_with(|| {
             let merchant: AccountId = 1;
             let miner: AccountId = 2;
-            let facilitator: AccountId = 100;
 
             // Create valid facilitator signature
             let amount: Balance = 500;
             let nonce: u64 = 1;
             let replay_fingerprint = H256::from_low_u64_be(42);
 
-            let mut message = Vec::new();
-            codec::Encode::encode_to(&merchant, &mut message);
-            codec::Encode::encode_to(&miner, &mut message);
-            codec::Encode::encode_to(&amount, &mut message);
-            codec::Encode::encode_to(&nonce, &mut message);
-            codec::Encode::encode_to(&replay_fingerprint, &mut message);
-            codec::Encode::encode_to(&facilitator, &mut message);
-            let hash = sp_io::hashing::blake2_256(&message);
-            let sig: Vec<u8> = hash.to_vec();
+            let sig = facilitator_sig(merchant, miner, amount, nonce, replay_fingerprint);
 
             // Submit payment intent
             let balance_before = pallet_balances::Pallet::<Test>::free_balance(merchant);
             assert!(pallet_x402_settlement::Pallet::<Test>::submit_payment_intent(
                 RuntimeOrigin::signed(merchant),
-                miner, amount, nonce, replay_fingerprint, sig,
+                miner, amount, nonce, replay_fingerprint, FacilitatorAccount::get(), sig,
             ).is_ok());
 
             // Verify funds reserved
@@ -918,8 +1403,8 @@ mod tests {
                 pallet_x402_settlement::pallet::PaymentIntentStatus::Pending
             ));
 
-            // Advance past expiry
-            run_to_block(1 + PaymentIntentTTL::get() + 1);
+            // Advance to the expiry block, where the intent is queued
+            run_to_block(1 + PaymentIntentTTL::get());
             <pallet_x402_settlement::Pallet<Test> as frame_support::traits::Hooks<BlockNumber>>::on_initialize(
                 System::block_number()
             );
@@ -955,20 +1440,12 @@ mod tests {
             let amount: Balance = 1_000;
             let nonce: u64 = 1;
             let replay_fingerprint = H256::from_low_u64_be(99);
-            let mut message = Vec::new();
-            codec::Encode::encode_to(&merchant, &mut message);
-            codec::Encode::encode_to(&miner, &mut message);
-            codec::Encode::encode_to(&amount, &mut message);
-            codec::Encode::encode_to(&nonce, &mut message);
-            codec::Encode::encode_to(&replay_fingerprint, &mut message);
-            codec::Encode::encode_to(&facilitator, &mut message);
-            let hash = sp_io::hashing::blake2_256(&message);
-            let sig: Vec<u8> = hash.to_vec();
+            let sig = facilitator_sig(merchant, miner, amount, nonce, replay_fingerprint);
 
             // Submit intent
             assert!(pallet_x402_settlement::Pallet::<Test>::submit_payment_intent(
                 RuntimeOrigin::signed(merchant),
-                miner, amount, nonce, replay_fingerprint, sig,
+                miner, amount, nonce, replay_fingerprint, FacilitatorAccount::get(), sig,
             ).is_ok());
 
             // Verify settlement (facilitator only)
@@ -1007,7 +1484,7 @@ mod tests {
             let bad_sig: Vec<u8> = vec![0u8; 32];
             assert!(pallet_x402_settlement::Pallet::<Test>::submit_payment_intent(
                 RuntimeOrigin::signed(merchant),
-                miner, amount, 2, H256::from_low_u64_be(100), bad_sig,
+                miner, amount, 2, H256::from_low_u64_be(100), FacilitatorAccount::get(), bad_sig,
             ).is_err());
 
             println!("PASS: x402_full_settlement_with_signature");
@@ -1029,7 +1506,9 @@ mod tests {
             // Submit a ZK proof
             assert!(pallet_zk_compute::Pallet::<Test>::submit_proof(
                 RuntimeOrigin::signed(miner),
+                pallet_zk_compute::HASH_COMMITMENT_CIRCUIT,
                 b"test-proof".to_vec(),
+                vec![],    // public inputs
                 (8, 8, 8), // dimensions
                 120,       // execution_time
                 42,        // request_id
@@ -1043,12 +1522,11 @@ mod tests {
                 pallet_zk_compute::pallet::ZkVerificationStatus::Pending
             ));
 
-            // Submit unsigned verification (simulating OCW)
-            assert!(pallet_zk_compute::Pallet::<Test>::submit_verification_unsigned(
-                RuntimeOrigin::none(),
-                task_id,
-                true, // verified
-            ).is_ok());
+            // Verdicts signed by non-authorities never reach the pallet
+            assert!(submit_zk_verification(ZK_AUTHORITY + 1, task_id, true).is_err());
+
+            // Submit signed verification from the session authority (simulating OCW)
+            assert!(submit_zk_verification(ZK_AUTHORITY, task_id, true).is_ok());
 
             // Task should now be Verified
             let verified_task = pallet_zk_compute::Tasks::<Test>::get(task_id).unwrap();
@@ -1066,17 +1544,15 @@ mod tests {
             // Submit another proof and fail verification
             assert!(pallet_zk_compute::Pallet::<Test>::submit_proof(
                 RuntimeOrigin::signed(miner),
+                pallet_zk_compute::HASH_COMMITMENT_CIRCUIT,
                 b"bad-proof".to_vec(),
+                vec![],
                 (4, 4, 4), 120, 43,
             ).is_ok());
 
             let task_id2 = pallet_zk_compute::pallet::NextTaskId::<Test>::get() - 1u64;
 
-            assert!(pallet_zk_compute::Pallet::<Test>::submit_verification_unsigned(
-                RuntimeOrigin::none(),
-                task_id2,
-                false, // failed verification
-            ).is_ok());
+            assert!(submit_zk_verification(ZK_AUTHORITY, task_id2, false).is_ok());
 
             let failed_task = pallet_zk_compute::Tasks::<Test>::get(task_id2).unwrap();
             assert!(matches!(
@@ -1088,4 +1564,75 @@ mod tests {
         });
     }
 
+    // ================================================================
+    // Test 9: Payment channel usage booked as a task-mode order
+    // ================================================================
+    #[test]
+    fn payment_channel_settles_into_task_order() {
+        new_test_ext().execute_with(|| {
+            use sp_core::Pair;
+            let customer: AccountId = 1;
+            let miner: AccountId = 2;
+            let admin: AccountId = 3;
+            let voucher_pair = sp_core::sr25519::Pair::from_seed(&[9u8; 32]);
+
+            assert!(pallet_task_mode::Pallet::<Test>::create_task_definition(
+                RuntimeOrigin::signed(admin),
+                b"llama-70b".to_vec(),
+                b"v1.0".to_vec(),
+                5,
+                15,
+                4096,
+                b"QmPolicyCid123".to_vec(),
+            ).is_ok());
+
+            assert!(pallet_x402_settlement::Pallet::<Test>::open_channel(
+                RuntimeOrigin::signed(customer),
+                miner,
+                0,
+                100_000,
+                pallet_x402_settlement::FacilitatorKey::Sr25519(voucher_pair.public().0),
+            ).is_ok());
+
+            // The miner closes with the last voucher the customer signed off-chain
+            let voucher = pallet_x402_settlement::ChannelVoucher {
+                amount: 40_000,
+                input_tokens: 3_000,
+                output_tokens: 9_000,
+            };
+            let message = pallet_x402_settlement::Pallet::<Test>::channel_voucher_message(0, &voucher);
+            assert!(pallet_x402_settlement::Pallet::<Test>::close_channel(
+                RuntimeOrigin::signed(miner),
+                0,
+                voucher,
+                voucher_pair.sign(&message).0.to_vec(),
+            ).is_ok());
+
+            let customer_before = pallet_balances::Pallet::<Test>::free_balance(customer);
+            let miner_before = pallet_balances::Pallet::<Test>::free_balance(miner);
+            let treasury_before = pallet_balances::Pallet::<Test>::free_balance(TreasuryAccount::get());
+            run_to_block(1 + ChannelDisputeWindow::get());
+            assert!(pallet_x402_settlement::Pallet::<Test>::settle_channel(RuntimeOrigin::signed(4), 0).is_ok());
+
+            // 15% of the usage is burned to the treasury, the rest paid to the miner
+            assert_eq!(pallet_balances::Pallet::<Test>::free_balance(miner), miner_before + 34_000);
+            assert_eq!(
+                pallet_balances::Pallet::<Test>::free_balance(TreasuryAccount::get()),
+                treasury_before + 6_000
+            );
+            assert_eq!(pallet_balances::Pallet::<Test>::free_balance(customer), customer_before + 60_000);
+            assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(customer), 0);
+
+            let order = pallet_task_mode::TaskOrders::<Test>::get(0).unwrap();
+            assert!(matches!(order.status, pallet_task_mode::TaskOrderStatus::Settled));
+            assert_eq!(order.customer, customer);
+            assert_eq!(order.miner, miner);
+            assert_eq!((order.input_tokens, order.output_tokens), (3_000, 9_000));
+            assert_eq!(order.total_dbc_charged, 40_000);
+            let era = pallet_task_mode::Pallet::<Test>::block_to_era(order.created_at);
+            assert_eq!(pallet_task_mode::EraStats::<Test>::get(era).total_miner_payout, 34_000);
+
+            println!("PASS: payment_channel_settles_into_task_order");
+        });
+    }
 }